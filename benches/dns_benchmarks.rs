@@ -4,17 +4,51 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use dnsrecon_rs::dns::resolver::DnsHelper;
 
 fn benchmark_dns_resolution(c: &mut Criterion) {
-    let dns_helper = DnsHelper::new("example.com".to_string()).unwrap();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let dns_helper = DnsHelper::default_resolver().unwrap();
     
     c.bench_function("dns_a_record_lookup", |b| {
         b.iter(|| {
-            let result = dns_helper.get_ip(black_box("example.com"));
+            let result = runtime.block_on(dns_helper.get_ip(black_box("example.com")));
             // We don't assert the result to avoid panics in benchmarks
             let _ = result;
         })
     });
 }
 
+fn benchmark_resolver_reuse(c: &mut Criterion) {
+    use trust_dns_resolver::config::{ResolverConfig, ResolverOpts};
+    use trust_dns_resolver::Resolver;
+    
+    // localhost comes from the hosts file, so this measures resolver overhead, not the network
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let dns_helper = DnsHelper::default_resolver().unwrap();
+    let mut group = c.benchmark_group("get_ip_1000_sequential");
+    group.sample_size(10);
+    
+    group.bench_function("fresh_resolver_per_lookup", |b| {
+        b.iter(|| {
+            for _ in 0..1000 {
+                let resolver = Resolver::new(ResolverConfig::default(), ResolverOpts::default()).unwrap();
+                let _ = resolver.ipv4_lookup(black_box("localhost"));
+                let _ = resolver.ipv6_lookup(black_box("localhost"));
+            }
+        })
+    });
+    
+    group.bench_function("shared_resolver", |b| {
+        b.iter(|| {
+            runtime.block_on(async {
+                for _ in 0..1000 {
+                    let _ = dns_helper.get_ip(black_box("localhost")).await;
+                }
+            })
+        })
+    });
+    
+    group.finish();
+}
+
 fn benchmark_json_output(c: &mut Criterion) {
     use dnsrecon_rs::dns::record::{DnsRecord, RecordType, RecordData};
     use dnsrecon_rs::output::json;
@@ -29,12 +63,12 @@ fn benchmark_json_output(c: &mut Criterion) {
     
     c.bench_function("json_output_formatting", |b| {
         b.iter(|| {
-            let result = json::to_json_string(black_box(&records));
+            let result = json::to_json_string(black_box(&records), true);
             // We don't assert the result to avoid panics in benchmarks
             let _ = result;
         })
     });
 }
 
-criterion_group!(benches, benchmark_dns_resolution, benchmark_json_output);
+criterion_group!(benches, benchmark_dns_resolution, benchmark_resolver_reuse, benchmark_json_output);
 criterion_main!(benches);
\ No newline at end of file
@@ -4,8 +4,15 @@
 
 use clap::Parser;
 use thiserror::Error;
+use crate::enumerate::sources::Source;
+use crate::enumerate::crt_sh::CrtShScope;
+use crate::dns::record::RecordType;
+use crate::dns::resolver::NsStrategy;
+use crate::config::ReconConfig;
+use crate::output::StdoutFormat;
 
 pub mod progress;
+pub mod summary;
 
 /// CLI-related errors
 #[derive(Error, Debug)]
@@ -38,58 +45,272 @@ pub fn parse_args() -> Result<Args, CliError> {
 }
 
 /// Main arguments structure
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(
     version = "0.1.0",
     about = "DNS Enumeration Tool - Rust Implementation",
 )]
 pub struct Args {
-    /// Domain to enumerate
+    /// Domain to enumerate; repeat to scan several domains in one run
     #[arg(short, long)]
-    pub domain: Option<String>,
+    pub domain: Vec<String>,
+    
+    /// File of domains to enumerate, one per line
+    #[arg(long)]
+    pub domain_file: Option<String>,
     
     /// Type of enumeration to perform
-	/// Available types: std, brt, zonewalk, reverse
+	/// Available types: std, brt, zonewalk, reverse, permute
     #[arg(short, long, value_parser = parse_enum_type, default_value = "std")]
     pub r#type: EnumType,
     
+    /// How to print results when no output file is given
+    /// Available formats: json, text, csv
+    #[arg(long, value_parser = parse_stdout_format, default_value = "json")]
+    pub format: StdoutFormat,
+    
     /// Output results to JSON file
     #[arg(short = 'j', long)]
     pub json_file: Option<String>,
     
+    /// Merge results into an existing JSON file instead of overwriting it
+    #[arg(long, requires = "json_file")]
+    pub json_append: bool,
+    
+    /// Compare the results against a previous JSON output and report new, gone and modified records
+    #[arg(long)]
+    pub diff_against: Option<String>,
+    
+    /// Compare two JSON result files and print the changes instead of scanning
+    #[arg(long, num_args = 2, value_names = ["OLD", "NEW"], conflicts_with = "diff_against")]
+    pub diff: Option<Vec<String>>,
+    
+    /// Write the --diff-against comparison as JSON to this file
+    #[arg(long, requires = "diff_against")]
+    pub diff_file: Option<String>,
+    
+    /// Write JSON output without indentation
+    #[arg(long)]
+    pub json_compact: bool,
+    
     /// Output results to XML file
     #[arg(short = 'x', long)]
     pub xml_file: Option<String>,
     
+    /// Output results to CSV file
+    #[arg(long)]
+    pub csv_file: Option<String>,
+    
     /// Output results to SQLite database
     #[arg(short = 's', long)]
     pub sqlite_file: Option<String>,
     
+    /// Write records to the SQLite database as they are found, committing periodically,
+    /// so an interrupted scan keeps what it found so far
+    #[arg(long, requires = "sqlite_file")]
+    pub sqlite_incremental: bool,
+    
+    /// Output security findings (SPF/DMARC policy issues, wildcards, ...) to JSON file
+    #[arg(long)]
+    pub findings_file: Option<String>,
+    
+    /// Output the scan summary, including time spent per source, to JSON file
+    #[arg(long)]
+    pub summary_json: Option<String>,
+    
+    /// Output results to a file in Elasticsearch _bulk format
+    #[arg(long)]
+    pub es_bulk: Option<String>,
+    
+    /// Elasticsearch index name used in _bulk output
+    #[arg(long, default_value = crate::output::elastic::DEFAULT_INDEX)]
+    pub es_index: String,
+    
+    /// Elasticsearch URL to POST the _bulk output to (e.g. http://localhost:9200)
+    #[arg(long)]
+    pub es_url: Option<String>,
+    
+    /// Output results to a file in BIND zone file format
+    #[arg(long)]
+    pub zone_file: Option<String>,
+    
+    /// Origin of the zone file (default: the target domain)
+    #[arg(long, requires = "zone_file")]
+    pub origin: Option<String>,
+    
     /// Wordlist for brute force enumeration (default: data/subdomains-top1mil-5000.txt)
     #[arg(short = 'D', long)]
     pub dict: Option<String>,
     
+    /// Label pattern to brute force, e.g. 'web[01-50]' or 'db-[a-c][1-3]'; repeat for several.
+    /// Used on its own when no --dict is given, otherwise in addition to the wordlist
+    #[arg(long)]
+    pub pattern: Option<Vec<String>>,
+    
+    /// Try common labels (www, mail, dev, api, ...) before the rest of the wordlist
+    #[arg(long)]
+    pub smart_order: bool,
+    
+    /// Keep brute-forced names whose addresses match a detected wildcard
+    #[arg(long)]
+    pub no_wildcard_filter: bool,
+    
+    /// Also brute force below names found by brute force, this many levels deep
+    #[arg(long, value_name = "N", default_value = "0")]
+    pub recurse_depth: usize,
+    
+    /// Most permutations of crt.sh names tried in permute mode
+    #[arg(long, value_name = "N", default_value_t = crate::enumerate::mutate::DEFAULT_MAX_PERMUTATIONS)]
+    pub max_permutations: usize,
+    
     /// Be verbose
     #[arg(short, long, action = clap::ArgAction::Count)]
     pub verbose: u8,
     
+    /// Disable colors in the end-of-run summary (also honors NO_COLOR)
+    #[arg(long)]
+    pub no_color: bool,
+    
     /// Number of concurrent threads
     #[arg(short = 'c', long, default_value = "10")]
     pub concurrency: usize,
     
+    /// Stop enumeration once this many records have been collected
+    #[arg(long)]
+    pub max_records: Option<usize>,
+    
+    /// Query A/AAAA records this many times and report the union, to catch
+    /// round-robin and GeoDNS address rotation
+    #[arg(long, value_name = "N")]
+    pub probe_rr: Option<usize>,
+    
+    /// Resolve A, AAAA, MX, NS, SOA, TXT, SPF, CAA and CNAME records for subdomains
+    /// found by crt.sh and the search engines, instead of only their addresses
+    #[arg(long)]
+    pub all_records: bool,
+    
+    /// Number of result pages to request from each search engine scraper
+    /// (default: 15 for Bing, 10 for Yandex)
+    #[arg(long, value_name = "N")]
+    pub scraper_pages: Option<usize>,
+    
+    /// Comma-separated sources for standard enumeration, run in the given order
+    /// Available sources: dns, crtsh, bing, yandex (default: all)
+    #[arg(long, value_delimiter = ',', value_parser = parse_source)]
+    pub sources: Option<Vec<Source>>,
+    
+    /// Comma-separated glob patterns of discovered names to leave out of the results
+    /// (e.g. '*.cdn.example.com,monitoring.*')
+    #[arg(long, visible_alias = "exclude-subdomains", value_delimiter = ',')]
+    pub exclude: Option<Vec<String>>,
+    
+    /// Comma-separated record types to query at the domain apex during standard enumeration
+    /// (default: A,AAAA,MX,NS,SOA,TXT,SPF,CAA,CNAME,HTTPS,SVCB)
+    #[arg(long, value_delimiter = ',', value_parser = parse_record_type)]
+    pub apex_records: Option<Vec<RecordType>>,
+    
+    /// Comma-separated record types to keep in the results (default: all)
+    #[arg(long, value_delimiter = ',', value_parser = parse_record_type)]
+    pub record_types: Option<Vec<RecordType>>,
+    
+    /// Check MX/NS records for misconfigurations and lame delegation
+    #[arg(long)]
+    pub check_consistency: bool,
+    
+    /// Query the zone's nameservers for version.bind, hostname.bind and id.server (CHAOS class)
+    #[arg(long)]
+    pub fingerprint_ns: bool,
+    
+    /// Resolve the zone's nameservers and tag their addresses as NS infrastructure (WHOIS with --whois)
+    #[arg(long)]
+    pub follow_ns: bool,
+    
+    /// Query SRV records of common services (SIP, LDAP, Kerberos, XMPP, ...) below the domain
+    #[arg(long)]
+    pub srv: bool,
+    
+    /// Check that address records have a matching PTR and PTR targets resolve back
+    #[arg(long)]
+    pub audit_rdns: bool,
+    
+    /// Recover names behind NSEC3 hashes in the results by hashing the --dict wordlist
+    #[arg(long)]
+    pub nsec3_crack: bool,
+    
+    /// Try AXFR against every nameserver of the domain and report which allow it, in any enumeration mode
+    #[arg(long)]
+    pub check_axfr: bool,
+    
+    /// Comma-separated client subnets (EDNS Client Subnet) to resolve the domain for,
+    /// reporting the addresses GeoDNS returns to each (e.g. '203.0.113.0/24,2001:db8::/56')
+    #[arg(long, value_delimiter = ',')]
+    pub ecs: Option<Vec<ipnetwork::IpNetwork>>,
+    
+    /// Add subdomain labels found on the target's homepage to the brute force wordlist
+    #[arg(long)]
+    pub seed_from_web: bool,
+    
     /// Nameservers to use for DNS queries
     #[arg(short = 'n', long)]
     pub nameservers: Option<String>,
     
-    /// TCP port to use for DNS queries
+    /// File of nameservers to use for DNS queries, one IP (optionally ip:port) per line;
+    /// merged with --nameservers
+    #[arg(long)]
+    pub nameservers_file: Option<String>,
+    
+    /// Order in which queries try the nameservers (default: fastest first, as measured)
+    /// Available strategies: round-robin, random, sequential
+    #[arg(long, value_parser = parse_ns_strategy)]
+    pub ns_strategy: Option<NsStrategy>,
+    
+    /// Query the nameservers over DNS-over-TLS (port 853 unless given), validating
+    /// their certificates against this name, e.g. cloudflare-dns.com for 1.1.1.1
+    #[arg(long, value_name = "NAME")]
+    pub dot_host: Option<String>,
+    
+    /// TCP port to use for DNS queries to nameservers given without a port
     #[arg(long, default_value = "53")]
     pub tcp_port: u16,
     
-    /// UDP port to use for DNS queries
+    /// UDP port to use for DNS queries to nameservers given without a port
     #[arg(long, default_value = "53")]
     pub udp_port: u16,
     
-    /// Perform a reverse lookup of a given CIDR or IP range
+    /// Local address to send DNS queries from
+    #[arg(long)]
+    pub bind: Option<std::net::IpAddr>,
+    
+    /// Source port to send DNS queries from (default: ephemeral)
+    #[arg(long, default_value = "0", requires = "bind")]
+    pub bind_port: u16,
+    
+    /// Skip IPv6 (AAAA) lookups, e.g. on IPv4-only networks
+    #[arg(long)]
+    pub no_ipv6: bool,
+    
+    /// Skip IPv4 (A) lookups, e.g. on IPv6-only networks
+    #[arg(long)]
+    pub no_ipv4: bool,
+    
+    /// Race A against AAAA and keep the first answer, when only whether a name resolves matters
+    #[arg(long)]
+    pub first_response: bool,
+    
+    /// Set the DO (DNSSEC OK) bit so servers return RRSIG and other DNSSEC records
+    #[arg(long)]
+    pub do_bit: bool,
+    
+    /// Set the CD (checking disabled) bit so validating resolvers return data
+    /// that fails DNSSEC validation instead of SERVFAIL
+    #[arg(long)]
+    pub cd_bit: bool,
+    
+    /// Query each nameserver individually and report disagreeing answers
+    #[arg(long)]
+    pub compare_resolvers: bool,
+    
+    /// Perform a reverse lookup of a given CIDR or IP range (comma-separate several)
     #[arg(short = 'r', long)]
     pub range: Option<String>,
     
@@ -97,9 +318,50 @@ pub struct Args {
     #[arg(short = 'R', long)]
     pub range_file: Option<String>,
     
+    /// Most addresses a single reverse lookup range may cover; networks
+    /// larger than a /16 are swept lazily instead of expanded up front
+    #[arg(long, value_name = "N", default_value_t = crate::utils::cidr::DEFAULT_MAX_RANGE_SIZE)]
+    pub max_range_size: usize,
+    
+    /// Fall back to certspotter when crt.sh is unavailable
+    #[arg(long)]
+    pub certspotter: bool,
+    
+    /// Which certificates to search crt.sh for
+    /// Available scopes: subdomains, apex, deep (%.%.domain), org:<name>
+    #[arg(long, value_parser = parse_crtsh_scope, default_value = "subdomains")]
+    pub crtsh_scope: CrtShScope,
+    
+    /// Attach WHOIS data to each resolved address in reverse mode and with --follow-ns (IP targets always get WHOIS)
+    #[arg(long)]
+    pub whois: bool,
+    
+    /// In reverse mode, also resolve the CNAME, A and AAAA records of every PTR target
+    #[arg(long)]
+    pub reverse_enrich: bool,
+    
+    /// In reverse mode, keep only PTR records whose target resolves back to the same address
+    #[arg(long)]
+    pub fcrdns: bool,
+    
+    /// Annotate A/AAAA records with ASN, organization and country from a MaxMind database (.mmdb)
+    #[cfg(feature = "geoip")]
+    #[arg(long, value_name = "MMDB")]
+    pub geoip: Option<String>,
+    
+    /// Maximum number of WHOIS queries in flight during reverse sweeps
+    #[arg(long, default_value_t = crate::enumerate::whois::DEFAULT_WHOIS_CONCURRENCY)]
+    pub whois_concurrency: usize,
+    
     /// HTTP proxy to use for requests (format: http://proxy:port or socks5://proxy:port)
+    /// Separate several proxies with commas to rotate scraper requests across them
     #[arg(long)]
     pub proxy: Option<String>,
+    
+    /// Resolve apex records through a JSON DoH endpoint over the HTTP client
+    /// (e.g. https://dns.google/resolve or https://cloudflare-dns.com/dns-query)
+    #[arg(long, value_name = "ENDPOINT")]
+    pub doh_json: Option<String>,
 }
 
 /// Types of enumeration that can be performed
@@ -113,6 +375,8 @@ pub enum EnumType {
     ZoneWalk,
     /// Reverse DNS lookup
     Reverse,
+    /// Brute force permutations of names found on crt.sh
+    Permute,
 }
 
 /// Parse enumeration type from string
@@ -122,56 +386,70 @@ fn parse_enum_type(s: &str) -> Result<EnumType, String> {
         "brt" | "bruteforce" => Ok(EnumType::BruteForce),
         "zonewalk" => Ok(EnumType::ZoneWalk),
         "reverse" => Ok(EnumType::Reverse),
+        "permute" | "alt" => Ok(EnumType::Permute),
         _ => Err(format!("Invalid enumeration type: {}", s)),
     }
 }
 
+/// Get the target IP address when the domain argument is an IP literal
+pub fn target_ip(args: &Args) -> Option<std::net::IpAddr> {
+    ReconConfig::from(args).target_ip()
+}
+
+/// Parse a standard enumeration source from string
+fn parse_source(s: &str) -> Result<Source, String> {
+    s.parse()
+}
+
+/// Parse a stdout output format from string
+fn parse_stdout_format(s: &str) -> Result<StdoutFormat, String> {
+    s.parse()
+}
+
+/// Parse a crt.sh search scope from string
+fn parse_crtsh_scope(s: &str) -> Result<CrtShScope, String> {
+    s.parse()
+}
+
+/// Parse a nameserver selection strategy from string
+fn parse_ns_strategy(s: &str) -> Result<NsStrategy, String> {
+    s.parse()
+}
+
+/// Parse a DNS record type from string
+fn parse_record_type(s: &str) -> Result<RecordType, String> {
+    s.parse()
+}
+
+/// Record types queried at the domain apex when `--apex-records` is not given
+pub const DEFAULT_APEX_RECORDS: [RecordType; 11] = [
+    RecordType::A,
+    RecordType::Aaaa,
+    RecordType::Mx,
+    RecordType::Ns,
+    RecordType::Soa,
+    RecordType::Txt,
+    RecordType::Spf,
+    RecordType::Caa,
+    RecordType::Cname,
+    RecordType::Https,
+    RecordType::Svcb,
+];
+
+/// Record types that are also queried at `www.<domain>` when selected for the apex
+pub const WWW_RECORDS: [RecordType; 2] = [RecordType::Https, RecordType::Svcb];
+
+/// Get the record types to query at the domain apex, in order
+pub fn apex_record_types(args: &Args) -> Vec<RecordType> {
+    ReconConfig::from(args).apex_record_types()
+}
+
+/// Get the standard enumeration sources to run, in order
+pub fn enabled_sources(args: &Args) -> Vec<Source> {
+    ReconConfig::from(args).enabled_sources()
+}
+
 /// Validate command line arguments
 pub fn validate_args(args: &Args) -> Result<(), CliError> {
-    // Validate domain is provided for most enumeration types
-    match args.r#type {
-        EnumType::Standard | EnumType::BruteForce | EnumType::ZoneWalk => {
-            if args.domain.is_none() && args.range.is_none() && args.range_file.is_none() {
-                return Err(CliError::InvalidArgument(
-                    "Domain, range, or range file must be specified for this enumeration type".to_string()
-                ));
-            }
-        },
-        EnumType::Reverse => {
-            if args.range.is_none() && args.range_file.is_none() {
-                return Err(CliError::InvalidArgument(
-                    "Range or range file must be specified for reverse enumeration".to_string()
-                ));
-            }
-        }
-    }
-    
-    // Validate wordlist is provided for brute force
-    if let EnumType::BruteForce = args.r#type {
-        if args.dict.is_none() {
-            // Use default wordlist if none provided
-            // Default to subdomains-top1mil-5000.txt for a balance of speed and coverage
-            eprintln!("No wordlist specified, using default: data/subdomains-top1mil-5000.txt");
-        }
-    }
-    
-    // Validate port numbers
-    if args.tcp_port == 0 || args.udp_port == 0 {
-        return Err(CliError::InvalidArgument(
-            "Port numbers must be between 1 and 65535".to_string()
-        ));
-    }
-    
-    // Validate nameservers if provided
-    if let Some(ref nameservers) = args.nameservers {
-        for ns in nameservers.split(',') {
-            if ns.trim().parse::<std::net::IpAddr>().is_err() {
-                return Err(CliError::InvalidArgument(
-                    format!("Invalid nameserver IP address: {}", ns)
-                ));
-            }
-        }
-    }
-    
-    Ok(())
+    ReconConfig::from(args).validate()
 }
\ No newline at end of file
@@ -1,6 +1,8 @@
 //! Progress reporting functionality
 
-use std::time::Instant;
+use std::io::{self, Write};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 /// Progress reporter trait
 pub trait ProgressReporter {
@@ -12,6 +14,13 @@ pub trait ProgressReporter {
     
     /// Report an error
     fn error(&self, message: &str);
+    
+    /// Show a block of text such as the end-of-run summary
+    ///
+    /// Printed to stderr by default, so results written to stdout stay parseable.
+    fn summary(&self, text: &str) {
+        eprint!("{}", text);
+    }
 }
 
 /// Simple progress reporter that prints to stdout
@@ -51,21 +60,116 @@ impl ProgressReporter for SimpleProgressReporter {
     }
 }
 
+/// Time spent in each phase of a scan, such as a scraper or brute force
+///
+/// Phases are kept in the order they first ran; a phase that runs several
+/// times accumulates its durations.
+#[derive(Debug, Default)]
+pub struct PhaseTimer {
+    phases: Mutex<Vec<(String, Duration)>>,
+}
+
+impl PhaseTimer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Start timing a phase; its time is added when the guard is dropped
+    pub fn start(&self, phase: &str) -> PhaseGuard<'_> {
+        PhaseGuard {
+            timer: self,
+            phase: phase.to_string(),
+            started: Instant::now(),
+        }
+    }
+    
+    /// Add time spent in a phase
+    pub fn record(&self, phase: &str, elapsed: Duration) {
+        let mut phases = self.phases.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match phases.iter_mut().find(|(name, _)| name == phase) {
+            Some((_, total)) => *total += elapsed,
+            None => phases.push((phase.to_string(), elapsed)),
+        }
+    }
+    
+    /// Time spent in each phase so far
+    pub fn timings(&self) -> Vec<(String, Duration)> {
+        self.phases.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+}
+
+/// Times a phase until dropped
+pub struct PhaseGuard<'a> {
+    timer: &'a PhaseTimer,
+    phase: String,
+    started: Instant,
+}
+
+impl Drop for PhaseGuard<'_> {
+    fn drop(&mut self) {
+        self.timer.record(&self.phase, self.started.elapsed());
+    }
+}
+
+/// Destination of progress lines
+enum Output {
+    /// Updates go to stdout and errors to stderr
+    Console,
+    /// Every line goes to a single writer
+    Writer(Box<dyn Write + Send>),
+}
+
 /// Progress reporter with timing information
+///
+/// Lines are written whole under a lock, so messages reported from many
+/// concurrent tasks never interleave.
 pub struct TimedProgressReporter {
     start_time: Instant,
+    output: Mutex<Output>,
 }
 
 impl TimedProgressReporter {
     pub fn new() -> Self {
         Self {
             start_time: Instant::now(),
+            output: Mutex::new(Output::Console),
+        }
+    }
+    
+    /// Create a reporter that writes every line, errors included, to `writer`
+    pub fn with_writer<W: Write + Send + 'static>(writer: W) -> Self {
+        Self {
+            start_time: Instant::now(),
+            output: Mutex::new(Output::Writer(Box::new(writer))),
         }
     }
     
     pub fn elapsed(&self) -> std::time::Duration {
         self.start_time.elapsed()
     }
+    
+    /// Write one complete line while holding the output lock
+    fn write_line(&self, line: &str, is_error: bool) {
+        self.write_text(&format!("{}\n", line), is_error);
+    }
+    
+    /// Write text in one piece while holding the output lock
+    ///
+    /// On the console, errors go to stderr and everything else to stdout.
+    fn write_text(&self, text: &str, to_stderr: bool) {
+        // A panic while printing must not silence every later message
+        let mut output = self.output.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        
+        // Progress output is best effort, so write failures are ignored
+        let _ = match *output {
+            Output::Console if to_stderr => write!(io::stderr().lock(), "{}", text),
+            Output::Console => {
+                let mut stdout = io::stdout().lock();
+                write!(stdout, "{}", text).and_then(|_| stdout.flush())
+            }
+            Output::Writer(ref mut writer) => write!(writer, "{}", text).and_then(|_| writer.flush()),
+        };
+    }
 }
 
 impl Default for TimedProgressReporter {
@@ -77,16 +181,20 @@ impl Default for TimedProgressReporter {
 impl ProgressReporter for TimedProgressReporter {
     fn update(&self, message: &str) {
         let elapsed = self.elapsed().as_secs_f32();
-        println!("[*] [{:.2}s] {}", elapsed, message);
+        self.write_line(&format!("[*] [{:.2}s] {}", elapsed, message), false);
     }
     
     fn finish(&self, message: &str) {
         let elapsed = self.elapsed().as_secs_f32();
-        println!("[+] [{:.2}s] {}", elapsed, message);
+        self.write_line(&format!("[+] [{:.2}s] {}", elapsed, message), false);
     }
     
     fn error(&self, message: &str) {
         let elapsed = self.elapsed().as_secs_f32();
-        eprintln!("[!] [{:.2}s] {}", elapsed, message);
+        self.write_line(&format!("[!] [{:.2}s] {}", elapsed, message), true);
+    }
+    
+    fn summary(&self, text: &str) {
+        self.write_text(text, true);
     }
 }
\ No newline at end of file
@@ -0,0 +1,149 @@
+//! Human-friendly end-of-run summary
+//!
+//! The summary is handed to the progress reporter, which writes it to stderr
+//! so JSON written to stdout stays parseable.
+
+use crate::cli::progress::ProgressReporter;
+use crate::dns::record::DnsRecord;
+use crate::enumerate::findings::{Finding, Severity};
+use owo_colors::OwoColorize;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::io::IsTerminal;
+use std::time::Duration;
+
+/// Information about a completed scan
+#[derive(Debug, Clone, Default)]
+pub struct ScanSummary {
+    /// Names of the enumeration sources that ran
+    pub sources: Vec<String>,
+    /// Total time taken by the scan
+    pub elapsed: Duration,
+    /// Findings raised during the scan
+    pub findings: Vec<Finding>,
+    /// Time spent in each phase of the scan, in the order they ran
+    pub timings: Vec<(String, Duration)>,
+}
+
+/// Decide whether the summary should use colors
+///
+/// Colors are disabled by `--no-color`, a non-empty `NO_COLOR` environment
+/// variable, or when stderr is not a terminal.
+pub fn use_color(no_color: bool) -> bool {
+    if no_color {
+        return false;
+    }
+    if std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+        return false;
+    }
+    std::io::stderr().is_terminal()
+}
+
+/// Print the scan summary through `progress`
+///
+/// Reporters show it on stderr unless they redirect it, as
+/// [`ProgressReporter::summary`] describes.
+pub fn print_summary(summary: &ScanSummary, records: &[DnsRecord], color: bool, progress: &dyn ProgressReporter) {
+    progress.summary(&render_summary(summary, records, color));
+}
+
+/// Render the scan summary as text
+pub fn render_summary(summary: &ScanSummary, records: &[DnsRecord], color: bool) -> String {
+    let mut type_counts: BTreeMap<String, usize> = BTreeMap::new();
+    for record in records {
+        let record_type = record.record_type.mnemonic();
+        *type_counts.entry(record_type).or_insert(0) += 1;
+    }
+
+    let unique_hosts: HashSet<String> = records.iter().map(|record| record.name.to_lowercase()).collect();
+
+    let mut out = String::new();
+    out.push_str(&format!("\n{}\n", paint_heading("Summary", color)));
+    out.push_str(&format!("  Records:      {}\n", records.len()));
+    for (record_type, count) in &type_counts {
+        out.push_str(&format!("    {:<10} {}\n", record_type, count));
+    }
+    out.push_str(&format!("  Unique hosts: {}\n", unique_hosts.len()));
+
+    if !summary.sources.is_empty() {
+        out.push_str(&format!("  Sources:      {}\n", summary.sources.join(", ")));
+    }
+    out.push_str(&format!("  Elapsed:      {:.2}s\n", summary.elapsed.as_secs_f32()));
+    if !summary.timings.is_empty() {
+        let timings: Vec<String> = summary
+            .timings
+            .iter()
+            .map(|(phase, elapsed)| format!("{} {:.2}s", phase, elapsed.as_secs_f32()))
+            .collect();
+        out.push_str(&format!("  Timings:      {}\n", timings.join(", ")));
+    }
+
+    if !summary.findings.is_empty() {
+        out.push_str(&format!("  Findings:     {}\n", summary.findings.len()));
+        for finding in &summary.findings {
+            out.push_str(&format!(
+                "    {} {} {}: {}\n",
+                paint_severity(finding.severity, color),
+                finding.category,
+                finding.subject,
+                finding.message
+            ));
+        }
+    }
+
+    out
+}
+
+/// Render the scan summary as JSON
+///
+/// Durations are in seconds, e.g. `"timings": { "crtsh": 4.2, "brute": 12.5 }`.
+pub fn summary_json(summary: &ScanSummary, records: &[DnsRecord]) -> Value {
+    let mut type_counts: BTreeMap<String, usize> = BTreeMap::new();
+    for record in records {
+        *type_counts.entry(record.record_type.mnemonic()).or_insert(0) += 1;
+    }
+    let unique_hosts: HashSet<String> = records.iter().map(|record| record.name.to_lowercase()).collect();
+
+    let mut timings = Map::new();
+    for (phase, elapsed) in &summary.timings {
+        timings.insert(phase.clone(), json!(seconds(*elapsed)));
+    }
+
+    json!({
+        "records": records.len(),
+        "record_types": type_counts,
+        "unique_hosts": unique_hosts.len(),
+        "sources": summary.sources,
+        "elapsed": seconds(summary.elapsed),
+        "timings": timings,
+        "findings": summary.findings,
+    })
+}
+
+/// A duration in seconds, rounded to hundredths
+fn seconds(elapsed: Duration) -> f64 {
+    (elapsed.as_secs_f64() * 100.0).round() / 100.0
+}
+
+/// Render a section heading
+fn paint_heading(text: &str, color: bool) -> String {
+    if color {
+        text.bold().to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Render a severity label, colored by severity
+fn paint_severity(severity: Severity, color: bool) -> String {
+    let label = format!("[{}]", severity);
+    if !color {
+        return label;
+    }
+    match severity {
+        Severity::High => label.red().bold().to_string(),
+        Severity::Medium => label.yellow().to_string(),
+        Severity::Low => label.cyan().to_string(),
+        Severity::Info => label.dimmed().to_string(),
+    }
+}
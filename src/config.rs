@@ -0,0 +1,803 @@
+//! Scan configuration
+//!
+//! `ReconConfig` holds everything a scan needs, independent of the command
+//! line. The CLI converts its `Args` into one; library users build one with
+//! `ReconConfig::builder()`. `EnumConfig` is the narrower set of settings the
+//! scrapers and `enumerate_domain` work from.
+
+use crate::cli::{Args, CliError, EnumType, DEFAULT_APEX_RECORDS};
+use crate::dns::record::RecordType;
+use crate::dns::resolver::NsStrategy;
+use crate::enumerate::crt_sh::CrtShScope;
+use crate::enumerate::sources::Source;
+use crate::output::StdoutFormat;
+use crate::utils::http::{DEFAULT_HTTP_TIMEOUT, DEFAULT_USER_AGENT};
+use crate::utils::validation::NameserverEntry;
+use ipnetwork::IpNetwork;
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::time::Duration;
+
+/// Configuration of a scan
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconConfig {
+    /// Domain (or IP address) to enumerate
+    pub domain: Option<String>,
+    /// Further domains to enumerate in the same run
+    pub domains: Vec<String>,
+    /// File of domains to enumerate, one per line
+    pub domain_file: Option<String>,
+    /// Type of enumeration to perform
+    pub enum_type: EnumType,
+    /// How results are printed when no output file is given
+    pub stdout_format: StdoutFormat,
+    /// JSON output file
+    pub json_file: Option<String>,
+    /// Merge results into an existing JSON file
+    pub json_append: bool,
+    /// Previous JSON output to compare the results against
+    pub diff_against: Option<String>,
+    /// File to write the comparison to
+    pub diff_file: Option<String>,
+    /// Old and new JSON result files to compare instead of scanning
+    pub diff: Option<(String, String)>,
+    /// Write JSON output without indentation
+    pub json_compact: bool,
+    /// XML output file
+    pub xml_file: Option<String>,
+    /// CSV output file
+    pub csv_file: Option<String>,
+    /// SQLite output database
+    pub sqlite_file: Option<String>,
+    /// Write records to the SQLite database as they are found
+    pub sqlite_incremental: bool,
+    /// Security findings output file
+    pub findings_file: Option<String>,
+    /// Scan summary output file
+    pub summary_json: Option<String>,
+    /// Elasticsearch _bulk output file
+    pub es_bulk: Option<String>,
+    /// Elasticsearch index name used in _bulk output
+    pub es_index: String,
+    /// Elasticsearch URL to POST the _bulk output to
+    pub es_url: Option<String>,
+    /// Zone file output file
+    pub zone_file: Option<String>,
+    /// Origin of the zone file, defaulting to the target domain
+    pub origin: Option<String>,
+    /// Wordlist for brute force enumeration
+    pub dict: Option<String>,
+    /// Label patterns to brute force, such as `web[01-50]`
+    pub pattern: Option<Vec<String>>,
+    /// Try common labels before the rest of the wordlist
+    pub smart_order: bool,
+    /// Keep brute-forced names whose addresses match a detected wildcard
+    pub no_wildcard_filter: bool,
+    /// Levels below names found by brute force to brute force as well
+    pub recurse_depth: usize,
+    /// Most permutations of crt.sh names tried in permute mode
+    pub max_permutations: usize,
+    /// Disable colors in the end-of-run summary
+    pub no_color: bool,
+    /// Number of concurrent lookups
+    pub concurrency: usize,
+    /// Stop enumeration once this many records have been collected
+    pub max_records: Option<usize>,
+    /// Query A/AAAA records this many times and report the union
+    pub probe_rr: Option<usize>,
+    /// Resolve every common record type for scraped subdomains, not only addresses
+    pub all_records: bool,
+    /// Result pages to request from each search engine (default: per engine)
+    pub scraper_pages: Option<usize>,
+    /// Standard enumeration sources, in order (default: all)
+    pub sources: Option<Vec<Source>>,
+    /// Glob patterns of discovered names to leave out of the results
+    pub exclude: Option<Vec<String>>,
+    /// Record types to query at the domain apex (default: `DEFAULT_APEX_RECORDS`)
+    pub apex_records: Option<Vec<RecordType>>,
+    /// Record types to keep in the results (default: all)
+    pub record_types: Option<Vec<RecordType>>,
+    /// Check MX/NS records for misconfigurations and lame delegation
+    pub check_consistency: bool,
+    /// Fingerprint the zone's nameservers over CHAOS class
+    pub fingerprint_ns: bool,
+    /// Resolve the zone's nameservers and tag their addresses
+    pub follow_ns: bool,
+    /// Query SRV records of common services
+    pub srv: bool,
+    /// Audit forward/reverse DNS consistency
+    pub audit_rdns: bool,
+    /// Recover names behind NSEC3 hashes with the wordlist
+    pub nsec3_crack: bool,
+    /// Report which nameservers allow AXFR
+    pub check_axfr: bool,
+    /// Client subnets to resolve the domain for
+    pub ecs: Option<Vec<IpNetwork>>,
+    /// Add subdomain labels found on the homepage to the wordlist
+    pub seed_from_web: bool,
+    /// Comma-separated nameservers to use for DNS queries
+    pub nameservers: Option<String>,
+    /// File of nameservers, one per line
+    pub nameservers_file: Option<String>,
+    /// Order in which queries try the nameservers (default: the resolver's own)
+    pub ns_strategy: Option<NsStrategy>,
+    /// TLS name to query the nameservers with over DNS-over-TLS
+    pub dot_host: Option<String>,
+    /// TCP port to use for DNS queries
+    pub tcp_port: u16,
+    /// UDP port to use for DNS queries
+    pub udp_port: u16,
+    /// Local address to send DNS queries from
+    pub bind: Option<IpAddr>,
+    /// Source port to send DNS queries from (0 for ephemeral)
+    pub bind_port: u16,
+    /// Skip IPv6 (AAAA) lookups
+    pub no_ipv6: bool,
+    /// Skip IPv4 (A) lookups
+    pub no_ipv4: bool,
+    /// Race A against AAAA and keep the first answer
+    pub first_response: bool,
+    /// Set the DO (DNSSEC OK) bit
+    pub do_bit: bool,
+    /// Set the CD (checking disabled) bit
+    pub cd_bit: bool,
+    /// Query each nameserver individually and report disagreeing answers
+    pub compare_resolvers: bool,
+    /// CIDR or IP range for reverse lookups
+    pub range: Option<String>,
+    /// File of CIDRs or IP ranges for reverse lookups
+    pub range_file: Option<String>,
+    /// Most addresses a single range may cover
+    pub max_range_size: usize,
+    /// Fall back to certspotter when crt.sh is unavailable
+    pub certspotter: bool,
+    /// Which certificates to search crt.sh for
+    pub crtsh_scope: CrtShScope,
+    /// Attach WHOIS data to resolved addresses
+    pub whois: bool,
+    /// Maximum number of WHOIS queries in flight during reverse sweeps
+    pub whois_concurrency: usize,
+    /// Forward-resolve PTR targets found in reverse mode
+    pub reverse_enrich: bool,
+    /// Drop PTR records that aren't forward-confirmed in reverse mode
+    pub fcrdns: bool,
+    /// MaxMind database to annotate address records from
+    #[cfg(feature = "geoip")]
+    pub geoip: Option<String>,
+    /// HTTP proxies (comma-separated) for scraper requests
+    pub proxy: Option<String>,
+    /// User-Agent sent with scraper requests
+    pub user_agent: String,
+    /// Timeout of a single scraper request
+    pub http_timeout: Duration,
+    /// JSON DoH endpoint to resolve apex records through
+    pub doh_json: Option<String>,
+}
+
+impl Default for ReconConfig {
+    /// The same defaults as the command line
+    fn default() -> Self {
+        Self {
+            domain: None,
+            domains: Vec::new(),
+            domain_file: None,
+            enum_type: EnumType::Standard,
+            stdout_format: StdoutFormat::default(),
+            json_file: None,
+            json_append: false,
+            diff_against: None,
+            diff_file: None,
+            diff: None,
+            json_compact: false,
+            xml_file: None,
+            csv_file: None,
+            sqlite_file: None,
+            sqlite_incremental: false,
+            findings_file: None,
+            summary_json: None,
+            es_bulk: None,
+            es_index: crate::output::elastic::DEFAULT_INDEX.to_string(),
+            es_url: None,
+            zone_file: None,
+            origin: None,
+            dict: None,
+            pattern: None,
+            smart_order: false,
+            no_wildcard_filter: false,
+            recurse_depth: 0,
+            max_permutations: crate::enumerate::mutate::DEFAULT_MAX_PERMUTATIONS,
+            no_color: false,
+            concurrency: 10,
+            max_records: None,
+            probe_rr: None,
+            all_records: false,
+            scraper_pages: None,
+            sources: None,
+            exclude: None,
+            apex_records: None,
+            record_types: None,
+            check_consistency: false,
+            fingerprint_ns: false,
+            follow_ns: false,
+            srv: false,
+            audit_rdns: false,
+            nsec3_crack: false,
+            check_axfr: false,
+            ecs: None,
+            seed_from_web: false,
+            nameservers: None,
+            nameservers_file: None,
+            ns_strategy: None,
+            dot_host: None,
+            tcp_port: 53,
+            udp_port: 53,
+            bind: None,
+            bind_port: 0,
+            no_ipv6: false,
+            no_ipv4: false,
+            first_response: false,
+            do_bit: false,
+            cd_bit: false,
+            compare_resolvers: false,
+            range: None,
+            range_file: None,
+            max_range_size: crate::utils::cidr::DEFAULT_MAX_RANGE_SIZE,
+            certspotter: false,
+            crtsh_scope: CrtShScope::default(),
+            whois: false,
+            whois_concurrency: crate::enumerate::whois::DEFAULT_WHOIS_CONCURRENCY,
+            reverse_enrich: false,
+            fcrdns: false,
+            #[cfg(feature = "geoip")]
+            geoip: None,
+            proxy: None,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            http_timeout: DEFAULT_HTTP_TIMEOUT,
+            doh_json: None,
+        }
+    }
+}
+
+impl ReconConfig {
+    /// Start building a configuration from the defaults
+    pub fn builder() -> ReconConfigBuilder {
+        ReconConfigBuilder::default()
+    }
+
+    /// Get the target IP address when the domain is an IP literal
+    pub fn target_ip(&self) -> Option<IpAddr> {
+        self.domain.as_ref()?.trim().parse().ok()
+    }
+
+    /// Get the record types to query at the domain apex, in order
+    pub fn apex_record_types(&self) -> Vec<RecordType> {
+        let mut selected = match self.apex_records {
+            Some(ref record_types) => {
+                let mut unique: Vec<RecordType> = Vec::new();
+                for record_type in record_types {
+                    if !unique.contains(record_type) {
+                        unique.push(record_type.clone());
+                    }
+                }
+                unique
+            }
+            None => DEFAULT_APEX_RECORDS.to_vec(),
+        };
+
+        // Disabled address families are never queried
+        selected.retain(|record_type| match record_type {
+            RecordType::A => !self.no_ipv4,
+            RecordType::Aaaa => !self.no_ipv6,
+            _ => true,
+        });
+        selected
+    }
+
+    /// Get the standard enumeration sources to run, in order
+    pub fn enabled_sources(&self) -> Vec<Source> {
+        match self.sources {
+            Some(ref sources) => {
+                let mut enabled = Vec::new();
+                for source in sources {
+                    if !enabled.contains(source) {
+                        enabled.push(*source);
+                    }
+                }
+                enabled
+            }
+            None => Source::ALL.to_vec(),
+        }
+    }
+
+    /// Check the configuration describes a scan that can run
+    pub fn validate(&self) -> Result<(), CliError> {
+        let targets = self.target_domains()?;
+
+        // Validate domain is provided for most enumeration types
+        match self.enum_type {
+            EnumType::Standard | EnumType::BruteForce | EnumType::ZoneWalk | EnumType::Permute => {
+                if targets.is_empty() && self.range.is_none() && self.range_file.is_none() {
+                    return Err(CliError::InvalidArgument(
+                        "Domain, range, or range file must be specified for this enumeration type".to_string()
+                    ));
+                }
+            },
+            EnumType::Reverse => {
+                if self.range.is_none() && self.range_file.is_none() {
+                    return Err(CliError::InvalidArgument(
+                        "Range or range file must be specified for reverse enumeration".to_string()
+                    ));
+                }
+            }
+        }
+
+        for target in &targets {
+            match target.parse::<IpAddr>() {
+                // An IP address target is profiled instead of enumerated
+                Ok(ip) => {
+                    if matches!(self.enum_type, EnumType::BruteForce | EnumType::ZoneWalk | EnumType::Permute) {
+                        return Err(CliError::InvalidArgument(
+                            format!("{} is an IP address; this enumeration type requires a domain name", ip)
+                        ));
+                    }
+                }
+                // Validate the domain can be converted to its ASCII (punycode) form
+                Err(_) => {
+                    crate::utils::validation::normalize_domain(target)
+                        .map_err(CliError::InvalidArgument)?;
+                }
+            }
+        }
+
+        // Validate label patterns before any queries
+        if let Some(ref patterns) = self.pattern {
+            crate::enumerate::pattern::expand_patterns(patterns)
+                .map_err(|e| CliError::InvalidArgument(e.to_string()))?;
+        }
+
+        // Validate the record limit
+        if self.max_records == Some(0) {
+            return Err(CliError::InvalidArgument(
+                "--max-records must be at least 1".to_string()
+            ));
+        }
+
+        // Validate the scraper page count
+        if self.scraper_pages == Some(0) {
+            return Err(CliError::InvalidArgument(
+                "--scraper-pages must be at least 1".to_string()
+            ));
+        }
+
+        // Validate the permutation cap
+        if self.max_permutations == 0 {
+            return Err(CliError::InvalidArgument(
+                "--max-permutations must be at least 1".to_string()
+            ));
+        }
+
+        if self.max_range_size == 0 {
+            return Err(CliError::InvalidArgument(
+                "--max-range-size must be at least 1".to_string()
+            ));
+        }
+
+        // Validate the WHOIS concurrency
+        if self.whois_concurrency == 0 {
+            return Err(CliError::InvalidArgument(
+                "--whois-concurrency must be at least 1".to_string()
+            ));
+        }
+
+        // At least one address family must stay enabled
+        if self.no_ipv4 && self.no_ipv6 {
+            return Err(CliError::InvalidArgument(
+                "--no-ipv4 and --no-ipv6 cannot be used together".to_string()
+            ));
+        }
+
+        // Validate the rotation probe count
+        if self.probe_rr == Some(0) {
+            return Err(CliError::InvalidArgument(
+                "--probe-rr must be at least 1".to_string()
+            ));
+        }
+
+        // Validate port numbers
+        if self.tcp_port == 0 || self.udp_port == 0 {
+            return Err(CliError::InvalidArgument(
+                "Port numbers must be between 1 and 65535".to_string()
+            ));
+        }
+
+        // Validate the bind address is usable as a local source address
+        if let Some(bind) = self.bind {
+            let is_broadcast = matches!(bind, IpAddr::V4(ip) if ip.is_broadcast());
+            if bind.is_multicast() || is_broadcast {
+                return Err(CliError::InvalidArgument(
+                    format!("Invalid bind address: {} is not a local unicast address", bind)
+                ));
+            }
+        }
+
+        // Validate every proxy in the rotation
+        if let Some(ref proxy) = self.proxy {
+            crate::utils::http::parse_proxies(proxy)
+                .map_err(|e| CliError::InvalidArgument(e.to_string()))?;
+        }
+
+        // Validate nameservers if provided
+        let nameservers = self.nameserver_addrs()?;
+
+        // DNS-over-TLS needs explicit nameservers, and the DO and CD bits and
+        // client subnets are set on hand-built queries that only go over UDP
+        if let Some(ref dot_host) = self.dot_host {
+            if dot_host.trim().is_empty() {
+                return Err(CliError::InvalidArgument("--dot-host needs a TLS name".to_string()));
+            }
+            if nameservers.is_empty() {
+                return Err(CliError::InvalidArgument(
+                    "--dot-host needs nameservers from --nameservers or --nameservers-file".to_string()
+                ));
+            }
+            if self.do_bit || self.cd_bit {
+                return Err(CliError::InvalidArgument(
+                    "--dot-host cannot be used with --do-bit or --cd-bit".to_string()
+                ));
+            }
+            if self.ecs.is_some() {
+                return Err(CliError::InvalidArgument(
+                    "--dot-host cannot be used with --ecs".to_string()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the nameservers to query, from `nameservers` and `nameservers_file`
+    ///
+    /// File entries follow the inline ones; blank lines and `#` comments are
+    /// skipped and duplicates dropped. Empty when no nameservers are configured.
+    pub fn nameserver_addrs(&self) -> Result<Vec<NameserverEntry>, CliError> {
+        let mut entries: Vec<String> = Vec::new();
+        if let Some(ref nameservers) = self.nameservers {
+            entries.extend(nameservers.split(',').map(String::from));
+        }
+        if let Some(ref nameservers_file) = self.nameservers_file {
+            let contents = std::fs::read_to_string(nameservers_file).map_err(|e| {
+                CliError::InvalidArgument(format!("Cannot read nameservers file {}: {}", nameservers_file, e))
+            })?;
+            entries.extend(
+                contents
+                    .lines()
+                    .map(|line| line.split('#').next().unwrap_or_default().trim())
+                    .filter(|line| !line.is_empty())
+                    .map(String::from),
+            );
+        }
+
+        let mut addrs = Vec::new();
+        for entry in entries {
+            let addr = crate::utils::validation::parse_nameserver_entry(&entry)
+                .map_err(CliError::InvalidArgument)?;
+            if !addrs.iter().any(|known: &NameserverEntry| known.addr == addr.addr) {
+                addrs.push(addr);
+            }
+        }
+        Ok(addrs)
+    }
+
+    /// Every domain to enumerate, in order
+    ///
+    /// The domain comes first, then the further domains and the entries of the
+    /// domain file, whose blank lines and `#` comments are skipped. Duplicates
+    /// are dropped ignoring case and a trailing dot.
+    pub fn target_domains(&self) -> Result<Vec<String>, CliError> {
+        let mut entries: Vec<String> = self.domain.iter().chain(&self.domains).cloned().collect();
+        if let Some(ref domain_file) = self.domain_file {
+            let contents = std::fs::read_to_string(domain_file).map_err(|e| {
+                CliError::InvalidArgument(format!("Cannot read domain file {}: {}", domain_file, e))
+            })?;
+            entries.extend(
+                contents
+                    .lines()
+                    .map(|line| line.split('#').next().unwrap_or_default().trim())
+                    .filter(|line| !line.is_empty())
+                    .map(String::from),
+            );
+        }
+
+        let mut seen = HashSet::new();
+        Ok(entries
+            .into_iter()
+            .map(|domain| domain.trim().to_string())
+            .filter(|domain| seen.insert(domain.trim_end_matches('.').to_lowercase()))
+            .collect())
+    }
+}
+
+impl From<Args> for ReconConfig {
+    fn from(args: Args) -> Self {
+        let mut domains = args.domain.into_iter();
+        Self {
+            domain: domains.next(),
+            domains: domains.collect(),
+            domain_file: args.domain_file,
+            enum_type: args.r#type,
+            stdout_format: args.format,
+            json_file: args.json_file,
+            json_append: args.json_append,
+            diff_against: args.diff_against,
+            diff_file: args.diff_file,
+            diff: args.diff.and_then(|files| <[String; 2]>::try_from(files).ok()).map(|[old, new]| (old, new)),
+            json_compact: args.json_compact,
+            xml_file: args.xml_file,
+            csv_file: args.csv_file,
+            sqlite_file: args.sqlite_file,
+            sqlite_incremental: args.sqlite_incremental,
+            findings_file: args.findings_file,
+            summary_json: args.summary_json,
+            es_bulk: args.es_bulk,
+            es_index: args.es_index,
+            es_url: args.es_url,
+            zone_file: args.zone_file,
+            origin: args.origin,
+            dict: args.dict,
+            pattern: args.pattern,
+            smart_order: args.smart_order,
+            no_wildcard_filter: args.no_wildcard_filter,
+            recurse_depth: args.recurse_depth,
+            max_permutations: args.max_permutations,
+            no_color: args.no_color,
+            concurrency: args.concurrency,
+            max_records: args.max_records,
+            probe_rr: args.probe_rr,
+            all_records: args.all_records,
+            scraper_pages: args.scraper_pages,
+            sources: args.sources,
+            exclude: args.exclude,
+            apex_records: args.apex_records,
+            record_types: args.record_types,
+            check_consistency: args.check_consistency,
+            fingerprint_ns: args.fingerprint_ns,
+            follow_ns: args.follow_ns,
+            srv: args.srv,
+            audit_rdns: args.audit_rdns,
+            nsec3_crack: args.nsec3_crack,
+            check_axfr: args.check_axfr,
+            ecs: args.ecs,
+            seed_from_web: args.seed_from_web,
+            nameservers: args.nameservers,
+            nameservers_file: args.nameservers_file,
+            ns_strategy: args.ns_strategy,
+            dot_host: args.dot_host,
+            tcp_port: args.tcp_port,
+            udp_port: args.udp_port,
+            bind: args.bind,
+            bind_port: args.bind_port,
+            no_ipv6: args.no_ipv6,
+            no_ipv4: args.no_ipv4,
+            first_response: args.first_response,
+            do_bit: args.do_bit,
+            cd_bit: args.cd_bit,
+            compare_resolvers: args.compare_resolvers,
+            range: args.range,
+            range_file: args.range_file,
+            max_range_size: args.max_range_size,
+            certspotter: args.certspotter,
+            crtsh_scope: args.crtsh_scope,
+            whois: args.whois,
+            whois_concurrency: args.whois_concurrency,
+            reverse_enrich: args.reverse_enrich,
+            fcrdns: args.fcrdns,
+            #[cfg(feature = "geoip")]
+            geoip: args.geoip,
+            proxy: args.proxy,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            http_timeout: DEFAULT_HTTP_TIMEOUT,
+            doh_json: args.doh_json,
+        }
+    }
+}
+
+impl From<&Args> for ReconConfig {
+    fn from(args: &Args) -> Self {
+        Self::from(args.clone())
+    }
+}
+
+/// Settings of the scrapers and of a library enumeration
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumConfig {
+    /// HTTP proxies (comma-separated) for scraper requests
+    pub proxy: Option<String>,
+    /// User-Agent sent with scraper requests
+    pub user_agent: String,
+    /// Timeout of a single scraper request
+    pub timeout: Duration,
+    /// Number of concurrent lookups
+    pub concurrency: usize,
+    /// Comma-separated nameservers to use for DNS queries (default: the system resolver)
+    pub nameservers: Option<String>,
+    /// Standard enumeration sources, in order (default: all)
+    pub sources: Option<Vec<Source>>,
+    /// Result pages to request from each search engine (default: per engine)
+    pub scraper_pages: Option<usize>,
+    /// Fall back to certspotter when crt.sh is unavailable
+    pub certspotter: bool,
+    /// Which certificates to search crt.sh for
+    pub crtsh_scope: CrtShScope,
+}
+
+impl Default for EnumConfig {
+    /// The same defaults as the command line
+    fn default() -> Self {
+        Self::from(&ReconConfig::default())
+    }
+}
+
+impl From<&ReconConfig> for EnumConfig {
+    fn from(config: &ReconConfig) -> Self {
+        Self {
+            proxy: config.proxy.clone(),
+            user_agent: config.user_agent.clone(),
+            timeout: config.http_timeout,
+            concurrency: config.concurrency,
+            nameservers: config.nameservers.clone(),
+            sources: config.sources.clone(),
+            scraper_pages: config.scraper_pages,
+            certspotter: config.certspotter,
+            crtsh_scope: config.crtsh_scope.clone(),
+        }
+    }
+}
+
+impl From<&Args> for EnumConfig {
+    fn from(args: &Args) -> Self {
+        Self::from(&ReconConfig::from(args))
+    }
+}
+
+impl EnumConfig {
+    /// Scan configuration of a standard enumeration of `domain` with these settings
+    ///
+    /// Everything these settings don't cover keeps its default.
+    pub fn recon_config(&self, domain: &str) -> ReconConfig {
+        ReconConfig {
+            domain: Some(domain.to_string()),
+            proxy: self.proxy.clone(),
+            user_agent: self.user_agent.clone(),
+            http_timeout: self.timeout,
+            concurrency: self.concurrency,
+            nameservers: self.nameservers.clone(),
+            sources: self.sources.clone(),
+            scraper_pages: self.scraper_pages,
+            certspotter: self.certspotter,
+            crtsh_scope: self.crtsh_scope.clone(),
+            ..ReconConfig::default()
+        }
+    }
+}
+
+/// Builder for `ReconConfig`, starting from the defaults
+#[derive(Debug, Clone, Default)]
+pub struct ReconConfigBuilder {
+    config: ReconConfig,
+}
+
+/// Setters assigning a field as given
+macro_rules! setters {
+    ($($field:ident: $ty:ty),* $(,)?) => {
+        $(
+            #[doc = concat!("Set `", stringify!($field), "`")]
+            pub fn $field(mut self, $field: $ty) -> Self {
+                self.config.$field = $field.into();
+                self
+            }
+        )*
+    };
+}
+
+/// Setters for optional fields, which take the value itself
+macro_rules! optional_setters {
+    ($($(#[$attr:meta])* $field:ident: $ty:ty),* $(,)?) => {
+        $(
+            $(#[$attr])*
+            #[doc = concat!("Set `", stringify!($field), "`")]
+            pub fn $field(mut self, $field: $ty) -> Self {
+                self.config.$field = Some($field.into());
+                self
+            }
+        )*
+    };
+}
+
+impl ReconConfigBuilder {
+    setters! {
+        domains: Vec<String>,
+        enum_type: EnumType,
+        stdout_format: StdoutFormat,
+        json_append: bool,
+        json_compact: bool,
+        sqlite_incremental: bool,
+        es_index: impl Into<String>,
+        smart_order: bool,
+        no_wildcard_filter: bool,
+        recurse_depth: usize,
+        max_permutations: usize,
+        max_range_size: usize,
+        all_records: bool,
+        no_color: bool,
+        concurrency: usize,
+        check_consistency: bool,
+        fingerprint_ns: bool,
+        follow_ns: bool,
+        srv: bool,
+        audit_rdns: bool,
+        nsec3_crack: bool,
+        check_axfr: bool,
+        seed_from_web: bool,
+        tcp_port: u16,
+        udp_port: u16,
+        bind_port: u16,
+        no_ipv6: bool,
+        no_ipv4: bool,
+        first_response: bool,
+        do_bit: bool,
+        cd_bit: bool,
+        compare_resolvers: bool,
+        certspotter: bool,
+        crtsh_scope: CrtShScope,
+        whois: bool,
+        whois_concurrency: usize,
+        reverse_enrich: bool,
+        fcrdns: bool,
+        user_agent: impl Into<String>,
+        http_timeout: Duration,
+    }
+
+    optional_setters! {
+        domain: impl Into<String>,
+        domain_file: impl Into<String>,
+        json_file: impl Into<String>,
+        diff_against: impl Into<String>,
+        diff_file: impl Into<String>,
+        diff: (String, String),
+        xml_file: impl Into<String>,
+        csv_file: impl Into<String>,
+        sqlite_file: impl Into<String>,
+        findings_file: impl Into<String>,
+        summary_json: impl Into<String>,
+        es_bulk: impl Into<String>,
+        es_url: impl Into<String>,
+        zone_file: impl Into<String>,
+        origin: impl Into<String>,
+        dict: impl Into<String>,
+        pattern: Vec<String>,
+        max_records: usize,
+        probe_rr: usize,
+        scraper_pages: usize,
+        sources: Vec<Source>,
+        exclude: Vec<String>,
+        apex_records: Vec<RecordType>,
+        record_types: Vec<RecordType>,
+        ecs: Vec<IpNetwork>,
+        nameservers: impl Into<String>,
+        nameservers_file: impl Into<String>,
+        ns_strategy: NsStrategy,
+        bind: IpAddr,
+        range: impl Into<String>,
+        range_file: impl Into<String>,
+        #[cfg(feature = "geoip")]
+        geoip: impl Into<String>,
+        proxy: impl Into<String>,
+        doh_json: impl Into<String>,
+        dot_host: impl Into<String>,
+    }
+
+    /// Finish building the configuration
+    pub fn build(self) -> ReconConfig {
+        self.config
+    }
+}
@@ -0,0 +1,32 @@
+//! Details attached to records by enrichment after resolution
+
+use serde::{Deserialize, Serialize};
+
+/// Network owner details parsed from a WHOIS response
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WhoisInfo {
+    /// Organization name
+    pub org_name: Option<String>,
+    /// Organization handle
+    pub org_handle: Option<String>,
+    /// Network ranges, as `start - end` or CIDR
+    pub net_ranges: Vec<String>,
+    /// Country code
+    pub country: Option<String>,
+    /// Abuse contact address
+    pub abuse_contact: Option<String>,
+    /// Origin autonomous system
+    pub asn: Option<String>,
+}
+
+/// Network owner and location of an address
+#[cfg(feature = "geoip")]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GeoInfo {
+    /// Autonomous system number
+    pub asn: Option<u32>,
+    /// Organization the autonomous system is registered to
+    pub org: Option<String>,
+    /// ISO 3166-1 country code
+    pub country: Option<String>,
+}
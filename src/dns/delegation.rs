@@ -0,0 +1,35 @@
+//! Parent-side delegation lookups
+
+use crate::dns::DnsError;
+use trust_dns_client::client::{Client, SyncClient};
+use trust_dns_client::udp::UdpClientConnection;
+use trust_dns_client::rr::{DNSClass, Name, RData, RecordType};
+use std::net::{IpAddr, SocketAddr};
+
+/// Ask a parent zone nameserver which nameservers it delegates a domain to
+///
+/// The parent answers with a referral, so the NS records are read from both the
+/// answer and authority sections of the response.
+pub fn delegation_ns(domain: &str, parent_nameserver: IpAddr) -> Result<Vec<String>, DnsError> {
+    let name = Name::from_ascii(domain)
+        .map_err(|e| DnsError::InvalidRecord(format!("Invalid domain name: {}", e)))?;
+    
+    let conn = UdpClientConnection::new(SocketAddr::new(parent_nameserver, 53))
+        .map_err(|e| DnsError::Other(format!("Failed to connect to {}: {}", parent_nameserver, e)))?;
+    let client = SyncClient::new(conn);
+    
+    let response = client.query(&name, DNSClass::IN, RecordType::NS)
+        .map_err(|e| DnsError::Other(format!("NS query to {} failed: {}", parent_nameserver, e)))?;
+    
+    let mut nameservers = Vec::new();
+    for record in response.answers().iter().chain(response.name_servers()) {
+        if let Some(RData::NS(ref ns)) = record.data() {
+            let ns = ns.to_string().trim_end_matches('.').to_lowercase();
+            if !nameservers.contains(&ns) {
+                nameservers.push(ns);
+            }
+        }
+    }
+    
+    Ok(nameservers)
+}
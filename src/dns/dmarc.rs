@@ -0,0 +1,83 @@
+//! DMARC record parsing
+
+use serde::{Deserialize, Serialize};
+
+/// A parsed DMARC record
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DmarcRecord {
+    /// Requested policy (`p`)
+    pub policy: Option<String>,
+    /// Policy for subdomains (`sp`)
+    pub subdomain_policy: Option<String>,
+    /// Aggregate report URIs (`rua`)
+    pub rua: Option<String>,
+    /// Forensic report URIs (`ruf`)
+    pub ruf: Option<String>,
+    /// Percentage of messages the policy applies to (`pct`)
+    pub pct: Option<u8>,
+    /// DKIM alignment mode (`adkim`)
+    pub adkim: Option<String>,
+    /// SPF alignment mode (`aspf`)
+    pub aspf: Option<String>,
+    /// Tags without a field of their own, such as `fo`, `ri` and `rf`, in record order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub other_tags: Vec<(String, String)>,
+}
+
+impl DmarcRecord {
+    /// The tags that are set, by their DMARC name, known tags first
+    pub fn tags(&self) -> Vec<(&str, String)> {
+        [
+            ("p", self.policy.clone()),
+            ("sp", self.subdomain_policy.clone()),
+            ("rua", self.rua.clone()),
+            ("ruf", self.ruf.clone()),
+            ("pct", self.pct.map(|pct| pct.to_string())),
+            ("adkim", self.adkim.clone()),
+            ("aspf", self.aspf.clone()),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.map(|value| (key, value)))
+        .chain(self.other_tags.iter().map(|(key, value)| (key.as_str(), value.clone())))
+        .collect()
+    }
+    
+    /// Render the tags as a DMARC TXT string, version first
+    pub fn to_txt(&self) -> String {
+        std::iter::once("v=DMARC1".to_string())
+            .chain(self.tags().into_iter().map(|(key, value)| format!("{}={}", key, value)))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// Parse a DMARC TXT string, returning `None` if it isn't a DMARC record
+pub fn parse_dmarc(txt: &str) -> Option<DmarcRecord> {
+    let mut tags = txt.split(';').map(|tag| tag.trim()).filter(|tag| !tag.is_empty());
+
+    let (version_key, version) = tags.next()?.split_once('=')?;
+    if !version_key.trim().eq_ignore_ascii_case("v") || !version.trim().eq_ignore_ascii_case("DMARC1") {
+        return None;
+    }
+
+    let mut record = DmarcRecord::default();
+    for tag in tags {
+        let (key, value) = match tag.split_once('=') {
+            Some((key, value)) => (key.trim().to_lowercase(), value.trim().to_string()),
+            None => continue,
+        };
+
+        match key.as_str() {
+            "p" => record.policy = Some(value.to_lowercase()),
+            "sp" => record.subdomain_policy = Some(value.to_lowercase()),
+            "rua" => record.rua = Some(value),
+            "ruf" => record.ruf = Some(value),
+            "pct" => record.pct = value.parse().ok(),
+            "adkim" => record.adkim = Some(value.to_lowercase()),
+            "aspf" => record.aspf = Some(value.to_lowercase()),
+            _ => record.other_tags.push((key, value)),
+        }
+    }
+
+    Some(record)
+}
@@ -0,0 +1,123 @@
+//! DNSSEC record data (RFC 4034)
+//!
+//! The resolver is built without trust-dns' DNSSEC support, so DNSKEY, DS,
+//! RRSIG and NSEC answers arrive as opaque wire data. These functions decode
+//! that data into records.
+
+use crate::dns::record::{wire_type_name, DnsRecord};
+use data_encoding::{BASE64, HEXUPPER};
+use trust_dns_resolver::proto::rr::{Name, RecordType as TrustDnsRecordType};
+use trust_dns_resolver::proto::serialize::binary::{BinDecodable, BinDecoder};
+
+/// Decode the wire data of a DNSKEY, DS, RRSIG or NSEC answer
+///
+/// Returns `None` for other types and for truncated or malformed data.
+pub fn record_from_wire(name: &str, record_type: TrustDnsRecordType, wire: &[u8]) -> Option<DnsRecord> {
+    let name = name.to_string();
+    match record_type {
+        TrustDnsRecordType::DNSKEY => {
+            let key = wire.get(4..)?;
+            Some(DnsRecord::new_dnskey(name, u16_at(wire, 0)?, wire[2], wire[3], BASE64.encode(key)))
+        }
+        TrustDnsRecordType::DS => {
+            let digest = wire.get(4..)?;
+            Some(DnsRecord::new_ds(name, u16_at(wire, 0)?, wire[2], wire[3], HEXUPPER.encode(digest)))
+        }
+        TrustDnsRecordType::RRSIG => {
+            let fixed = wire.get(..18)?;
+            let mut decoder = BinDecoder::new(&wire[18..]);
+            let signer_name = Name::read(&mut decoder).ok()?;
+            let signature = decoder.read_slice(decoder.len()).ok()?.unverified();
+            Some(DnsRecord::new_rrsig(
+                name,
+                type_name(u16_at(fixed, 0)?),
+                fixed[2],
+                fixed[3],
+                u32_at(fixed, 4)?,
+                u32_at(fixed, 8)?,
+                u32_at(fixed, 12)?,
+                u16_at(fixed, 16)?,
+                trim_dot(&signer_name),
+                BASE64.encode(signature),
+            ))
+        }
+        TrustDnsRecordType::NSEC => {
+            let mut decoder = BinDecoder::new(wire);
+            let next_domain = Name::read(&mut decoder).ok()?;
+            let bitmap = decoder.read_slice(decoder.len()).ok()?.unverified();
+            Some(DnsRecord::new_nsec(name, trim_dot(&next_domain), type_bitmap(bitmap)?))
+        }
+        _ => None,
+    }
+}
+
+/// Type mnemonics listed in an NSEC type bitmap, in type code order
+///
+/// The bitmap is a series of windows: a window number, a length and up to
+/// 32 bytes in which each set bit marks a type present at the owner name.
+pub fn type_bitmap(mut bitmap: &[u8]) -> Option<Vec<String>> {
+    let mut types = Vec::new();
+    while !bitmap.is_empty() {
+        let window = *bitmap.first()? as u16;
+        let len = *bitmap.get(1)? as usize;
+        let bits = bitmap.get(2..2 + len)?;
+        for (i, byte) in bits.iter().enumerate() {
+            for bit in 0..8 {
+                if byte & (0x80 >> bit) != 0 {
+                    types.push(type_name(window * 256 + i as u16 * 8 + bit));
+                }
+            }
+        }
+        bitmap = &bitmap[2 + len..];
+    }
+    Some(types)
+}
+
+/// Parse an RRSIG expiration or inception time
+///
+/// Presentation format allows both seconds since the Unix epoch and
+/// `YYYYMMDDHHmmSS` in UTC.
+pub fn parse_signature_time(time: &str) -> Option<u32> {
+    if time.len() != 14 {
+        return time.parse().ok();
+    }
+
+    let field = |range: std::ops::Range<usize>| time.get(range)?.parse::<i64>().ok();
+    let (year, month, day) = (field(0..4)?, field(4..6)?, field(6..8)?);
+    let (hour, minute, second) = (field(8..10)?, field(10..12)?, field(12..14)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    // Days since 1970-01-01 of the civil date
+    let (year, month) = if month <= 2 { (year - 1, month + 9) } else { (year, month - 3) };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146097 + day_of_era - 719468;
+
+    (days * 86400 + hour * 3600 + minute * 60 + second).try_into().ok()
+}
+
+/// Mnemonic of a type code, or `TYPE<n>` (RFC 3597) if it has none
+fn type_name(code: u16) -> String {
+    wire_type_name(TrustDnsRecordType::from(code))
+}
+
+/// Name without its trailing dot, keeping the root as `.`
+fn trim_dot(name: &Name) -> String {
+    if name.is_root() {
+        ".".to_string()
+    } else {
+        name.to_string().trim_end_matches('.').to_string()
+    }
+}
+
+fn u16_at(wire: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(wire.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn u32_at(wire: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(wire.get(at..at + 4)?.try_into().ok()?))
+}
@@ -0,0 +1,271 @@
+//! DNS resolution over the JSON DoH API (`application/dns-json`)
+//!
+//! Google and Cloudflare answer `GET <endpoint>?name=X&type=A` with a JSON
+//! document, which lets names be resolved with a plain HTTPS client, including
+//! through the proxy used by the scrapers.
+
+use crate::dns::dnssec;
+use crate::dns::record::{wire_type_from_name, DnsRecord, RecordType};
+use crate::dns::DnsError;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Google's JSON DoH endpoint
+pub const GOOGLE_ENDPOINT: &str = "https://dns.google/resolve";
+
+/// Cloudflare's JSON DoH endpoint
+pub const CLOUDFLARE_ENDPOINT: &str = "https://cloudflare-dns.com/dns-query";
+
+/// DNS response code for a name that does not exist
+const NXDOMAIN: u32 = 3;
+
+/// JSON DoH response
+#[derive(Debug, Deserialize)]
+struct DohResponse {
+    #[serde(rename = "Status")]
+    status: u32,
+    #[serde(rename = "Answer", default)]
+    answer: Vec<DohAnswer>,
+}
+
+/// A single answer of a JSON DoH response
+#[derive(Debug, Deserialize)]
+struct DohAnswer {
+    name: String,
+    #[serde(rename = "type")]
+    rtype: u16,
+    #[serde(rename = "TTL")]
+    ttl: Option<u32>,
+    data: String,
+}
+
+/// Resolve `name` records of type `rtype` through the JSON DoH API at `endpoint`
+///
+/// Only answers of the requested type are returned, so the CNAME chain that led
+/// to them is dropped. A name that does not exist yields no records.
+pub async fn resolve(
+    name: &str,
+    rtype: RecordType,
+    endpoint: &str,
+    client: &reqwest::Client,
+) -> Result<Vec<DnsRecord>, DnsError> {
+    let response = client
+        .get(endpoint)
+        .query(&[("name", name), ("type", rtype.mnemonic().as_str())])
+        .header(reqwest::header::ACCEPT, "application/dns-json")
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| DnsError::Other(format!("DoH JSON request to {} failed: {}", endpoint, e)))?;
+
+    let body = response
+        .text()
+        .await
+        .map_err(|e| DnsError::Other(format!("Failed to read DoH JSON response: {}", e)))?;
+
+    parse_response(name, &body, &rtype)
+}
+
+/// Parse a JSON DoH response body for `name` into records of type `rtype`
+///
+/// Records are named after the queried name, like the other resolution
+/// paths do, even when the answers were reached through a CNAME chain.
+pub fn parse_response(name: &str, body: &str, rtype: &RecordType) -> Result<Vec<DnsRecord>, DnsError> {
+    let response: DohResponse = serde_json::from_str(body)
+        .map_err(|e| DnsError::InvalidRecord(format!("Invalid DoH JSON response: {}", e)))?;
+
+    match response.status {
+        0 => {}
+        NXDOMAIN => return Ok(Vec::new()),
+        status => return Err(DnsError::Other(format!("DoH JSON query failed with response code {}", status))),
+    }
+
+    let wanted = type_code(rtype);
+    let mut records = Vec::new();
+    for answer in response.answer.iter().filter(|answer| answer.rtype == wanted) {
+        let mut record = parse_answer(name, answer, rtype)?;
+        record.ttl = answer.ttl;
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+/// Numeric type code of a record type, or 0 for an unknown mnemonic
+fn type_code(rtype: &RecordType) -> u16 {
+    match rtype {
+        RecordType::A => 1,
+        RecordType::Ns => 2,
+        RecordType::Cname => 5,
+        RecordType::Soa => 6,
+        RecordType::Ptr => 12,
+        RecordType::Mx => 15,
+        RecordType::Txt => 16,
+        RecordType::Aaaa => 28,
+        RecordType::Srv => 33,
+        RecordType::Spf => 99,
+        RecordType::Svcb => 64,
+        RecordType::Https => 65,
+        RecordType::Caa => 257,
+        RecordType::Ds => 43,
+        RecordType::Rrsig => 46,
+        RecordType::Nsec => 47,
+        RecordType::Dnskey => 48,
+        RecordType::Other(rtype) => wire_type_from_name(rtype).map(u16::from).unwrap_or(0),
+    }
+}
+
+/// Convert a single answer to a query for `name` into a record
+fn parse_answer(name: &str, answer: &DohAnswer, rtype: &RecordType) -> Result<DnsRecord, DnsError> {
+    let name = trim_dot(name);
+    let data = answer.data.trim();
+    let invalid = || DnsError::InvalidRecord(format!("Malformed {:?} data '{}' for {}", rtype, data, answer.name));
+    let fields: Vec<&str> = data.split_whitespace().collect();
+
+    let record = match rtype {
+        RecordType::A => DnsRecord::new_a(name, data.parse()?),
+        RecordType::Aaaa => DnsRecord::new_aaaa(name, data.parse()?),
+        RecordType::Ns => DnsRecord::new_ns(name, trim_dot(data)),
+        RecordType::Cname => DnsRecord::new_cname(name, trim_dot(data)),
+        RecordType::Ptr => DnsRecord::new_ptr(name, trim_dot(data)),
+        RecordType::Txt => DnsRecord::new_txt(name, unquote(data)),
+        RecordType::Spf => DnsRecord::new_spf(name, unquote(data)),
+        RecordType::Mx => match fields.as_slice() {
+            [preference, exchange] => {
+                DnsRecord::new_mx(name, preference.parse().map_err(|_| invalid())?, trim_dot(exchange))
+            }
+            _ => return Err(invalid()),
+        },
+        RecordType::Srv => match fields.as_slice() {
+            [priority, weight, port, target] => DnsRecord::new_srv(
+                name,
+                priority.parse().map_err(|_| invalid())?,
+                weight.parse().map_err(|_| invalid())?,
+                port.parse().map_err(|_| invalid())?,
+                trim_dot(target),
+            ),
+            _ => return Err(invalid()),
+        },
+        RecordType::Soa => match fields.as_slice() {
+            [mname, rname, serial, refresh, retry, expire, minimum] => DnsRecord::new_soa(
+                name,
+                trim_dot(mname),
+                trim_dot(rname),
+                serial.parse().map_err(|_| invalid())?,
+                refresh.parse().map_err(|_| invalid())?,
+                retry.parse().map_err(|_| invalid())?,
+                expire.parse().map_err(|_| invalid())?,
+                minimum.parse().map_err(|_| invalid())?,
+            ),
+            _ => return Err(invalid()),
+        },
+        RecordType::Svcb | RecordType::Https => match fields.as_slice() {
+            [priority, target, params @ ..] => {
+                let priority = priority.parse().map_err(|_| invalid())?;
+                let target = if *target == "." { target.to_string() } else { trim_dot(target) };
+                let params: BTreeMap<String, String> = params
+                    .iter()
+                    .map(|param| match param.split_once('=') {
+                        Some((key, value)) => (key.to_lowercase(), value.trim_matches('"').to_string()),
+                        None => (param.to_lowercase(), String::new()),
+                    })
+                    .collect();
+                if *rtype == RecordType::Https {
+                    DnsRecord::new_https(name, priority, target, params)
+                } else {
+                    DnsRecord::new_svcb(name, priority, target, params)
+                }
+            }
+            _ => return Err(invalid()),
+        },
+        RecordType::Caa => {
+            let mut parts = data.splitn(3, char::is_whitespace);
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some(flags), Some(tag), Some(value)) => {
+                    DnsRecord::new_caa(name, flags.parse().map_err(|_| invalid())?, tag.to_string(), unquote(value))
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        // Base64 and hex fields may be split by whitespace
+        RecordType::Dnskey => match fields.as_slice() {
+            [flags, protocol, algorithm, key @ ..] if !key.is_empty() => DnsRecord::new_dnskey(
+                name,
+                flags.parse().map_err(|_| invalid())?,
+                protocol.parse().map_err(|_| invalid())?,
+                algorithm.parse().map_err(|_| invalid())?,
+                key.concat(),
+            ),
+            _ => return Err(invalid()),
+        },
+        RecordType::Ds => match fields.as_slice() {
+            [key_tag, algorithm, digest_type, digest @ ..] if !digest.is_empty() => DnsRecord::new_ds(
+                name,
+                key_tag.parse().map_err(|_| invalid())?,
+                algorithm.parse().map_err(|_| invalid())?,
+                digest_type.parse().map_err(|_| invalid())?,
+                digest.concat().to_uppercase(),
+            ),
+            _ => return Err(invalid()),
+        },
+        RecordType::Rrsig => match fields.as_slice() {
+            [type_covered, algorithm, labels, original_ttl, expiration, inception, key_tag, signer_name, signature @ ..]
+                if !signature.is_empty() =>
+            {
+                DnsRecord::new_rrsig(
+                    name,
+                    type_covered.to_uppercase(),
+                    algorithm.parse().map_err(|_| invalid())?,
+                    labels.parse().map_err(|_| invalid())?,
+                    original_ttl.parse().map_err(|_| invalid())?,
+                    dnssec::parse_signature_time(expiration).ok_or_else(invalid)?,
+                    dnssec::parse_signature_time(inception).ok_or_else(invalid)?,
+                    key_tag.parse().map_err(|_| invalid())?,
+                    if *signer_name == "." { signer_name.to_string() } else { trim_dot(signer_name) },
+                    signature.concat(),
+                )
+            }
+            _ => return Err(invalid()),
+        },
+        RecordType::Nsec => match fields.as_slice() {
+            [next_domain, types @ ..] => DnsRecord::new_nsec(
+                name,
+                trim_dot(next_domain),
+                types.iter().map(|rtype| rtype.to_uppercase()).collect(),
+            ),
+            _ => return Err(invalid()),
+        },
+        RecordType::Other(other) => DnsRecord::new_other(name, other.clone(), data.to_string()),
+    };
+
+    Ok(record)
+}
+
+/// Strip the trailing dot of a fully-qualified name
+fn trim_dot(name: &str) -> String {
+    name.trim_end_matches('.').to_string()
+}
+
+/// Join the quoted character-strings of TXT-like data, e.g. `"v=spf1 " "-all"`
+fn unquote(data: &str) -> String {
+    if !data.starts_with('"') {
+        return data.to_string();
+    }
+
+    let mut text = String::new();
+    let mut in_quotes = false;
+    let mut chars = data.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '\\' if in_quotes => {
+                if let Some(escaped) = chars.next() {
+                    text.push(escaped);
+                }
+            }
+            _ if in_quotes => text.push(c),
+            _ => {}
+        }
+    }
+    text
+}
@@ -3,14 +3,23 @@
 //! This module provides DNS enumeration capabilities using the trust-dns crates.
 
 use thiserror::Error;
-use trust_dns_resolver::error::ResolveError;
+use trust_dns_resolver::error::{ResolveError, ResolveErrorKind};
+use trust_dns_resolver::proto::op::ResponseCode;
 use std::io;
 use std::net::AddrParseError;
 
 pub mod resolver;
 pub mod record;
 pub mod zone_transfer;
+pub mod delegation;
+pub mod raw;
+pub mod doh_json;
+pub mod nsec3;
+pub mod dnssec;
+pub mod zone_walk;
 pub mod error;
+pub mod dmarc;
+pub mod annotation;
 
 /// DNS-related errors
 #[derive(Error, Debug)]
@@ -35,4 +44,26 @@ pub enum DnsError {
     
     #[error("Other DNS error: {0}")]
     Other(String),
+}
+
+impl DnsError {
+    /// Check whether the error means the queried name does not exist (NXDOMAIN)
+    pub fn is_nxdomain(&self) -> bool {
+        match self {
+            DnsError::Resolution(e) => matches!(
+                e.kind(),
+                ResolveErrorKind::NoRecordsFound { response_code: ResponseCode::NXDomain, .. }
+            ),
+            _ => false,
+        }
+    }
+    
+    /// Check whether the error means the queried name has no records of the
+    /// requested type, either because it doesn't exist or the answer was empty
+    pub fn is_no_records(&self) -> bool {
+        match self {
+            DnsError::Resolution(e) => matches!(e.kind(), ResolveErrorKind::NoRecordsFound { .. }),
+            _ => false,
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,152 @@
+//! NSEC3 owner name hashing and cracking (RFC 5155)
+//!
+//! Signed zones using NSEC3 only reveal hashes of their names. Hashing
+//! candidate names with the zone's salt and iteration count and comparing
+//! against those hashes recovers the names that are in a wordlist.
+
+use crate::dns::record::{DnsRecord, RecordData, RecordType};
+use crate::dns::DnsError;
+use data_encoding::{BASE32HEX_NOPAD, BASE64, HEXLOWER_PERMISSIVE};
+use ring::digest::{digest, SHA1_FOR_LEGACY_USE_ONLY};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Hash algorithm number of SHA-1, the only one defined for NSEC3
+pub const SHA1_ALGORITHM: u8 = 1;
+
+/// Salt and iteration count a zone hashes its names with
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Nsec3Params {
+    pub salt: Vec<u8>,
+    pub iterations: u16,
+}
+
+impl Nsec3Params {
+    /// Parse the parameters from NSEC3 or NSEC3PARAM record data
+    ///
+    /// Accepts presentation format (`1 0 10 AABBCCDD ...`, with `-` for no
+    /// salt) and the base64 wire data kept for types without dedicated
+    /// support. Algorithms other than SHA-1 are rejected.
+    pub fn parse(value: &str) -> Option<Self> {
+        let fields: Vec<&str> = value.split_whitespace().collect();
+        if fields.len() >= 4 {
+            if fields[0].parse::<u8>().ok()? != SHA1_ALGORITHM {
+                return None;
+            }
+            let iterations = fields[2].parse().ok()?;
+            let salt = parse_salt(fields[3]).ok()?;
+            return Some(Self { salt, iterations });
+        }
+
+        // Wire format: algorithm, flags, iterations, salt length, salt
+        let wire = BASE64.decode(value.trim().as_bytes()).ok()?;
+        if wire.len() < 5 || wire[0] != SHA1_ALGORITHM {
+            return None;
+        }
+        let iterations = u16::from_be_bytes([wire[2], wire[3]]);
+        let salt = wire.get(5..5 + wire[4] as usize)?.to_vec();
+        Some(Self { salt, iterations })
+    }
+}
+
+/// Parse a hex salt, where `-` means no salt
+pub fn parse_salt(salt: &str) -> Result<Vec<u8>, DnsError> {
+    if salt == "-" {
+        return Ok(Vec::new());
+    }
+    HEXLOWER_PERMISSIVE
+        .decode(salt.as_bytes())
+        .map_err(|e| DnsError::Other(format!("Invalid NSEC3 salt '{}': {}", salt, e)))
+}
+
+/// Canonical wire format of a name: lowercased, length-prefixed labels
+fn wire_name(name: &str) -> Result<Vec<u8>, DnsError> {
+    let mut wire = Vec::new();
+    for label in name.trim_end_matches('.').split('.').filter(|label| !label.is_empty()) {
+        if label.len() > 63 {
+            return Err(DnsError::InvalidRecord(format!("Label too long in {}", name)));
+        }
+        wire.push(label.len() as u8);
+        wire.extend(label.to_ascii_lowercase().bytes());
+    }
+    wire.push(0);
+    Ok(wire)
+}
+
+/// Compute the NSEC3 hash of a name, as the base32hex label used in owner names
+pub fn nsec3_hash(name: &str, salt: &[u8], iterations: u16) -> Result<String, DnsError> {
+    let mut input = wire_name(name)?;
+    input.extend_from_slice(salt);
+    let mut hash = digest(&SHA1_FOR_LEGACY_USE_ONLY, &input).as_ref().to_vec();
+
+    for _ in 0..iterations {
+        hash.extend_from_slice(salt);
+        hash = digest(&SHA1_FOR_LEGACY_USE_ONLY, &hash).as_ref().to_vec();
+    }
+
+    Ok(BASE32HEX_NOPAD.encode(&hash).to_lowercase())
+}
+
+/// Recover the names behind hashed NSEC3 owner names
+///
+/// `hashes` are owner names such as `2t7b4g4vsa5smi47k61mv5bv1a22bojr.example.com`;
+/// each word in `wordlist` is tried as a label directly below the zone the
+/// hash belongs to, as is the zone apex itself. Returns the lowercased hashed
+/// owner names that matched, mapped to the plaintext names.
+pub fn crack(hashes: &[String], salt: &[u8], iterations: u16, wordlist: &[String]) -> BTreeMap<String, String> {
+    let mut zones: HashMap<String, HashSet<String>> = HashMap::new();
+    for owner in hashes {
+        let owner = owner.trim_end_matches('.').to_lowercase();
+        if let Some((hash, zone)) = owner.split_once('.') {
+            zones.entry(zone.to_string()).or_default().insert(hash.to_string());
+        }
+    }
+
+    let mut cracked = BTreeMap::new();
+    for (zone, mut wanted) in zones {
+        let candidates = std::iter::once(zone.clone())
+            .chain(wordlist.iter().map(|word| format!("{}.{}", word.to_lowercase(), zone)));
+
+        for candidate in candidates {
+            let hash = match nsec3_hash(&candidate, salt, iterations) {
+                Ok(hash) => hash,
+                Err(e) => {
+                    tracing::debug!("Skipping NSEC3 candidate {}: {}", candidate, e);
+                    continue;
+                }
+            };
+            if wanted.remove(&hash) {
+                cracked.insert(format!("{}.{}", hash, zone), candidate);
+                if wanted.is_empty() {
+                    break;
+                }
+            }
+        }
+    }
+
+    cracked
+}
+
+/// Crack the owner names of the NSEC3 records among `records`
+///
+/// Records are grouped by the salt and iteration count in their data, so
+/// records of several zones can be cracked together.
+pub fn crack_records(records: &[DnsRecord], wordlist: &[String]) -> BTreeMap<String, String> {
+    let mut groups: HashMap<Nsec3Params, Vec<String>> = HashMap::new();
+    for record in records {
+        if record.record_type != RecordType::Other("NSEC3".to_string()) {
+            continue;
+        }
+        if let RecordData::Other { value, .. } = &record.data {
+            match Nsec3Params::parse(value) {
+                Some(params) => groups.entry(params).or_default().push(record.name.clone()),
+                None => tracing::debug!("Unsupported NSEC3 data for {}: {}", record.name, value),
+            }
+        }
+    }
+
+    let mut cracked = BTreeMap::new();
+    for (params, hashes) in groups {
+        cracked.extend(crack(&hashes, &params.salt, params.iterations, wordlist));
+    }
+    cracked
+}
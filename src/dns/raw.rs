@@ -0,0 +1,113 @@
+//! Hand-built DNS queries for options the resolver does not expose
+
+use crate::dns::DnsError;
+use ipnetwork::IpNetwork;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use trust_dns_resolver::proto::op::{Message, MessageType, OpCode, Query};
+use trust_dns_resolver::proto::rr::rdata::opt::{ClientSubnet, EdnsOption};
+use trust_dns_resolver::proto::rr::{DNSClass, Name, RecordType};
+
+/// EDNS payload size advertised when the DO bit is set
+///
+/// DNSSEC answers carry signatures and easily exceed the classic 512 bytes.
+pub const EDNS_PAYLOAD_SIZE: u16 = 4096;
+
+/// Flags set on outgoing queries for DNSSEC debugging
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueryFlags {
+    /// DO (DNSSEC OK): ask the server to include RRSIG and other DNSSEC records
+    pub dnssec_ok: bool,
+    /// CD (checking disabled): ask a validating resolver to return data even
+    /// if it fails DNSSEC validation, instead of SERVFAIL
+    pub checking_disabled: bool,
+}
+
+impl QueryFlags {
+    /// Check whether no flag is set
+    pub fn is_empty(&self) -> bool {
+        !self.dnssec_ok && !self.checking_disabled
+    }
+    
+    /// Set the flags on a query message
+    ///
+    /// The DO bit lives in the EDNS OPT record, which is added if missing.
+    pub fn apply(&self, message: &mut Message) {
+        if self.checking_disabled {
+            message.set_checking_disabled(true);
+        }
+        if self.dnssec_ok {
+            let mut edns = message.extensions().clone().unwrap_or_default();
+            edns.set_dnssec_ok(true);
+            edns.set_max_payload(EDNS_PAYLOAD_SIZE.max(edns.max_payload()));
+            message.set_edns(edns);
+        }
+    }
+}
+
+/// Attach an EDNS Client Subnet option (RFC 7871) to a query
+///
+/// GeoDNS servers answer as if the query came from a client in `subnet`. The
+/// EDNS OPT record is added if missing.
+pub fn set_client_subnet(message: &mut Message, subnet: IpNetwork) {
+    let mut edns = message.extensions().clone().unwrap_or_default();
+    edns.options_mut()
+        .insert(EdnsOption::Subnet(ClientSubnet::new(subnet.network(), subnet.prefix(), 0)));
+    message.set_edns(edns);
+}
+
+/// Build a standard query message for a single question
+pub fn build_query(name: &str, record_type: RecordType, class: DNSClass) -> Result<Message, DnsError> {
+    let name = Name::from_ascii(name)
+        .map_err(|e| DnsError::InvalidRecord(format!("Invalid name {}: {}", name, e)))?;
+    
+    let mut query = Query::query(name, record_type);
+    query.set_query_class(class);
+    
+    let mut message = Message::new();
+    message
+        .set_id(rand::random())
+        .set_message_type(MessageType::Query)
+        .set_op_code(OpCode::Query)
+        .set_recursion_desired(true)
+        .add_query(query);
+    
+    Ok(message)
+}
+
+/// Send a query over UDP and wait for the matching response
+pub async fn send_query(message: &Message, server: SocketAddr, timeout: Duration) -> Result<Message, DnsError> {
+    let local: SocketAddr = if server.is_ipv4() {
+        ([0, 0, 0, 0], 0).into()
+    } else {
+        ([0u16; 8], 0).into()
+    };
+    let socket = UdpSocket::bind(local).await?;
+    
+    let request = message
+        .to_vec()
+        .map_err(|e| DnsError::Other(format!("Failed to encode query: {}", e)))?;
+    socket.send_to(&request, server).await?;
+    
+    let receive = async {
+        let mut buffer = [0u8; EDNS_PAYLOAD_SIZE as usize];
+        loop {
+            let (len, from) = socket.recv_from(&mut buffer).await?;
+            
+            // Ignore stray datagrams that aren't the answer to this query
+            if from != server {
+                continue;
+            }
+            let response = match Message::from_vec(&buffer[..len]) {
+                Ok(response) => response,
+                Err(_) => continue,
+            };
+            if response.id() == message.id() {
+                return Ok(response);
+            }
+        }
+    };
+    
+    tokio::time::timeout(timeout, receive).await.map_err(|_| DnsError::Timeout)?
+}
@@ -1,10 +1,20 @@
 //! DNS record types and structures
 
-use std::net::{Ipv4Addr, Ipv6Addr};
-use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use crate::dns::annotation::WhoisInfo;
+#[cfg(feature = "geoip")]
+use crate::dns::annotation::GeoInfo;
+use crate::dns::dmarc::DmarcRecord;
+use trust_dns_resolver::proto::rr::RecordType as TrustDnsRecordType;
+
+/// Type code of DNAME (RFC 6672), which trust-dns has no variant for
+const DNAME_CODE: u16 = 39;
 
 /// DNS record types supported by DNSRecon
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum RecordType {
     A,
     Aaaa,
@@ -17,21 +27,133 @@ pub enum RecordType {
     Srv,
     Caa,
     Cname,
+    Svcb,
+    Https,
+    Dnskey,
+    Ds,
+    Rrsig,
+    Nsec,
+    /// A type without dedicated support, by its mnemonic (e.g. `HINFO`)
+    Other(String),
     // Add more record types as needed
 }
 
+impl RecordType {
+    /// Presentation name of the type, such as `AAAA` or `NSEC`
+    pub fn mnemonic(&self) -> String {
+        let name = match self {
+            RecordType::A => "A",
+            RecordType::Aaaa => "AAAA",
+            RecordType::Mx => "MX",
+            RecordType::Ns => "NS",
+            RecordType::Soa => "SOA",
+            RecordType::Spf => "SPF",
+            RecordType::Txt => "TXT",
+            RecordType::Ptr => "PTR",
+            RecordType::Srv => "SRV",
+            RecordType::Caa => "CAA",
+            RecordType::Cname => "CNAME",
+            RecordType::Svcb => "SVCB",
+            RecordType::Https => "HTTPS",
+            RecordType::Dnskey => "DNSKEY",
+            RecordType::Ds => "DS",
+            RecordType::Rrsig => "RRSIG",
+            RecordType::Nsec => "NSEC",
+            RecordType::Other(rtype) => return rtype.to_uppercase(),
+        };
+        name.to_string()
+    }
+}
+
+impl FromStr for RecordType {
+    type Err = String;
+    
+    /// Parse a record type name such as `aaaa` or `MX`, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "A" => Ok(RecordType::A),
+            "AAAA" => Ok(RecordType::Aaaa),
+            "MX" => Ok(RecordType::Mx),
+            "NS" => Ok(RecordType::Ns),
+            "SOA" => Ok(RecordType::Soa),
+            "SPF" => Ok(RecordType::Spf),
+            "TXT" => Ok(RecordType::Txt),
+            "PTR" => Ok(RecordType::Ptr),
+            "SRV" => Ok(RecordType::Srv),
+            "CAA" => Ok(RecordType::Caa),
+            "CNAME" => Ok(RecordType::Cname),
+            "SVCB" => Ok(RecordType::Svcb),
+            "HTTPS" => Ok(RecordType::Https),
+            "DNSKEY" => Ok(RecordType::Dnskey),
+            "DS" => Ok(RecordType::Ds),
+            "RRSIG" => Ok(RecordType::Rrsig),
+            "NSEC" => Ok(RecordType::Nsec),
+            // Any other type the resolver can query is kept by its mnemonic
+            other if wire_type_from_name(other).is_some() => Ok(RecordType::Other(other.to_string())),
+            _ => Err(format!(
+                "Unknown record type '{}'; supported types: A, AAAA, MX, NS, SOA, SPF, TXT, PTR, SRV, CAA, CNAME, SVCB, HTTPS, DNSKEY, DS, RRSIG, NSEC, \
+                 any other standard mnemonic such as NAPTR or DNAME, or TYPE<n>",
+                s.trim()
+            )),
+        }
+    }
+}
+
+/// Wire type of a record type mnemonic, ignoring case
+///
+/// Besides the mnemonics trust-dns knows this accepts `DNAME` and RFC 3597
+/// `TYPE<n>` names.
+pub fn wire_type_from_name(name: &str) -> Option<TrustDnsRecordType> {
+    let name = name.trim().to_uppercase();
+    if let Ok(record_type) = name.parse() {
+        return Some(record_type);
+    }
+    match name.as_str() {
+        "DNAME" => Some(TrustDnsRecordType::from(DNAME_CODE)),
+        _ => name.strip_prefix("TYPE")?.parse::<u16>().ok().map(TrustDnsRecordType::from),
+    }
+}
+
+/// Mnemonic of a wire type, or `TYPE<n>` (RFC 3597) if it has none
+pub fn wire_type_name(record_type: TrustDnsRecordType) -> String {
+    match record_type {
+        TrustDnsRecordType::Unknown(DNAME_CODE) => "DNAME".to_string(),
+        TrustDnsRecordType::Unknown(code) => format!("TYPE{}", code),
+        record_type => record_type.to_string(),
+    }
+}
+
 /// Generic DNS record structure
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DnsRecord {
     #[serde(rename = "type")]
     pub record_type: RecordType,
     pub name: String,
+    /// Domain being enumerated when the record was found
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
     pub data: RecordData,
     pub ttl: Option<u32>,
+    /// WHOIS details for the address, when WHOIS enrichment ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub whois: Option<WhoisInfo>,
+    /// ASN and country of the address, when GeoIP annotation ran
+    #[cfg(feature = "geoip")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geoip: Option<GeoInfo>,
+    /// How the record was found when it wasn't queried for directly, such as `ns-infrastructure`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+    /// Category of a TXT or SPF record's string, such as `google-site-verification`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub txt_category: Option<String>,
+    /// Whether the record is a CNAME pointing at a name that doesn't exist
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dangling: bool,
 }
 
 /// Data contained in different types of DNS records
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum RecordData {
     A(Ipv4Addr),
     Aaaa(Ipv6Addr),
@@ -47,6 +169,7 @@ pub enum RecordData {
         minimum: u32 
     },
     Spf(String),
+    Dmarc(DmarcRecord),
     Txt(String),
     Ptr(String),
     Srv { 
@@ -61,48 +184,117 @@ pub enum RecordData {
         value: String 
     },
     Cname(String),
+    Svcb {
+        priority: u16,
+        target: String,
+        params: BTreeMap<String, String>
+    },
+    Https {
+        priority: u16,
+        target: String,
+        params: BTreeMap<String, String>
+    },
+    Dnskey {
+        flags: u16,
+        protocol: u8,
+        algorithm: u8,
+        /// Base64
+        public_key: String
+    },
+    Ds {
+        key_tag: u16,
+        algorithm: u8,
+        digest_type: u8,
+        /// Uppercase hex
+        digest: String
+    },
+    Rrsig {
+        /// Mnemonic of the signed type, e.g. `A`
+        type_covered: String,
+        algorithm: u8,
+        labels: u8,
+        original_ttl: u32,
+        /// Seconds since the Unix epoch
+        expiration: u32,
+        /// Seconds since the Unix epoch
+        inception: u32,
+        key_tag: u16,
+        signer_name: String,
+        /// Base64
+        signature: String
+    },
+    Nsec {
+        next_domain: String,
+        /// Mnemonics of the types present at the owner name
+        types: Vec<String>
+    },
+    /// Data of a type without dedicated support, in presentation format
+    Other {
+        rtype: String,
+        value: String
+    },
     // Add more record data types as needed
 }
 
 impl DnsRecord {
-    /// Create a new A record
-    pub fn new_a(name: String, address: Ipv4Addr) -> Self {
+    /// Identity of the record for de-duplication: its lowercased name, type and data
+    pub fn dedup_key(&self) -> (String, RecordType, RecordData) {
+        (self.name.to_lowercase(), self.record_type.clone(), self.data.clone())
+    }
+    
+    /// Note how the record was found
+    pub fn with_origin(mut self, origin: impl Into<String>) -> Self {
+        self.origin = Some(origin.into());
+        self
+    }
+    
+    /// Record the domain being enumerated when the record was found
+    pub fn with_domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+    
+    /// Set the TTL the record was served with
+    pub fn with_ttl(mut self, ttl: impl Into<Option<u32>>) -> Self {
+        self.ttl = ttl.into();
+        self
+    }
+    
+    /// Create a record without a TTL or any annotations
+    fn with_data(record_type: RecordType, name: String, data: RecordData) -> Self {
         Self {
-            record_type: RecordType::A,
+            record_type,
             name,
-            data: RecordData::A(address),
+            domain: None,
+            data,
             ttl: None,
+            whois: None,
+            #[cfg(feature = "geoip")]
+            geoip: None,
+            origin: None,
+            txt_category: None,
+            dangling: false,
         }
     }
     
+    /// Create a new A record
+    pub fn new_a(name: String, address: Ipv4Addr) -> Self {
+        Self::with_data(RecordType::A, name, RecordData::A(address))
+    }
+    
     /// Create a new AAAA record
     pub fn new_aaaa(name: String, address: Ipv6Addr) -> Self {
-        Self {
-            record_type: RecordType::Aaaa,
-            name,
-            data: RecordData::Aaaa(address),
-            ttl: None,
-        }
+        Self::with_data(RecordType::Aaaa, name, RecordData::Aaaa(address))
     }
     
     /// Create a new MX record
     pub fn new_mx(name: String, preference: u16, exchange: String) -> Self {
-        Self {
-            record_type: RecordType::Mx,
-            name,
-            data: RecordData::Mx { preference, exchange },
-            ttl: None,
-        }
+        Self::with_data(RecordType::Mx, name, RecordData::Mx { preference, exchange })
     }
     
     /// Create a new NS record
     pub fn new_ns(name: String, nameserver: String) -> Self {
-        Self {
-            record_type: RecordType::Ns,
-            name,
-            data: RecordData::Ns(nameserver),
-            ttl: None,
-        }
+        Self::with_data(RecordType::Ns, name, RecordData::Ns(nameserver))
     }
     
     /// Create a new SOA record
@@ -116,84 +308,192 @@ impl DnsRecord {
         expire: u32,
         minimum: u32,
     ) -> Self {
-        Self {
-            record_type: RecordType::Soa,
-            name,
-            data: RecordData::Soa {
-                mname,
-                rname,
-                serial,
-                refresh,
-                retry,
-                expire,
-                minimum,
-            },
-            ttl: None,
-        }
+        Self::with_data(RecordType::Soa, name, RecordData::Soa {
+            mname,
+            rname,
+            serial,
+            refresh,
+            retry,
+            expire,
+            minimum,
+        })
     }
     
     /// Create a new TXT record
     pub fn new_txt(name: String, data: String) -> Self {
-        Self {
-            record_type: RecordType::Txt,
-            name,
-            data: RecordData::Txt(data),
-            ttl: None,
-        }
+        Self::with_data(RecordType::Txt, name, RecordData::Txt(data))
     }
     
     /// Create a new SPF record
     pub fn new_spf(name: String, data: String) -> Self {
-        Self {
-            record_type: RecordType::Spf,
-            name,
-            data: RecordData::Spf(data),
-            ttl: None,
-        }
+        Self::with_data(RecordType::Spf, name, RecordData::Spf(data))
+    }
+    
+    /// Create a new DMARC record from its parsed tags
+    pub fn new_dmarc(name: String, dmarc: DmarcRecord) -> Self {
+        Self::with_data(RecordType::Txt, name, RecordData::Dmarc(dmarc))
     }
     
     /// Create a new PTR record
     pub fn new_ptr(name: String, target: String) -> Self {
-        Self {
-            record_type: RecordType::Ptr,
-            name,
-            data: RecordData::Ptr(target),
-            ttl: None,
-        }
+        Self::with_data(RecordType::Ptr, name, RecordData::Ptr(target))
     }
     
     /// Create a new SRV record
     pub fn new_srv(name: String, priority: u16, weight: u16, port: u16, target: String) -> Self {
-        Self {
-            record_type: RecordType::Srv,
-            name,
-            data: RecordData::Srv {
-                priority,
-                weight,
-                port,
-                target,
-            },
-            ttl: None,
-        }
+        Self::with_data(RecordType::Srv, name, RecordData::Srv {
+            priority,
+            weight,
+            port,
+            target,
+        })
     }
     
     /// Create a new CAA record
     pub fn new_caa(name: String, flags: u8, tag: String, value: String) -> Self {
-        Self {
-            record_type: RecordType::Caa,
-            name,
-            data: RecordData::Caa { flags, tag, value },
-            ttl: None,
-        }
+        Self::with_data(RecordType::Caa, name, RecordData::Caa { flags, tag, value })
     }
     
     /// Create a new CNAME record
     pub fn new_cname(name: String, target: String) -> Self {
-        Self {
-            record_type: RecordType::Cname,
+        Self::with_data(RecordType::Cname, name, RecordData::Cname(target))
+    }
+    
+    /// Create a new SVCB record
+    ///
+    /// `params` maps SvcParam keys such as `alpn` to their presentation values.
+    pub fn new_svcb(name: String, priority: u16, target: String, params: BTreeMap<String, String>) -> Self {
+        Self::with_data(RecordType::Svcb, name, RecordData::Svcb { priority, target, params })
+    }
+    
+    /// Create a new HTTPS record
+    ///
+    /// `params` maps SvcParam keys such as `alpn` to their presentation values.
+    pub fn new_https(name: String, priority: u16, target: String, params: BTreeMap<String, String>) -> Self {
+        Self::with_data(RecordType::Https, name, RecordData::Https { priority, target, params })
+    }
+    
+    /// Create a new DNSKEY record
+    ///
+    /// `public_key` is the key in base64.
+    pub fn new_dnskey(name: String, flags: u16, protocol: u8, algorithm: u8, public_key: String) -> Self {
+        Self::with_data(
+            RecordType::Dnskey,
             name,
-            data: RecordData::Cname(target),
-            ttl: None,
+            RecordData::Dnskey { flags, protocol, algorithm, public_key },
+        )
+    }
+    
+    /// Create a new DS record
+    ///
+    /// `digest` is the digest of the child's DNSKEY in hex.
+    pub fn new_ds(name: String, key_tag: u16, algorithm: u8, digest_type: u8, digest: String) -> Self {
+        Self::with_data(RecordType::Ds, name, RecordData::Ds { key_tag, algorithm, digest_type, digest })
+    }
+    
+    /// Create a new RRSIG record
+    ///
+    /// `expiration` and `inception` are seconds since the Unix epoch and
+    /// `signature` is base64.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_rrsig(
+        name: String,
+        type_covered: String,
+        algorithm: u8,
+        labels: u8,
+        original_ttl: u32,
+        expiration: u32,
+        inception: u32,
+        key_tag: u16,
+        signer_name: String,
+        signature: String,
+    ) -> Self {
+        Self::with_data(RecordType::Rrsig, name, RecordData::Rrsig {
+            type_covered,
+            algorithm,
+            labels,
+            original_ttl,
+            expiration,
+            inception,
+            key_tag,
+            signer_name,
+            signature,
+        })
+    }
+    
+    /// Create a new NSEC record
+    pub fn new_nsec(name: String, next_domain: String, types: Vec<String>) -> Self {
+        Self::with_data(RecordType::Nsec, name, RecordData::Nsec { next_domain, types })
+    }
+    
+    /// Create a record of a type without dedicated support
+    ///
+    /// `rtype` is the type mnemonic and `value` the data in presentation format.
+    pub fn new_other(name: String, rtype: String, value: String) -> Self {
+        let rtype = rtype.to_uppercase();
+        Self::with_data(RecordType::Other(rtype.clone()), name, RecordData::Other { rtype, value })
+    }
+    
+    /// Render the record data as a single string, close to zone file presentation
+    ///
+    /// For example an MX record renders as `10 mail.example.com` and an A
+    /// record as its address.
+    pub fn value_string(&self) -> String {
+        match &self.data {
+            RecordData::A(address) => address.to_string(),
+            RecordData::Aaaa(address) => address.to_string(),
+            RecordData::Mx { preference, exchange } => format!("{} {}", preference, exchange),
+            RecordData::Ns(target) | RecordData::Ptr(target) | RecordData::Cname(target) => target.clone(),
+            RecordData::Soa { mname, rname, serial, refresh, retry, expire, minimum } => {
+                format!("{} {} {} {} {} {} {}", mname, rname, serial, refresh, retry, expire, minimum)
+            },
+            RecordData::Txt(text) | RecordData::Spf(text) => text.clone(),
+            RecordData::Dmarc(dmarc) => dmarc.to_txt(),
+            RecordData::Srv { priority, weight, port, target } => {
+                format!("{} {} {} {}", priority, weight, port, target)
+            },
+            RecordData::Caa { flags, tag, value } => format!("{} {} \"{}\"", flags, tag, value),
+            RecordData::Svcb { priority, target, params } | RecordData::Https { priority, target, params } => {
+                let mut value = format!("{} {}", priority, target);
+                for (key, param) in params {
+                    value.push_str(&format!(" {}={}", key, param));
+                }
+                value
+            },
+            RecordData::Dnskey { flags, protocol, algorithm, public_key } => {
+                format!("{} {} {} {}", flags, protocol, algorithm, public_key)
+            },
+            RecordData::Ds { key_tag, algorithm, digest_type, digest } => {
+                format!("{} {} {} {}", key_tag, algorithm, digest_type, digest)
+            },
+            RecordData::Rrsig {
+                type_covered,
+                algorithm,
+                labels,
+                original_ttl,
+                expiration,
+                inception,
+                key_tag,
+                signer_name,
+                signature,
+            } => format!(
+                "{} {} {} {} {} {} {} {} {}",
+                type_covered, algorithm, labels, original_ttl, expiration, inception, key_tag, signer_name, signature
+            ),
+            RecordData::Nsec { next_domain, types } => format!("{} {}", next_domain, types.join(" ")),
+            RecordData::Other { value, .. } => value.clone(),
         }
     }
-}
\ No newline at end of file
+}
+
+/// Collect the A/AAAA addresses from a set of records
+pub fn record_ips(records: &[DnsRecord]) -> HashSet<IpAddr> {
+    records
+        .iter()
+        .filter_map(|record| match record.data {
+            RecordData::A(ip) => Some(IpAddr::V4(ip)),
+            RecordData::Aaaa(ip) => Some(IpAddr::V6(ip)),
+            _ => None,
+        })
+        .collect()
+}
@@ -1,32 +1,153 @@
 //! DNS resolver functionality
 
-use crate::dns::{record::DnsRecord, DnsError};
+use crate::dns::{dnssec, raw::{self, QueryFlags}, record::{record_ips, wire_type_from_name, wire_type_name, DnsRecord, RecordData, RecordType}, DnsError};
+use crate::utils::generate_testname;
+use crate::utils::validation::NameserverEntry;
+use ipnetwork::IpNetwork;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
+use std::fmt;
 use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use trust_dns_client::client::{Client, SyncClient};
+use trust_dns_client::rr::Name;
+use trust_dns_client::udp::UdpClientConnection;
 use trust_dns_resolver::config::*;
-use trust_dns_resolver::Resolver;
-use trust_dns_resolver::proto::rr::{RData, RecordType as TrustDnsRecordType};
-use tokio::task;
+use trust_dns_resolver::TokioAsyncResolver;
+use trust_dns_resolver::proto::op::{Message, ResponseCode};
+use trust_dns_resolver::proto::rr::{DNSClass, RData, Record, RecordType as TrustDnsRecordType};
+use trust_dns_resolver::proto::rr::rdata::caa::{Value as CaaValue, CAA};
+use trust_dns_resolver::proto::rr::rdata::SVCB;
+use futures_util::future::join_all;
+
+/// Record types resolved together by [`DnsHelper::profile_host`]
+pub const PROFILE_HOST_TYPES: [RecordType; 5] = [
+    RecordType::A,
+    RecordType::Aaaa,
+    RecordType::Mx,
+    RecordType::Txt,
+    RecordType::Cname,
+];
+
+/// Record types resolved together by [`DnsHelper::get_all_records`]
+pub const ALL_RECORD_TYPES: [RecordType; 9] = [
+    RecordType::A,
+    RecordType::Aaaa,
+    RecordType::Mx,
+    RecordType::Ns,
+    RecordType::Soa,
+    RecordType::Txt,
+    RecordType::Spf,
+    RecordType::Caa,
+    RecordType::Cname,
+];
+
+/// Order in which queries try the configured nameservers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NsStrategy {
+    /// Start each query at the next nameserver in turn
+    RoundRobin,
+    /// Shuffle the nameservers before each query
+    Random,
+    /// Always try the nameservers in the order given
+    Sequential,
+}
+
+impl FromStr for NsStrategy {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "round-robin" | "roundrobin" => Ok(NsStrategy::RoundRobin),
+            "random" => Ok(NsStrategy::Random),
+            "sequential" => Ok(NsStrategy::Sequential),
+            _ => Err(format!("Invalid nameserver strategy: {} (expected round-robin, random or sequential)", s)),
+        }
+    }
+}
+
+impl fmt::Display for NsStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NsStrategy::RoundRobin => f.write_str("round-robin"),
+            NsStrategy::Random => f.write_str("random"),
+            NsStrategy::Sequential => f.write_str("sequential"),
+        }
+    }
+}
+
+/// Most CNAME hops followed by [`DnsHelper::resolve_cname_chain`]
+pub const MAX_CNAME_HOPS: usize = 10;
+
+/// Port DNS-over-TLS nameservers listen on (RFC 7858)
+pub const DOT_PORT: u16 = 853;
+
+/// Number of random names resolved when probing a domain for a wildcard
+const WILDCARD_PROBES: usize = 2;
+
+/// Length of the random label used for wildcard probes
+const PROBE_LABEL_LENGTH: usize = 16;
+
+/// Resolvers built for a helper, keyed by the nameserver the rotation starts at
+/// and whether answers skip the cache
+type ResolverCache = Mutex<HashMap<(usize, bool), Arc<TokioAsyncResolver>>>;
 
 /// DNS helper struct for performing DNS queries
+#[derive(Clone)]
 pub struct DnsHelper {
     config: ResolverConfig,
     options: ResolverOpts,
+    ipv4: bool,
+    ipv6: bool,
+    flags: QueryFlags,
+    first_response: bool,
+    client_subnet: Option<IpNetwork>,
+    rotation: Option<Arc<AtomicUsize>>,
+    fresh_answers: bool,
+    resolvers: Arc<ResolverCache>,
 }
 
 impl DnsHelper {
-    /// Create a new DNS helper
+    /// Create a DNS helper querying the nameservers of `config` with default options
+    fn from_config(config: ResolverConfig) -> Self {
+        Self {
+            config,
+            options: ResolverOpts::default(),
+            ipv4: true,
+            ipv6: true,
+            flags: QueryFlags::default(),
+            first_response: false,
+            client_subnet: None,
+            rotation: None,
+            fresh_answers: false,
+            resolvers: Arc::default(),
+        }
+    }
+    
+    /// Create a new DNS helper, ignoring the domain
+    #[deprecated(note = "the domain is unused; use `DnsHelper::default_resolver()`")]
     pub fn new(_domain: String) -> Result<Self, DnsError> {
-        let config = ResolverConfig::default();
-        let options = ResolverOpts::default();
-        Ok(Self { config, options })
+        Self::default_resolver()
+    }
+    
+    /// Create a DNS helper using the default resolver configuration
+    pub fn default_resolver() -> Result<Self, DnsError> {
+        Ok(Self::from_config(ResolverConfig::default()))
     }
     
     /// Create a new DNS helper with custom nameservers
-    pub fn with_nameservers(_domain: String, nameservers: Vec<IpAddr>) -> Result<Self, DnsError> {
+    pub fn with_nameservers(nameservers: Vec<IpAddr>) -> Result<Self, DnsError> {
+        Self::with_nameserver_addrs(nameservers.into_iter().map(|ns| SocketAddr::new(ns, 53)).collect())
+    }
+    
+    /// Create a new DNS helper with custom nameservers listening on any port
+    pub fn with_nameserver_addrs(nameservers: Vec<SocketAddr>) -> Result<Self, DnsError> {
         let mut config = ResolverConfig::new();
         for ns in nameservers {
             config.add_name_server(NameServerConfig {
-                socket_addr: (ns, 53).into(),
+                socket_addr: ns,
                 protocol: trust_dns_resolver::config::Protocol::Udp,
                 tls_dns_name: None,
                 trust_negative_responses: false,
@@ -34,21 +155,77 @@ impl DnsHelper {
             });
         }
         
-        let options = ResolverOpts::default();
-        Ok(Self { config, options })
+        Ok(Self::from_config(config))
+    }
+    
+    /// Create a DNS helper querying nameservers over DNS-over-TLS
+    ///
+    /// `tls_dns_name` is the name the nameservers' certificates are validated
+    /// against, e.g. `cloudflare-dns.com` for 1.1.1.1.
+    pub fn with_tls_nameservers(nameservers: Vec<SocketAddr>, tls_dns_name: impl Into<String>) -> Result<Self, DnsError> {
+        let tls_dns_name = tls_dns_name.into();
+        let mut config = ResolverConfig::new();
+        for ns in nameservers {
+            config.add_name_server(NameServerConfig {
+                socket_addr: ns,
+                protocol: trust_dns_resolver::config::Protocol::Tls,
+                tls_dns_name: Some(tls_dns_name.clone()),
+                trust_negative_responses: false,
+                bind_addr: None,
+            });
+        }
+        
+        Ok(Self::from_config(config))
+    }
+    
+    /// Create a new DNS helper with custom nameservers, ignoring the domain
+    #[deprecated(note = "the domain is unused; use `DnsHelper::with_nameservers(nameservers)`")]
+    pub fn with_domain_and_nameservers(_domain: String, nameservers: Vec<IpAddr>) -> Result<Self, DnsError> {
+        Self::with_nameservers(nameservers)
+    }
+    
+    /// Create a DNS helper for nameservers given with or without a port
+    ///
+    /// A nameserver with its own port is queried over UDP on that port; the
+    /// others get a UDP entry on `udp_port` and a TCP entry on `tcp_port`.
+    pub fn with_nameserver_entries(
+        nameservers: &[NameserverEntry],
+        tcp_port: u16,
+        udp_port: u16,
+    ) -> Result<Self, DnsError> {
+        let mut config = ResolverConfig::new();
+        for ns in nameservers {
+            let endpoints = if ns.port_given {
+                vec![(ns.addr, Protocol::Udp)]
+            } else {
+                vec![(SocketAddr::new(ns.addr.ip(), udp_port), Protocol::Udp), (SocketAddr::new(ns.addr.ip(), tcp_port), Protocol::Tcp)]
+            };
+            for (socket_addr, protocol) in endpoints {
+                config.add_name_server(NameServerConfig {
+                    socket_addr,
+                    protocol,
+                    tls_dns_name: None,
+                    trust_negative_responses: false,
+                    bind_addr: None,
+                });
+            }
+        }
+        
+        Ok(Self::from_config(config))
     }
     
     /// Create a new DNS helper with custom nameservers and ports
+    ///
+    /// Each nameserver gets a UDP entry on `udp_port` and a TCP entry on `tcp_port`.
     pub fn with_nameservers_and_ports(
-        _domain: String,
         nameservers: Vec<IpAddr>,
-        _tcp_port: u16,
-        _udp_port: u16,
+        tcp_port: u16,
+        udp_port: u16,
     ) -> Result<Self, DnsError> {
         let mut config = ResolverConfig::new();
         for ns in nameservers {
             config.add_name_server(NameServerConfig {
-                socket_addr: SocketAddr::new(ns, 53),
+                socket_addr: SocketAddr::new(ns, udp_port),
                 protocol: trust_dns_resolver::config::Protocol::Udp,
                 tls_dns_name: None,
                 trust_negative_responses: false,
@@ -56,7 +233,7 @@ impl DnsHelper {
             });
             
             config.add_name_server(NameServerConfig {
-                socket_addr: SocketAddr::new(ns, 53),
+                socket_addr: SocketAddr::new(ns, tcp_port),
                 protocol: trust_dns_resolver::config::Protocol::Tcp,
                 tls_dns_name: None,
                 trust_negative_responses: false,
@@ -64,317 +241,1197 @@ impl DnsHelper {
             });
         }
         
-        let options = ResolverOpts::default();
-        Ok(Self { config, options })
+        Ok(Self::from_config(config))
     }
     
-    /// Resolve A records for a host
-    pub fn get_a(&self, host: &str) -> Result<Vec<DnsRecord>, DnsError> {
-        let config = self.config.clone();
-        let options = self.options.clone();
-        let host = host.to_string();
+    /// Create a new DNS helper with custom nameservers and ports, ignoring the domain
+    #[deprecated(note = "the domain is unused; use `DnsHelper::with_nameservers_and_ports(nameservers, tcp_port, udp_port)`")]
+    pub fn with_domain_nameservers_and_ports(
+        _domain: String,
+        nameservers: Vec<IpAddr>,
+        tcp_port: u16,
+        udp_port: u16,
+    ) -> Result<Self, DnsError> {
+        Self::with_nameservers_and_ports(nameservers, tcp_port, udp_port)
+    }
+    
+    /// Bind outgoing DNS queries to a local address and source port
+    ///
+    /// The bind address is applied to every configured nameserver of the same
+    /// address family; a port of 0 lets the OS pick an ephemeral source port.
+    pub fn with_bind_addr(mut self, bind_addr: SocketAddr) -> Self {
+        let mut config = ResolverConfig::from_parts(
+            self.config.domain().cloned(),
+            self.config.search().to_vec(),
+            Vec::<NameServerConfig>::new(),
+        );
+        
+        for ns in self.config.name_servers() {
+            let mut ns = ns.clone();
+            if ns.socket_addr.is_ipv4() == bind_addr.is_ipv4() {
+                ns.bind_addr = Some(bind_addr);
+            } else {
+                tracing::debug!("Not binding {} to {}: address family mismatch", ns.socket_addr, bind_addr);
+            }
+            config.add_name_server(ns);
+        }
+        
+        self.config = config;
+        self.resolvers = Arc::default();
+        self
+    }
+    
+    /// Restrict address lookups to the enabled address families
+    ///
+    /// Disabled families are skipped by `get_ip`, `profile_host` and every other
+    /// address lookup that goes through [`DnsHelper::address_types`].
+    pub fn with_address_families(mut self, ipv4: bool, ipv6: bool) -> Self {
+        self.ipv4 = ipv4;
+        self.ipv6 = ipv6;
+        self
+    }
+    
+    /// Race A against AAAA in `get_ip`, keeping whichever answers first
+    ///
+    /// Useful when only knowing that a name resolves matters. The race only
+    /// happens when both address families are enabled and no query flags are set.
+    pub fn with_first_response(mut self, first_response: bool) -> Self {
+        self.first_response = first_response;
+        self
+    }
+    
+    /// Check whether address lookups race A against AAAA
+    pub fn races_addresses(&self) -> bool {
+        self.first_response && self.ipv4 && self.ipv6 && !self.uses_raw_queries()
+    }
+    
+    /// Get the address record types that lookups should query
+    pub fn address_types(&self) -> Vec<RecordType> {
+        let mut types = Vec::new();
+        if self.ipv4 {
+            types.push(RecordType::A);
+        }
+        if self.ipv6 {
+            types.push(RecordType::Aaaa);
+        }
+        types
+    }
+    
+    /// Set the DO and CD flags on outgoing queries
+    ///
+    /// The resolver does not expose these flags, so while any flag is set
+    /// [`DnsHelper::lookup_type`] sends hand-built queries to the configured
+    /// nameservers instead. The DO bit also turns on EDNS in the resolver options.
+    pub fn with_query_flags(mut self, flags: QueryFlags) -> Self {
+        self.flags = flags;
+        self.options.edns0 |= flags.dnssec_ok;
+        self.resolvers = Arc::default();
+        self
+    }
+    
+    /// Get the flags set on outgoing queries
+    pub fn query_flags(&self) -> QueryFlags {
+        self.flags
+    }
+    
+    /// Send an EDNS Client Subnet option with every query
+    ///
+    /// GeoDNS servers then answer as they would for a client in `subnet`. Like
+    /// the query flags, this makes [`DnsHelper::lookup_type`] send hand-built queries.
+    pub fn with_client_subnet(mut self, subnet: Option<IpNetwork>) -> Self {
+        self.client_subnet = subnet;
+        self.options.edns0 |= subnet.is_some();
+        self.resolvers = Arc::default();
+        self
+    }
+    
+    /// Get the client subnet sent with queries
+    pub fn client_subnet(&self) -> Option<IpNetwork> {
+        self.client_subnet
+    }
+    
+    /// Check whether lookups need hand-built queries for options the resolver lacks
+    fn uses_raw_queries(&self) -> bool {
+        !self.flags.is_empty() || self.client_subnet.is_some()
+    }
+    
+    /// Choose the order in which queries try the configured nameservers
+    ///
+    /// Round-robin is done here by rotating the nameserver list between lookups
+    /// (see [`DnsHelper::next_config`]), with one resolver kept per starting nameserver.
+    pub fn with_ns_strategy(mut self, strategy: NsStrategy) -> Self {
+        self.options.shuffle_dns_servers = strategy == NsStrategy::Random;
+        self.options.server_ordering_strategy = match strategy {
+            NsStrategy::Random => ServerOrderingStrategy::QueryStatistics,
+            NsStrategy::RoundRobin | NsStrategy::Sequential => ServerOrderingStrategy::UserProvidedOrder,
+        };
+        self.rotation = match strategy {
+            NsStrategy::RoundRobin => Some(Arc::new(AtomicUsize::new(0))),
+            _ => None,
+        };
+        self.resolvers = Arc::default();
+        self
+    }
+    
+    /// Check whether lookups rotate through the nameservers
+    pub fn rotates_nameservers(&self) -> bool {
+        self.rotation.is_some()
+    }
+    
+    /// Get the resolver configuration for the next lookup
+    ///
+    /// With round-robin, each call starts the nameserver list one server
+    /// further along; otherwise this is the configuration as is.
+    pub fn next_config(&self) -> ResolverConfig {
+        self.config_from(self.next_rotation())
+    }
+    
+    /// Get the resolver for the next lookup, building it on first use
+    ///
+    /// Resolvers are shared by clones of the helper and rebuilt only when a
+    /// builder method changes the configuration. Their connections run on the
+    /// Tokio runtime of the first lookup.
+    pub fn resolver(&self) -> Arc<TokioAsyncResolver> {
+        let start = self.next_rotation();
+        // A lookup that panicked while building a resolver leaves the map usable
+        let mut resolvers = self.resolvers.lock().unwrap_or_else(PoisonError::into_inner);
+        resolvers
+            .entry((start, self.fresh_answers))
+            .or_insert_with(|| {
+                let mut options = self.options;
+                if self.fresh_answers {
+                    options.cache_size = 0;
+                }
+                Arc::new(TokioAsyncResolver::tokio(self.config_from(start), options))
+            })
+            .clone()
+    }
+    
+    /// Index of the nameserver the next lookup starts at
+    fn next_rotation(&self) -> usize {
+        match self.rotation {
+            Some(ref rotation) => {
+                let servers = self.nameserver_ips().len();
+                if servers < 2 {
+                    0
+                } else {
+                    rotation.fetch_add(1, Ordering::Relaxed) % servers
+                }
+            }
+            None => 0,
+        }
+    }
+    
+    /// Resolver configuration with the nameserver list starting at `start`
+    fn config_from(&self, start: usize) -> ResolverConfig {
+        let addrs = self.nameserver_ips();
+        if start == 0 || start >= addrs.len() {
+            return self.config.clone();
+        }
+        
+        let mut config = ResolverConfig::from_parts(
+            self.config.domain().cloned(),
+            self.config.search().to_vec(),
+            Vec::<NameServerConfig>::new(),
+        );
+        // Keep the UDP and TCP entries of a nameserver together, even on different ports
+        for addr in addrs[start..].iter().chain(&addrs[..start]) {
+            for ns in self.config.name_servers() {
+                if ns.socket_addr.ip() == *addr {
+                    config.add_name_server(ns.clone());
+                }
+            }
+        }
+        config
+    }
+    
+    /// Get the resolver configuration used for queries
+    pub fn config(&self) -> &ResolverConfig {
+        &self.config
+    }
+    
+    /// Get the resolver options used for queries
+    pub fn options(&self) -> &ResolverOpts {
+        &self.options
+    }
+    
+    /// Get the distinct nameserver addresses in the configuration
+    pub fn nameserver_ips(&self) -> Vec<IpAddr> {
+        let mut ips = Vec::new();
+        for ns in self.config.name_servers() {
+            let ip = ns.socket_addr.ip();
+            if !ips.contains(&ip) {
+                ips.push(ip);
+            }
+        }
+        ips
+    }
+    
+    /// Get the distinct UDP nameserver addresses that hand-built queries are sent to
+    pub fn udp_nameservers(&self) -> Vec<SocketAddr> {
+        let mut servers = Vec::new();
+        for ns in self.config.name_servers() {
+            if ns.protocol == Protocol::Udp && !servers.contains(&ns.socket_addr) {
+                servers.push(ns.socket_addr);
+            }
+        }
+        servers
+    }
+    
+    /// Create a helper that only queries a single configured nameserver
+    pub fn for_nameserver(&self, nameserver: IpAddr) -> DnsHelper {
+        let mut config = ResolverConfig::new();
+        for ns in self.config.name_servers() {
+            if ns.socket_addr.ip() == nameserver {
+                config.add_name_server(ns.clone());
+            }
+        }
         
-        task::block_in_place(|| {
-            let resolver = Resolver::new(config, options)?;
-            let response = resolver.ipv4_lookup(&host)?;
-            let mut records = Vec::new();
+        DnsHelper {
+            config,
+            options: self.options,
+            ipv4: self.ipv4,
+            ipv6: self.ipv6,
+            flags: self.flags,
+            first_response: self.first_response,
+            client_subnet: self.client_subnet,
+            rotation: None,
+            fresh_answers: self.fresh_answers,
+            resolvers: Arc::default(),
+        }
+    }
+    
+    /// Resolve records of the given type for a name
+    pub async fn lookup_type(&self, name: &str, record_type: RecordType) -> Result<Vec<DnsRecord>, DnsError> {
+        if self.uses_raw_queries() {
+            return self.lookup_with_flags(name, record_type).await;
+        }
+        
+        match record_type {
+            RecordType::A => self.get_a(name).await,
+            RecordType::Aaaa => self.get_aaaa(name).await,
+            RecordType::Mx => self.get_mx(name).await,
+            RecordType::Ns => self.get_ns(name).await,
+            RecordType::Soa => self.get_soa(name).await,
+            RecordType::Spf => self.get_spf(name).await,
+            RecordType::Txt => self.get_txt(name).await,
+            RecordType::Ptr => self.get_ptr(name).await,
+            RecordType::Srv => self.get_srv(name).await,
+            RecordType::Caa => self.get_caa(name).await,
+            RecordType::Cname => self.get_cname(name).await,
+            RecordType::Svcb => self.get_svcb(name).await,
+            RecordType::Https => self.get_https(name).await,
+            RecordType::Dnskey => self.get_dnskey(name).await,
+            RecordType::Ds => self.get_ds(name).await,
+            RecordType::Rrsig => self.get_rrsig(name).await,
+            RecordType::Nsec => self.get_nsec(name).await,
+            RecordType::Other(ref rtype) => self.get_other(name, rtype).await,
+        }
+    }
+    
+    /// Build the query sent for a name and record type, with the query flags and client subnet set
+    ///
+    /// PTR queries for an IP address are sent for its reverse name.
+    pub fn build_query(&self, name: &str, record_type: RecordType) -> Result<Message, DnsError> {
+        let query_name = match (&record_type, name.parse::<IpAddr>()) {
+            (RecordType::Ptr, Ok(ip)) => crate::utils::reverse::reverse_name(ip),
+            _ => name.to_string(),
+        };
+        let mut message = raw::build_query(&query_name, wire_type(&record_type)?, DNSClass::IN)?;
+        self.flags.apply(&mut message);
+        if let Some(subnet) = self.client_subnet {
+            raw::set_client_subnet(&mut message, subnet);
+        }
+        Ok(message)
+    }
+    
+    /// Resolve records with hand-built queries carrying the query flags
+    ///
+    /// UDP nameservers are tried in order until one answers. NXDOMAIN and empty
+    /// answers give no records; other error codes, such as the SERVFAIL a
+    /// validating resolver returns for bogus data without the CD bit, are errors.
+    async fn lookup_with_flags(&self, name: &str, record_type: RecordType) -> Result<Vec<DnsRecord>, DnsError> {
+        let query = self.build_query(name, record_type.clone())?;
+        
+        let mut last_error = DnsError::Other("No UDP nameservers configured".to_string());
+        for server in self.udp_nameservers() {
+            let response = match raw::send_query(&query, server, self.options.timeout).await {
+                Ok(response) => response,
+                Err(e) => {
+                    tracing::debug!("{:?} query for {} to {} failed: {}", record_type, name, server, e);
+                    last_error = e;
+                    continue;
+                }
+            };
             
-            for record in response.iter() {
-                records.push(DnsRecord::new_a(host.clone(), **record));
+            match response.response_code() {
+                ResponseCode::NoError | ResponseCode::NXDomain => {}
+                code => {
+                    return Err(DnsError::Other(format!("{} answered {:?} {} with {}", server, record_type, name, code)));
+                }
+            }
+            
+            let signatures = response
+                .answers()
+                .iter()
+                .filter(|record| record.record_type() == TrustDnsRecordType::RRSIG)
+                .count();
+            if signatures > 0 {
+                tracing::debug!("{} RRSIG records in the answer for {:?} {}", signatures, record_type, name);
             }
             
-            Ok::<Vec<DnsRecord>, DnsError>(records)
+            let wire = wire_type(&record_type)?;
+            let ttl = rrset_ttl(response.answers(), wire);
+            let records = response
+                .answers()
+                .iter()
+                .filter(|record| record.record_type() == wire)
+                .filter_map(|record| record.data().and_then(|data| record_from_rdata(name, data)))
+                .filter_map(|record| match record_type {
+                    // SPF is published as TXT
+                    RecordType::Spf => match record.data {
+                        crate::dns::record::RecordData::Txt(ref txt) if txt.starts_with("v=spf1") => {
+                            Some(DnsRecord::new_spf(record.name.clone(), txt.clone()))
+                        }
+                        _ => None,
+                    },
+                    _ => Some(record),
+                })
+                .map(|record| record.with_ttl(ttl))
+                .collect();
+            return Ok(records);
+        }
+        
+        Err(last_error)
+    }
+    
+    /// Query a CHAOS-class TXT name such as `version.bind` on a specific nameserver
+    ///
+    /// Returns the TXT string, or `None` if the server refused, timed out or
+    /// gave no answer.
+    pub async fn query_chaos(&self, name: &str, server: SocketAddr) -> Option<String> {
+        let query_name = Name::from_ascii(name).ok()?;
+        let timeout = self.options.timeout;
+        
+        // The synchronous client blocks until the server answers or times out
+        let response = tokio::task::spawn_blocking(move || {
+            let conn = UdpClientConnection::with_timeout(server, timeout)?;
+            SyncClient::new(conn).query(&query_name, DNSClass::CH, TrustDnsRecordType::TXT)
         })
+        .await
+        .ok()?;
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::debug!("CHAOS query for {} to {} failed: {}", name, server, e);
+                return None;
+            }
+        };
+        
+        response.answers().iter().find_map(|record| match record.data() {
+            Some(RData::TXT(txt)) => Some(
+                txt.txt_data()
+                    .iter()
+                    .map(|bytes| String::from_utf8_lossy(bytes))
+                    .collect::<Vec<_>>()
+                    .join(""),
+            ),
+            _ => None,
+        })
+    }
+    
+    /// Query a name against each configured nameserver individually
+    ///
+    /// Returns the answer from every nameserver that responded. Nameservers that
+    /// fail to answer are logged and left out of the result.
+    pub async fn query_all_nameservers(&self, name: &str, record_type: RecordType) -> Vec<(IpAddr, Vec<DnsRecord>)> {
+        let mut answers = Vec::new();
+        
+        for nameserver in self.nameserver_ips() {
+            match self.for_nameserver(nameserver).lookup_type(name, record_type.clone()).await {
+                Ok(records) => answers.push((nameserver, records)),
+                Err(e) => {
+                    tracing::debug!("Nameserver {} failed to answer {:?} {}: {}", nameserver, record_type, name, e);
+                }
+            }
+        }
+        
+        answers
+    }
+    
+    /// Resolve A records for a host
+    pub async fn get_a(&self, host: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        let resolver = self.resolver();
+        let host = host.to_string();
+        
+        let response = resolver.ipv4_lookup(&host).await?;
+        let ttl = rrset_ttl(response.as_lookup().record_iter(), TrustDnsRecordType::A);
+        let mut records = Vec::new();
+        
+        for record in response.iter() {
+            records.push(DnsRecord::new_a(host.clone(), **record).with_ttl(ttl));
+        }
+        
+        Ok(records)
     }
     
     /// Resolve AAAA records for a host
-    pub fn get_aaaa(&self, host: &str) -> Result<Vec<DnsRecord>, DnsError> {
-        let config = self.config.clone();
-        let options = self.options.clone();
+    pub async fn get_aaaa(&self, host: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        let resolver = self.resolver();
         let host = host.to_string();
         
-        task::block_in_place(|| {
-            let resolver = Resolver::new(config, options)?;
-            let response = resolver.ipv6_lookup(&host)?;
-            let mut records = Vec::new();
-            
-            for record in response.iter() {
-                records.push(DnsRecord::new_aaaa(host.clone(), **record));
-            }
-            
-            Ok::<Vec<DnsRecord>, DnsError>(records)
-        })
+        let response = resolver.ipv6_lookup(&host).await?;
+        let ttl = rrset_ttl(response.as_lookup().record_iter(), TrustDnsRecordType::AAAA);
+        let mut records = Vec::new();
+        
+        for record in response.iter() {
+            records.push(DnsRecord::new_aaaa(host.clone(), **record).with_ttl(ttl));
+        }
+        
+        Ok(records)
     }
     
     /// Resolve both A and AAAA records
-    pub fn get_ip(&self, hostname: &str) -> Result<Vec<DnsRecord>, DnsError> {
+    ///
+    /// A type whose lookup fails contributes nothing, so a name that does not
+    /// exist gives an empty answer. With [`DnsHelper::with_first_response`] only
+    /// the first non-empty answer is kept, and an error is returned when both
+    /// lookups fail.
+    pub async fn get_ip(&self, hostname: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        if self.races_addresses() {
+            return self.get_ip_first(hostname).await;
+        }
+        
         let mut records = Vec::new();
         
-        // Try A records
-        match self.get_a(hostname) {
-            Ok(a_records) => records.extend(a_records),
-            Err(e) => {
-                // Log error but continue
-                tracing::debug!("Failed to get A records for {}: {}", hostname, e);
+        // Try A and/or AAAA records, depending on the enabled address families
+        for record_type in self.address_types() {
+            match self.lookup_type(hostname, record_type.clone()).await {
+                Ok(address_records) => records.extend(address_records),
+                Err(e) => {
+                    // Log error but continue
+                    tracing::debug!("Failed to get {:?} records for {}: {}", record_type, hostname, e);
+                }
             }
         }
         
-        // Try AAAA records
-        match self.get_aaaa(hostname) {
-            Ok(aaaa_records) => records.extend(aaaa_records),
-            Err(e) => {
-                // Log error but continue
-                tracing::debug!("Failed to get AAAA records for {}: {}", hostname, e);
+        Ok(records)
+    }
+    
+    /// Resolve A and AAAA concurrently on the async resolver and return the first non-empty answer
+    pub async fn get_ip_first(&self, hostname: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        let resolver = self.resolver();
+        
+        let ipv4 = async {
+            let response = resolver.ipv4_lookup(hostname).await?;
+            let ttl = rrset_ttl(response.as_lookup().record_iter(), TrustDnsRecordType::A);
+            Ok(response.iter().map(|record| DnsRecord::new_a(hostname.to_string(), **record).with_ttl(ttl)).collect())
+        };
+        let ipv6 = async {
+            let response = resolver.ipv6_lookup(hostname).await?;
+            let ttl = rrset_ttl(response.as_lookup().record_iter(), TrustDnsRecordType::AAAA);
+            Ok(response.iter().map(|record| DnsRecord::new_aaaa(hostname.to_string(), **record).with_ttl(ttl)).collect())
+        };
+        
+        first_response(ipv4, ipv6).await
+    }
+    
+    /// Resolve A and AAAA records `rounds` times and return the union of the answers
+    ///
+    /// Round-robin and GeoDNS setups hand out different address sets on repeated
+    /// queries, so a single lookup can miss part of a host's footprint.
+    pub async fn get_ip_rotation(&self, hostname: &str, rounds: usize) -> Result<Vec<DnsRecord>, DnsError> {
+        // Cached answers would repeat the first round, so every round queries the nameservers
+        let fresh = DnsHelper { fresh_answers: true, ..self.clone() };
+        collect_rotation(rounds, || fresh.get_ip(hostname)).await
+    }
+    
+    /// Check whether `domain` has a wildcard by resolving random names under it
+    ///
+    /// Returns the sorted A/AAAA answer set when every probe resolves, and
+    /// `None` when any probe has no addresses. Errors from [`DnsHelper::get_ip`]
+    /// are returned as is.
+    pub async fn detect_wildcard(&self, domain: &str) -> Result<Option<Vec<IpAddr>>, DnsError> {
+        let mut wildcard_ips = Vec::new();
+        for _ in 0..WILDCARD_PROBES {
+            let testname = generate_testname(PROBE_LABEL_LENGTH, domain);
+            let ips = record_ips(&self.get_ip(&testname).await?);
+            // A random name without addresses means there is no wildcard
+            if ips.is_empty() {
+                return Ok(None);
             }
+            wildcard_ips.extend(ips);
         }
         
+        wildcard_ips.sort();
+        wildcard_ips.dedup();
+        Ok(Some(wildcard_ips))
+    }
+    
+    /// Resolve the common record types for a host concurrently
+    ///
+    /// Issues A, AAAA, MX, TXT and CNAME queries at once and returns every
+    /// record found. Types without an answer contribute nothing.
+    pub async fn profile_host(&self, name: &str) -> Vec<DnsRecord> {
+        self.lookup_types(name, &PROFILE_HOST_TYPES).await.unwrap_or_default()
+    }
+    
+    /// Resolve every type in [`ALL_RECORD_TYPES`] for a name and merge the answers
+    ///
+    /// The types are queried concurrently; disabled address families are
+    /// skipped. A type that fails is logged and left out, and only when
+    /// every type fails is the last error returned. SPF records are taken
+    /// from the TXT answer rather than queried again.
+    pub async fn get_all_records(&self, name: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        let record_types: Vec<RecordType> = ALL_RECORD_TYPES
+            .into_iter()
+            .filter(|record_type| *record_type != RecordType::Spf)
+            .collect();
+        let mut records = self.lookup_types(name, &record_types).await?;
+        
+        // Keep the SPF records next to the TXT records they were read from
+        let spf = spf_from_txt(&records);
+        let after_txt = records
+            .iter()
+            .rposition(|record| record.record_type == RecordType::Txt)
+            .map_or(records.len(), |index| index + 1);
+        records.splice(after_txt..after_txt, spf);
         Ok(records)
     }
     
+    /// Resolve several record types for a name concurrently and merge the answers
+    ///
+    /// Disabled address families are skipped. A type that fails is logged and
+    /// left out, and only when every type fails is the last error returned.
+    async fn lookup_types(&self, name: &str, record_types: &[RecordType]) -> Result<Vec<DnsRecord>, DnsError> {
+        let record_types: Vec<&RecordType> = record_types
+            .iter()
+            .filter(|record_type| match record_type {
+                RecordType::A => self.ipv4,
+                RecordType::Aaaa => self.ipv6,
+                _ => true,
+            })
+            .collect();
+        
+        let responses = join_all(record_types.iter().map(|record_type| self.lookup_type(name, (*record_type).clone()))).await;
+        let mut records = Vec::new();
+        let mut last_error = None;
+        let mut answered = false;
+        
+        for (record_type, response) in record_types.iter().zip(responses) {
+            match response {
+                Ok(found) => {
+                    answered = true;
+                    records.extend(found);
+                },
+                Err(e) => {
+                    tracing::debug!("Failed to get {:?} records for {}: {}", record_type, name, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        
+        match last_error {
+            Some(e) if !answered => Err(e),
+            _ => Ok(records),
+        }
+    }
+    
     /// Resolve MX records for the domain
-    pub fn get_mx(&self, domain: &str) -> Result<Vec<DnsRecord>, DnsError> {
-        let config = self.config.clone();
-        let options = self.options.clone();
+    pub async fn get_mx(&self, domain: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        let resolver = self.resolver();
         let domain = domain.to_string();
         
-        task::block_in_place(|| {
-            let resolver = Resolver::new(config, options)?;
-            let response = resolver.mx_lookup(&domain)?;
-            let mut records = Vec::new();
-            
-            for record in response.iter() {
-                let exchange = record.exchange().to_string();
-                // Remove the trailing dot if present
-                let exchange = exchange.trim_end_matches('.').to_string();
-                records.push(DnsRecord::new_mx(
-                    domain.clone(),
-                    record.preference(),
-                    exchange,
-                ));
-            }
-            
-            Ok::<Vec<DnsRecord>, DnsError>(records)
-        })
+        let response = resolver.mx_lookup(&domain).await?;
+        let ttl = rrset_ttl(response.as_lookup().record_iter(), TrustDnsRecordType::MX);
+        let mut records = Vec::new();
+        
+        for record in response.iter() {
+            let exchange = record.exchange().to_string();
+            // Remove the trailing dot if present
+            let exchange = exchange.trim_end_matches('.').to_string();
+            records.push(DnsRecord::new_mx(
+                domain.clone(),
+                record.preference(),
+                exchange,
+            ).with_ttl(ttl));
+        }
+        
+        Ok(records)
     }
     
     /// Resolve NS records for the domain
-    pub fn get_ns(&self, domain: &str) -> Result<Vec<DnsRecord>, DnsError> {
-        let config = self.config.clone();
-        let options = self.options.clone();
+    pub async fn get_ns(&self, domain: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        let resolver = self.resolver();
         let domain = domain.to_string();
         
-        task::block_in_place(|| {
-            let resolver = Resolver::new(config, options)?;
-            let response = resolver.ns_lookup(&domain)?;
-            let mut records = Vec::new();
-            
-            for record in response.iter() {
-                let nameserver = record.to_string();
-                // Remove the trailing dot if present
-                let nameserver = nameserver.trim_end_matches('.').to_string();
-                records.push(DnsRecord::new_ns(domain.clone(), nameserver));
-            }
-            
-            Ok::<Vec<DnsRecord>, DnsError>(records)
-        })
+        let response = resolver.ns_lookup(&domain).await?;
+        let ttl = rrset_ttl(response.as_lookup().record_iter(), TrustDnsRecordType::NS);
+        let mut records = Vec::new();
+        
+        for record in response.iter() {
+            let nameserver = record.to_string();
+            // Remove the trailing dot if present
+            let nameserver = nameserver.trim_end_matches('.').to_string();
+            records.push(DnsRecord::new_ns(domain.clone(), nameserver).with_ttl(ttl));
+        }
+        
+        Ok(records)
     }
     
     /// Resolve SOA records for the domain
-    pub fn get_soa(&self, domain: &str) -> Result<Vec<DnsRecord>, DnsError> {
-        let config = self.config.clone();
-        let options = self.options.clone();
+    pub async fn get_soa(&self, domain: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        let resolver = self.resolver();
         let domain = domain.to_string();
         
-        task::block_in_place(|| {
-            let resolver = Resolver::new(config, options)?;
-            let response = resolver.soa_lookup(&domain)?;
-            let mut records = Vec::new();
-            
-            for record in response.iter() {
-                let mname = record.mname().to_string();
-                let mname = mname.trim_end_matches('.').to_string();
-                let rname = record.rname().to_string();
-                let rname = rname.trim_end_matches('.').to_string();
-                
-                records.push(DnsRecord::new_soa(
-                    domain.clone(),
-                    mname,
-                    rname,
-                    record.serial().try_into().unwrap_or(0),
-                    record.refresh().try_into().unwrap_or(0),
-                    record.retry().try_into().unwrap_or(0),
-                    record.expire().try_into().unwrap_or(0),
-                    record.minimum().try_into().unwrap_or(0),
-                ));
-            }
+        let response = resolver.soa_lookup(&domain).await?;
+        let ttl = rrset_ttl(response.as_lookup().record_iter(), TrustDnsRecordType::SOA);
+        let mut records = Vec::new();
+        
+        for record in response.iter() {
+            let mname = record.mname().to_string();
+            let mname = mname.trim_end_matches('.').to_string();
+            let rname = record.rname().to_string();
+            let rname = rname.trim_end_matches('.').to_string();
             
-            Ok::<Vec<DnsRecord>, DnsError>(records)
-        })
+            records.push(DnsRecord::new_soa(
+                domain.clone(),
+                mname,
+                rname,
+                record.serial().try_into().unwrap_or(0),
+                record.refresh().try_into().unwrap_or(0),
+                record.retry().try_into().unwrap_or(0),
+                record.expire().try_into().unwrap_or(0),
+                record.minimum().try_into().unwrap_or(0),
+            ).with_ttl(ttl));
+        }
+        
+        Ok(records)
     }
     
     /// Resolve TXT records for the domain
-    pub fn get_txt(&self, domain: &str) -> Result<Vec<DnsRecord>, DnsError> {
-        let config = self.config.clone();
-        let options = self.options.clone();
+    pub async fn get_txt(&self, domain: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        let resolver = self.resolver();
         let domain = domain.to_string();
         
-        task::block_in_place(|| {
-            let resolver = Resolver::new(config, options)?;
-            let response = resolver.txt_lookup(&domain)?;
-            let mut records = Vec::new();
-            
-            for record in response.iter() {
-                let txt_data = record.txt_data();
-                // Join all TXT data parts into a single string
-                let data = txt_data
-                    .iter()
-                    .map(|bytes| String::from_utf8_lossy(bytes))
-                    .collect::<Vec<_>>()
-                    .join("");
-                
-                records.push(DnsRecord::new_txt(domain.clone(), data));
-            }
+        let response = resolver.txt_lookup(&domain).await?;
+        let ttl = rrset_ttl(response.as_lookup().record_iter(), TrustDnsRecordType::TXT);
+        let mut records = Vec::new();
+        
+        for record in response.iter() {
+            let txt_data = record.txt_data();
+            // Join all TXT data parts into a single string
+            let data = txt_data
+                .iter()
+                .map(|bytes| String::from_utf8_lossy(bytes))
+                .collect::<Vec<_>>()
+                .join("");
             
-            Ok::<Vec<DnsRecord>, DnsError>(records)
-        })
+            records.push(DnsRecord::new_txt(domain.clone(), data).with_ttl(ttl));
+        }
+        
+        Ok(records)
     }
     
-    /// Resolve SPF records for the domain
-    pub fn get_spf(&self, domain: &str) -> Result<Vec<DnsRecord>, DnsError> {
-        // SPF records are stored as TXT records with a specific format
-        let txt_records = self.get_txt(domain)?;
-        let mut spf_records = Vec::new();
-        
-        for record in txt_records {
-            if let DnsRecord {
-                record_type: crate::dns::record::RecordType::Txt,
-                name,
-                data: crate::dns::record::RecordData::Txt(data),
-                ..
-            } = record {
-                if data.starts_with("v=spf1") {
-                    spf_records.push(DnsRecord::new_spf(name, data));
-                }
-            }
-        }
+    /// Resolve the DMARC policy of the domain from the TXT records at `_dmarc.<domain>`
+    ///
+    /// TXT strings that aren't `v=DMARC1` records are skipped.
+    pub async fn get_dmarc(&self, domain: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        let dmarc_name = format!("_dmarc.{}", domain.trim_end_matches('.'));
+        let txt_records = if self.uses_raw_queries() {
+            self.lookup_with_flags(&dmarc_name, RecordType::Txt).await?
+        } else {
+            self.get_txt(&dmarc_name).await?
+        };
         
-        Ok(spf_records)
+        Ok(txt_records
+            .into_iter()
+            .filter_map(|record| match record.data {
+                RecordData::Txt(ref data) => crate::dns::dmarc::parse_dmarc(data)
+                    .map(|dmarc| DnsRecord::new_dmarc(record.name.clone(), dmarc).with_ttl(record.ttl)),
+                _ => None,
+            })
+            .collect())
+    }
+    
+    /// Resolve SPF records for the domain
+    pub async fn get_spf(&self, domain: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        Ok(spf_from_txt(&self.get_txt(domain).await?))
     }
     
     /// Resolve PTR records for an IP address
-    pub fn get_ptr(&self, ip: &str) -> Result<Vec<DnsRecord>, DnsError> {
-        let config = self.config.clone();
-        let options = self.options.clone();
-        let ip = ip.to_string();
-        
-        task::block_in_place(|| {
-            let resolver = Resolver::new(config, options)?;
-            let response = resolver.reverse_lookup(ip.parse()?)?;
-            let mut records = Vec::new();
-            
-            for record in response.iter() {
-                let target = record.to_string();
-                // Remove the trailing dot if present
-                let target = target.trim_end_matches('.').to_string();
-                records.push(DnsRecord::new_ptr(ip.clone(), target));
+    pub async fn get_ptr(&self, ip: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        let ip: IpAddr = ip.parse()?;
+        self.get_ptr_name(&crate::utils::reverse::reverse_name(ip)).await
+    }
+    
+    /// Resolve PTR records by reverse query name (e.g. `1.2.0.192.in-addr.arpa`)
+    ///
+    /// Records are named after the address the query name encodes, like
+    /// [`DnsHelper::get_ptr`] does.
+    pub async fn get_ptr_name(&self, reverse_name: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        let resolver = self.resolver();
+        let reverse_name = reverse_name.to_string();
+        let owner = crate::utils::reverse::ip_from_reverse_name(&reverse_name)
+            .map(|ip| ip.to_string())
+            .unwrap_or_else(|| reverse_name.clone());
+        
+        let response = resolver.lookup(&reverse_name, TrustDnsRecordType::PTR).await?;
+        let ttl = rrset_ttl(response.record_iter(), TrustDnsRecordType::PTR);
+        let mut records = Vec::new();
+        
+        for record in response.record_iter() {
+            if let Some(RData::PTR(ref ptr)) = record.data() {
+                let target = ptr.to_string().trim_end_matches('.').to_string();
+                records.push(DnsRecord::new_ptr(owner.clone(), target).with_ttl(ttl));
             }
-            
-            Ok::<Vec<DnsRecord>, DnsError>(records)
-        })
+        }
+        
+        Ok(records)
     }
     
     /// Resolve SRV records for a service
-    pub fn get_srv(&self, service: &str) -> Result<Vec<DnsRecord>, DnsError> {
-        let config = self.config.clone();
-        let options = self.options.clone();
+    pub async fn get_srv(&self, service: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        let resolver = self.resolver();
         let service = service.to_string();
         
-        task::block_in_place(|| {
-            let resolver = Resolver::new(config, options)?;
-            let response = resolver.srv_lookup(&service)?;
-            let mut records = Vec::new();
-            
-            for record in response.iter() {
-                let target = record.target().to_string();
-                // Remove the trailing dot if present
-                let target = target.trim_end_matches('.').to_string();
-                
-                records.push(DnsRecord::new_srv(
-                    service.clone(),
-                    record.priority(),
-                    record.weight(),
-                    record.port(),
-                    target,
-                ));
-            }
+        let response = resolver.srv_lookup(&service).await?;
+        let ttl = rrset_ttl(response.as_lookup().record_iter(), TrustDnsRecordType::SRV);
+        let mut records = Vec::new();
+        
+        for record in response.iter() {
+            let target = record.target().to_string();
+            // Remove the trailing dot if present
+            let target = target.trim_end_matches('.').to_string();
             
-            Ok::<Vec<DnsRecord>, DnsError>(records)
-        })
+            records.push(DnsRecord::new_srv(
+                service.clone(),
+                record.priority(),
+                record.weight(),
+                record.port(),
+                target,
+            ).with_ttl(ttl));
+        }
+        
+        Ok(records)
     }
     
     /// Resolve CAA records for the domain
-    pub fn get_caa(&self, domain: &str) -> Result<Vec<DnsRecord>, DnsError> {
-        let config = self.config.clone();
-        let options = self.options.clone();
+    pub async fn get_caa(&self, domain: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        let resolver = self.resolver();
         let domain = domain.to_string();
         let record_type = TrustDnsRecordType::CAA;
         
-        task::block_in_place(|| {
-            let resolver = Resolver::new(config, options)?;
-            // For CAA records, we need to do a raw query since trust-dns doesn't have a direct method
-            match resolver.lookup(&domain, record_type) {
-                Ok(response) => {
-                    let mut records = Vec::new();
-                    
-                    for record in response.record_iter() {
-                        if let Some(RData::CAA(ref caa)) = record.data() {
-                            // For now, let's create a simple representation using debug formatting
-                            let caa_str = format!("{:?}", caa);
-                            
-                            records.push(DnsRecord::new_txt(
-                                domain.clone(),
-                                caa_str,
-                            ));
-                        }
-                    }
-                    
-                    Ok::<Vec<DnsRecord>, DnsError>(records)
-                },
-                Err(e) => {
-                    // If no CAA records are found, that's not an error - just return empty vec
-                    if e.to_string().contains("no record found") {
-                        Ok(Vec::new())
-                    } else {
-                        Err(e.into())
+        // For CAA records, we need to do a raw query since trust-dns doesn't have a direct method
+        match resolver.lookup(&domain, record_type).await {
+            Ok(response) => {
+                let ttl = rrset_ttl(response.record_iter(), record_type);
+                let mut records = Vec::new();
+                
+                for record in response.record_iter() {
+                    if let Some(RData::CAA(ref caa)) = record.data() {
+                        records.push(caa_record(&domain, caa).with_ttl(ttl));
                     }
                 }
+                
+                Ok(records)
+            },
+            Err(e) => {
+                // If no CAA records are found, that's not an error - just return empty vec
+                if e.to_string().contains("no record found") {
+                    Ok(Vec::new())
+                } else {
+                    Err(e.into())
+                }
+            }
+        }
+    }
+    
+    /// Follow the CNAME chain of a host down to its addresses
+    ///
+    /// See [`follow_cname_chain`]; a target that does not exist marks the
+    /// CNAME pointing at it as dangling.
+    pub async fn resolve_cname_chain(&self, host: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        follow_cname_chain(host, |name| async move {
+            match self.get_cname(&name).await {
+                Ok(cnames) if !cnames.is_empty() => Ok(cnames),
+                Err(e) if e.is_nxdomain() => Err(e),
+                // The name exists but is not an alias
+                _ => self.get_ip(&name).await,
             }
         })
+        .await
     }
     
     /// Resolve CNAME records for a host
-    pub fn get_cname(&self, host: &str) -> Result<Vec<DnsRecord>, DnsError> {
-        let config = self.config.clone();
-        let options = self.options.clone();
+    pub async fn get_cname(&self, host: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        let resolver = self.resolver();
         let host = host.to_string();
         let record_type = TrustDnsRecordType::CNAME;
         
-        task::block_in_place(|| {
-            let resolver = Resolver::new(config, options)?;
-            let response = resolver.lookup(&host, record_type)?;
-            let mut records = Vec::new();
-            
-            for record in response.record_iter() {
-                if let Some(RData::CNAME(ref cname)) = record.data() {
-                    let target = cname.to_string();
-                    // Remove the trailing dot if present
-                    let target = target.trim_end_matches('.').to_string();
-                    
-                    records.push(DnsRecord::new_cname(host.clone(), target));
-                }
+        let response = resolver.lookup(&host, record_type).await?;
+        let ttl = rrset_ttl(response.record_iter(), record_type);
+        let mut records = Vec::new();
+        
+        for record in response.record_iter() {
+            if let Some(RData::CNAME(ref cname)) = record.data() {
+                let target = cname.to_string();
+                // Remove the trailing dot if present
+                let target = target.trim_end_matches('.').to_string();
+                
+                records.push(DnsRecord::new_cname(host.clone(), target).with_ttl(ttl));
             }
-            
-            Ok::<Vec<DnsRecord>, DnsError>(records)
+        }
+        
+        Ok(records)
+    }
+    
+    /// Resolve SVCB records for a host
+    pub async fn get_svcb(&self, host: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        self.get_converted(host, TrustDnsRecordType::SVCB).await
+    }
+    
+    /// Resolve HTTPS records for a host
+    ///
+    /// HTTPS records advertise ALPN protocols (e.g. `h3`), address hints and ECH
+    /// configuration for the host's HTTPS endpoints.
+    pub async fn get_https(&self, host: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        self.get_converted(host, TrustDnsRecordType::HTTPS).await
+    }
+    
+    /// Resolve the DNSKEY records of a zone
+    pub async fn get_dnskey(&self, zone: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        self.get_converted(zone, TrustDnsRecordType::DNSKEY).await
+    }
+    
+    /// Resolve the DS records a parent zone publishes for a child zone
+    pub async fn get_ds(&self, zone: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        self.get_converted(zone, TrustDnsRecordType::DS).await
+    }
+    
+    /// Resolve the RRSIG records of a name
+    ///
+    /// Each signature covers one RRset of the name; its `type_covered` says which.
+    pub async fn get_rrsig(&self, name: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        self.get_converted(name, TrustDnsRecordType::RRSIG).await
+    }
+    
+    /// Resolve the NSEC record of a name
+    pub async fn get_nsec(&self, name: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        self.get_converted(name, TrustDnsRecordType::NSEC).await
+    }
+    
+    /// Resolve records of a type without dedicated support, such as `HINFO`
+    ///
+    /// Answers the crate has no record format for are returned as
+    /// [`RecordData::Other`](crate::dns::record::RecordData::Other) records.
+    pub async fn get_other(&self, name: &str, rtype: &str) -> Result<Vec<DnsRecord>, DnsError> {
+        let record_type = wire_type(&RecordType::Other(rtype.to_string()))?;
+        let resolver = self.resolver();
+        let name = name.to_string();
+        
+        let response = resolver.lookup(&name, record_type).await?;
+        let ttl = rrset_ttl(response.record_iter(), record_type);
+        let records = response
+            .record_iter()
+            .filter(|record| record.record_type() == record_type)
+            .filter_map(|record| record.data().and_then(|data| record_from_rdata(&name, data)))
+            .map(|record| record.with_ttl(ttl))
+            .collect();
+        
+        Ok(records)
+    }
+    
+    /// Resolve records of the given type for a host, converting each answer to our record format
+    async fn get_converted(&self, host: &str, record_type: TrustDnsRecordType) -> Result<Vec<DnsRecord>, DnsError> {
+        let resolver = self.resolver();
+        let host = host.to_string();
+        
+        let response = resolver.lookup(&host, record_type).await?;
+        let ttl = rrset_ttl(response.record_iter(), record_type);
+        let records = response
+            .record_iter()
+            .filter(|record| record.record_type() == record_type)
+            .filter_map(|record| record.data().and_then(|data| record_from_rdata(&host, data)))
+            .map(|record| record.with_ttl(ttl))
+            .collect();
+        
+        Ok(records)
+    }
+}
+
+/// TTL of an RRset: the lowest TTL among the answers of the given type
+pub fn rrset_ttl<'a>(records: impl IntoIterator<Item = &'a Record>, record_type: TrustDnsRecordType) -> Option<u32> {
+    records
+        .into_iter()
+        .filter(|record| record.record_type() == record_type)
+        .map(|record| record.ttl())
+        .min()
+}
+
+/// SPF records published among TXT records, keeping their names and TTLs
+fn spf_from_txt(records: &[DnsRecord]) -> Vec<DnsRecord> {
+    records
+        .iter()
+        .filter_map(|record| match record.data {
+            crate::dns::record::RecordData::Txt(ref txt) if txt.starts_with("v=spf1") => {
+                Some(DnsRecord::new_spf(record.name.clone(), txt.clone()).with_ttl(record.ttl))
+            }
+            _ => None,
         })
+        .collect()
+}
+
+/// Target of an SVCB-style record, keeping `.` (the owner name itself) as is
+fn svc_target(svcb: &SVCB) -> String {
+    let target = svcb.target_name().to_string();
+    if target == "." {
+        target
+    } else {
+        target.trim_end_matches('.').to_string()
+    }
+}
+
+/// Convert a CAA answer into a record with its flags, property tag and value
+///
+/// The issuer-critical bit becomes flags `128`. Issuer values are written as
+/// `ca.example.net; key=value`, with `;` alone forbidding issuance; iodef
+/// values are their URL.
+pub fn caa_record(name: &str, caa: &CAA) -> DnsRecord {
+    let flags = if caa.issuer_critical() { 128 } else { 0 };
+    let value = match caa.value() {
+        CaaValue::Issuer(None, params) if params.is_empty() => ";".to_string(),
+        CaaValue::Issuer(issuer, params) => {
+            let mut value = issuer
+                .as_ref()
+                .map(|issuer| issuer.to_string().trim_end_matches('.').to_string())
+                .unwrap_or_default();
+            for param in params {
+                value.push_str(&format!("; {}", param));
+            }
+            value
+        },
+        CaaValue::Url(url) => url.to_string(),
+        CaaValue::Unknown(bytes) => String::from_utf8_lossy(bytes).into_owned(),
+    };
+    DnsRecord::new_caa(name.to_string(), flags, caa.tag().as_str().to_string(), value)
+}
+
+/// Convert SvcParams to presentation-format key/value pairs
+fn svc_params(svcb: &SVCB) -> BTreeMap<String, String> {
+    svcb.svc_params()
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string().trim_end_matches(',').to_string()))
+        .collect()
+}
+
+/// Wire record type queried for one of our record types
+fn wire_type(record_type: &RecordType) -> Result<TrustDnsRecordType, DnsError> {
+    Ok(match record_type {
+        RecordType::A => TrustDnsRecordType::A,
+        RecordType::Aaaa => TrustDnsRecordType::AAAA,
+        RecordType::Mx => TrustDnsRecordType::MX,
+        RecordType::Ns => TrustDnsRecordType::NS,
+        RecordType::Soa => TrustDnsRecordType::SOA,
+        RecordType::Spf | RecordType::Txt => TrustDnsRecordType::TXT,
+        RecordType::Ptr => TrustDnsRecordType::PTR,
+        RecordType::Srv => TrustDnsRecordType::SRV,
+        RecordType::Caa => TrustDnsRecordType::CAA,
+        RecordType::Cname => TrustDnsRecordType::CNAME,
+        RecordType::Svcb => TrustDnsRecordType::SVCB,
+        RecordType::Https => TrustDnsRecordType::HTTPS,
+        RecordType::Dnskey => TrustDnsRecordType::DNSKEY,
+        RecordType::Ds => TrustDnsRecordType::DS,
+        RecordType::Rrsig => TrustDnsRecordType::RRSIG,
+        RecordType::Nsec => TrustDnsRecordType::NSEC,
+        RecordType::Other(rtype) => wire_type_from_name(rtype)
+            .ok_or_else(|| DnsError::InvalidRecord(format!("Unknown record type {}", rtype)))?,
+    })
+}
+
+/// Convert the data of an answer to our record format
+fn record_from_rdata(name: &str, data: &RData) -> Option<DnsRecord> {
+    let name = name.to_string();
+    match data {
+        RData::A(ref ipv4) => Some(DnsRecord::new_a(name, **ipv4)),
+        RData::AAAA(ref ipv6) => Some(DnsRecord::new_aaaa(name, **ipv6)),
+        RData::MX(ref mx) => {
+            let exchange = mx.exchange().to_string().trim_end_matches('.').to_string();
+            Some(DnsRecord::new_mx(name, mx.preference(), exchange))
+        },
+        RData::TXT(ref txt) => {
+            // Join all TXT data parts into a single string
+            let data = txt
+                .txt_data()
+                .iter()
+                .map(|bytes| String::from_utf8_lossy(bytes))
+                .collect::<Vec<_>>()
+                .join("");
+            Some(DnsRecord::new_txt(name, data))
+        },
+        RData::CNAME(ref cname) => {
+            let target = cname.to_string().trim_end_matches('.').to_string();
+            Some(DnsRecord::new_cname(name, target))
+        },
+        RData::NS(ref ns) => {
+            let nameserver = ns.to_string().trim_end_matches('.').to_string();
+            Some(DnsRecord::new_ns(name, nameserver))
+        },
+        RData::PTR(ref ptr) => {
+            let target = ptr.to_string().trim_end_matches('.').to_string();
+            Some(DnsRecord::new_ptr(name, target))
+        },
+        RData::SOA(ref soa) => Some(DnsRecord::new_soa(
+            name,
+            soa.mname().to_string().trim_end_matches('.').to_string(),
+            soa.rname().to_string().trim_end_matches('.').to_string(),
+            soa.serial(),
+            soa.refresh().try_into().unwrap_or(0),
+            soa.retry().try_into().unwrap_or(0),
+            soa.expire().try_into().unwrap_or(0),
+            soa.minimum(),
+        )),
+        RData::SRV(ref srv) => Some(DnsRecord::new_srv(
+            name,
+            srv.priority(),
+            srv.weight(),
+            srv.port(),
+            srv.target().to_string().trim_end_matches('.').to_string(),
+        )),
+        RData::CAA(ref caa) => Some(caa_record(&name, caa)),
+        RData::SVCB(ref svcb) => {
+            Some(DnsRecord::new_svcb(name, svcb.svc_priority(), svc_target(svcb), svc_params(svcb)))
+        },
+        RData::HTTPS(ref https) => {
+            Some(DnsRecord::new_https(name, https.0.svc_priority(), svc_target(&https.0), svc_params(&https.0)))
+        },
+        // DNSSEC types come as wire data, see `dnssec`
+        RData::Unknown { code, rdata } => dnssec::record_from_wire(&name, TrustDnsRecordType::from(*code), rdata.anything())
+            .or_else(|| Some(DnsRecord::new_other(name, wire_type_name(data.record_type()), data.to_string()))),
+        // Keep the real type and presentation-format data of everything else
+        other => Some(DnsRecord::new_other(name, other.record_type().to_string(), other.to_string())),
+    }
+}
+
+/// Race two lookups and return the first non-empty answer
+///
+/// When neither lookup finds records the answer is empty, unless both failed,
+/// in which case the error of the one that finished last is returned.
+pub async fn first_response<A, B>(a: A, b: B) -> Result<Vec<DnsRecord>, DnsError>
+where
+    A: Future<Output = Result<Vec<DnsRecord>, DnsError>>,
+    B: Future<Output = Result<Vec<DnsRecord>, DnsError>>,
+{
+    tokio::pin!(a);
+    tokio::pin!(b);
+    let (mut a_done, mut b_done) = (false, false);
+    let mut found_nothing = false;
+    let mut last_error = None;
+    
+    while !(a_done && b_done) {
+        let result = tokio::select! {
+            result = &mut a, if !a_done => {
+                a_done = true;
+                result
+            },
+            result = &mut b, if !b_done => {
+                b_done = true;
+                result
+            },
+        };
+        
+        match result {
+            Ok(records) if !records.is_empty() => return Ok(records),
+            Ok(_) => found_nothing = true,
+            Err(e) => {
+                tracing::debug!("Address lookup failed: {}", e);
+                last_error = Some(e);
+            }
+        }
     }
-}
\ No newline at end of file
+    
+    match last_error {
+        Some(e) if !found_nothing => Err(e),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Follow a CNAME chain from `host`, looking up each name with `lookup`
+///
+/// `lookup` returns the CNAME of a name, or its addresses when it has none.
+/// Every CNAME hop is returned in order, followed by the addresses of the
+/// last target. When a target is NXDOMAIN the CNAME pointing at it is marked
+/// [`dangling`](DnsRecord::dangling). The walk stops at a loop or after
+/// [`MAX_CNAME_HOPS`] hops; only a failure to look up `host` itself is an error.
+pub async fn follow_cname_chain<F, Fut>(host: &str, mut lookup: F) -> Result<Vec<DnsRecord>, DnsError>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<Vec<DnsRecord>, DnsError>>,
+{
+    let normalize = |name: &str| name.trim_end_matches('.').to_lowercase();
+    let mut seen = HashSet::from([normalize(host)]);
+    let mut chain: Vec<DnsRecord> = Vec::new();
+    let mut current = host.to_string();
+    
+    loop {
+        let records = match lookup(current.clone()).await {
+            Ok(records) => records,
+            Err(e) if chain.is_empty() => return Err(e),
+            Err(e) => {
+                if e.is_nxdomain() {
+                    if let Some(last) = chain.last_mut() {
+                        last.dangling = true;
+                    }
+                } else {
+                    tracing::debug!("Failed to follow the CNAME chain of {} at {}: {}", host, current, e);
+                }
+                break;
+            }
+        };
+        
+        let cname = records.iter().find_map(|record| match &record.data {
+            RecordData::Cname(target) => Some((target.clone(), record.clone())),
+            _ => None,
+        });
+        let Some((target, cname)) = cname else {
+            chain.extend(records);
+            break;
+        };
+        chain.push(cname);
+        
+        if chain.len() >= MAX_CNAME_HOPS {
+            tracing::debug!("CNAME chain of {} is longer than {} hops", host, MAX_CNAME_HOPS);
+            break;
+        }
+        if !seen.insert(normalize(&target)) {
+            tracing::debug!("CNAME chain of {} loops at {}", host, target);
+            break;
+        }
+        current = target;
+    }
+    
+    Ok(chain)
+}
+
+/// Run `lookup` `rounds` times (at least once) and return the union of its records
+///
+/// Records are kept in the order they were first seen; repeats across rounds are
+/// dropped regardless of TTL.
+pub async fn collect_rotation<F, Fut>(rounds: usize, mut lookup: F) -> Result<Vec<DnsRecord>, DnsError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Vec<DnsRecord>, DnsError>>,
+{
+    let mut seen = HashSet::new();
+    let mut records = Vec::new();
+    
+    for _ in 0..rounds.max(1) {
+        for record in lookup().await? {
+            if seen.insert(format!("{}|{:?}", record.name.to_lowercase(), record.data)) {
+                records.push(record);
+            }
+        }
+    }
+    
+    Ok(records)
+}
@@ -1,48 +1,96 @@
 //! Zone transfer functionality
 
-use crate::dns::{record::DnsRecord, DnsError};
+use crate::dns::{record::{DnsRecord, RecordData}, resolver::DnsHelper, DnsError};
 use trust_dns_client::client::{Client, SyncClient};
+use trust_dns_client::error::{ClientError, ClientErrorKind};
+use trust_dns_client::proto::error::ProtoErrorKind;
 use trust_dns_client::tcp::TcpClientConnection;
 use trust_dns_client::rr::{Name, RecordType, RData};
-use std::net::SocketAddr;
+use trust_dns_client::op::ResponseCode;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+use futures_util::future::join_all;
+use tokio::task;
+
+/// How long to wait for a nameserver when checking whether it allows AXFR
+const AXFR_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Outcome of an AXFR attempt against one nameserver
+#[derive(Debug, Clone)]
+pub enum AxfrStatus {
+    /// The nameserver handed out the zone
+    Allowed(Vec<DnsRecord>),
+    /// The nameserver refused the transfer or the TCP connection
+    Refused,
+    /// The attempt failed for another reason, such as a timeout
+    Error(String),
+}
 
 /// Attempt zone transfer from a nameserver
-pub fn zone_transfer(domain: &str, nameserver: &str) -> Result<Vec<DnsRecord>, DnsError> {
-    // Parse the domain name
-    let name = Name::from_ascii(domain)
-        .map_err(|e| DnsError::InvalidRecord(format!("Invalid domain name: {}", e)))?;
+///
+/// `nameserver` is an IP address, a socket address or a hostname. Hostnames
+/// are resolved with `dns_helper` and each of their addresses is tried in
+/// turn until one hands out the zone.
+pub async fn zone_transfer(domain: &str, nameserver: &str, dns_helper: &DnsHelper) -> Result<Vec<DnsRecord>, DnsError> {
+    Name::from_ascii(domain).map_err(|e| DnsError::InvalidRecord(format!("Invalid domain name: {}", e)))?;
     
-    // Parse the nameserver address
-    let ns_addr: SocketAddr = format!("{}:53", nameserver)
-        .parse()
-        .map_err(|e| DnsError::InvalidRecord(format!("Invalid nameserver address: {}", e)))?;
-    
-    // Create a TCP connection to the nameserver
-    let conn = TcpClientConnection::new(ns_addr)
-        .map_err(|e| DnsError::ZoneTransferFailed(format!("Failed to connect to nameserver: {}", e)))?;
-    
-    // Create a client
-    let client = SyncClient::new(conn);
-    
-    // Perform the AXFR query
-    let response = client.query(&name, trust_dns_client::rr::DNSClass::IN, RecordType::AXFR)
-        .map_err(|e| DnsError::ZoneTransferFailed(format!("AXFR query failed: {}", e)))?;
-    
-    // Parse the response and convert to our format
-    let mut records = Vec::new();
-    
-    for record in response.answers() {
-        // Convert each record to our internal format
-        if let Ok(dns_record) = convert_record(record, domain) {
-            records.push(dns_record);
+    let mut last_error = DnsError::ZoneTransferFailed(format!("No addresses found for nameserver {}", nameserver));
+    for addr in resolve_nameserver(nameserver, dns_helper).await? {
+        match transfer_result(check_axfr_in_background(domain, addr).await) {
+            Ok(records) => return Ok(records),
+            Err(e) => {
+                tracing::debug!("Zone transfer of {} from {} ({}) failed: {}", domain, nameserver, addr, e);
+                last_error = e;
+            }
         }
     }
     
-    Ok(records)
+    Err(last_error)
+}
+
+/// Get the addresses to try a zone transfer against
+///
+/// IP and socket addresses are used as given; hostnames are resolved with
+/// `dns_helper` and their addresses used on port 53.
+pub async fn resolve_nameserver(nameserver: &str, dns_helper: &DnsHelper) -> Result<Vec<SocketAddr>, DnsError> {
+    if let Some(addr) = nameserver_addr(nameserver) {
+        return Ok(vec![addr]);
+    }
+    Ok(dns_helper
+        .get_ip(nameserver.trim())
+        .await?
+        .iter()
+        .filter_map(|record| match record.data {
+            RecordData::A(ip) => Some(SocketAddr::new(IpAddr::V4(ip), 53)),
+            RecordData::Aaaa(ip) => Some(SocketAddr::new(IpAddr::V6(ip), 53)),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Attempt zone transfer from a nameserver address
+///
+/// This blocks on the TCP connection; see [`check_axfr`].
+pub fn zone_transfer_from(domain: &str, ns_addr: SocketAddr) -> Result<Vec<DnsRecord>, DnsError> {
+    Name::from_ascii(domain).map_err(|e| DnsError::InvalidRecord(format!("Invalid domain name: {}", e)))?;
+    transfer_result(check_axfr(domain, ns_addr))
+}
+
+/// Turn the outcome of an AXFR attempt into the zone's records or an error
+fn transfer_result(status: AxfrStatus) -> Result<Vec<DnsRecord>, DnsError> {
+    match status {
+        AxfrStatus::Allowed(records) => Ok(records),
+        AxfrStatus::Refused => Err(DnsError::ZoneTransferFailed("Zone transfer refused".to_string())),
+        AxfrStatus::Error(e) => Err(DnsError::ZoneTransferFailed(e)),
+    }
 }
 
 /// Convert a trust-dns record to our internal format
-fn convert_record(record: &trust_dns_client::rr::Record, domain: &str) -> Result<DnsRecord, DnsError> {
+///
+/// Types without dedicated support become [`RecordData::Other`](crate::dns::record::RecordData::Other)
+/// records that keep the real type name and data.
+pub fn convert_record(record: &trust_dns_client::rr::Record, domain: &str) -> Result<DnsRecord, DnsError> {
     let name = record.name().to_string();
     let name = name.trim_end_matches('.').to_string();
     
@@ -122,15 +170,113 @@ fn convert_record(record: &trust_dns_client::rr::Record, domain: &str) -> Result
             }
             Err(DnsError::InvalidRecord("Invalid SOA record".to_string()))
         },
-        _ => {
-            // For other record types, we'll create a generic record with string data
-            if let Some(ref rdata) = record.data() {
-                let data = format!("{:?}", rdata);
-                // This is a simplified approach - in a real implementation, we'd handle each type specifically
-                Ok(DnsRecord::new_txt(name, data))
+        record_type => {
+            // Keep the real type and presentation-format data of other record types
+            if let Some(rdata) = record.data() {
+                Ok(DnsRecord::new_other(name, record_type.to_string(), rdata.to_string()))
             } else {
                 Err(DnsError::InvalidRecord("Record has no data".to_string()))
             }
         }
     }
+}
+
+/// Try AXFR of `domain` against every nameserver and report each outcome
+///
+/// Nameservers are IP addresses, optionally with a port (default 53). The
+/// attempts run concurrently on the blocking thread pool.
+pub async fn check_axfr_all(domain: &str, nameservers: &[String]) -> Vec<(String, AxfrStatus)> {
+    let attempts = nameservers.iter().map(|nameserver| async move {
+        let status = match nameserver_addr(nameserver) {
+            Some(addr) => check_axfr_in_background(domain, addr).await,
+            None => AxfrStatus::Error(format!("Invalid nameserver address: {}", nameserver)),
+        };
+        (nameserver.clone(), status)
+    });
+    join_all(attempts).await
+}
+
+/// Run [`check_axfr`] on the blocking thread pool
+async fn check_axfr_in_background(domain: &str, addr: SocketAddr) -> AxfrStatus {
+    let domain = domain.to_string();
+    task::spawn_blocking(move || check_axfr(&domain, addr))
+        .await
+        .unwrap_or_else(|e| AxfrStatus::Error(format!("Zone transfer task failed: {}", e)))
+}
+
+/// Try AXFR of `domain` against a single nameserver
+///
+/// This blocks until the nameserver answers or times out, so async code runs
+/// it with [`tokio::task::spawn_blocking`].
+pub fn check_axfr(domain: &str, addr: SocketAddr) -> AxfrStatus {
+    let name = match Name::from_ascii(domain) {
+        Ok(name) => name,
+        Err(e) => return AxfrStatus::Error(format!("Invalid domain name: {}", e)),
+    };
+    
+    let conn = match TcpClientConnection::with_timeout(addr, AXFR_TIMEOUT) {
+        Ok(conn) => conn,
+        Err(e) => return AxfrStatus::Error(format!("Failed to connect to nameserver: {}", e)),
+    };
+    
+    let client = SyncClient::new(conn);
+    match client.query(&name, trust_dns_client::rr::DNSClass::IN, RecordType::AXFR) {
+        Ok(response) => {
+            let records = response
+                .answers()
+                .iter()
+                .filter_map(|record| convert_record(record, domain).ok())
+                .collect();
+            classify_response(response.response_code(), records)
+        }
+        Err(e) => classify_query_error(&e),
+    }
+}
+
+/// Classify a failed AXFR query
+///
+/// The connection is only opened by the query, so servers that don't serve
+/// TCP at all surface here as I/O errors.
+pub fn classify_query_error(error: &ClientError) -> AxfrStatus {
+    let io_error = match error.kind() {
+        ClientErrorKind::Io(e) => Some(e),
+        ClientErrorKind::Proto(e) => match e.kind() {
+            ProtoErrorKind::Io(e) => Some(e),
+            _ => None,
+        },
+        _ => None,
+    };
+    match io_error {
+        Some(e) => classify_connect_error(e),
+        None => AxfrStatus::Error(format!("AXFR query failed: {}", error)),
+    }
+}
+
+/// Classify a failed TCP connection to a nameserver
+pub fn classify_connect_error(error: &io::Error) -> AxfrStatus {
+    match error.kind() {
+        io::ErrorKind::ConnectionRefused => AxfrStatus::Refused,
+        _ => AxfrStatus::Error(format!("Failed to connect to nameserver: {}", error)),
+    }
+}
+
+/// Classify the answer to an AXFR query
+///
+/// Servers deny transfers with REFUSED or NOTAUTH, and some answer with an
+/// empty NOERROR response instead.
+pub fn classify_response(response_code: ResponseCode, records: Vec<DnsRecord>) -> AxfrStatus {
+    match response_code {
+        ResponseCode::NoError if !records.is_empty() => AxfrStatus::Allowed(records),
+        ResponseCode::NoError | ResponseCode::Refused | ResponseCode::NotAuth => AxfrStatus::Refused,
+        code => AxfrStatus::Error(format!("AXFR answered with {}", code)),
+    }
+}
+
+/// Parse a nameserver given as an IP address or socket address
+fn nameserver_addr(nameserver: &str) -> Option<SocketAddr> {
+    let nameserver = nameserver.trim();
+    nameserver
+        .parse::<SocketAddr>()
+        .ok()
+        .or_else(|| nameserver.parse::<IpAddr>().ok().map(|ip| SocketAddr::new(ip, 53)))
 }
\ No newline at end of file
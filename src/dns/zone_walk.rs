@@ -0,0 +1,98 @@
+//! NSEC zone walking (RFC 4034)
+//!
+//! Every NSEC record names the next owner name in the zone, so following the
+//! chain from the apex lists every name until it wraps back around. Zones
+//! signed with NSEC3 publish hashed names instead and can't be walked.
+
+use crate::dns::record::{DnsRecord, RecordData};
+use crate::dns::resolver::DnsHelper;
+use crate::dns::DnsError;
+use std::collections::HashSet;
+use std::future::Future;
+
+/// Most NSEC records followed in one walk, in case a chain never wraps around
+pub const MAX_WALK_STEPS: usize = 10_000;
+
+/// What walking a zone found
+#[derive(Debug, Clone)]
+pub enum ZoneWalk {
+    /// The NSEC record of every name on the chain, apex first
+    Nsec(Vec<DnsRecord>),
+    /// The zone uses NSEC3; holds its NSEC3PARAM records
+    Nsec3(Vec<DnsRecord>),
+    /// Neither NSEC nor NSEC3 records were found
+    Unsigned,
+}
+
+/// Walk the NSEC chain of `domain`
+///
+/// When the apex has no NSEC record the zone is checked for NSEC3PARAM to
+/// tell NSEC3 zones apart from unsigned ones.
+pub async fn nsec_walk(domain: &str, dns_helper: &DnsHelper) -> ZoneWalk {
+    let chain = walk_chain(domain, |name| async move { dns_helper.get_nsec(&name).await }).await;
+    if !chain.is_empty() {
+        return ZoneWalk::Nsec(chain);
+    }
+
+    match dns_helper.get_other(domain, "NSEC3PARAM").await {
+        Ok(params) if !params.is_empty() => ZoneWalk::Nsec3(params),
+        _ => ZoneWalk::Unsigned,
+    }
+}
+
+/// Follow the NSEC chain from `domain`, looking up each name's NSEC records with `lookup`
+///
+/// The walk stops when the next name is the apex again, leaves the zone,
+/// repeats an earlier name, or has no NSEC record, and after
+/// [`MAX_WALK_STEPS`] names. Lookup errors end the walk with the names found
+/// so far.
+pub async fn walk_chain<F, Fut>(domain: &str, mut lookup: F) -> Vec<DnsRecord>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<Vec<DnsRecord>, DnsError>>,
+{
+    let apex = normalize(domain);
+    let mut seen = HashSet::from([apex.clone()]);
+    let mut chain = Vec::new();
+    let mut current = apex.clone();
+
+    while chain.len() < MAX_WALK_STEPS {
+        let records = match lookup(current.clone()).await {
+            Ok(records) => records,
+            Err(e) => {
+                tracing::debug!("NSEC lookup for {} failed: {}", current, e);
+                break;
+            }
+        };
+        let nsec = records.into_iter().find_map(|record| match &record.data {
+            RecordData::Nsec { next_domain, .. } => Some((normalize(next_domain), record)),
+            _ => None,
+        });
+        let Some((next, record)) = nsec else {
+            tracing::debug!("No NSEC record at {}, stopping the walk", current);
+            break;
+        };
+        chain.push(record);
+
+        // The last NSEC of a zone points back at the apex
+        if next == apex {
+            break;
+        }
+        if !next.ends_with(&format!(".{}", apex)) {
+            tracing::debug!("NSEC at {} points outside {}: {}", current, apex, next);
+            break;
+        }
+        if !seen.insert(next.clone()) {
+            tracing::debug!("NSEC chain of {} loops at {}", apex, next);
+            break;
+        }
+        current = next;
+    }
+
+    chain
+}
+
+/// Lowercase name without the trailing dot
+fn normalize(name: &str) -> String {
+    name.trim_end_matches('.').to_lowercase()
+}
@@ -0,0 +1,89 @@
+//! Zone apex CNAME and ALIAS/ANAME flattening checks
+
+use crate::dns::record::{DnsRecord, RecordData, RecordType};
+use crate::dns::resolver::DnsHelper;
+use crate::enumerate::findings::{Finding, Severity};
+use crate::enumerate::wildcard::record_ips;
+
+/// Check whether a record is owned by `name`, ignoring case and a trailing dot
+fn owned_by(record: &DnsRecord, name: &str) -> bool {
+    record.name.trim_end_matches('.').eq_ignore_ascii_case(name.trim_end_matches('.'))
+}
+
+/// Flag a CNAME record at the zone apex
+///
+/// A CNAME cannot coexist with the SOA and NS records every apex carries
+/// (RFC 1034 section 3.6.2), so resolvers may answer unpredictably.
+pub fn apex_cname_findings(domain: &str, records: &[DnsRecord]) -> Vec<Finding> {
+    records
+        .iter()
+        .filter(|record| owned_by(record, domain))
+        .filter_map(|record| match record.data {
+            RecordData::Cname(ref target) => Some(Finding::new(
+                Severity::Medium,
+                "apex-cname",
+                domain,
+                format!(
+                    "Zone apex has a CNAME to {}; a CNAME cannot coexist with the apex SOA and NS records",
+                    target.trim_end_matches('.')
+                ),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Note apex addresses that look like a flattened CNAME (ALIAS/ANAME)
+///
+/// When a name inside the zone is a CNAME and resolves to exactly the apex
+/// address set, the provider has most likely flattened the same target into
+/// the apex A/AAAA records.
+pub fn flattening_findings(domain: &str, records: &[DnsRecord]) -> Vec<Finding> {
+    let apex_records: Vec<DnsRecord> = records.iter().filter(|record| owned_by(record, domain)).cloned().collect();
+    let apex_ips = record_ips(&apex_records);
+    if apex_ips.is_empty() {
+        return Vec::new();
+    }
+
+    let mut findings = Vec::new();
+    for record in records.iter().filter(|record| !owned_by(record, domain)) {
+        let target = match record.data {
+            RecordData::Cname(ref target) => target.trim_end_matches('.'),
+            _ => continue,
+        };
+
+        let name_records: Vec<DnsRecord> = records.iter().filter(|other| owned_by(other, &record.name)).cloned().collect();
+        if record_ips(&name_records) == apex_ips {
+            findings.push(Finding::new(
+                Severity::Info,
+                "apex-flattening",
+                domain,
+                format!(
+                    "Apex addresses match {} (CNAME to {}); the apex is likely a flattened ALIAS/ANAME record",
+                    record.name, target
+                ),
+            ));
+        }
+    }
+
+    findings
+}
+
+/// Run the apex checks, looking up the apex CNAME if it wasn't collected yet
+pub async fn run_checks(domain: &str, records: &[DnsRecord], dns_helper: &DnsHelper) -> Vec<Finding> {
+    let mut records = records.to_vec();
+
+    let has_apex_cname = records
+        .iter()
+        .any(|record| owned_by(record, domain) && record.record_type == RecordType::Cname);
+    if !has_apex_cname {
+        match dns_helper.lookup_type(domain, RecordType::Cname).await {
+            Ok(cnames) => records.extend(cnames),
+            Err(e) => tracing::debug!("No CNAME at the apex of {}: {}", domain, e),
+        }
+    }
+
+    let mut findings = apex_cname_findings(domain, &records);
+    findings.extend(flattening_findings(domain, &records));
+    findings
+}
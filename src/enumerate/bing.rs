@@ -1,31 +1,53 @@
 //! Bing search enumeration
 
 use crate::enumerate::EnumerationError;
-use crate::utils::http::create_http_client;
-use crate::cli::Args;
+use crate::utils::http::ClientPool;
+use crate::utils::validation::{is_subdomain_of, normalize_domain};
+use crate::config::EnumConfig;
 use scraper::{Html, Selector};
 use tokio::time::{sleep, Duration};
 use url::Url;
 
+/// Bing search endpoint
+pub const BING_URL: &str = "https://www.bing.com/search";
+
+/// Result pages requested from Bing when `--scraper-pages` is not given
+pub const DEFAULT_BING_PAGES: usize = 15;
+
+/// User-Agent sent with Bing searches, overriding the one of the shared clients
+pub const BING_USER_AGENT: &str = "Mozilla/5.0 (compatible; bingbot/2.0; +http://www.bing.com/bingbot.htm)";
+
 /// Scrape Bing for subdomains of a domain
-pub async fn scrape_bing(domain: &str, args: &Args) -> Result<Vec<String>, EnumerationError> {
+///
+/// Requests rotate across `clients`, which are shared with the other scrapers.
+pub async fn scrape_bing(domain: &str, clients: &ClientPool, config: &EnumConfig) -> Result<Vec<String>, EnumerationError> {
+    let pages = config.scraper_pages.unwrap_or(DEFAULT_BING_PAGES);
+    scrape_bing_pages(domain, clients, BING_URL, pages).await
+}
+
+/// Scrape the first `pages` result pages of a Bing search endpoint
+pub async fn scrape_bing_pages(
+    domain: &str,
+    clients: &ClientPool,
+    base_url: &str,
+    pages: usize,
+) -> Result<Vec<String>, EnumerationError> {
     let mut subdomains = Vec::new();
     
-    // Create HTTP client with appropriate settings
-    let client = create_http_client(
-        args,
-        "Mozilla/5.0 (compatible; bingbot/2.0; +http://www.bing.com/bingbot.htm)"
-    )?;
-    
-    // Perform multiple searches with pagination
-    for i in (1..=150).step_by(10) {
+    // Perform multiple searches with pagination, ten results per page
+    for i in (0..pages).map(|page| 1 + page * 10) {
         let url = format!(
-            "https://www.bing.com/search?q=domain%3A{}&qs=n&first={}",
-            domain, i
+            "{}?q=domain%3A{}&qs=n&first={}",
+            base_url, domain, i
         );
         
         // Send request
-        let response = client.get(&url).send().await?;
+        let response = clients
+            .next_client()
+            .get(&url)
+            .header(reqwest::header::USER_AGENT, BING_USER_AGENT)
+            .send()
+            .await?;
         
         // Check if we got a successful response
         if !response.status().is_success() {
@@ -92,11 +114,11 @@ pub async fn scrape_bing(domain: &str, args: &Args) -> Result<Vec<String>, Enume
 }
 
 /// Scrape Bing with retry logic for subdomains of a domain
-pub async fn scrape_bing_with_retry(domain: &str, args: &Args, max_retries: u32) -> Result<Vec<String>, EnumerationError> {
+pub async fn scrape_bing_with_retry(domain: &str, clients: &ClientPool, config: &EnumConfig, max_retries: u32) -> Result<Vec<String>, EnumerationError> {
     let mut retries = 0;
     
     loop {
-        match scrape_bing(domain, args).await {
+        match scrape_bing(domain, clients, config).await {
             Ok(subdomains) => return Ok(subdomains),
             Err(e) => {
                 if retries >= max_retries {
@@ -125,11 +147,8 @@ fn extract_subdomain_from_url(url: &str, domain: &str) -> Option<String> {
     if let Ok(parsed_url) = Url::parse(url) {
         if let Some(host) = parsed_url.host_str() {
             // Check if it's a subdomain of our target domain
-            if host.ends_with(domain) && host.len() > domain.len() {
-                // Make sure it's actually a subdomain (not the domain itself)
-                if host.len() > domain.len() && host.as_bytes()[host.len() - domain.len() - 1] == b'.' {
-                    return Some(host.to_string());
-                }
+            if is_subdomain_of(host, domain) {
+                return Some(host.to_string());
             }
         }
     }
@@ -144,12 +163,13 @@ fn extract_subdomain_from_url(url: &str, domain: &str) -> Option<String> {
         url.split('/').next()?
     };
     
+    // Normalize the hostname so Unicode hosts compare against the punycode domain
+    let hostname = normalize_domain(hostname).ok()?;
+    let hostname = hostname.as_str();
+    
     // Check if it's a subdomain of our target domain
-    if hostname.ends_with(domain) && hostname.len() > domain.len() {
-        // Make sure it's actually a subdomain (not the domain itself)
-        if hostname.len() > domain.len() && hostname.as_bytes()[hostname.len() - domain.len() - 1] == b'.' {
-            return Some(hostname.to_string());
-        }
+    if is_subdomain_of(hostname, domain) {
+        return Some(hostname.to_string());
     }
     
     None
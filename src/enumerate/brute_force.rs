@@ -3,11 +3,44 @@
 use crate::dns::resolver::DnsHelper;
 use crate::dns::record::DnsRecord;
 use crate::enumerate::EnumerationError;
+use crate::enumerate::wildcard::WildcardTracker;
+use crate::enumerate::limit::RecordLimit;
+use crate::enumerate::negative_cache::NegativeCache;
+use crate::enumerate::pattern::expand_patterns;
+use crate::utils::concurrency::resolve_bounded;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
-use tokio::sync::Semaphore;
-use tokio::task;
 use std::sync::Arc;
+use std::collections::{HashSet, VecDeque};
+use std::sync::mpsc::Sender;
+use std::future::Future;
+
+/// Most words tried below found names in one recursive brute force
+pub const MAX_RECURSIVE_CANDIDATES: usize = 500_000;
+
+/// Common subdomain labels, most frequent first
+///
+/// Taken from the head of the bundled subdomains-top1mil list, which is ranked
+/// by how often each label was seen.
+pub const COMMON_LABELS: &[&str] = &[
+    "www", "mail", "ftp", "localhost", "webmail", "smtp", "webdisk", "pop", "cpanel", "whm",
+    "ns1", "ns2", "autodiscover", "autoconfig", "ns", "test", "m", "blog", "dev", "www2",
+    "ns3", "pop3", "forum", "admin", "mail2", "vpn", "mx", "imap", "old", "new",
+    "mobile", "mysql", "beta", "support", "cp", "secure", "shop", "demo", "dns2", "ns4",
+    "dns1", "static", "lists", "web", "www1", "img", "news", "portal", "server", "wiki",
+    "api", "media", "images", "backup", "dns", "sql", "intranet", "stage", "staging", "app",
+];
+
+/// Order words so that common labels are tried first
+///
+/// Words found in [`COMMON_LABELS`] move to the front in frequency order; every
+/// other word keeps its position relative to the rest of the wordlist.
+pub fn smart_order(words: &mut [String]) {
+    words.sort_by_cached_key(|word| {
+        let word = word.to_lowercase();
+        COMMON_LABELS.iter().position(|label| *label == word).unwrap_or(usize::MAX)
+    });
+}
 
 /// Perform brute force enumeration using a wordlist
 pub async fn brute_force(
@@ -32,7 +65,7 @@ pub async fn brute_force(
         let subdomain = format!("{}.{}", word, domain);
         
         // Try to resolve the subdomain
-        match dns_helper.get_ip(&subdomain) {
+        match dns_helper.get_ip(&subdomain).await {
             Ok(records) => {
                 if !records.is_empty() {
                     found_records.extend(records);
@@ -55,76 +88,406 @@ pub async fn brute_force_concurrent(
     dns_helper: Arc<DnsHelper>,
     concurrency: usize,
 ) -> Result<Vec<DnsRecord>, EnumerationError> {
-    // Read all words from the wordlist
-    let file = File::open(wordlist_path)?;
-    let reader = BufReader::new(file);
-    
+    brute_force_with_context(domain, wordlist_path, dns_helper, concurrency, BruteForceContext::default()).await
+}
+
+/// Shared state for a brute force run
+#[derive(Clone, Default)]
+pub struct BruteForceContext {
+    /// Wildcard answer sets detected per zone level
+    pub wildcards: Arc<WildcardTracker>,
+    /// Cap on the total number of records collected
+    pub limit: Arc<RecordLimit>,
+    /// Extra words tried in addition to the wordlist
+    pub seeds: Vec<String>,
+    /// Names known not to exist, whose subtrees are skipped
+    pub negative_cache: Arc<NegativeCache>,
+    /// Receives every kept record as soon as it is found
+    pub record_tx: Option<Sender<DnsRecord>>,
+    /// Try common labels before the rest of the wordlist
+    pub smart_order: bool,
+    /// Keep names whose answer matches a detected wildcard instead of dropping them
+    pub keep_wildcard_answers: bool,
+    /// Label patterns such as `web[01-50]`, expanded into extra words
+    pub patterns: Vec<String>,
+    /// Levels below found names to brute force as well; 0 only tries `word.domain`
+    pub recurse_depth: usize,
+    /// Domain recorded on every record found, normally the scan target
+    pub target_domain: Option<String>,
+}
+
+/// Build the list of words to try from the wordlist, seeds and patterns
+///
+/// Seed words and pattern labels the wordlist doesn't already cover follow
+/// the wordlist; without a wordlist only they are tried.
+pub fn candidate_words(
+    wordlist_path: Option<&str>,
+    context: &BruteForceContext,
+) -> Result<Vec<String>, EnumerationError> {
     let mut words = Vec::new();
-    for line in reader.lines() {
-        let word = line?;
-        // Skip empty lines and comments
-        if word.is_empty() || word.starts_with('#') {
-            continue;
+    if let Some(wordlist_path) = wordlist_path {
+        // Read all words from the wordlist
+        let file = File::open(wordlist_path)?;
+        let reader = BufReader::new(file);
+        
+        for line in reader.lines() {
+            let word = line?;
+            // Skip empty lines and comments
+            if word.is_empty() || word.starts_with('#') {
+                continue;
+            }
+            words.push(word);
         }
-        words.push(word);
     }
     
-    // Create a semaphore to limit concurrency
-    let semaphore = Arc::new(Semaphore::new(concurrency));
+    // Add seed words and pattern labels that the wordlist doesn't already cover
+    let mut known: HashSet<String> = words.iter().map(|word| word.to_lowercase()).collect();
+    let extra = context.seeds.iter().cloned().chain(expand_patterns(&context.patterns)?);
+    for word in extra {
+        if known.insert(word.to_lowercase()) {
+            words.push(word);
+        }
+    }
     
-    // Create tasks for each word
-    let mut tasks = Vec::new();
-    let domain = domain.to_string();
+    if context.smart_order {
+        smart_order(&mut words);
+    }
     
-    for word in words {
+    Ok(words)
+}
+
+/// Perform brute force enumeration with concurrency using shared run state
+///
+/// See [`brute_force_recursive`].
+pub async fn brute_force_with_context(
+    domain: &str,
+    wordlist_path: &str,
+    dns_helper: Arc<DnsHelper>,
+    concurrency: usize,
+    context: BruteForceContext,
+) -> Result<Vec<DnsRecord>, EnumerationError> {
+    brute_force_recursive(domain, Some(wordlist_path), dns_helper, concurrency, context).await
+}
+
+/// Brute force `domain` and, with `recurse_depth` set, the names found below it
+///
+/// Each name is brute forced like the domain itself (see
+/// [`recurse_found_names`]); at most [`MAX_RECURSIVE_CANDIDATES`] words are
+/// tried below found names. Without a wordlist only seeds and patterns are tried.
+pub async fn brute_force_recursive(
+    domain: &str,
+    wordlist_path: Option<&str>,
+    dns_helper: Arc<DnsHelper>,
+    concurrency: usize,
+    context: BruteForceContext,
+) -> Result<Vec<DnsRecord>, EnumerationError> {
+    if context.recurse_depth == 0 {
+        return brute_force_level(domain, wordlist_path, dns_helper, concurrency, context).await;
+    }
+    
+    let words_per_base = candidate_words(None, &context)?.len()
+        + wordlist_path.map(wordlist_words).transpose()?.map_or(0, Iterator::count);
+    recurse_found_names(domain, context.recurse_depth, words_per_base, MAX_RECURSIVE_CANDIDATES, |base| {
         let dns_helper = dns_helper.clone();
-        let domain = domain.clone();
-        let semaphore = semaphore.clone();
+        let context = context.clone();
+        async move {
+            if context.limit.is_reached() {
+                return Ok(Vec::new());
+            }
+            brute_force_level(&base, wordlist_path, dns_helper, concurrency, context).await
+        }
+    })
+    .await
+}
+
+/// Brute force `domain` once with `brute`, then every newly found name below it
+///
+/// Found names are queued one level deeper until they are `recurse_depth`
+/// levels below `domain`, and each name is brute forced at most once. A name
+/// is skipped when brute forcing it would take the words tried below found
+/// names past `max_candidates`.
+pub async fn recurse_found_names<F, Fut>(
+    domain: &str,
+    recurse_depth: usize,
+    words_per_base: usize,
+    max_candidates: usize,
+    mut brute: F,
+) -> Result<Vec<DnsRecord>, EnumerationError>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<Vec<DnsRecord>, EnumerationError>>,
+{
+    let domain = domain.trim_end_matches('.').to_lowercase();
+    let mut tried = HashSet::from([domain.clone()]);
+    let mut queue = VecDeque::from([(domain, 0)]);
+    let mut candidates = 0;
+    let mut found_records = Vec::new();
+    
+    while let Some((base, depth)) = queue.pop_front() {
+        if depth > 0 {
+            if candidates + words_per_base > max_candidates {
+                tracing::debug!("Not brute forcing {}: candidate cap of {} reached", base, max_candidates);
+                continue;
+            }
+            candidates += words_per_base;
+        }
         
-        let task = task::spawn(async move {
-            // Acquire a permit from the semaphore
-            let _permit = semaphore.acquire().await.unwrap();
-            
-            let subdomain = format!("{}.{}", word, domain);
-            
-            // Try to resolve the subdomain
-            match dns_helper.get_ip(&subdomain) {
-                Ok(records) => {
-                    if !records.is_empty() {
-                        Some(records)
-                    } else {
-                        None
-                    }
-                }
-                Err(e) => {
-                    // Log the error but continue
-                    tracing::debug!("Failed to resolve {}: {}", subdomain, e);
-                    None
+        let records = brute(base.clone()).await?;
+        if depth < recurse_depth {
+            let suffix = format!(".{}", base);
+            for record in &records {
+                let name = record.name.trim_end_matches('.').to_lowercase();
+                if name.ends_with(&suffix) && tried.insert(name.clone()) {
+                    queue.push_back((name, depth + 1));
                 }
             }
-        });
+        }
+        found_records.extend(records);
+    }
+    
+    Ok(found_records)
+}
+
+/// Brute force the labels directly under `domain`
+///
+/// Without smart ordering the wordlist is streamed through
+/// [`brute_force_streaming_with_context`]. Smart ordering needs every word up
+/// front, so the words then come from [`candidate_words`] and are resolved
+/// like [`brute_force_words`] does.
+async fn brute_force_level(
+    domain: &str,
+    wordlist_path: Option<&str>,
+    dns_helper: Arc<DnsHelper>,
+    concurrency: usize,
+    context: BruteForceContext,
+) -> Result<Vec<DnsRecord>, EnumerationError> {
+    if !context.smart_order {
+        return brute_force_streaming_with_context(domain, wordlist_path, dns_helper, concurrency, context).await;
+    }
+    
+    let Some(lookup) = WordLookup::prepare(domain, dns_helper, &context).await else {
+        return Ok(Vec::new());
+    };
+    let words = candidate_words(wordlist_path, &context)?;
+    lookup.resolve_all(words.into_iter().map(Ok), concurrency).await
+}
+
+/// Resolve each word as a label under `domain`, concurrently
+///
+/// The target domain is probed for a wildcard before any words are tried, and
+/// names whose addresses are explained by that wildcard are dropped unless the
+/// context keeps wildcard answers. Detected
+/// levels remain available on the context for reporting. Once the record limit
+/// is reached no further lookups are started.
+pub async fn brute_force_words(
+    domain: &str,
+    words: Vec<String>,
+    dns_helper: Arc<DnsHelper>,
+    concurrency: usize,
+    context: BruteForceContext,
+) -> Result<Vec<DnsRecord>, EnumerationError> {
+    let Some(lookup) = WordLookup::prepare(domain, dns_helper, &context).await else {
+        return Ok(Vec::new());
+    };
+    lookup.resolve_all(words.into_iter().map(Ok), concurrency).await
+}
+
+/// Everything a single word lookup needs, shared by all lookups of a run
+struct WordLookup {
+    domain: String,
+    dns_helper: Arc<DnsHelper>,
+    tracker: Arc<WildcardTracker>,
+    keep_wildcard_answers: bool,
+    limit: Arc<RecordLimit>,
+    negative_cache: Arc<NegativeCache>,
+    record_tx: Option<Sender<DnsRecord>>,
+    target_domain: Option<String>,
+}
+
+impl WordLookup {
+    /// Probe `domain` for a wildcard and capture the run state for lookups under it
+    ///
+    /// Returns `None` when `domain` is known not to exist, as nothing can exist below it.
+    async fn prepare(domain: &str, dns_helper: Arc<DnsHelper>, context: &BruteForceContext) -> Option<Self> {
+        if context.negative_cache.covers(domain) {
+            tracing::debug!("Skipping brute force of {}: name does not exist", domain);
+            return None;
+        }
+        
+        // Detect a wildcard at the target level before brute forcing under it
+        if let Some(wildcard_ips) = context.wildcards.detect(domain, &dns_helper).await {
+            tracing::warn!("Wildcard DNS detected for *.{} resolving to {:?}", domain, wildcard_ips);
+        }
         
-        tasks.push(task);
+        Some(Self {
+            domain: domain.to_string(),
+            dns_helper,
+            tracker: context.wildcards.clone(),
+            keep_wildcard_answers: context.keep_wildcard_answers,
+            limit: context.limit.clone(),
+            negative_cache: context.negative_cache.clone(),
+            record_tx: context.record_tx.clone(),
+            target_domain: context.target_domain.clone(),
+        })
     }
     
-    // Collect results
-    let mut found_records = Vec::new();
-    for task in tasks {
-        if let Ok(Some(records)) = task.await {
-            found_records.extend(records);
+    /// Resolve every word with at most `concurrency` lookups in flight
+    async fn resolve_all<I>(&self, words: I, concurrency: usize) -> Result<Vec<DnsRecord>, EnumerationError>
+    where
+        I: IntoIterator<Item = std::io::Result<String>>,
+    {
+        resolve_words_bounded(words, concurrency, &self.limit, |word| self.resolve(word)).await
+    }
+    
+    /// Resolve `word` as a label under the domain, returning the records kept
+    async fn resolve(&self, word: String) -> Option<Vec<DnsRecord>> {
+        // Skip queued lookups if the limit was reached while waiting
+        if self.limit.is_reached() {
+            return None;
+        }
+        
+        let subdomain = format!("{}.{}", word, self.domain);
+        
+        // Skip names under a parent that is known not to exist
+        if self.negative_cache.covers(&subdomain) {
+            return None;
+        }
+        
+        // Try to resolve the subdomain, remembering names that don't exist
+        let mut records = Vec::new();
+        if self.dns_helper.races_addresses() {
+            // Only whether the name resolves matters, so keep the first answer
+            match self.dns_helper.get_ip_first(&subdomain).await {
+                Ok(found) => records = found,
+                Err(e) if e.is_nxdomain() => {
+                    self.negative_cache.insert(&subdomain);
+                    return None;
+                }
+                Err(e) => tracing::debug!("Failed to resolve {}: {}", subdomain, e),
+            }
+        } else {
+            for record_type in self.dns_helper.address_types() {
+                match self.dns_helper.lookup_type(&subdomain, record_type.clone()).await {
+                    Ok(found) => records.extend(found),
+                    Err(e) if e.is_nxdomain() => {
+                        self.negative_cache.insert(&subdomain);
+                        return None;
+                    }
+                    Err(e) => {
+                        // Log the error but continue
+                        tracing::debug!("Failed to get {:?} records for {}: {}", record_type, subdomain, e);
+                    }
+                }
+            }
+        }
+        
+        if records.is_empty() {
+            None
+        } else if !self.keep_wildcard_answers && self.tracker.is_wildcard_answer(&subdomain, &records) {
+            tracing::debug!("Dropping {}: answer matches wildcard", subdomain);
+            None
+        } else {
+            records.truncate(self.limit.reserve(records.len()));
+            if let Some(ref target_domain) = self.target_domain {
+                for record in &mut records {
+                    record.domain = Some(target_domain.clone());
+                }
+            }
+            if let Some(ref record_tx) = self.record_tx {
+                for record in &records {
+                    // A closed channel only means nobody is streaming anymore
+                    let _ = record_tx.send(record.clone());
+                }
+            }
+            if records.is_empty() { None } else { Some(records) }
         }
     }
+}
+
+/// Read the words of a wordlist lazily, one line at a time
+///
+/// Empty lines and comments are skipped.
+pub fn wordlist_words(wordlist_path: &str) -> Result<impl Iterator<Item = std::io::Result<String>>, EnumerationError> {
+    let file = File::open(wordlist_path)?;
+    Ok(BufReader::new(file)
+        .lines()
+        .filter(|line| !matches!(line, Ok(word) if word.is_empty() || word.starts_with('#'))))
+}
+
+/// Resolve words with `resolve`, keeping at most `concurrency` lookups alive
+///
+/// Words are pulled from the iterator only when a lookup slot is free, and a
+/// word that found nothing is dropped as soon as its lookup finishes, so
+/// memory grows with the records found rather than with the wordlist. No
+/// further words are read once `limit` is reached, and a read error stops the run.
+pub async fn resolve_words_bounded<I, F, Fut>(
+    words: I,
+    concurrency: usize,
+    limit: &RecordLimit,
+    resolve: F,
+) -> Result<Vec<DnsRecord>, EnumerationError>
+where
+    I: IntoIterator<Item = std::io::Result<String>>,
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Option<Vec<DnsRecord>>>,
+{
+    let mut read_error = None;
+    let words = words
+        .into_iter()
+        // Stop reading words once enough records have been collected
+        .take_while(|_| !limit.is_reached())
+        .map_while(|word| word.map_err(|e| read_error = Some(e)).ok());
     
-    Ok(found_records)
+    let mut found = Vec::new();
+    resolve_bounded(words, concurrency, resolve, |records| found.extend(records.into_iter().flatten())).await;
+    if let Some(e) = read_error {
+        return Err(e.into());
+    }
+    
+    Ok(found)
 }
 
 /// Perform brute force enumeration with concurrency (streaming version)
+///
+/// The wordlist is read lazily and only `concurrency` lookups exist at any
+/// time, so huge wordlists don't have to fit in memory.
 pub async fn brute_force_streaming(
     domain: &str,
     wordlist_path: &str,
     dns_helper: Arc<DnsHelper>,
     concurrency: usize,
 ) -> Result<Vec<DnsRecord>, EnumerationError> {
-    // For now, just call the concurrent version since the streaming version is complex
-    brute_force_concurrent(domain, wordlist_path, dns_helper, concurrency).await
+    brute_force_streaming_with_context(domain, Some(wordlist_path), dns_helper, concurrency, BruteForceContext::default()).await
+}
+
+/// Perform streaming brute force enumeration using shared run state
+///
+/// Seed words and pattern labels are tried first, then the wordlist is
+/// streamed line by line, skipping words already tried as seeds or patterns.
+/// Smart ordering needs the whole wordlist up front and is not applied.
+pub async fn brute_force_streaming_with_context(
+    domain: &str,
+    wordlist_path: Option<&str>,
+    dns_helper: Arc<DnsHelper>,
+    concurrency: usize,
+    context: BruteForceContext,
+) -> Result<Vec<DnsRecord>, EnumerationError> {
+    let Some(lookup) = WordLookup::prepare(domain, dns_helper, &context).await else {
+        return Ok(Vec::new());
+    };
+    
+    let extra = candidate_words(None, &BruteForceContext { smart_order: false, ..context.clone() })?;
+    let tried: HashSet<String> = extra.iter().map(|word| word.to_lowercase()).collect();
+    let wordlist = match wordlist_path {
+        Some(wordlist_path) => Some(wordlist_words(wordlist_path)?),
+        None => None,
+    };
+    let words = extra
+        .into_iter()
+        .map(Ok)
+        .chain(wordlist.into_iter().flatten().filter(|word| {
+            !matches!(word, Ok(word) if tried.contains(&word.to_lowercase()))
+        }));
+    
+    lookup.resolve_all(words, concurrency).await
 }
\ No newline at end of file
@@ -0,0 +1,168 @@
+//! MX/NS consistency and delegation checks
+//!
+//! Each check works on records already collected by standard enumeration, with
+//! any extra lookups passed in so the checks can run against synthetic data.
+
+use crate::dns::delegation::delegation_ns;
+use crate::dns::record::{DnsRecord, RecordData};
+use crate::dns::resolver::DnsHelper;
+use crate::enumerate::findings::{Finding, Severity};
+use crate::enumerate::wildcard::{parent_zone, record_ips};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use tokio::task;
+
+/// Nameserver targets of the NS records for `domain`
+pub fn ns_targets(domain: &str, records: &[DnsRecord]) -> Vec<String> {
+    records
+        .iter()
+        .filter(|record| record.name.eq_ignore_ascii_case(domain))
+        .filter_map(|record| match &record.data {
+            RecordData::Ns(ns) => Some(ns.trim_end_matches('.').to_lowercase()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Exchange targets of the MX records for `domain`
+pub fn mx_targets(domain: &str, records: &[DnsRecord]) -> Vec<String> {
+    records
+        .iter()
+        .filter(|record| record.name.eq_ignore_ascii_case(domain))
+        .filter_map(|record| match &record.data {
+            RecordData::Mx { exchange, .. } => Some(exchange.trim_end_matches('.').to_lowercase()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Flag MX and NS records whose target is an IP literal instead of a hostname
+pub fn check_ip_literal_targets(domain: &str, records: &[DnsRecord]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    
+    for (kind, targets) in [("NS", ns_targets(domain, records)), ("MX", mx_targets(domain, records))] {
+        for target in targets {
+            if target.parse::<IpAddr>().is_ok() {
+                findings.push(Finding::new(
+                    Severity::Medium,
+                    "consistency",
+                    domain,
+                    format!("{} record points at IP literal {}; it must name a host", kind, target),
+                ));
+            }
+        }
+    }
+    
+    findings
+}
+
+/// Flag NS records whose target has no A/AAAA records
+pub fn check_unresolvable_ns<F>(domain: &str, records: &[DnsRecord], resolve: F) -> Vec<Finding>
+where
+    F: Fn(&str) -> Vec<DnsRecord>,
+{
+    ns_targets(domain, records)
+        .into_iter()
+        .filter(|ns| ns.parse::<IpAddr>().is_err() && record_ips(&resolve(ns)).is_empty())
+        .map(|ns| {
+            Finding::new(
+                Severity::Medium,
+                "consistency",
+                domain,
+                format!("Nameserver {} does not resolve to an address", ns),
+            )
+        })
+        .collect()
+}
+
+/// Flag MX records whose exchange is an alias (RFC 2181 section 10.3)
+pub fn check_mx_cname<F>(domain: &str, records: &[DnsRecord], lookup_cname: F) -> Vec<Finding>
+where
+    F: Fn(&str) -> Vec<DnsRecord>,
+{
+    let mut findings = Vec::new();
+    
+    for exchange in mx_targets(domain, records) {
+        let alias = lookup_cname(&exchange).into_iter().find_map(|record| match record.data {
+            RecordData::Cname(target) => Some(target),
+            _ => None,
+        });
+        if let Some(target) = alias {
+            findings.push(Finding::new(
+                Severity::Low,
+                "consistency",
+                domain,
+                format!("MX exchange {} is a CNAME to {}; MX targets must not be aliases", exchange, target),
+            ));
+        }
+    }
+    
+    findings
+}
+
+/// Flag nameservers delegated by the parent zone but missing from the zone's own NS set
+pub fn check_lame_delegation(domain: &str, zone_ns: &[String], parent_ns: &[String]) -> Vec<Finding> {
+    parent_ns
+        .iter()
+        .filter(|ns| !zone_ns.iter().any(|zone| zone.eq_ignore_ascii_case(ns)))
+        .map(|ns| {
+            Finding::new(
+                Severity::Medium,
+                "consistency",
+                domain,
+                format!("Parent zone delegates to {} but the zone's own NS records do not list it", ns),
+            )
+        })
+        .collect()
+}
+
+/// Look up the NS set the parent zone delegates `domain` to
+async fn parent_delegation(domain: &str, dns_helper: &DnsHelper) -> Option<Vec<String>> {
+    let parent = parent_zone(domain)?;
+    let parent_records = dns_helper.get_ns(parent).await.ok()?;
+    
+    for parent_ns in ns_targets(parent, &parent_records) {
+        let addresses = dns_helper.get_ip(&parent_ns).await.unwrap_or_default();
+        for address in record_ips(&addresses) {
+            // The delegation query is a blocking client call
+            let zone = domain.to_string();
+            match task::spawn_blocking(move || delegation_ns(&zone, address)).await {
+                Ok(Ok(nameservers)) if !nameservers.is_empty() => return Some(nameservers),
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => tracing::debug!("Delegation lookup via {} failed: {}", parent_ns, e),
+                Err(e) => tracing::debug!("Delegation lookup task via {} failed: {}", parent_ns, e),
+            }
+        }
+    }
+    
+    None
+}
+
+/// Run every consistency check against the records collected for `domain`
+pub async fn run_checks(domain: &str, records: &[DnsRecord], dns_helper: &DnsHelper) -> Vec<Finding> {
+    let mut findings = check_ip_literal_targets(domain, records);
+    
+    let mut addresses = HashMap::new();
+    for ns in ns_targets(domain, records) {
+        let found = dns_helper.get_ip(&ns).await.unwrap_or_default();
+        addresses.insert(ns, found);
+    }
+    findings.extend(check_unresolvable_ns(domain, records, |name| {
+        addresses.get(name).cloned().unwrap_or_default()
+    }));
+    
+    let mut cnames = HashMap::new();
+    for exchange in mx_targets(domain, records) {
+        let found = dns_helper.get_cname(&exchange).await.unwrap_or_default();
+        cnames.insert(exchange, found);
+    }
+    findings.extend(check_mx_cname(domain, records, |name| {
+        cnames.get(name).cloned().unwrap_or_default()
+    }));
+    
+    if let Some(parent_ns) = parent_delegation(domain, dns_helper).await {
+        findings.extend(check_lame_delegation(domain, &ns_targets(domain, records), &parent_ns));
+    }
+    
+    findings
+}
@@ -1,24 +1,220 @@
 //! Certificate Transparency log scraping from crt.sh
 
 use crate::enumerate::EnumerationError;
-use crate::utils::http::create_http_client;
-use crate::cli::Args;
+use crate::utils::http::{get_with_backoff, ClientPool};
+use crate::utils::validation::normalize_domain;
+use crate::config::EnumConfig;
 use scraper::{Html, Selector};
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
 use tokio::time::{sleep, Duration};
 
+/// Base URL of crt.sh
+pub const CRTSH_URL: &str = "https://crt.sh/";
+
+/// Issuances endpoint of the certspotter API
+pub const CERTSPOTTER_URL: &str = "https://api.certspotter.com/v1/issuances";
+
+/// Which certificates a crt.sh query matches
+///
+/// Available scopes:
+/// - `subdomains`: certificates for names under the domain (`%.{domain}`)
+/// - `apex`: certificates for the domain itself (`{domain}`)
+/// - `deep`: certificates two or more levels below the domain (`%.%.{domain}`)
+/// - `org:<name>`: certificates whose subject organization is `<name>`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CrtShScope {
+    #[default]
+    Subdomains,
+    Apex,
+    Deep,
+    Org(String),
+}
+
+impl CrtShScope {
+    /// Query string parameter crt.sh is searched with, already URL-encoded
+    pub fn query(&self, domain: &str) -> String {
+        let (key, value) = match self {
+            CrtShScope::Subdomains => ("q", format!("%.{}", domain)),
+            CrtShScope::Apex => ("q", domain.to_string()),
+            CrtShScope::Deep => ("q", format!("%.%.{}", domain)),
+            // crt.sh searches the subject organization through the O parameter
+            CrtShScope::Org(name) => ("O", name.clone()),
+        };
+        format!("{}={}", key, url::form_urlencoded::byte_serialize(value.as_bytes()).collect::<String>())
+    }
+}
+
+impl fmt::Display for CrtShScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CrtShScope::Subdomains => f.write_str("subdomains"),
+            CrtShScope::Apex => f.write_str("apex"),
+            CrtShScope::Deep => f.write_str("deep"),
+            CrtShScope::Org(name) => write!(f, "org:{}", name),
+        }
+    }
+}
+
+impl FromStr for CrtShScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some((prefix, name)) = s.split_once(':') {
+            if prefix.eq_ignore_ascii_case("org") {
+                let name = name.trim();
+                if name.is_empty() {
+                    return Err("crt.sh scope 'org:' needs an organization name".to_string());
+                }
+                return Ok(CrtShScope::Org(name.to_string()));
+            }
+        }
+        match s.to_lowercase().as_str() {
+            "subdomains" => Ok(CrtShScope::Subdomains),
+            "apex" => Ok(CrtShScope::Apex),
+            "deep" => Ok(CrtShScope::Deep),
+            _ => Err(format!(
+                "Unknown crt.sh scope '{}'; available scopes: subdomains, apex, deep, org:<name>",
+                s
+            )),
+        }
+    }
+}
+
+/// Build the crt.sh search URL for a scope, asking for JSON output if `json` is set
+pub fn crtsh_query_url(crtsh_url: &str, domain: &str, scope: &CrtShScope, json: bool) -> String {
+    let mut url = format!("{}?{}", crtsh_url, scope.query(domain));
+    if json {
+        url.push_str("&output=json");
+    }
+    url
+}
+
+/// A certificate entry returned by the crt.sh JSON API
+#[derive(Debug, Deserialize)]
+struct CrtShEntry {
+    /// Newline-separated names from the certificate's SANs
+    name_value: String,
+    common_name: Option<String>,
+}
+
+/// A certificate issuance returned by the certspotter API
+#[derive(Debug, Deserialize)]
+struct CertSpotterIssuance {
+    #[serde(default)]
+    dns_names: Vec<String>,
+}
+
 /// Scrape crt.sh for subdomains of a domain
-pub async fn scrape_crtsh(domain: &str, args: &Args) -> Result<Vec<String>, EnumerationError> {
-    let url = format!("https://crt.sh/?q=%.{}", domain);
+///
+/// See [`scrape_ct_chain`] for the order in which CT sources are tried. The
+/// request goes through the next client of `clients`.
+pub async fn scrape_crtsh(domain: &str, clients: &ClientPool, config: &EnumConfig) -> Result<Vec<String>, EnumerationError> {
+    let certspotter_url = if config.certspotter { Some(CERTSPOTTER_URL) } else { None };
+    scrape_ct_chain_with_scope(domain, clients.next_client(), CRTSH_URL, certspotter_url, &config.crtsh_scope).await
+}
+
+/// Query certificate transparency logs, degrading through fallback sources
+///
+/// The crt.sh JSON API is tried first, then the crt.sh HTML page, and finally
+/// certspotter if a URL for it is given. The first path that succeeds wins; an
+/// error is returned only when every path failed.
+pub async fn scrape_ct_chain(
+    domain: &str,
+    client: &reqwest::Client,
+    crtsh_url: &str,
+    certspotter_url: Option<&str>,
+) -> Result<Vec<String>, EnumerationError> {
+    scrape_ct_chain_with_scope(domain, client, crtsh_url, certspotter_url, &CrtShScope::Subdomains).await
+}
+
+/// Like [`scrape_ct_chain`], searching crt.sh with the given scope
+///
+/// The certspotter fallback always looks up the domain and its subdomains.
+pub async fn scrape_ct_chain_with_scope(
+    domain: &str,
+    client: &reqwest::Client,
+    crtsh_url: &str,
+    certspotter_url: Option<&str>,
+    scope: &CrtShScope,
+) -> Result<Vec<String>, EnumerationError> {
+    match crtsh_json(domain, client, crtsh_url, scope).await {
+        Ok(subdomains) => {
+            tracing::info!("crt.sh JSON API returned {} names for {}", subdomains.len(), domain);
+            return Ok(subdomains);
+        }
+        Err(e) => {
+            tracing::warn!("crt.sh JSON API failed, falling back to HTML: {}", e);
+        }
+    }
     
-    // Create HTTP client with appropriate settings
-    let client = create_http_client(
-        args,
-        "Mozilla/5.0 (compatible; DNSRecon-rs/0.1; +https://github.com/example/dnsrecon-rs)"
-    )?;
+    let error = match crtsh_html(domain, client, crtsh_url, scope).await {
+        Ok(subdomains) => {
+            tracing::info!("crt.sh HTML page returned {} names for {}", subdomains.len(), domain);
+            return Ok(subdomains);
+        }
+        Err(e) => {
+            tracing::warn!("crt.sh HTML page failed: {}", e);
+            e
+        }
+    };
+    
+    let certspotter_url = match certspotter_url {
+        Some(url) => url,
+        None => return Err(error),
+    };
     
-    // Send request
-    let response = client.get(&url).send().await?;
-    let body = response.text().await?;
+    match certspotter(domain, client, certspotter_url).await {
+        Ok(subdomains) => {
+            tracing::info!("certspotter returned {} names for {}", subdomains.len(), domain);
+            Ok(subdomains)
+        }
+        Err(e) => {
+            tracing::warn!("certspotter failed: {}", e);
+            Err(e)
+        }
+    }
+}
+
+/// Query the crt.sh JSON API
+async fn crtsh_json(
+    domain: &str,
+    client: &reqwest::Client,
+    crtsh_url: &str,
+    scope: &CrtShScope,
+) -> Result<Vec<String>, EnumerationError> {
+    let url = crtsh_query_url(crtsh_url, domain, scope, true);
+    let body = get_with_backoff(client, &url).await?;
+    parse_crtsh_json(domain, scope, &body)
+}
+
+/// Extract the names of a crt.sh JSON API response
+///
+/// Every SAN in `name_value` and the common name are collected, with `*.`
+/// wildcards stripped, lowercased and deduplicated.
+pub fn parse_crtsh_json(domain: &str, scope: &CrtShScope, body: &str) -> Result<Vec<String>, EnumerationError> {
+    let entries: Vec<CrtShEntry> = serde_json::from_str(body)
+        .map_err(|e| EnumerationError::Parse(format!("Invalid crt.sh JSON: {}", e)))?;
+    
+    let names = entries.iter().flat_map(|entry| {
+        entry.name_value.lines().chain(entry.common_name.as_deref())
+    });
+    Ok(collect_scoped(domain, scope, names))
+}
+
+/// Scrape the crt.sh HTML results page
+async fn crtsh_html(
+    domain: &str,
+    client: &reqwest::Client,
+    crtsh_url: &str,
+    scope: &CrtShScope,
+) -> Result<Vec<String>, EnumerationError> {
+    let url = crtsh_query_url(crtsh_url, domain, scope, false);
+    
+    // Send request, backing off if crt.sh rate limits us
+    let body = get_with_backoff(client, &url).await?;
     
     // Parse HTML
     let document = Html::parse_document(&body);
@@ -26,14 +222,54 @@ pub async fn scrape_crtsh(domain: &str, args: &Args) -> Result<Vec<String>, Enum
         EnumerationError::Parse("Failed to parse CSS selector".to_string())
     )?;
     
+    let names: Vec<String> = document
+        .select(&selector)
+        .flat_map(|element| element.text().map(|text| text.to_string()).collect::<Vec<_>>())
+        .collect();
+    Ok(collect_scoped(domain, scope, names.iter().map(|name| name.as_str())))
+}
+
+/// Query the certspotter issuances API
+async fn certspotter(domain: &str, client: &reqwest::Client, certspotter_url: &str) -> Result<Vec<String>, EnumerationError> {
+    let url = format!("{}?domain={}&include_subdomains=true&expand=dns_names", certspotter_url, domain);
+    let body = get_with_backoff(client, &url).await?;
+    
+    let issuances: Vec<CertSpotterIssuance> = serde_json::from_str(&body)
+        .map_err(|e| EnumerationError::Parse(format!("Invalid certspotter JSON: {}", e)))?;
+    
+    let names = issuances.iter().flat_map(|issuance| issuance.dns_names.iter().map(|name| name.as_str()));
+    Ok(collect_subdomains(domain, names))
+}
+
+/// Collect the names found by a crt.sh search
+///
+/// Organization searches keep names of any domain, since related domains are
+/// what they are for; every other scope keeps only names under `domain`.
+fn collect_scoped<'a>(domain: &str, scope: &CrtShScope, names: impl Iterator<Item = &'a str>) -> Vec<String> {
+    if !matches!(scope, CrtShScope::Org(_)) {
+        return collect_subdomains(domain, names);
+    }
+    
+    let mut collected: Vec<String> = names
+        .filter_map(|name| normalize_domain(name.trim().trim_start_matches("*.")).ok())
+        .collect();
+    collected.sort();
+    collected.dedup();
+    collected
+}
+
+/// Normalize certificate names and keep the unique ones under `domain`
+fn collect_subdomains<'a>(domain: &str, names: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let suffix = format!(".{}", domain);
     let mut subdomains = Vec::new();
     
-    for element in document.select(&selector) {
-        if let Some(text) = element.text().next() {
-            let subdomain = text.trim();
-            // Filter for valid subdomains
-            if subdomain.ends_with(domain) && !subdomain.starts_with("*.") {
-                subdomains.push(subdomain.to_string());
+    for name in names {
+        // Wildcard entries still reveal the name they cover
+        let name = name.trim().trim_start_matches("*.");
+        // Normalize so Unicode names compare against the punycode domain
+        if let Ok(name) = normalize_domain(name) {
+            if name == domain || name.ends_with(&suffix) {
+                subdomains.push(name);
             }
         }
     }
@@ -42,15 +278,15 @@ pub async fn scrape_crtsh(domain: &str, args: &Args) -> Result<Vec<String>, Enum
     subdomains.sort();
     subdomains.dedup();
     
-    Ok(subdomains)
+    subdomains
 }
 
 /// Scrape crt.sh with retry logic for subdomains of a domain
-pub async fn scrape_crtsh_with_retry(domain: &str, args: &Args, max_retries: u32) -> Result<Vec<String>, EnumerationError> {
+pub async fn scrape_crtsh_with_retry(domain: &str, clients: &ClientPool, config: &EnumConfig, max_retries: u32) -> Result<Vec<String>, EnumerationError> {
     let mut retries = 0;
     
     loop {
-        match scrape_crtsh(domain, args).await {
+        match scrape_crtsh(domain, clients, config).await {
             Ok(subdomains) => return Ok(subdomains),
             Err(e) => {
                 if retries >= max_retries {
@@ -0,0 +1,43 @@
+//! DMARC policy parsing and classification
+
+use crate::enumerate::findings::{Finding, Severity};
+
+pub use crate::dns::dmarc::{parse_dmarc, DmarcRecord};
+
+/// Classify the DMARC policy of a domain from the TXT strings at `_dmarc.<domain>`
+///
+/// Flags a missing DMARC record and `p=none` (monitoring only) policies.
+pub fn dmarc_findings(domain: &str, txt_records: &[String]) -> Vec<Finding> {
+    policy_findings(domain, txt_records.iter().find_map(|txt| parse_dmarc(txt)).as_ref())
+}
+
+/// Classify the DMARC policy of a domain, `None` meaning it has no DMARC record
+pub fn policy_findings(domain: &str, record: Option<&DmarcRecord>) -> Vec<Finding> {
+    let record = match record {
+        Some(record) => record,
+        None => {
+            return vec![Finding::new(
+                Severity::Medium,
+                "dmarc",
+                domain,
+                "No DMARC record found; receivers get no policy for spoofed mail",
+            )];
+        }
+    };
+
+    match record.policy.as_deref() {
+        Some("none") => vec![Finding::new(
+            Severity::Low,
+            "dmarc",
+            domain,
+            "DMARC policy is p=none (monitoring only); spoofed mail is not rejected",
+        )],
+        None => vec![Finding::new(
+            Severity::Medium,
+            "dmarc",
+            domain,
+            "DMARC record has no p= policy tag",
+        )],
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,54 @@
+//! GeoDNS mapping with EDNS Client Subnet
+//!
+//! Resolving a name on behalf of different client networks shows which
+//! addresses a GeoDNS setup hands out to each region.
+
+use crate::dns::record::DnsRecord;
+use crate::dns::resolver::DnsHelper;
+use crate::enumerate::findings::{Finding, Severity};
+use crate::enumerate::wildcard::record_ips;
+use ipnetwork::IpNetwork;
+use std::collections::{BTreeSet, HashMap};
+use std::net::IpAddr;
+
+/// Resolve a name's addresses once per client subnet with `lookup`
+pub fn address_sets<F>(subnets: &[IpNetwork], lookup: F) -> Vec<(IpNetwork, BTreeSet<IpAddr>)>
+where
+    F: Fn(IpNetwork) -> Vec<DnsRecord>,
+{
+    subnets
+        .iter()
+        .map(|subnet| (*subnet, record_ips(&lookup(*subnet)).into_iter().collect()))
+        .collect()
+}
+
+/// Note the addresses returned to each client subnet
+pub fn ecs_findings(name: &str, sets: &[(IpNetwork, BTreeSet<IpAddr>)]) -> Vec<Finding> {
+    sets.iter()
+        .map(|(subnet, ips)| {
+            let message = if ips.is_empty() {
+                format!("No addresses returned for client subnet {}", subnet)
+            } else {
+                let ips: Vec<String> = ips.iter().map(IpAddr::to_string).collect();
+                format!("Client subnet {} gets {}", subnet, ips.join(", "))
+            };
+            Finding::new(Severity::Info, "ecs", name, message)
+        })
+        .collect()
+}
+
+/// Resolve `name` for every client subnet and report the addresses returned
+pub async fn report(name: &str, subnets: &[IpNetwork], dns_helper: &DnsHelper) -> Vec<Finding> {
+    let mut answers = HashMap::new();
+    for subnet in subnets {
+        let found = dns_helper
+            .clone()
+            .with_client_subnet(Some(*subnet))
+            .get_ip(name)
+            .await
+            .unwrap_or_default();
+        answers.insert(*subnet, found);
+    }
+    let sets = address_sets(subnets, |subnet| answers.get(&subnet).cloned().unwrap_or_default());
+    ecs_findings(name, &sets)
+}
@@ -0,0 +1,57 @@
+//! Glob-based exclusion of uninteresting discovered names
+
+use crate::dns::record::{DnsRecord, RecordData};
+use crate::enumerate::EnumerationError;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+/// Set of glob patterns such as `*.cdn.example.com` or `monitoring.*`
+///
+/// Matching ignores case and a trailing dot, and `*` may span several labels.
+#[derive(Clone)]
+pub struct ExcludeFilter {
+    set: GlobSet,
+}
+
+impl ExcludeFilter {
+    /// Compile the given patterns, failing on the first invalid one
+    pub fn new(patterns: &[String]) -> Result<Self, EnumerationError> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            let pattern = pattern.trim().trim_end_matches('.');
+            if pattern.is_empty() {
+                continue;
+            }
+            let glob = GlobBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| EnumerationError::Parse(format!("Invalid exclude pattern '{}': {}", pattern, e)))?;
+            builder.add(glob);
+        }
+
+        let set = builder
+            .build()
+            .map_err(|e| EnumerationError::Parse(format!("Invalid exclude patterns: {}", e)))?;
+        Ok(Self { set })
+    }
+
+    /// Check whether a name matches any pattern
+    pub fn is_excluded(&self, name: &str) -> bool {
+        self.set.is_match(name.trim_end_matches('.'))
+    }
+
+    /// Check whether a record's discovered name matches any pattern
+    ///
+    /// The discovered name of a PTR record is its target hostname; for every other
+    /// record it is the record's owner name.
+    pub fn excludes_record(&self, record: &DnsRecord) -> bool {
+        match record.data {
+            RecordData::Ptr(ref target) => self.is_excluded(target),
+            _ => self.is_excluded(&record.name),
+        }
+    }
+
+    /// Drop records whose discovered name matches any pattern
+    pub fn apply(&self, records: Vec<DnsRecord>) -> Vec<DnsRecord> {
+        records.into_iter().filter(|record| !self.excludes_record(record)).collect()
+    }
+}
@@ -0,0 +1,49 @@
+//! Forward-confirmed reverse DNS
+//!
+//! Anyone controlling a reverse zone can point a PTR at any hostname. A PTR is
+//! only trustworthy when its target resolves back to the address it was found
+//! for, so unconfirmed PTRs can be dropped from reverse sweep results.
+
+use crate::dns::record::{DnsRecord, RecordData};
+use crate::enumerate::reverse_enrich::{ptr_targets, TargetRecords};
+use crate::enumerate::wildcard::record_ips;
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+/// Whether `ip` is among the addresses of the forward records of a PTR target
+pub fn is_forward_confirmed(ip: IpAddr, forward: &[DnsRecord]) -> bool {
+    record_ips(forward).contains(&ip)
+}
+
+/// Check every PTR record against the forward records of its target
+///
+/// `forward` returns the A/AAAA records of a hostname and is called once per
+/// target. The result holds one flag per record: PTRs whose target doesn't
+/// resolve back to the address they were found for are `false`, every other
+/// record is `true`.
+pub fn confirm_with<F>(records: &[DnsRecord], forward: F) -> Vec<bool>
+where
+    F: Fn(&str) -> Vec<DnsRecord>,
+{
+    let resolved: HashMap<String, Vec<DnsRecord>> =
+        ptr_targets(records).into_iter().map(|target| (target.clone(), forward(&target))).collect();
+
+    records
+        .iter()
+        .map(|record| match &record.data {
+            RecordData::Ptr(target) => {
+                let target = target.trim_end_matches('.').to_lowercase();
+                match (record.name.parse::<IpAddr>(), resolved.get(&target)) {
+                    (Ok(ip), Some(found)) => is_forward_confirmed(ip, found),
+                    _ => false,
+                }
+            }
+            _ => true,
+        })
+        .collect()
+}
+
+/// Confirm every PTR record against the forward records of its resolved target
+pub fn confirm(records: &[DnsRecord], resolved: &TargetRecords) -> Vec<bool> {
+    confirm_with(records, |target| resolved.get(target).cloned().unwrap_or_default())
+}
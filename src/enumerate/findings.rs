@@ -0,0 +1,57 @@
+//! Security findings produced during enumeration
+//!
+//! Findings turn raw DNS records into actionable observations, such as an
+//! overly permissive SPF policy or a missing DMARC record.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Severity of a finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Severity {
+    Info,
+    Low,
+    Medium,
+    High,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Severity::Info => "INFO",
+            Severity::Low => "LOW",
+            Severity::Medium => "MEDIUM",
+            Severity::High => "HIGH",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// A single observation about the target
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Finding {
+    pub severity: Severity,
+    /// Short machine-friendly category, e.g. `spf` or `dmarc`
+    pub category: String,
+    /// Name the finding is about
+    pub subject: String,
+    pub message: String,
+}
+
+impl Finding {
+    /// Create a new finding
+    pub fn new(severity: Severity, category: &str, subject: &str, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            category: category.to_string(),
+            subject: subject.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {} {}: {}", self.severity, self.category, self.subject, self.message)
+    }
+}
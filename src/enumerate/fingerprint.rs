@@ -0,0 +1,54 @@
+//! Nameserver fingerprinting through CHAOS-class TXT queries
+
+use crate::dns::record::DnsRecord;
+use crate::dns::resolver::DnsHelper;
+use crate::enumerate::consistency::ns_targets;
+use crate::enumerate::findings::{Finding, Severity};
+use crate::enumerate::wildcard::record_ips;
+use std::net::SocketAddr;
+
+/// CHAOS names that commonly reveal nameserver software and identity
+pub const CHAOS_NAMES: [&str; 3] = ["version.bind", "hostname.bind", "id.server"];
+
+/// Query the CHAOS banners of a single nameserver
+///
+/// A disclosed software version is reported as low severity, host identity
+/// banners as informational.
+pub async fn fingerprint_nameserver(dns_helper: &DnsHelper, ns_name: &str, server: SocketAddr) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    
+    for chaos_name in CHAOS_NAMES {
+        if let Some(banner) = dns_helper.query_chaos(chaos_name, server).await {
+            let severity = if chaos_name == "version.bind" { Severity::Low } else { Severity::Info };
+            findings.push(Finding::new(
+                severity,
+                "ns-fingerprint",
+                ns_name,
+                format!("{} ({}) answers CH TXT {}: {}", ns_name, server.ip(), chaos_name, banner),
+            ));
+        }
+    }
+    
+    findings
+}
+
+/// Fingerprint every nameserver of a zone
+///
+/// Uses the NS records already collected for `domain`, looking them up if
+/// standard enumeration did not query NS.
+pub async fn fingerprint_zone_nameservers(domain: &str, records: &[DnsRecord], dns_helper: &DnsHelper) -> Vec<Finding> {
+    let mut nameservers = ns_targets(domain, records);
+    if nameservers.is_empty() {
+        nameservers = ns_targets(domain, &dns_helper.get_ns(domain).await.unwrap_or_default());
+    }
+    
+    let mut findings = Vec::new();
+    for ns in nameservers {
+        let addresses = dns_helper.get_ip(&ns).await.unwrap_or_default();
+        for address in record_ips(&addresses) {
+            findings.extend(fingerprint_nameserver(dns_helper, &ns, SocketAddr::new(address, 53)).await);
+        }
+    }
+    
+    findings
+}
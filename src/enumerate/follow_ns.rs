@@ -0,0 +1,74 @@
+//! Following NS delegations to map the DNS hosting footprint
+
+use crate::dns::record::{DnsRecord, RecordData};
+use crate::dns::resolver::DnsHelper;
+use crate::dns::DnsError;
+use crate::enumerate::whois;
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use tokio::task;
+
+/// Origin of records describing the zone's nameserver infrastructure
+pub const NS_INFRASTRUCTURE_ORIGIN: &str = "ns-infrastructure";
+
+/// Nameserver hosts named by the NS records, lowercased and deduplicated
+pub fn nameserver_hosts(records: &[DnsRecord]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    records
+        .iter()
+        .filter_map(|record| match &record.data {
+            RecordData::Ns(host) => Some(host.trim_end_matches('.').to_lowercase()),
+            _ => None,
+        })
+        .filter(|host| !host.is_empty() && seen.insert(host.clone()))
+        .collect()
+}
+
+/// Resolve every nameserver named by the NS records with `lookup`
+///
+/// The address records returned are tagged as NS infrastructure. Failed
+/// lookups are skipped.
+pub fn follow_nameservers<F>(records: &[DnsRecord], mut lookup: F) -> Vec<DnsRecord>
+where
+    F: FnMut(&str) -> Result<Vec<DnsRecord>, DnsError>,
+{
+    let mut results = Vec::new();
+
+    for host in nameserver_hosts(records) {
+        match lookup(&host) {
+            Ok(addresses) => {
+                results.extend(addresses.into_iter().map(|record| record.with_origin(NS_INFRASTRUCTURE_ORIGIN)));
+            }
+            Err(e) => tracing::debug!("Failed to resolve nameserver {}: {}", host, e),
+        }
+    }
+
+    results
+}
+
+/// Resolve the zone's nameservers, optionally attaching WHOIS data to their addresses
+pub async fn follow(records: &[DnsRecord], dns_helper: &DnsHelper, with_whois: bool) -> Vec<DnsRecord> {
+    let mut resolved = HashMap::new();
+    for host in nameserver_hosts(records) {
+        let addresses = dns_helper.get_ip(&host).await;
+        resolved.insert(host, addresses);
+    }
+    let mut results = follow_nameservers(records, |host| resolved.remove(host).unwrap_or_else(|| Ok(Vec::new())));
+
+    if with_whois {
+        for record in &mut results {
+            let ip = match record.data {
+                RecordData::A(address) => IpAddr::V4(address),
+                RecordData::Aaaa(address) => IpAddr::V6(address),
+                _ => continue,
+            };
+            match task::spawn_blocking(move || whois::cached_lookup(ip)).await {
+                Ok(Ok(data)) => record.whois = Some(whois::parse_whois(&data)),
+                Ok(Err(e)) => tracing::debug!("WHOIS lookup failed for {}: {}", ip, e),
+                Err(e) => tracing::debug!("WHOIS lookup for {} did not finish: {}", ip, e),
+            }
+        }
+    }
+
+    results
+}
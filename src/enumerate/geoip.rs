@@ -0,0 +1,86 @@
+//! GeoIP/ASN annotation of resolved addresses
+//!
+//! Looks addresses up in a local MaxMind database (GeoLite2/GeoIP2 ASN,
+//! Country or City) so A/AAAA records carry their network owner and country
+//! without any queries leaving the machine.
+
+use crate::dns::record::{DnsRecord, RecordData};
+use crate::enumerate::EnumerationError;
+use maxminddb::{MaxMindDBError, Reader};
+use serde::Deserialize;
+use std::net::IpAddr;
+
+pub use crate::dns::annotation::GeoInfo;
+
+/// Fields read from a database entry; ASN and country databases each fill some of them
+#[derive(Deserialize)]
+struct Entry<'a> {
+    autonomous_system_number: Option<u32>,
+    autonomous_system_organization: Option<&'a str>,
+    country: Option<Country<'a>>,
+}
+
+#[derive(Deserialize)]
+struct Country<'a> {
+    iso_code: Option<&'a str>,
+}
+
+/// A MaxMind database opened for lookups
+pub struct GeoIpDatabase {
+    reader: Reader<Vec<u8>>,
+}
+
+impl GeoIpDatabase {
+    /// Load the `.mmdb` file at `path`
+    pub fn open(path: &str) -> Result<Self, EnumerationError> {
+        let reader = Reader::open_readfile(path)
+            .map_err(|e| EnumerationError::Other(format!("Cannot open GeoIP database {}: {}", path, e)))?;
+        Ok(GeoIpDatabase { reader })
+    }
+
+    /// Look up an address, returning `None` when the database has nothing for it
+    pub fn lookup(&self, ip: IpAddr) -> Option<GeoInfo> {
+        let entry: Entry = match self.reader.lookup(ip) {
+            Ok(entry) => entry,
+            Err(MaxMindDBError::AddressNotFoundError(_)) => return None,
+            Err(e) => {
+                tracing::debug!("GeoIP lookup for {} failed: {}", ip, e);
+                return None;
+            }
+        };
+
+        let info = GeoInfo {
+            asn: entry.autonomous_system_number,
+            org: entry.autonomous_system_organization.map(str::to_string),
+            country: entry.country.and_then(|country| country.iso_code).map(str::to_string),
+        };
+        (info != GeoInfo::default()).then_some(info)
+    }
+
+    /// Look up the address of an A/AAAA record; other records have none
+    pub fn lookup_record(&self, record: &DnsRecord) -> Option<GeoInfo> {
+        match record.data {
+            RecordData::A(ip) => self.lookup(IpAddr::V4(ip)),
+            RecordData::Aaaa(ip) => self.lookup(IpAddr::V6(ip)),
+            _ => None,
+        }
+    }
+}
+
+/// Attach GeoIP data to every A/AAAA record found in `database`
+///
+/// Returns the number of records annotated.
+pub fn annotate(records: &mut [DnsRecord], database: &GeoIpDatabase) -> usize {
+    records.iter_mut().map(|record| annotate_record(record, database)).filter(|&annotated| annotated).count()
+}
+
+/// Attach GeoIP data to one A/AAAA record, returning whether the database had any
+pub fn annotate_record(record: &mut DnsRecord, database: &GeoIpDatabase) -> bool {
+    match database.lookup_record(record) {
+        Some(info) => {
+            record.geoip = Some(info);
+            true
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,124 @@
+//! Single-IP profiling: reverse lookup, forward confirmation and WHOIS
+
+use crate::dns::record::{DnsRecord, RecordData};
+use crate::dns::resolver::DnsHelper;
+use crate::enumerate::findings::{Finding, Severity};
+use crate::enumerate::whois::{self, WhoisInfo};
+use std::net::IpAddr;
+use tokio::task;
+
+/// Everything learned about a single IP address
+#[derive(Debug, Clone)]
+pub struct IpProfile {
+    /// The profiled address
+    pub ip: IpAddr,
+    /// PTR records for the address
+    pub ptr_records: Vec<DnsRecord>,
+    /// A/AAAA records of the PTR targets
+    pub forward_records: Vec<DnsRecord>,
+    /// Parsed WHOIS data, if the lookup succeeded
+    pub whois: Option<WhoisInfo>,
+}
+
+impl IpProfile {
+    /// Check whether any PTR target resolves back to the profiled address
+    pub fn forward_confirmed(&self) -> bool {
+        self.forward_records.iter().any(|record| match &record.data {
+            RecordData::A(address) => IpAddr::V4(*address) == self.ip,
+            RecordData::Aaaa(address) => IpAddr::V6(*address) == self.ip,
+            _ => false,
+        })
+    }
+
+    /// DNS records gathered for the address, PTR records first
+    ///
+    /// The WHOIS data is attached to the PTR records.
+    pub fn records(&self) -> Vec<DnsRecord> {
+        let mut records = self.ptr_records.clone();
+        for record in &mut records {
+            record.whois = self.whois.clone();
+        }
+        records.extend(self.forward_records.iter().cloned());
+        records
+    }
+
+    /// Findings describing the forward confirmation and WHOIS ownership
+    pub fn findings(&self) -> Vec<Finding> {
+        let subject = self.ip.to_string();
+        let mut findings = Vec::new();
+
+        if self.ptr_records.is_empty() {
+            findings.push(Finding::new(Severity::Info, "ip-profile", &subject, "No PTR record"));
+        } else if self.forward_confirmed() {
+            findings.push(Finding::new(
+                Severity::Info,
+                "ip-profile",
+                &subject,
+                format!("PTR {} is forward-confirmed", ptr_targets(&self.ptr_records).join(", ")),
+            ));
+        } else {
+            findings.push(Finding::new(
+                Severity::Low,
+                "ip-profile",
+                &subject,
+                format!("PTR {} does not resolve back to {}", ptr_targets(&self.ptr_records).join(", "), self.ip),
+            ));
+        }
+
+        if let Some(ref whois) = self.whois {
+            if let Some(ref org) = whois.org_name {
+                findings.push(Finding::new(Severity::Info, "ip-profile", &subject, format!("WHOIS organization: {}", org)));
+            }
+            if let Some(ref asn) = whois.asn {
+                findings.push(Finding::new(Severity::Info, "ip-profile", &subject, format!("Origin AS: {}", asn)));
+            }
+        }
+
+        findings
+    }
+}
+
+/// Targets of a set of PTR records
+fn ptr_targets(records: &[DnsRecord]) -> Vec<String> {
+    records
+        .iter()
+        .filter_map(|record| match &record.data {
+            RecordData::Ptr(target) => Some(target.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Profile an IP address
+///
+/// Looks up the PTR records, forward-resolves every PTR target and queries
+/// WHOIS for the owning network. Lookup failures leave the
+/// corresponding part of the profile empty.
+pub async fn profile_ip(ip: IpAddr, dns_helper: &DnsHelper) -> IpProfile {
+    let ptr_records = dns_helper.get_ptr(&ip.to_string()).await.unwrap_or_else(|e| {
+        tracing::debug!("Failed to get PTR record for {}: {}", ip, e);
+        Vec::new()
+    });
+
+    let mut forward_records = Vec::new();
+    for target in ptr_targets(&ptr_records) {
+        match dns_helper.get_ip(&target).await {
+            Ok(records) => forward_records.extend(records),
+            Err(e) => tracing::debug!("Failed to resolve PTR target {}: {}", target, e),
+        }
+    }
+
+    let whois = match task::spawn_blocking(move || whois::cached_lookup(ip)).await {
+        Ok(Ok(data)) => Some(whois::parse_whois(&data)),
+        Ok(Err(e)) => {
+            tracing::debug!("WHOIS lookup failed for {}: {}", ip, e);
+            None
+        }
+        Err(e) => {
+            tracing::debug!("WHOIS lookup for {} did not finish: {}", ip, e);
+            None
+        }
+    };
+
+    IpProfile { ip, ptr_records, forward_records, whois }
+}
@@ -0,0 +1,69 @@
+//! Record-count limiting for time-boxed scans
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Thread-safe cap on the total number of records collected
+///
+/// Concurrent tasks reserve room for the records they found; once the cap is
+/// reached every further reservation returns zero.
+pub struct RecordLimit {
+    max: Option<usize>,
+    count: AtomicUsize,
+}
+
+impl RecordLimit {
+    /// Create a limit of `max` records, or no limit if `None`
+    pub fn new(max: Option<usize>) -> Self {
+        Self {
+            max,
+            count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Create a limit that never stops collection
+    pub fn unlimited() -> Self {
+        Self::new(None)
+    }
+
+    /// Check whether the limit has been reached
+    pub fn is_reached(&self) -> bool {
+        match self.max {
+            Some(max) => self.count.load(Ordering::SeqCst) >= max,
+            None => false,
+        }
+    }
+
+    /// Reserve room for `n` records, returning how many of them may be kept
+    pub fn reserve(&self, n: usize) -> usize {
+        let max = match self.max {
+            Some(max) => max,
+            None => {
+                self.count.fetch_add(n, Ordering::SeqCst);
+                return n;
+            }
+        };
+
+        let previous = self.count.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+            Some((count + n).min(max.max(count)))
+        });
+        // fetch_update with a closure returning Some never fails
+        let previous = previous.unwrap_or_else(|count| count);
+        max.saturating_sub(previous).min(n)
+    }
+
+    /// Number of records counted so far
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+
+    /// The configured maximum, if any
+    pub fn max(&self) -> Option<usize> {
+        self.max
+    }
+}
+
+impl Default for RecordLimit {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
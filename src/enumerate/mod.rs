@@ -11,6 +11,32 @@ pub mod bing;
 pub mod yandex;
 pub mod whois;
 pub mod brute_force;
+pub mod wildcard;
+pub mod findings;
+pub mod spf;
+pub mod dmarc;
+pub mod limit;
+pub mod nameserver_diff;
+pub mod scrape_seeds;
+pub mod ip_profile;
+pub mod sources;
+pub mod consistency;
+pub mod fingerprint;
+pub mod negative_cache;
+pub mod exclude;
+pub mod apex;
+pub mod follow_ns;
+pub mod txt_classify;
+pub mod rdns_audit;
+pub mod ecs;
+pub mod pattern;
+pub mod mutate;
+pub mod reverse_enrich;
+pub mod fcrdns;
+#[cfg(feature = "geoip")]
+pub mod geoip;
+pub mod reverse_sweep;
+pub mod srv;
 
 /// Enumeration-related errors
 #[derive(Error, Debug)]
@@ -0,0 +1,93 @@
+//! Permutations of known subdomains
+//!
+//! Names found elsewhere (crt.sh, search engines) hint at a zone's naming
+//! scheme. Like altdns, each known name is combined with a list of words
+//! (`dev-api`, `api-dev`, `dev.api`, `api.dev`) and has its trailing number
+//! shifted (`web01` gives `web00` and `web02`, `api` gives `api1` and `api2`),
+//! producing new candidates to brute force.
+
+/// Default cap on the number of permutations tried
+pub const DEFAULT_MAX_PERMUTATIONS: usize = 10_000;
+
+/// Generate candidate names from `known` names and `words`
+///
+/// Names and candidates are relative to the target domain. Only the first
+/// label of a name is mutated, apart from a new leading label. Candidates are
+/// lowercased and deduplicated, known names are left out, and at most `max`
+/// candidates are returned in generation order.
+pub fn generate_permutations(known: &[String], words: &[String], max: usize) -> Vec<String> {
+    let known: Vec<String> = known
+        .iter()
+        .map(|name| name.trim_matches('.').to_lowercase())
+        .filter(|name| !name.is_empty())
+        .collect();
+    let words: Vec<String> = words
+        .iter()
+        .map(|word| word.trim().to_lowercase())
+        .filter(|word| !word.is_empty() && !word.contains('.'))
+        .collect();
+
+    let mut seen: std::collections::HashSet<String> = known.iter().cloned().collect();
+    let mut candidates = Vec::new();
+    for name in &known {
+        let (label, rest) = match name.find('.') {
+            Some(dot) => name.split_at(dot),
+            None => (name.as_str(), ""),
+        };
+
+        let mutations = words
+            .iter()
+            .flat_map(|word| {
+                [
+                    format!("{}-{}{}", word, label, rest),
+                    format!("{}-{}{}", label, word, rest),
+                    format!("{}.{}", word, name),
+                    format!("{}.{}{}", label, word, rest),
+                ]
+            })
+            .chain(number_variants(label).into_iter().map(|label| format!("{}{}", label, rest)));
+
+        for candidate in mutations {
+            if candidates.len() >= max {
+                return candidates;
+            }
+            if seen.insert(candidate.clone()) {
+                candidates.push(candidate);
+            }
+        }
+    }
+
+    candidates
+}
+
+/// Names below `domain` relative to it, without wildcards or the apex
+pub fn relative_names(domain: &str, names: &[String]) -> Vec<String> {
+    let suffix = format!(".{}", domain.trim_matches('.').to_lowercase());
+    names
+        .iter()
+        .filter_map(|name| {
+            let name = name.trim_end_matches('.').trim_start_matches("*.").to_lowercase();
+            name.strip_suffix(&suffix).map(str::to_string)
+        })
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Labels with the trailing number shifted by one, or `1` and `2` appended
+///
+/// Shifted numbers keep their zero-padding.
+fn number_variants(label: &str) -> Vec<String> {
+    let stem = label.trim_end_matches(|c: char| c.is_ascii_digit());
+    let digits = &label[stem.len()..];
+    let Ok(number) = digits.parse::<u64>() else {
+        return vec![format!("{}1", label), format!("{}2", label)];
+    };
+
+    let width = digits.len();
+    let mut variants = Vec::new();
+    if let Some(lower) = number.checked_sub(1) {
+        variants.push(format!("{}{:0width$}", stem, lower));
+    }
+    variants.push(format!("{}{:0width$}", stem, number + 1));
+    variants
+}
@@ -0,0 +1,51 @@
+//! Comparison of answers returned by different nameservers
+//!
+//! Differing answers for the same query can indicate split-horizon DNS, stale
+//! secondaries, or a resolver returning poisoned data.
+
+use crate::dns::record::DnsRecord;
+use crate::enumerate::findings::{Finding, Severity};
+use std::collections::BTreeSet;
+use std::net::IpAddr;
+
+/// Normalized set of answer values, independent of order and TTL
+fn answer_set(records: &[DnsRecord]) -> BTreeSet<String> {
+    records
+        .iter()
+        .map(|record| {
+            let data = serde_json::to_string(&record.data).unwrap_or_default();
+            format!("{}|{:?}|{}", record.name.to_lowercase(), record.record_type, data)
+        })
+        .collect()
+}
+
+/// Check whether the nameservers returned different answers
+pub fn answers_differ(answers: &[(IpAddr, Vec<DnsRecord>)]) -> bool {
+    let mut sets = answers.iter().map(|(_, records)| answer_set(records));
+    match sets.next() {
+        Some(first) => sets.any(|set| set != first),
+        None => false,
+    }
+}
+
+/// Raise a finding when nameservers disagree about the answer to a query
+pub fn nameserver_findings(name: &str, query: &str, answers: &[(IpAddr, Vec<DnsRecord>)]) -> Vec<Finding> {
+    if !answers_differ(answers) {
+        return Vec::new();
+    }
+
+    let details: Vec<String> = answers
+        .iter()
+        .map(|(nameserver, records)| {
+            let values: Vec<String> = records.iter().map(DnsRecord::value_string).collect();
+            format!("{} -> [{}]", nameserver, values.join(", "))
+        })
+        .collect();
+
+    vec![Finding::new(
+        Severity::Medium,
+        "resolver-mismatch",
+        name,
+        format!("Nameservers disagree on {} answer: {}", query, details.join("; ")),
+    )]
+}
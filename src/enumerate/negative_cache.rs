@@ -0,0 +1,106 @@
+//! Negative cache of names known not to exist
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+/// Names kept by [`NegativeCache::new`] before the least recently used is evicted
+pub const DEFAULT_NEGATIVE_CACHE_CAPACITY: usize = 10_000;
+
+/// Thread-safe set of names that returned NXDOMAIN
+///
+/// A name that does not exist has no children, so any name at or below a cached
+/// entry can be skipped without querying. The cache holds at most `capacity`
+/// names and evicts the least recently used one beyond that, so a long
+/// wordlist full of missing words doesn't grow it without bound.
+#[derive(Debug)]
+pub struct NegativeCache {
+    capacity: usize,
+    entries: Mutex<Entries>,
+}
+
+/// Cached names with the tick they were last used at, and the reverse index
+/// used to find the least recently used name
+#[derive(Debug, Default)]
+struct Entries {
+    names: HashMap<String, u64>,
+    by_use: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl Entries {
+    /// Mark `name` as used now, adding it if it isn't cached yet
+    fn touch(&mut self, name: String) {
+        self.tick += 1;
+        if let Some(previous) = self.names.insert(name.clone(), self.tick) {
+            self.by_use.remove(&previous);
+        }
+        self.by_use.insert(self.tick, name);
+    }
+}
+
+impl Default for NegativeCache {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_NEGATIVE_CACHE_CAPACITY)
+    }
+}
+
+impl NegativeCache {
+    /// Create an empty cache holding up to [`DEFAULT_NEGATIVE_CACHE_CAPACITY`] names
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty cache holding up to `capacity` names
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: Mutex::default(),
+        }
+    }
+
+    /// Record that a name returned NXDOMAIN
+    pub fn insert(&self, name: &str) {
+        let name = name.trim_end_matches('.').to_lowercase();
+        let mut entries = self.entries.lock().unwrap();
+        entries.touch(name);
+        while entries.names.len() > self.capacity {
+            let Some((_, oldest)) = entries.by_use.pop_first() else { break };
+            entries.names.remove(&oldest);
+        }
+    }
+
+    /// Check whether a name or any of its ancestors is known not to exist
+    pub fn covers(&self, name: &str) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.names.is_empty() {
+            return false;
+        }
+
+        let mut current = name.trim_end_matches('.').to_lowercase();
+        loop {
+            if entries.names.contains_key(&current) {
+                entries.touch(current);
+                return true;
+            }
+            match current.split_once('.') {
+                Some((_, parent)) if !parent.is_empty() => current = parent.to_string(),
+                _ => return false,
+            }
+        }
+    }
+
+    /// Forget every cached name
+    pub fn clear(&self) {
+        *self.entries.lock().unwrap() = Entries::default();
+    }
+
+    /// Number of cached names
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().names.len()
+    }
+
+    /// Check whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,112 @@
+//! Label patterns for structured naming schemes
+//!
+//! A pattern such as `web[01-50]` or `db-[a-c][1-3]` expands into every label
+//! it describes. Bracketed numeric ranges keep the zero-padding of their start
+//! (`[01-50]` gives `01` to `50`); any other bracket is a character class of
+//! single characters and `a-z` style ranges.
+
+use crate::enumerate::EnumerationError;
+
+/// Upper bound on the number of labels a pattern may expand to
+pub const MAX_PATTERN_EXPANSION: usize = 100_000;
+
+/// Expand a pattern into the labels it describes, in order
+pub fn expand_pattern(pattern: &str) -> Result<Vec<String>, EnumerationError> {
+    let mut labels = vec![String::new()];
+    let mut rest = pattern.trim();
+
+    while !rest.is_empty() {
+        let (choices, remainder) = match rest.find('[') {
+            Some(0) => {
+                let end = rest.find(']').ok_or_else(|| invalid(pattern, "unclosed '['"))?;
+                (bracket_choices(pattern, &rest[1..end])?, &rest[end + 1..])
+            }
+            Some(start) => (vec![rest[..start].to_string()], &rest[start..]),
+            None => (vec![rest.to_string()], ""),
+        };
+        if choices.iter().any(|choice| choice.contains(']')) {
+            return Err(invalid(pattern, "unmatched ']'"));
+        }
+
+        if labels.len().saturating_mul(choices.len()) > MAX_PATTERN_EXPANSION {
+            return Err(invalid(pattern, &format!("expands to more than {} labels", MAX_PATTERN_EXPANSION)));
+        }
+        labels = labels
+            .iter()
+            .flat_map(|label| choices.iter().map(move |choice| format!("{}{}", label, choice)))
+            .collect();
+        rest = remainder;
+    }
+
+    Ok(labels)
+}
+
+/// Expand several patterns, keeping the first occurrence of each label
+pub fn expand_patterns(patterns: &[String]) -> Result<Vec<String>, EnumerationError> {
+    let mut labels = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    for pattern in patterns {
+        for label in expand_pattern(pattern)? {
+            if seen.insert(label.clone()) {
+                labels.push(label);
+            }
+        }
+    }
+    Ok(labels)
+}
+
+/// Choices described by the inside of a bracket
+fn bracket_choices(pattern: &str, body: &str) -> Result<Vec<String>, EnumerationError> {
+    if body.is_empty() {
+        return Err(invalid(pattern, "empty '[]'"));
+    }
+
+    // Numeric range such as 01-50
+    if let Some((start, end)) = body.split_once('-') {
+        let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+        if is_number(start) && is_number(end) {
+            let first: u64 = start.parse().map_err(|_| invalid(pattern, "range start too large"))?;
+            let last: u64 = end.parse().map_err(|_| invalid(pattern, "range end too large"))?;
+            if first > last {
+                return Err(invalid(pattern, &format!("range {} runs backwards", body)));
+            }
+            if last - first >= MAX_PATTERN_EXPANSION as u64 {
+                return Err(invalid(pattern, &format!("expands to more than {} labels", MAX_PATTERN_EXPANSION)));
+            }
+            let width = if start.len() > 1 && start.starts_with('0') { start.len() } else { 0 };
+            return Ok((first..=last).map(|n| format!("{:0width$}", n, width = width)).collect());
+        }
+    }
+
+    // Character class such as a-z0-9 or xyz
+    let chars: Vec<char> = body.chars().collect();
+    let mut choices: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let (from, to) = if i + 2 < chars.len() && chars[i + 1] == '-' {
+            i += 3;
+            (chars[i - 3], chars[i - 1])
+        } else {
+            i += 1;
+            (chars[i - 1], chars[i - 1])
+        };
+        if from > to {
+            return Err(invalid(pattern, &format!("range {}-{} runs backwards", from, to)));
+        }
+        for c in from..=to {
+            let choice = c.to_string();
+            if !choices.contains(&choice) {
+                choices.push(choice);
+            }
+            if choices.len() > MAX_PATTERN_EXPANSION {
+                return Err(invalid(pattern, &format!("expands to more than {} labels", MAX_PATTERN_EXPANSION)));
+            }
+        }
+    }
+    Ok(choices)
+}
+
+/// Error for a pattern that can't be expanded
+fn invalid(pattern: &str, reason: &str) -> EnumerationError {
+    EnumerationError::Parse(format!("Invalid pattern '{}': {}", pattern, reason))
+}
@@ -0,0 +1,156 @@
+//! Forward/reverse DNS consistency audit
+//!
+//! Every address record should have a PTR naming it back, and every PTR
+//! target should resolve to the address it was found for. Lookups are passed
+//! in so the checks can run against synthetic data.
+
+use crate::dns::record::{DnsRecord, RecordData};
+use crate::dns::resolver::DnsHelper;
+use crate::dns::DnsError;
+use crate::enumerate::fcrdns::is_forward_confirmed;
+use crate::enumerate::findings::{Finding, Severity};
+use crate::enumerate::wildcard::record_ips;
+use crate::utils::concurrency::resolve_bounded;
+use std::collections::{BTreeSet, HashMap};
+use std::net::IpAddr;
+use std::sync::Arc;
+
+/// Hostnames and addresses of the A/AAAA records, deduplicated
+pub fn forward_pairs(records: &[DnsRecord]) -> BTreeSet<(String, IpAddr)> {
+    records
+        .iter()
+        .filter_map(|record| match record.data {
+            RecordData::A(ip) => Some((normalize(&record.name), IpAddr::V4(ip))),
+            RecordData::Aaaa(ip) => Some((normalize(&record.name), IpAddr::V6(ip))),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Addresses and targets of the PTR records, deduplicated
+///
+/// PTR records are named after the address they were found for; records
+/// whose name isn't an address are skipped.
+pub fn reverse_pairs(records: &[DnsRecord]) -> BTreeSet<(IpAddr, String)> {
+    records
+        .iter()
+        .filter_map(|record| match &record.data {
+            RecordData::Ptr(target) => {
+                let ip = record.name.parse::<IpAddr>().ok()?;
+                Some((ip, normalize(target)))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Check forward and reverse records against each other
+///
+/// `reverse` returns the PTR records of an address and `forward` the A/AAAA
+/// records of a hostname. Either returns `None` when its lookup failed, and
+/// no finding is raised for a pair whose lookup was inconclusive.
+pub fn audit_with<R, F>(records: &[DnsRecord], reverse: R, forward: F) -> Vec<Finding>
+where
+    R: Fn(IpAddr) -> Option<Vec<DnsRecord>>,
+    F: Fn(&str) -> Option<Vec<DnsRecord>>,
+{
+    let mut findings = Vec::new();
+
+    for (name, ip) in forward_pairs(records) {
+        let Some(ptrs) = reverse(ip) else { continue };
+        let targets: BTreeSet<String> = ptrs
+            .iter()
+            .filter_map(|record| match &record.data {
+                RecordData::Ptr(target) => Some(normalize(target)),
+                _ => None,
+            })
+            .collect();
+
+        if targets.is_empty() {
+            findings.push(Finding::new(
+                Severity::Low,
+                "rdns",
+                &name,
+                format!("{} has no PTR record", ip),
+            ));
+        } else if !targets.contains(&name) {
+            let targets: Vec<&str> = targets.iter().map(String::as_str).collect();
+            findings.push(Finding::new(
+                Severity::Low,
+                "rdns",
+                &name,
+                format!("PTR for {} points at {} instead", ip, targets.join(", ")),
+            ));
+        }
+    }
+
+    for (ip, target) in reverse_pairs(records) {
+        let Some(forward_records) = forward(&target) else { continue };
+        let ips = record_ips(&forward_records);
+
+        if ips.is_empty() {
+            findings.push(Finding::new(
+                Severity::Low,
+                "rdns",
+                &ip.to_string(),
+                format!("PTR target {} does not resolve", target),
+            ));
+        } else if !is_forward_confirmed(ip, &forward_records) {
+            let mut ips: Vec<String> = ips.iter().map(IpAddr::to_string).collect();
+            ips.sort();
+            findings.push(Finding::new(
+                Severity::Low,
+                "rdns",
+                &ip.to_string(),
+                format!("PTR target {} resolves to {} instead", target, ips.join(", ")),
+            ));
+        }
+    }
+
+    findings
+}
+
+/// Audit forward/reverse consistency of the records, looking up PTR and
+/// address records concurrently
+pub async fn audit(records: &[DnsRecord], dns_helper: Arc<DnsHelper>, concurrency: usize) -> Vec<Finding> {
+    let ips: BTreeSet<IpAddr> = forward_pairs(records).into_iter().map(|(_, ip)| ip).collect();
+    let names: BTreeSet<String> = reverse_pairs(records).into_iter().map(|(_, target)| target).collect();
+
+    let dns_helper = &dns_helper;
+    let mut ptrs: HashMap<IpAddr, Vec<DnsRecord>> = HashMap::new();
+    resolve_bounded(
+        &ips,
+        concurrency,
+        |ip| async move { (*ip, conclusive(dns_helper.get_ptr(&ip.to_string()).await, &ip.to_string())) },
+        |(ip, found)| ptrs.extend(found.map(|found| (ip, found))),
+    )
+    .await;
+    let mut addresses: HashMap<&str, Vec<DnsRecord>> = HashMap::new();
+    resolve_bounded(
+        &names,
+        concurrency,
+        |name| async move { (name.as_str(), conclusive(dns_helper.get_ip(name).await, name)) },
+        |(name, found)| addresses.extend(found.map(|found| (name, found))),
+    )
+    .await;
+
+    audit_with(records, |ip| ptrs.get(&ip).cloned(), |name| addresses.get(name).cloned())
+}
+
+/// Records of a lookup, with a name that has no records counted as an empty
+/// answer and any other failure as inconclusive
+fn conclusive(answer: Result<Vec<DnsRecord>, DnsError>, name: &str) -> Option<Vec<DnsRecord>> {
+    match answer {
+        Ok(records) => Some(records),
+        Err(e) if e.is_no_records() => Some(Vec::new()),
+        Err(e) => {
+            tracing::debug!("Lookup for {} failed, skipping its rDNS checks: {}", name, e);
+            None
+        }
+    }
+}
+
+/// Lowercase a hostname and drop its trailing dot
+fn normalize(name: &str) -> String {
+    name.trim_end_matches('.').to_lowercase()
+}
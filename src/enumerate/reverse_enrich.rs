@@ -0,0 +1,86 @@
+//! Forward enrichment of reverse sweep results
+//!
+//! A PTR only names a host. Resolving each PTR target's CNAME and address
+//! records turns a flat list of PTRs into an inventory of hosts, including
+//! the other addresses they answer on.
+
+use crate::dns::record::{DnsRecord, RecordData};
+use crate::dns::resolver::DnsHelper;
+use crate::utils::concurrency::resolve_bounded;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Origin of records found by forward-resolving PTR targets
+pub const REVERSE_ENRICH_ORIGIN: &str = "reverse-enrich";
+
+/// Targets of the PTR records, lowercased and deduplicated
+pub fn ptr_targets(records: &[DnsRecord]) -> BTreeSet<String> {
+    records
+        .iter()
+        .filter_map(|record| match &record.data {
+            RecordData::Ptr(target) => Some(target.trim_end_matches('.').to_lowercase()),
+            _ => None,
+        })
+        .filter(|target| !target.is_empty())
+        .collect()
+}
+
+/// Forward-resolve every PTR target with `lookup`
+///
+/// `lookup` returns the CNAME, A and AAAA records of a hostname. Each target
+/// is looked up once, and records repeated across targets are kept once.
+pub fn enrich_with<F>(records: &[DnsRecord], lookup: F) -> Vec<DnsRecord>
+where
+    F: Fn(&str) -> Vec<DnsRecord>,
+{
+    let mut seen = HashSet::new();
+    let mut enriched = Vec::new();
+
+    for target in ptr_targets(records) {
+        for record in lookup(&target) {
+            let key = (record.name.to_lowercase(), record.record_type.mnemonic(), record.value_string());
+            if seen.insert(key) {
+                enriched.push(record.with_origin(REVERSE_ENRICH_ORIGIN));
+            }
+        }
+    }
+
+    enriched
+}
+
+/// Records of every PTR target, keyed by the normalized target
+pub type TargetRecords = HashMap<String, Vec<DnsRecord>>;
+
+/// Look up the CNAME, A and AAAA records of every PTR target once, with at
+/// most `concurrency` lookups in flight
+///
+/// Both [`enrich`] and [`crate::enumerate::fcrdns::confirm`] work from the
+/// result, so a run doing both resolves each target a single time.
+pub async fn resolve_targets(records: &[DnsRecord], dns_helper: &DnsHelper, concurrency: usize) -> TargetRecords {
+    let targets = ptr_targets(records);
+    let mut resolved = TargetRecords::new();
+    resolve_bounded(
+        targets,
+        concurrency,
+        |target| async move {
+            let mut found = dns_helper.get_cname(&target).await.unwrap_or_default();
+            match dns_helper.get_ip(&target).await {
+                Ok(addresses) => found.extend(addresses),
+                Err(e) => tracing::debug!("Failed to resolve PTR target {}: {}", target, e),
+            }
+            (target, found)
+        },
+        |(target, found)| {
+            // A target without records is the same as a missing one
+            if !found.is_empty() {
+                resolved.insert(target, found);
+            }
+        },
+    )
+    .await;
+    resolved
+}
+
+/// Forward records of the PTR targets, taken from the resolved targets
+pub fn enrich(records: &[DnsRecord], resolved: &TargetRecords) -> Vec<DnsRecord> {
+    enrich_with(records, |target| resolved.get(target).cloned().unwrap_or_default())
+}
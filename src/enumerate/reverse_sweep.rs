@@ -0,0 +1,54 @@
+//! Concurrent PTR sweeps over address ranges
+
+use crate::dns::record::DnsRecord;
+use crate::utils::concurrency::resolve_bounded;
+use std::cell::RefCell;
+use std::future::Future;
+use std::net::IpAddr;
+
+/// PTR records found for one address of a sweep
+pub type SweepResult = (Option<IpAddr>, Vec<DnsRecord>);
+
+/// Look up every target with `lookup`, keeping at most `concurrency` lookups alive
+///
+/// Targets are pulled from the iterator only when a lookup slot is free, so
+/// large ranges are never expanded up front. `on_completed` gets the number
+/// of finished lookups and PTR records found so far after each lookup.
+/// Returns the number of finished lookups and the results that found PTR
+/// records, in the order the lookups finish; addresses without records are
+/// dropped as soon as their lookup finishes.
+pub async fn sweep<I, F, Fut, P>(targets: I, concurrency: usize, mut lookup: F, on_completed: P) -> (usize, Vec<SweepResult>)
+where
+    I: IntoIterator,
+    F: FnMut(I::Item) -> Fut,
+    Fut: Future<Output = SweepResult>,
+    P: FnMut(usize, usize),
+{
+    let progress = RefCell::new((0, 0, on_completed));
+    let mut results = Vec::new();
+
+    resolve_bounded(
+        targets,
+        concurrency,
+        |target| {
+            let (pending, progress) = (lookup(target), &progress);
+            async move {
+                let result = pending.await;
+                let (completed, found, on_completed) = &mut *progress.borrow_mut();
+                *completed += 1;
+                *found += result.1.len();
+                on_completed(*completed, *found);
+                result
+            }
+        },
+        |result| {
+            if !result.1.is_empty() {
+                results.push(result);
+            }
+        },
+    )
+    .await;
+
+    let (completed, _, _) = progress.into_inner();
+    (completed, results)
+}
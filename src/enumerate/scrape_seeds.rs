@@ -0,0 +1,102 @@
+//! Brute-force seed words scraped from the target's own web content
+
+use scraper::{Html, Selector};
+use std::collections::BTreeSet;
+use url::Url;
+
+/// Elements and attributes that can reference other hosts or paths
+const LINK_SELECTORS: [(&str, &str); 5] = [
+    ("a[href]", "href"),
+    ("link[href]", "href"),
+    ("script[src]", "src"),
+    ("img[src]", "src"),
+    ("form[action]", "action"),
+];
+
+/// Fetch the target's homepage and extract candidate subdomain labels
+///
+/// Tries HTTPS first and falls back to HTTP. Fetch failures are logged and
+/// yield no labels, so seeding never aborts a brute force run.
+pub async fn extract_labels(domain: &str, client: &reqwest::Client) -> Vec<String> {
+    for scheme in ["https", "http"] {
+        let url = format!("{}://{}/", scheme, domain);
+        let body = match client.get(&url).send().await {
+            Ok(response) => match response.text().await {
+                Ok(body) => body,
+                Err(e) => {
+                    tracing::debug!("Failed to read {}: {}", url, e);
+                    continue;
+                }
+            },
+            Err(e) => {
+                tracing::debug!("Failed to fetch {}: {}", url, e);
+                continue;
+            }
+        };
+        
+        return labels_from_html(domain, &body);
+    }
+    
+    Vec::new()
+}
+
+/// Extract candidate subdomain labels from an HTML document
+///
+/// Collects the subdomain part of every referenced hostname under `domain`,
+/// plus word tokens from the paths of links that stay on the target site.
+pub fn labels_from_html(domain: &str, html: &str) -> Vec<String> {
+    let domain = domain.trim_end_matches('.').to_lowercase();
+    let suffix = format!(".{}", domain);
+    let base = match Url::parse(&format!("https://{}/", domain)) {
+        Ok(base) => base,
+        Err(_) => return Vec::new(),
+    };
+    
+    let document = Html::parse_document(html);
+    let mut labels = BTreeSet::new();
+    
+    for (selector_str, attribute) in LINK_SELECTORS {
+        let selector = match Selector::parse(selector_str) {
+            Ok(selector) => selector,
+            Err(_) => continue,
+        };
+        
+        for element in document.select(&selector) {
+            let link = match element.value().attr(attribute).and_then(|value| base.join(value).ok()) {
+                Some(link) => link,
+                None => continue,
+            };
+            let host = match link.host_str() {
+                Some(host) => host.to_lowercase(),
+                None => continue,
+            };
+            
+            if let Some(subdomain) = host.strip_suffix(&suffix) {
+                if !subdomain.is_empty() {
+                    labels.insert(subdomain.to_string());
+                }
+            } else if host != domain {
+                // Paths on other sites say nothing about the target's naming
+                continue;
+            }
+            
+            for segment in link.path().split('/') {
+                for token in segment.split(|c: char| !c.is_ascii_alphanumeric() && c != '-') {
+                    let token = token.trim_matches('-').to_lowercase();
+                    if is_candidate_label(&token) {
+                        labels.insert(token);
+                    }
+                }
+            }
+        }
+    }
+    
+    labels.into_iter().collect()
+}
+
+/// Check whether a token is a plausible subdomain label
+fn is_candidate_label(token: &str) -> bool {
+    (2..=63).contains(&token.len())
+        && !token.chars().all(|c| c.is_ascii_digit())
+        && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
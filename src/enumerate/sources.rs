@@ -0,0 +1,55 @@
+//! Selectable sources for standard enumeration
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A source of records used by standard enumeration
+///
+/// Available identifiers:
+/// - `dns`: apex A/AAAA, MX, NS, SOA, TXT, SPF and CAA records plus email policy checks
+/// - `crtsh`: certificate transparency logs from crt.sh
+/// - `bing`: Bing search results
+/// - `yandex`: Yandex search results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Source {
+    Dns,
+    Crtsh,
+    Bing,
+    Yandex,
+}
+
+impl Source {
+    /// Every source, in the default order
+    pub const ALL: [Source; 4] = [Source::Dns, Source::Crtsh, Source::Bing, Source::Yandex];
+
+    /// Identifier used on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            Source::Dns => "dns",
+            Source::Crtsh => "crtsh",
+            Source::Bing => "bing",
+            Source::Yandex => "yandex",
+        }
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Source {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase();
+        Source::ALL
+            .into_iter()
+            .find(|source| source.name() == name)
+            .ok_or_else(|| {
+                let available: Vec<&str> = Source::ALL.iter().map(|source| source.name()).collect();
+                format!("Unknown source '{}'; available sources: {}", s.trim(), available.join(", "))
+            })
+    }
+}
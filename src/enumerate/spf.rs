@@ -0,0 +1,332 @@
+//! SPF policy parsing, classification and expansion
+
+use crate::dns::record::{DnsRecord, RecordData, RecordType};
+use crate::dns::resolver::DnsHelper;
+use crate::dns::DnsError;
+use crate::enumerate::findings::{Finding, Severity};
+use ipnetwork::IpNetwork;
+use std::future::Future;
+use std::net::IpAddr;
+use std::pin::Pin;
+
+/// Most DNS-querying terms an SPF evaluation may use (RFC 7208 section 4.6.4)
+pub const MAX_SPF_LOOKUPS: usize = 10;
+
+/// A parsed SPF record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpfRecord {
+    /// Mechanisms and modifiers in the order they appear, excluding `v=spf1`
+    pub terms: Vec<String>,
+    /// Qualifier of the `all` mechanism (`+`, `-`, `~` or `?`), if present
+    pub all_qualifier: Option<char>,
+}
+
+/// Parse an SPF TXT string, returning `None` if it isn't an SPF record
+pub fn parse_spf(txt: &str) -> Option<SpfRecord> {
+    let mut parts = txt.split_whitespace();
+    if !parts.next()?.eq_ignore_ascii_case("v=spf1") {
+        return None;
+    }
+
+    let terms: Vec<String> = parts.map(|term| term.to_string()).collect();
+    let all_qualifier = terms.iter().find_map(|term| {
+        let lower = term.to_lowercase();
+        match lower.as_str() {
+            "all" => Some('+'),
+            "+all" | "-all" | "~all" | "?all" => lower.chars().next(),
+            _ => None,
+        }
+    });
+
+    Some(SpfRecord { terms, all_qualifier })
+}
+
+/// Classify the SPF policy of a domain from its TXT strings
+///
+/// Flags a missing SPF record, multiple SPF records, and `+all` / `?all` policies
+/// that let any host send mail for the domain.
+pub fn spf_findings(domain: &str, txt_records: &[String]) -> Vec<Finding> {
+    let spf_records: Vec<SpfRecord> = txt_records.iter().filter_map(|txt| parse_spf(txt)).collect();
+    let mut findings = Vec::new();
+
+    if spf_records.is_empty() {
+        findings.push(Finding::new(
+            Severity::Medium,
+            "spf",
+            domain,
+            "No SPF record found; any host can send mail claiming to be from this domain",
+        ));
+        return findings;
+    }
+
+    if spf_records.len() > 1 {
+        findings.push(Finding::new(
+            Severity::Medium,
+            "spf",
+            domain,
+            format!("{} SPF records found; multiple records cause a permanent error", spf_records.len()),
+        ));
+    }
+
+    for record in &spf_records {
+        match record.all_qualifier {
+            Some('+') => findings.push(Finding::new(
+                Severity::High,
+                "spf",
+                domain,
+                "SPF policy ends in +all, allowing any host to send mail",
+            )),
+            Some('?') => findings.push(Finding::new(
+                Severity::Low,
+                "spf",
+                domain,
+                "SPF policy ends in ?all (neutral), which gives receivers no guidance",
+            )),
+            _ => {}
+        }
+    }
+
+    findings
+}
+
+/// The SPF record of one domain and the records it pulls in
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpfNode {
+    /// Domain the record was looked up at
+    pub domain: String,
+    /// The record's TXT string, if the domain has one
+    pub record: Option<String>,
+    /// Ranges authorized by this record's own `ip4`, `ip6`, `a` and `mx` terms
+    pub networks: Vec<IpNetwork>,
+    /// Records pulled in by `include:` and `redirect=`, in order
+    pub includes: Vec<SpfNode>,
+    /// Why the record couldn't be looked up, for included domains
+    pub error: Option<String>,
+}
+
+/// An SPF record with its includes resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpfExpansion {
+    /// The record of the queried domain, with everything it includes
+    pub tree: SpfNode,
+    /// Every range authorized anywhere in the tree, sorted and deduplicated
+    pub networks: Vec<IpNetwork>,
+    /// DNS-querying terms evaluated, counting the one over the limit
+    pub lookups: usize,
+    /// Whether the record needs more than [`MAX_SPF_LOOKUPS`] lookups
+    pub limit_exceeded: bool,
+}
+
+/// Expand the SPF record of `domain`, following includes and resolving `a` and `mx` terms
+pub async fn expand_spf(domain: &str, dns_helper: &DnsHelper) -> Result<SpfExpansion, DnsError> {
+    expand_spf_with(domain, |name, record_type| async move { dns_helper.lookup_type(&name, record_type).await }).await
+}
+
+/// Expand the SPF record of `domain`, resolving names with `lookup`
+///
+/// Only terms with the `+` qualifier (or none) authorize ranges and have
+/// their includes followed. `include`, `a`, `mx`, `ptr`, `exists` and
+/// `redirect` each count as a lookup; expansion stops at the first one over
+/// [`MAX_SPF_LOOKUPS`], which also ends include loops. Failing lookups of
+/// included domains are kept in the tree; a failing lookup of `domain` itself
+/// is returned as the error.
+pub async fn expand_spf_with<F, Fut>(domain: &str, lookup: F) -> Result<SpfExpansion, DnsError>
+where
+    F: FnMut(String, RecordType) -> Fut + Send,
+    Fut: Future<Output = Result<Vec<DnsRecord>, DnsError>> + Send,
+{
+    let mut walk = SpfWalk { lookup, lookups: 0, limit_exceeded: false };
+    let tree = expand_node(domain.trim_end_matches('.').to_lowercase(), &mut walk).await?;
+
+    let mut networks = Vec::new();
+    collect_networks(&tree, &mut networks);
+    networks.sort();
+    networks.dedup();
+
+    Ok(SpfExpansion { tree, networks, lookups: walk.lookups, limit_exceeded: walk.limit_exceeded })
+}
+
+/// Lookup function and lookup count shared by every record of an expansion
+struct SpfWalk<F> {
+    lookup: F,
+    lookups: usize,
+    limit_exceeded: bool,
+}
+
+impl<F, Fut> SpfWalk<F>
+where
+    F: FnMut(String, RecordType) -> Fut + Send,
+    Fut: Future<Output = Result<Vec<DnsRecord>, DnsError>> + Send,
+{
+    /// Count a DNS-querying term, returning whether it is still within the limit
+    fn count_lookup(&mut self) -> bool {
+        self.lookups += 1;
+        if self.lookups > MAX_SPF_LOOKUPS {
+            self.limit_exceeded = true;
+        }
+        !self.limit_exceeded
+    }
+
+    /// Addresses of `host`, as networks with the given prefix lengths
+    async fn networks(&mut self, host: &str, prefix_v4: u8, prefix_v6: u8) -> Vec<IpNetwork> {
+        let mut networks = Vec::new();
+        for record_type in [RecordType::A, RecordType::Aaaa] {
+            let records = match (self.lookup)(host.to_string(), record_type).await {
+                Ok(records) => records,
+                Err(e) => {
+                    tracing::debug!("SPF address lookup for {} failed: {}", host, e);
+                    continue;
+                }
+            };
+            for record in records {
+                let (address, prefix) = match record.data {
+                    RecordData::A(address) => (IpAddr::V4(address), prefix_v4),
+                    RecordData::Aaaa(address) => (IpAddr::V6(address), prefix_v6),
+                    _ => continue,
+                };
+                if let Ok(network) = IpNetwork::new(address, prefix) {
+                    networks.extend(normalize_network(network));
+                }
+            }
+        }
+        networks
+    }
+
+    /// Mail exchangers of `domain`
+    async fn exchanges(&mut self, domain: &str) -> Vec<String> {
+        match (self.lookup)(domain.to_string(), RecordType::Mx).await {
+            Ok(records) => records
+                .into_iter()
+                .filter_map(|record| match record.data {
+                    RecordData::Mx { exchange, .. } => Some(exchange.trim_end_matches('.').to_string()),
+                    _ => None,
+                })
+                .collect(),
+            Err(e) => {
+                tracing::debug!("SPF MX lookup for {} failed: {}", domain, e);
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// Look up and expand the SPF record of `domain`
+fn expand_node<'a, F, Fut>(
+    domain: String,
+    walk: &'a mut SpfWalk<F>,
+) -> Pin<Box<dyn Future<Output = Result<SpfNode, DnsError>> + Send + 'a>>
+where
+    F: FnMut(String, RecordType) -> Fut + Send,
+    Fut: Future<Output = Result<Vec<DnsRecord>, DnsError>> + Send,
+{
+    Box::pin(async move {
+        let txt_records = (walk.lookup)(domain.clone(), RecordType::Txt).await?;
+        let mut node = SpfNode { domain: domain.clone(), ..Default::default() };
+        let Some((txt, record)) = txt_records.into_iter().find_map(|record| match record.data {
+            RecordData::Txt(txt) | RecordData::Spf(txt) => parse_spf(&txt).map(|spf| (txt, spf)),
+            _ => None,
+        }) else {
+            node.error = Some("no SPF record".to_string());
+            return Ok(node);
+        };
+        node.record = Some(txt);
+
+        let mut redirect = None;
+        for term in &record.terms {
+            if walk.limit_exceeded {
+                break;
+            }
+            let (pass, term) = match term.strip_prefix(['+', '-', '~', '?']) {
+                Some(rest) => (term.starts_with('+'), rest.to_lowercase()),
+                None => (true, term.to_lowercase()),
+            };
+            if let Some(target) = term.strip_prefix("redirect=") {
+                redirect = Some(target.to_string());
+                continue;
+            }
+
+            let (name, rest) = term.split_at(term.find([':', '/']).unwrap_or(term.len()));
+            match name {
+                "ip4" | "ip6" => {
+                    if let (true, Some(Ok(network))) = (pass, rest.strip_prefix(':').map(str::parse::<IpNetwork>)) {
+                        node.networks.extend(normalize_network(network));
+                    }
+                }
+                "a" | "mx" => {
+                    if !walk.count_lookup() || !pass {
+                        continue;
+                    }
+                    let (target, prefix_v4, prefix_v6) = target_and_prefixes(rest, &domain);
+                    let hosts = if name == "mx" { walk.exchanges(&target).await } else { vec![target] };
+                    for host in hosts {
+                        node.networks.extend(walk.networks(&host, prefix_v4, prefix_v6).await);
+                    }
+                }
+                "include" => {
+                    if !walk.count_lookup() || !pass {
+                        continue;
+                    }
+                    let target = rest.trim_start_matches(':').trim_end_matches('.').to_string();
+                    node.includes.push(expand_child(target, walk).await);
+                }
+                "ptr" | "exists" => {
+                    walk.count_lookup();
+                }
+                _ => {}
+            }
+        }
+
+        // A redirect only applies when the record has no `all` of its own
+        if let (Some(target), None) = (redirect, record.all_qualifier) {
+            if !walk.limit_exceeded && walk.count_lookup() {
+                node.includes.push(expand_child(target.trim_end_matches('.').to_string(), walk).await);
+            }
+        }
+
+        Ok(node)
+    })
+}
+
+/// Expand an included domain, keeping a failed lookup in the tree
+async fn expand_child<F, Fut>(domain: String, walk: &mut SpfWalk<F>) -> SpfNode
+where
+    F: FnMut(String, RecordType) -> Fut + Send,
+    Fut: Future<Output = Result<Vec<DnsRecord>, DnsError>> + Send,
+{
+    match expand_node(domain.clone(), walk).await {
+        Ok(node) => node,
+        Err(e) => SpfNode { domain, error: Some(e.to_string()), ..Default::default() },
+    }
+}
+
+/// Target domain and prefix lengths of an `a` or `mx` term, given what follows its name
+///
+/// `a:mail.example.com/24//64` gives the domain and both prefixes; the
+/// domain defaults to the record's own and the prefixes to full addresses.
+fn target_and_prefixes(rest: &str, domain: &str) -> (String, u8, u8) {
+    let (spec, cidr) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let target = match spec.strip_prefix(':') {
+        Some(target) if !target.is_empty() => target.trim_end_matches('.').to_string(),
+        _ => domain.to_string(),
+    };
+    let (prefix_v4, prefix_v6) = match cidr.split_once("//") {
+        Some((v4, v6)) => (v4, Some(v6)),
+        None => (cidr, None),
+    };
+    let prefix_v4 = prefix_v4.strip_prefix('/').and_then(|prefix| prefix.parse().ok()).unwrap_or(32);
+    let prefix_v6 = prefix_v6.and_then(|prefix| prefix.parse().ok()).unwrap_or(128);
+    (target, prefix_v4, prefix_v6)
+}
+
+/// Network with its host bits cleared, so `192.0.2.1/24` becomes `192.0.2.0/24`
+fn normalize_network(network: IpNetwork) -> Option<IpNetwork> {
+    IpNetwork::new(network.network(), network.prefix()).ok()
+}
+
+/// Add the networks of `node` and everything it includes to `networks`
+fn collect_networks(node: &SpfNode, networks: &mut Vec<IpNetwork>) {
+    networks.extend(&node.networks);
+    for include in &node.includes {
+        collect_networks(include, networks);
+    }
+}
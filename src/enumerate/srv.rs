@@ -0,0 +1,97 @@
+//! SRV record enumeration of well-known services
+//!
+//! Services such as SIP, LDAP, Kerberos and XMPP announce their hosts with SRV
+//! records at `_service._proto.<domain>`, so querying a list of common
+//! service names finds hosts that brute forcing plain labels misses.
+
+use crate::dns::record::DnsRecord;
+use crate::dns::resolver::DnsHelper;
+use crate::dns::DnsError;
+use crate::utils::concurrency::resolve_bounded;
+use std::future::Future;
+
+/// Service and protocol labels queried below the domain
+pub const COMMON_SERVICES: &[&str] = &[
+    "_sip._tcp",
+    "_sip._udp",
+    "_sip._tls",
+    "_sips._tcp",
+    "_sipfederationtls._tcp",
+    "_sipinternaltls._tcp",
+    "_h323cs._tcp",
+    "_h323ls._udp",
+    "_ldap._tcp",
+    "_ldaps._tcp",
+    "_gc._tcp",
+    "_kerberos._tcp",
+    "_kerberos._udp",
+    "_kerberos-master._tcp",
+    "_kerberos-master._udp",
+    "_kpasswd._tcp",
+    "_kpasswd._udp",
+    "_xmpp-client._tcp",
+    "_xmpp-server._tcp",
+    "_jabber._tcp",
+    "_autodiscover._tcp",
+    "_caldav._tcp",
+    "_caldavs._tcp",
+    "_carddav._tcp",
+    "_carddavs._tcp",
+    "_imap._tcp",
+    "_imaps._tcp",
+    "_pop3._tcp",
+    "_pop3s._tcp",
+    "_submission._tcp",
+    "_submissions._tcp",
+    "_smtp._tcp",
+    "_ntp._udp",
+    "_stun._udp",
+    "_stun._tcp",
+    "_turn._udp",
+    "_turn._tcp",
+    "_matrix._tcp",
+    "_minecraft._tcp",
+    "_http._tcp",
+    "_https._tcp",
+];
+
+/// Query the SRV records of every service in [`COMMON_SERVICES`] below `domain`
+/// with at most `concurrency` lookups in flight
+pub async fn enumerate_services(domain: &str, dns_helper: &DnsHelper, concurrency: usize) -> Vec<DnsRecord> {
+    enumerate_services_with(domain, COMMON_SERVICES, concurrency, |name| async move { dns_helper.get_srv(&name).await }).await
+}
+
+/// Query `services` below `domain` concurrently, resolving each name with `lookup`
+///
+/// At most `concurrency` lookups are in flight. Records come back in service
+/// order. A service that doesn't exist or fails to resolve is skipped.
+pub async fn enumerate_services_with<F, Fut>(domain: &str, services: &[&str], concurrency: usize, lookup: F) -> Vec<DnsRecord>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<Vec<DnsRecord>, DnsError>>,
+{
+    let domain = domain.trim_end_matches('.');
+    let names = services.iter().map(|service| format!("{}.{}", service, domain));
+    let lookup = &lookup;
+
+    // Only services that answered are kept, tagged with their position for sorting
+    let mut found = Vec::new();
+    resolve_bounded(
+        names.enumerate(),
+        concurrency,
+        |(index, name)| async move {
+            let answer = lookup(name.clone()).await;
+            (index, name, answer)
+        },
+        |(index, name, answer)| match answer {
+            Ok(records) if !records.is_empty() => found.push((index, records)),
+            Ok(_) => {}
+            Err(e) if e.is_nxdomain() => {}
+            Err(e) => tracing::debug!("SRV lookup for {} failed: {}", name, e),
+        },
+    )
+    .await;
+
+    found.sort_by_key(|(index, _)| *index);
+    found.into_iter().flat_map(|(_, records)| records).collect()
+}
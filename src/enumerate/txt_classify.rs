@@ -0,0 +1,79 @@
+//! TXT record classification by well-known prefixes
+
+use crate::dns::record::{DnsRecord, RecordData};
+use crate::enumerate::findings::{Finding, Severity};
+use std::collections::BTreeSet;
+
+/// Known TXT prefixes, the category they map to and the service they reveal
+///
+/// Prefixes are matched case-insensitively against the start of the string.
+const TXT_PREFIXES: &[(&str, &str, Option<&str>)] = &[
+    ("v=spf1", "spf", None),
+    ("v=dkim1", "dkim", None),
+    ("v=dmarc1", "dmarc", None),
+    ("google-site-verification=", "google-site-verification", Some("Google Workspace / Search Console")),
+    ("ms=", "ms", Some("Microsoft 365")),
+    ("atlassian-domain-verification=", "atlassian", Some("Atlassian")),
+    ("facebook-domain-verification=", "facebook", Some("Facebook")),
+    ("apple-domain-verification=", "apple", Some("Apple")),
+    ("adobe-idp-site-verification=", "adobe", Some("Adobe")),
+    ("docusign=", "docusign", Some("DocuSign")),
+    ("stripe-verification=", "stripe", Some("Stripe")),
+    ("zoom-domain-verification", "zoom", Some("Zoom")),
+    ("globalsign-domain-verification=", "globalsign", Some("GlobalSign")),
+];
+
+/// Category of TXT strings that match no known prefix
+pub const GENERIC_CATEGORY: &str = "generic";
+
+/// Classify a TXT string, returning its category
+pub fn classify_txt(txt: &str) -> &'static str {
+    lookup(txt).map(|(_, category, _)| *category).unwrap_or(GENERIC_CATEGORY)
+}
+
+/// Third-party service a TXT string verifies the domain for, if any
+pub fn txt_service(txt: &str) -> Option<&'static str> {
+    lookup(txt).and_then(|(_, _, service)| *service)
+}
+
+/// Find the known prefix a TXT string starts with
+fn lookup(txt: &str) -> Option<&'static (&'static str, &'static str, Option<&'static str>)> {
+    let txt = txt.trim().trim_matches('"');
+    TXT_PREFIXES.iter().find(|(prefix, _, _)| {
+        txt.get(..prefix.len()).is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+    })
+}
+
+/// Set the category of every TXT and SPF record from its string
+pub fn categorize_txt_records(records: &mut [DnsRecord]) {
+    for record in records {
+        let category = match &record.data {
+            RecordData::Txt(txt) | RecordData::Spf(txt) => classify_txt(txt),
+            _ => continue,
+        };
+        record.txt_category = Some(category.to_string());
+    }
+}
+
+/// Note the third-party services the domain's TXT records verify it for
+pub fn service_findings(domain: &str, records: &[DnsRecord]) -> Vec<Finding> {
+    let services: BTreeSet<&str> = records
+        .iter()
+        .filter_map(|record| match &record.data {
+            RecordData::Txt(txt) => txt_service(txt),
+            _ => None,
+        })
+        .collect();
+
+    services
+        .into_iter()
+        .map(|service| {
+            Finding::new(
+                Severity::Info,
+                "txt-service",
+                domain,
+                format!("TXT record verifies the domain with {}", service),
+            )
+        })
+        .collect()
+}
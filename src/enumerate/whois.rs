@@ -1,10 +1,43 @@
 //! WHOIS lookup functionality
 
 use crate::enumerate::EnumerationError;
+use ipnetwork::IpNetwork;
+use std::collections::HashMap;
 use std::net::{IpAddr, TcpStream};
 use std::io::{Read, Write, BufReader, BufRead};
-use std::time::Duration;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use regex::Regex;
+use tokio::sync::Semaphore;
+use tokio::task;
+
+pub use crate::dns::annotation::WhoisInfo;
+
+/// Parse a WHOIS response into structured fields
+pub fn parse_whois(data: &str) -> WhoisInfo {
+    let net_ranges = parse_whois_nets(data)
+        .into_iter()
+        .map(|(start, end)| if start == end { start } else { format!("{} - {}", start, end) })
+        .collect();
+    
+    WhoisInfo {
+        org_name: found(get_whois_orgname(data)),
+        org_handle: found(get_whois_org_handle(data)),
+        net_ranges,
+        country: found(get_whois_country(data)),
+        abuse_contact: found(get_whois_abuse_contact(data)),
+        asn: found(get_whois_asn(data)),
+    }
+}
+
+/// Convert the "Not Found" marker returned by the field parsers to `None`
+fn found(value: String) -> Option<String> {
+    if value == "Not Found" {
+        None
+    } else {
+        Some(value)
+    }
+}
 
 /// Perform WHOIS lookup for an IP address
 pub fn whois_lookup(ip: IpAddr) -> Result<String, EnumerationError> {
@@ -38,12 +71,24 @@ pub fn whois_lookup(ip: IpAddr) -> Result<String, EnumerationError> {
 
 /// Perform WHOIS lookup with referral handling
 pub fn whois_lookup_with_referral(ip: IpAddr) -> Result<String, EnumerationError> {
+    referral_lookup(ip, |_| {})
+}
+
+/// Perform WHOIS lookup with referral handling, calling `before_query` with
+/// each server right before it is queried
+fn referral_lookup<F>(ip: IpAddr, before_query: F) -> Result<String, EnumerationError>
+where
+    F: Fn(&str),
+{
     // First, query ARIN (default for most IPs)
-    let mut response = whois_lookup(ip)?;
+    let server = get_whois_server(ip);
+    before_query(server);
+    let mut response = whois_lookup_to_server(ip, server)?;
     
     // Check if we need to follow a referral
     if let Some(referral_server) = extract_referral_server(&response) {
         // Query the referral server
+        before_query(&referral_server);
         let referral_response = whois_lookup_to_server(ip, &referral_server)?;
         response.push_str("\n--- Referral Server Response ---\n");
         response.push_str(&referral_response);
@@ -52,6 +97,197 @@ pub fn whois_lookup_with_referral(ip: IpAddr) -> Result<String, EnumerationError
     Ok(response)
 }
 
+/// Minimum delay between queries to the same WHOIS server in [`cached_lookup`]
+pub const DEFAULT_WHOIS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Spaces out queries to each WHOIS server
+#[derive(Debug)]
+pub struct ServerRateLimiter {
+    interval: Duration,
+    next_query: Mutex<HashMap<String, Instant>>,
+}
+
+impl ServerRateLimiter {
+    /// Create a limiter allowing one query per `interval` to each server
+    pub fn new(interval: Duration) -> Self {
+        Self { interval, next_query: Mutex::new(HashMap::new()) }
+    }
+    
+    /// Block until `server` may be queried again, and claim that slot
+    pub fn wait(&self, server: &str) {
+        let delay = {
+            let mut next_query = self.next_query.lock().unwrap();
+            let now = Instant::now();
+            let key = server.to_lowercase();
+            let slot = next_query.get(&key).copied().unwrap_or(now).max(now);
+            next_query.insert(key, slot + self.interval);
+            slot - now
+        };
+        if !delay.is_zero() {
+            tracing::debug!("Waiting {:?} before querying WHOIS server {}", delay, server);
+            std::thread::sleep(delay);
+        }
+    }
+}
+
+/// Address range covered by a cached WHOIS response
+#[derive(Debug, Clone)]
+enum CachedRange {
+    Range(IpAddr, IpAddr),
+    Network(IpNetwork),
+}
+
+impl CachedRange {
+    /// Parse a range reported by [`parse_whois_nets`]
+    fn parse(start: &str, end: &str) -> Option<Self> {
+        if let Ok(network) = start.parse::<IpNetwork>() {
+            return Some(CachedRange::Network(network));
+        }
+        let start: IpAddr = start.parse().ok()?;
+        let end: IpAddr = end.parse().ok()?;
+        (start.is_ipv4() == end.is_ipv4() && start <= end).then_some(CachedRange::Range(start, end))
+    }
+    
+    /// Check whether `ip` falls in the range
+    fn contains(&self, ip: IpAddr) -> bool {
+        match self {
+            CachedRange::Range(start, end) => *start <= ip && ip <= *end,
+            CachedRange::Network(network) => network.contains(ip),
+        }
+    }
+}
+
+/// WHOIS responses shared by every address of the netblock they describe
+///
+/// A response is cached under the network ranges it reports, so later lookups
+/// of addresses in the same netblock don't query the server again. Queries
+/// that do go out are spaced per server by a [`ServerRateLimiter`].
+#[derive(Debug)]
+pub struct WhoisCache {
+    entries: Mutex<Vec<(CachedRange, String)>>,
+    limiter: ServerRateLimiter,
+}
+
+impl WhoisCache {
+    /// Create an empty cache querying each server at most once per `interval`
+    pub fn new(interval: Duration) -> Self {
+        Self { entries: Mutex::new(Vec::new()), limiter: ServerRateLimiter::new(interval) }
+    }
+    
+    /// Get the WHOIS response for `ip`, following referrals on a cache miss
+    pub fn lookup(&self, ip: IpAddr) -> Result<String, EnumerationError> {
+        self.lookup_with(ip, |ip| referral_lookup(ip, |server| self.limiter.wait(server)))
+    }
+    
+    /// Get the WHOIS response for `ip`, calling `query` on a cache miss
+    pub fn lookup_with<F>(&self, ip: IpAddr, query: F) -> Result<String, EnumerationError>
+    where
+        F: FnOnce(IpAddr) -> Result<String, EnumerationError>,
+    {
+        if let Some(response) = self.cached(ip) {
+            tracing::debug!("Using cached WHOIS response for {}", ip);
+            return Ok(response);
+        }
+        
+        let response = query(ip)?;
+        let mut ranges: Vec<CachedRange> = parse_whois_nets(&response)
+            .iter()
+            .filter_map(|(start, end)| CachedRange::parse(start, end))
+            .collect();
+        // Without a usable range only this address can reuse the response
+        if ranges.is_empty() {
+            ranges.push(CachedRange::Network(IpNetwork::from(ip)));
+        }
+        
+        let mut entries = self.entries.lock().unwrap();
+        for range in ranges {
+            entries.push((range, response.clone()));
+        }
+        Ok(response)
+    }
+    
+    /// Get the cached response covering `ip`, if any
+    fn cached(&self, ip: IpAddr) -> Option<String> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .iter()
+            .find(|(range, _)| range.contains(ip))
+            .map(|(_, response)| response.clone())
+    }
+}
+
+/// Perform a WHOIS lookup with referral handling through the process-wide cache
+///
+/// Addresses in an already queried netblock reuse its response, and queries
+/// to each server are at least [`DEFAULT_WHOIS_INTERVAL`] apart.
+pub fn cached_lookup(ip: IpAddr) -> Result<String, EnumerationError> {
+    static CACHE: OnceLock<WhoisCache> = OnceLock::new();
+    CACHE.get_or_init(|| WhoisCache::new(DEFAULT_WHOIS_INTERVAL)).lookup(ip)
+}
+
+/// Default number of WHOIS queries in flight in [`lookup_all`]
+///
+/// Kept low since WHOIS servers quickly block clients that query in bulk.
+pub const DEFAULT_WHOIS_CONCURRENCY: usize = 2;
+
+/// Look up WHOIS data for many addresses with at most `concurrency` in flight
+///
+/// Addresses are grouped by /24 (/64 for IPv6) and each group is looked up in
+/// order by a single task, so the netblock answer of its first address is
+/// cached before the others ask. Failed lookups are left out of the result.
+pub async fn lookup_all<F>(ips: &[IpAddr], concurrency: usize, lookup: F) -> HashMap<IpAddr, WhoisInfo>
+where
+    F: Fn(IpAddr) -> Result<String, EnumerationError> + Send + Sync + 'static,
+{
+    let mut groups: Vec<(IpNetwork, Vec<IpAddr>)> = Vec::new();
+    for &ip in ips {
+        let prefix = if ip.is_ipv4() { 24 } else { 64 };
+        let block = IpNetwork::new(ip, prefix)
+            .and_then(|network| IpNetwork::new(network.network(), prefix))
+            .unwrap_or_else(|_| IpNetwork::from(ip));
+        match groups.iter_mut().find(|(network, _)| *network == block) {
+            Some((_, group)) if !group.contains(&ip) => group.push(ip),
+            Some(_) => {}
+            None => groups.push((block, vec![ip])),
+        }
+    }
+
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let lookup = Arc::new(lookup);
+    let mut tasks = Vec::new();
+
+    for (_, group) in groups {
+        let semaphore = semaphore.clone();
+        let lookup = lookup.clone();
+
+        tasks.push(task::spawn(async move {
+            let _permit = semaphore.acquire().await.unwrap();
+            task::spawn_blocking(move || {
+                group
+                    .into_iter()
+                    .filter_map(|ip| match lookup(ip) {
+                        Ok(data) => Some((ip, parse_whois(&data))),
+                        Err(e) => {
+                            tracing::debug!("WHOIS lookup failed for {}: {}", ip, e);
+                            None
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .await
+            .unwrap_or_default()
+        }));
+    }
+
+    let mut results = HashMap::new();
+    for task in tasks {
+        if let Ok(found) = task.await {
+            results.extend(found);
+        }
+    }
+    results
+}
+
 /// Perform WHOIS lookup to a specific server
 fn whois_lookup_to_server(ip: IpAddr, server: &str) -> Result<String, EnumerationError> {
     // Connect to the WHOIS server with timeout
@@ -141,8 +377,9 @@ fn extract_referral_server(data: &str) -> Option<String> {
 pub fn parse_whois_nets(data: &str) -> Vec<(String, String)> {
     let mut nets = Vec::new();
     
-    // Match patterns like "NetRange: 192.0.2.0 - 192.0.2.255"
-    let re = Regex::new(r#"NetRange:\s*([^\s]+)\s*-\s*([^\s]+)"#).unwrap();
+    // Match patterns like "NetRange: 192.0.2.0 - 192.0.2.255" (ARIN) and
+    // "inetnum: 192.0.2.0 - 192.0.2.255" (RIPE, APNIC, AFRINIC, LACNIC)
+    let re = Regex::new(r#"(?m)^(?:NetRange|inetnum):\s*([^\s]+)\s*-\s*([^\s]+)"#).unwrap();
     
     for captures in re.captures_iter(data) {
         if captures.len() >= 3 {
@@ -152,8 +389,8 @@ pub fn parse_whois_nets(data: &str) -> Vec<(String, String)> {
         }
     }
     
-    // Also match CIDR patterns like "CIDR: 192.0.2.0/24"
-    let cidr_re = Regex::new(r#"CIDR:\s*([^\s]+)"#).unwrap();
+    // Also match CIDR patterns like "CIDR: 192.0.2.0/24" and "inet6num: 2001:db8::/32"
+    let cidr_re = Regex::new(r#"(?m)^(?:CIDR|inet6num):\s*([^\s,]+)"#).unwrap();
     
     for captures in cidr_re.captures_iter(data) {
         if captures.len() >= 2 {
@@ -209,5 +446,63 @@ pub fn get_whois_org_handle(data: &str) -> String {
         }
     }
     
+    "Not Found".to_string()
+}
+
+/// Extract the origin autonomous system from WHOIS data
+pub fn get_whois_asn(data: &str) -> String {
+    // Look for origin AS patterns
+    let patterns = vec![
+        r#"(?i)OriginAS:\s*(AS\d+)"#,
+        r#"(?i)origin:\s*(AS\d+)"#,
+        r#"(?i)aut-num:\s*(AS\d+)"#,
+    ];
+    
+    for pattern in patterns {
+        if let Ok(re) = Regex::new(pattern) {
+            if let Some(captures) = re.captures(data) {
+                if let Some(asn) = captures.get(1) {
+                    return asn.as_str().to_uppercase();
+                }
+            }
+        }
+    }
+    
+    "Not Found".to_string()
+}
+
+/// Extract the country code from WHOIS data
+pub fn get_whois_country(data: &str) -> String {
+    // ARIN uses "Country:", the other registries "country:"
+    if let Ok(re) = Regex::new(r#"(?mi)^country:\s*([A-Za-z]{2})\b"#) {
+        if let Some(captures) = re.captures(data) {
+            if let Some(country) = captures.get(1) {
+                return country.as_str().to_uppercase();
+            }
+        }
+    }
+    
+    "Not Found".to_string()
+}
+
+/// Extract the abuse contact address from WHOIS data
+pub fn get_whois_abuse_contact(data: &str) -> String {
+    // Look for abuse contact patterns
+    let patterns = vec![
+        r#"OrgAbuseEmail:\s*(\S+)"#,
+        r#"abuse-mailbox:\s*(\S+)"#,
+        r#"% Abuse contact for '[^']*' is '([^']+)'"#,
+    ];
+    
+    for pattern in patterns {
+        if let Ok(re) = Regex::new(pattern) {
+            if let Some(captures) = re.captures(data) {
+                if let Some(contact) = captures.get(1) {
+                    return contact.as_str().trim().to_string();
+                }
+            }
+        }
+    }
+    
     "Not Found".to_string()
 }
\ No newline at end of file
@@ -0,0 +1,99 @@
+//! Wildcard DNS detection
+//!
+//! Wildcards can exist at any level of a zone (`*.example.com`,
+//! `*.internal.example.com`, ...). The tracker keeps one wildcard answer set per
+//! level so brute-force results under a wildcard subtree can be filtered.
+
+use crate::dns::record::DnsRecord;
+use crate::dns::resolver::DnsHelper;
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::sync::Mutex;
+
+pub use crate::dns::record::record_ips;
+
+/// Tracks detected wildcard answer sets per zone level
+pub struct WildcardTracker {
+    levels: Mutex<HashMap<String, Option<HashSet<IpAddr>>>>,
+}
+
+impl WildcardTracker {
+    pub fn new() -> Self {
+        Self {
+            levels: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Detect whether a zone level has a wildcard, caching the result
+    ///
+    /// Returns the wildcard answer set if random names under `zone` resolve.
+    pub async fn detect(&self, zone: &str, dns_helper: &DnsHelper) -> Option<HashSet<IpAddr>> {
+        let zone = zone.to_lowercase();
+        if let Some(cached) = self.levels.lock().unwrap().get(&zone) {
+            return cached.clone();
+        }
+
+        let result = match dns_helper.detect_wildcard(&zone).await {
+            Ok(wildcard_ips) => wildcard_ips.map(|ips| ips.into_iter().collect()),
+            Err(e) => {
+                tracing::debug!("Wildcard probe under {} failed: {}", zone, e);
+                None
+            }
+        };
+        self.insert(&zone, result.clone());
+        result
+    }
+
+    /// Record the wildcard answer set for a zone level
+    pub fn insert(&self, zone: &str, wildcard_ips: Option<HashSet<IpAddr>>) {
+        self.levels.lock().unwrap().insert(zone.to_lowercase(), wildcard_ips);
+    }
+
+    /// Get the known wildcard answer set for a zone level
+    pub fn wildcard_ips(&self, zone: &str) -> Option<HashSet<IpAddr>> {
+        self.levels.lock().unwrap().get(&zone.to_lowercase()).cloned().flatten()
+    }
+
+    /// Check whether the addresses for a name are exactly the wildcard answer
+    /// set of its parent level
+    pub fn is_wildcard_answer(&self, name: &str, records: &[DnsRecord]) -> bool {
+        let parent = match parent_zone(name) {
+            Some(parent) => parent,
+            None => return false,
+        };
+
+        match self.wildcard_ips(parent) {
+            Some(wildcard_ips) => record_ips(records) == wildcard_ips,
+            None => false,
+        }
+    }
+
+    /// List every level where a wildcard was detected, with its answer set
+    pub fn detected_levels(&self) -> Vec<(String, Vec<IpAddr>)> {
+        let levels = self.levels.lock().unwrap();
+        let mut detected: Vec<(String, Vec<IpAddr>)> = levels
+            .iter()
+            .filter_map(|(zone, ips)| {
+                ips.as_ref().map(|ips| {
+                    let mut ips: Vec<IpAddr> = ips.iter().copied().collect();
+                    ips.sort();
+                    (zone.clone(), ips)
+                })
+            })
+            .collect();
+        detected.sort();
+        detected
+    }
+}
+
+impl Default for WildcardTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+/// Get the parent zone of a name (`a.b.example.com` -> `b.example.com`)
+pub fn parent_zone(name: &str) -> Option<&str> {
+    name.split_once('.').map(|(_, parent)| parent).filter(|parent| !parent.is_empty())
+}
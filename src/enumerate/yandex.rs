@@ -1,31 +1,53 @@
 //! Yandex search enumeration
 
 use crate::enumerate::EnumerationError;
-use crate::utils::http::create_http_client;
-use crate::cli::Args;
+use crate::utils::http::ClientPool;
+use crate::utils::validation::{is_subdomain_of, normalize_domain};
+use crate::config::EnumConfig;
 use scraper::{Html, Selector};
 use tokio::time::{sleep, Duration};
 use url::Url;
 
+/// Yandex search endpoint
+pub const YANDEX_URL: &str = "https://yandex.com/search/";
+
+/// Result pages requested from Yandex when `--scraper-pages` is not given
+pub const DEFAULT_YANDEX_PAGES: usize = 10;
+
+/// User-Agent sent with Yandex searches, overriding the one of the shared clients
+pub const YANDEX_USER_AGENT: &str = "Mozilla/5.0 (compatible; YandexBot/3.0; +http://yandex.com/bots)";
+
 /// Scrape Yandex for subdomains of a domain
-pub async fn scrape_yandex(domain: &str, args: &Args) -> Result<Vec<String>, EnumerationError> {
+///
+/// Each page is fetched with the next client of the shared pool, sent as [`YANDEX_USER_AGENT`].
+pub async fn scrape_yandex(domain: &str, clients: &ClientPool, config: &EnumConfig) -> Result<Vec<String>, EnumerationError> {
+    let pages = config.scraper_pages.unwrap_or(DEFAULT_YANDEX_PAGES);
+    scrape_yandex_pages(domain, clients, YANDEX_URL, pages).await
+}
+
+/// Scrape the first `pages` result pages of a Yandex search endpoint
+pub async fn scrape_yandex_pages(
+    domain: &str,
+    clients: &ClientPool,
+    base_url: &str,
+    pages: usize,
+) -> Result<Vec<String>, EnumerationError> {
     let mut subdomains = Vec::new();
     
-    // Create HTTP client with appropriate settings
-    let client = create_http_client(
-        args,
-        "Mozilla/5.0 (compatible; YandexBot/3.0; +http://yandex.com/bots)"
-    )?;
-    
     // Perform multiple searches with pagination
-    for i in 0..10 {
+    for i in 0..pages {
         let url = format!(
-            "https://yandex.com/search/?text=site:{}&p={}",
-            domain, i
+            "{}?text=site:{}&p={}",
+            base_url, domain, i
         );
         
         // Send request
-        let response = client.get(&url).send().await?;
+        let response = clients
+            .next_client()
+            .get(&url)
+            .header(reqwest::header::USER_AGENT, YANDEX_USER_AGENT)
+            .send()
+            .await?;
         
         // Check if we got a successful response
         if !response.status().is_success() {
@@ -78,11 +100,11 @@ pub async fn scrape_yandex(domain: &str, args: &Args) -> Result<Vec<String>, Enu
 }
 
 /// Scrape Yandex with retry logic
-pub async fn scrape_yandex_with_retry(domain: &str, args: &Args, max_retries: u32) -> Result<Vec<String>, EnumerationError> {
+pub async fn scrape_yandex_with_retry(domain: &str, clients: &ClientPool, config: &EnumConfig, max_retries: u32) -> Result<Vec<String>, EnumerationError> {
     let mut retries = 0;
     
     loop {
-        match scrape_yandex(domain, args).await {
+        match scrape_yandex(domain, clients, config).await {
             Ok(subdomains) => return Ok(subdomains),
             Err(e) => {
                 if retries >= max_retries {
@@ -111,11 +133,8 @@ fn extract_subdomain_from_url(url: &str, domain: &str) -> Option<String> {
     if let Ok(parsed_url) = Url::parse(url) {
         if let Some(host) = parsed_url.host_str() {
             // Check if it's a subdomain of our target domain
-            if host.ends_with(domain) && host.len() > domain.len() {
-                // Make sure it's actually a subdomain (not the domain itself)
-                if host.len() > domain.len() && host.as_bytes()[host.len() - domain.len() - 1] == b'.' {
-                    return Some(host.to_string());
-                }
+            if is_subdomain_of(host, domain) {
+                return Some(host.to_string());
             }
         }
     }
@@ -130,12 +149,13 @@ fn extract_subdomain_from_url(url: &str, domain: &str) -> Option<String> {
         url.split('/').next()?
     };
     
+    // Normalize the hostname so Unicode hosts compare against the punycode domain
+    let hostname = normalize_domain(hostname).ok()?;
+    let hostname = hostname.as_str();
+    
     // Check if it's a subdomain of our target domain
-    if hostname.ends_with(domain) && hostname.len() > domain.len() {
-        // Make sure it's actually a subdomain (not the domain itself)
-        if hostname.len() > domain.len() && hostname.as_bytes()[hostname.len() - domain.len() - 1] == b'.' {
-            return Some(hostname.to_string());
-        }
+    if is_subdomain_of(hostname, domain) {
+        return Some(hostname.to_string());
     }
     
     None
@@ -5,6 +5,7 @@
 //! and concurrent operations.
 
 pub mod cli;
+pub mod config;
 pub mod dns;
 pub mod enumerate;
 pub mod output;
@@ -14,9 +15,13 @@ use std::path::PathBuf;
 
 use thiserror::Error;
 use std::sync::Arc;
-use std::net::IpAddr;
 use std::collections::HashSet;
 use crate::cli::progress::ProgressReporter;
+use crate::config::{EnumConfig, ReconConfig};
+use crate::enumerate::findings::{Finding, Severity};
+use crate::dns::record::RecordType;
+use crate::dns::resolver::PROFILE_HOST_TYPES;
+use crate::enumerate::sources::Source;
 
 /// Main error type for the application
 #[derive(Error, Debug)]
@@ -42,118 +47,453 @@ pub enum DnsReconError {
 
 /// Main application entry point
 ///
-/// This function orchestrates the DNS enumeration process based on the provided arguments.
+/// Converts the command line arguments into a `ReconConfig` and runs the scan.
 pub async fn run(args: cli::Args) -> Result<(), DnsReconError> {
-    // Validate arguments
-    cli::validate_args(&args)?;
+    run_with_config(ReconConfig::from(args)).await
+}
+
+/// Run the scan described by the command line arguments, reporting progress to `reporter`
+pub async fn run_with_reporter(args: cli::Args, reporter: &dyn ProgressReporter) -> Result<(), DnsReconError> {
+    run_config_with_reporter(ReconConfig::from(args), reporter).await
+}
+
+/// Run a scan described by `config`
+///
+/// Progress is printed with a [`cli::progress::TimedProgressReporter`].
+pub async fn run_with_config(config: ReconConfig) -> Result<(), DnsReconError> {
+    run_config_with_reporter(config, &cli::progress::TimedProgressReporter::new()).await
+}
+
+/// Run a scan described by `config`, reporting progress to `progress`
+///
+/// This function orchestrates the DNS enumeration process based on the configuration.
+pub async fn run_config_with_reporter(config: ReconConfig, progress: &dyn ProgressReporter) -> Result<(), DnsReconError> {
+    let started = std::time::Instant::now();
+    let phases = cli::progress::PhaseTimer::new();
+    
+    // Comparing two earlier result files needs no scan
+    if let Some((ref old_file, ref new_file)) = config.diff {
+        return compare_result_files(old_file, new_file, &config, progress);
+    }
+    
+    // Validate the configuration
+    config.validate()?;
+    
+    // Compile exclusion patterns up front so a bad pattern fails before any queries
+    let exclude_filter = match config.exclude {
+        Some(ref patterns) => Some(enumerate::exclude::ExcludeFilter::new(patterns)?),
+        None => None,
+    };
+    
+    // Load the GeoIP database up front so a bad path fails before any queries
+    #[cfg(feature = "geoip")]
+    let geoip_database = match config.geoip {
+        Some(ref path) => Some(Arc::new(enumerate::geoip::GeoIpDatabase::open(path)?)),
+        None => None,
+    };
     
-    // Initialize progress reporter
-    let progress = cli::progress::TimedProgressReporter::new();
     progress.update("Starting DNS enumeration");
     
-    // Initialize results vector
+    // Start the incremental SQLite writer before any records are found
+    let sqlite_stream = match (config.sqlite_incremental, &config.sqlite_file) {
+        (true, Some(sqlite_file)) => {
+            progress.update(&format!("Writing results incrementally to SQLite database: {}", sqlite_file));
+            let sink = output::sqlite::SqliteSink::open(sqlite_file)?;
+            // Streamed records are annotated as they are written
+            #[cfg(feature = "geoip")]
+            let sink = match geoip_database {
+                Some(ref database) => sink.with_geoip(database.clone()),
+                None => sink,
+            };
+            let (record_tx, record_rx) = std::sync::mpsc::channel();
+            Some((record_tx, output::sqlite::spawn_writer(sink, record_rx, exclude_filter.clone(), config.record_types.clone())))
+        }
+        _ => None,
+    };
+    
+    // Initialize results and findings
     let mut all_results = Vec::new();
+    let mut findings: Vec<Finding> = Vec::new();
+    let mut sources: Vec<String> = Vec::new();
     
     // Set up DNS resolver
     progress.update("Setting up DNS resolver");
-    let dns_helper = if let Some(ref nameservers) = args.nameservers {
-        let ns_ips: Result<Vec<IpAddr>, _> = nameservers
-            .split(',')
-            .map(|ns| ns.trim().parse())
-            .collect();
-        let ns_ips = ns_ips.map_err(|e| DnsReconError::Other(format!("Invalid nameserver: {}", e)))?;
-        dns::resolver::DnsHelper::with_nameservers(
-            args.domain.clone().unwrap_or_default(),
-            ns_ips
-        )?
-    } else {
-        dns::resolver::DnsHelper::new(args.domain.clone().unwrap_or_default())?
+    let dns_helper = Arc::new(build_dns_helper(&config, progress)?);
+    
+    // Scan every target domain in turn; reverse sweeps don't depend on one
+    let targets = match config.enum_type {
+        cli::EnumType::Reverse => vec![config.domain.clone()],
+        _ => {
+            let domains = config.target_domains()?;
+            if domains.is_empty() { vec![None] } else { domains.into_iter().map(Some).collect() }
+        }
+    };
+    // Normalize internationalized domain names to punycode before any queries
+    let targets = targets
+        .into_iter()
+        .map(|target| match target {
+            Some(domain) if domain.trim().parse::<std::net::IpAddr>().is_err() => {
+                utils::validation::normalize_domain(&domain).map(Some).map_err(DnsReconError::Other)
+            }
+            target => Ok(target),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let record_tx = sqlite_stream.as_ref().map(|(record_tx, _)| record_tx);
+    for (index, target) in targets.iter().enumerate() {
+        let mut scan = config.clone();
+        scan.domain = target.clone();
+        if targets.len() > 1 {
+            progress.update(&format!("Scanning domain {} of {}: {}", index + 1, targets.len(), scan.domain.as_deref().unwrap_or_default()));
+        }
+        
+        let mut results = enumerate_target(&scan, &dns_helper, progress, &phases, record_tx, &mut findings, &mut sources).await?;
+        if let (Some(ref domain), None) = (&scan.domain, scan.target_ip()) {
+            tag_domain(&mut results, domain);
+        }
+        // Identical records found for different domains are kept apart
+        all_results.extend(deduplicate_records(results));
+    }
+    let mut seen_sources = HashSet::new();
+    sources.retain(|source| seen_sources.insert(source.clone()));
+    
+    // Drop excluded names before anything is counted or written
+    if let Some(ref exclude_filter) = exclude_filter {
+        let before = all_results.len();
+        all_results = exclude_filter.apply(all_results);
+        progress.update(&format!("Excluded {} records matching --exclude", before - all_results.len()));
+    }
+    
+    // Keep only the requested record types
+    if let Some(ref record_types) = config.record_types {
+        let before = all_results.len();
+        all_results = retain_record_types(all_results, record_types);
+        progress.update(&format!("Dropped {} records not matching --record-types", before - all_results.len()));
+    }
+    
+    // Enforce the record limit for enumeration types that don't stop early
+    if let Some(max_records) = config.max_records {
+        if all_results.len() >= max_records {
+            progress.update(&format!("Record limit of {} reached, stopping enumeration", max_records));
+            all_results.truncate(max_records);
+        }
+    }
+    
+    #[cfg(feature = "geoip")]
+    if let Some(ref geoip_database) = geoip_database {
+        let annotated = enumerate::geoip::annotate(&mut all_results, geoip_database);
+        progress.update(&format!("Annotated {} address records with GeoIP data", annotated));
+    }
+    
+    // Stream whatever the incremental writer hasn't seen yet and wait for it to finish
+    if let Some((record_tx, writer)) = sqlite_stream {
+        // The writer skips the records brute force already streamed, so zone
+        // transfer and NSEC3 results found alongside them still get written
+        for record in &all_results {
+            let _ = record_tx.send(record.clone());
+        }
+        drop(record_tx);
+        let written = writer
+            .join()
+            .map_err(|_| DnsReconError::Other("SQLite writer thread panicked".to_string()))??;
+        progress.update(&format!("Wrote {} records to SQLite incrementally", written));
+    }
+    
+    progress.update(&format!("Enumeration completed. Found {} records", all_results.len()));
+    
+    if config.audit_rdns {
+        progress.update("Auditing forward and reverse DNS consistency");
+        findings.extend(enumerate::rdns_audit::audit(&all_results, dns_helper.clone(), config.concurrency).await);
+    }
+    
+    // Report findings
+    for finding in &findings {
+        progress.update(&format!("Finding: {}", finding));
+    }
+    
+    // Compare against the previous scan before any output file can overwrite it
+    if let Some(ref previous_file) = config.diff_against {
+        progress.update(&format!("Comparing results against {}", previous_file));
+        let previous = output::diff::read_previous(previous_file)?;
+        let diff = output::diff::diff_records(&previous, &all_results);
+        progress.summary(&output::diff::render_diff_summary(&diff));
+        
+        if let Some(ref diff_file) = config.diff_file {
+            progress.update(&format!("Writing changes to JSON file: {}", diff_file));
+            output::diff::write_diff(&diff, diff_file)?;
+        }
+    }
+    
+    // Output results
+    if let Some(ref findings_file) = config.findings_file {
+        progress.update(&format!("Writing findings to JSON file: {}", findings_file));
+        output::format_findings(&findings, findings_file)?;
+    }
+    
+    // Outputs describing a single target name the first one scanned
+    let first_target = targets.first().cloned().flatten();
+    let metadata = output::elastic::ScanMetadata::new(first_target.as_deref().unwrap_or_default());
+    let mut outputs = Vec::new();
+    if let Some(ref json_file) = config.json_file {
+        outputs.push(output::FileOutput::Json {
+            filename: json_file.clone(),
+            pretty: !config.json_compact,
+            append: config.json_append,
+        });
+    }
+    if let Some(ref xml_file) = config.xml_file {
+        outputs.push(output::FileOutput::Xml { filename: xml_file.clone() });
+    }
+    if let Some(ref csv_file) = config.csv_file {
+        outputs.push(output::FileOutput::Csv { filename: csv_file.clone() });
+    }
+    if let (Some(ref sqlite_file), false) = (&config.sqlite_file, config.sqlite_incremental) {
+        outputs.push(output::FileOutput::Sqlite { filename: sqlite_file.clone() });
+    }
+    if let Some(ref es_bulk) = config.es_bulk {
+        outputs.push(output::FileOutput::EsBulk {
+            filename: es_bulk.clone(),
+            index: config.es_index.clone(),
+            metadata: metadata.clone(),
+        });
+    }
+    if let Some(ref zone_file) = config.zone_file {
+        let origin = config.origin.clone().or_else(|| first_target.clone()).unwrap_or_else(|| ".".to_string());
+        outputs.push(output::FileOutput::ZoneFile { filename: zone_file.clone(), origin });
+    }
+    
+    for file_output in &outputs {
+        match file_output {
+            output::FileOutput::Json { append: true, .. } => progress.update(&format!("Merging results into {}", file_output)),
+            _ => progress.update(&format!("Writing results to {}", file_output)),
+        }
+    }
+    let all_results = Arc::new(all_results);
+    output::write_outputs(Arc::clone(&all_results), outputs).await?;
+    
+    if let Some(ref es_url) = config.es_url {
+        progress.update(&format!("Sending results to Elasticsearch at {}", es_url));
+        let client = utils::http::create_http_client(&EnumConfig::from(&config))?;
+        output::elastic::post_bulk(&client, es_url, &all_results, &config.es_index, &metadata).await?;
+    }
+    
+    // If no output files specified, print to stdout
+    if config.json_file.is_none() && config.xml_file.is_none() && config.csv_file.is_none()
+        && config.sqlite_file.is_none() && config.es_bulk.is_none() && config.es_url.is_none()
+        && config.zone_file.is_none()
+    {
+        progress.update("Writing results to stdout");
+        let rendered = config.stdout_format.render(&all_results, !config.json_compact)?;
+        println!("{}", rendered.trim_end_matches('\n'));
+    }
+    
+    let summary = cli::summary::ScanSummary {
+        sources,
+        elapsed: started.elapsed(),
+        findings,
+        timings: phases.timings(),
     };
+    if let Some(ref summary_file) = config.summary_json {
+        progress.update(&format!("Writing summary to JSON file: {}", summary_file));
+        output::json::write_summary(&summary, &all_results, summary_file)?;
+    }
+    cli::summary::print_summary(&summary, &all_results, cli::summary::use_color(config.no_color), progress);
     
-    let dns_helper = Arc::new(dns_helper);
+    progress.finish(&format!("DNS enumeration completed successfully in {:.2}s", started.elapsed().as_secs_f32()));
+    
+    Ok(())
+}
+
+/// Run the enumeration selected by `config` against its single target
+///
+/// Records found by brute force are also sent to `record_tx` as they are found.
+async fn enumerate_target(
+    config: &ReconConfig,
+    dns_helper: &Arc<dns::resolver::DnsHelper>,
+    progress: &dyn ProgressReporter,
+    phases: &cli::progress::PhaseTimer,
+    record_tx: Option<&std::sync::mpsc::Sender<dns::record::DnsRecord>>,
+    findings: &mut Vec<Finding>,
+    sources: &mut Vec<String>,
+) -> Result<Vec<dns::record::DnsRecord>, DnsReconError> {
+    let mut results = Vec::new();
     
     // Execute requested enumeration techniques based on type
-    match args.r#type {
+    match config.enum_type {
         cli::EnumType::Standard => {
-            if let Some(ref domain) = args.domain {
+            if let Some(ip) = config.target_ip() {
+                let _phase = phases.start("profile");
+                progress.update(&format!("Profiling IP address: {}", ip));
+                let profile = enumerate::ip_profile::profile_ip(ip, dns_helper).await;
+                results.extend(profile.records());
+                findings.extend(profile.findings());
+                sources.extend(["reverse", "whois"].map(String::from));
+            } else if let Some(ref domain) = config.domain {
                 progress.update(&format!("Performing standard enumeration for domain: {}", domain));
-                all_results.extend(perform_standard_enumeration(dns_helper.clone(), domain, &args, &progress).await?);
+                let enum_config = EnumConfig::from(config);
+                results.extend(
+                    perform_standard_enumeration(dns_helper.clone(), domain, config, &enum_config, progress, phases, findings).await?,
+                );
+                sources.extend(config.enabled_sources().iter().map(|source| source.to_string()));
             }
         },
         cli::EnumType::BruteForce => {
-            if let Some(ref domain) = args.domain {
-                let wordlist = args.dict.as_ref().map(|s| s.as_str()).unwrap_or("data/subdomains-top1mil-5000.txt");
-                // Resolve the wordlist path correctly
-                let resolved_wordlist = resolve_wordlist_path(wordlist)?;
-                progress.update(&format!("Performing brute force enumeration for domain: {} with wordlist: {}", domain, resolved_wordlist));
-                all_results.extend(
-                    enumerate::brute_force::brute_force_concurrent(
+            if let Some(ref domain) = config.domain {
+                let _phase = phases.start("brute");
+                // Patterns given without a wordlist are brute forced on their own
+                let resolved_wordlist = match (&config.dict, &config.pattern) {
+                    (None, Some(_)) => None,
+                    (dict, _) => {
+                        // Default to subdomains-top1mil-5000.txt for a balance of speed and coverage
+                        if dict.is_none() {
+                            progress.update("No wordlist specified, using default: data/subdomains-top1mil-5000.txt");
+                        }
+                        let wordlist = dict.as_deref().unwrap_or("data/subdomains-top1mil-5000.txt");
+                        // Resolve the wordlist path correctly
+                        Some(resolve_wordlist_path(wordlist)?)
+                    }
+                };
+                match resolved_wordlist {
+                    Some(ref wordlist) => progress.update(&format!("Performing brute force enumeration for domain: {} with wordlist: {}", domain, wordlist)),
+                    None => progress.update(&format!("Performing brute force enumeration for domain: {} with patterns", domain)),
+                }
+                let mut seeds = Vec::new();
+                if config.seed_from_web {
+                    progress.update(&format!("Collecting seed words from the web content of {}", domain));
+                    let client = utils::http::create_http_client(&EnumConfig::from(config))?;
+                    seeds = enumerate::scrape_seeds::extract_labels(domain, &client).await;
+                    progress.update(&format!("Found {} seed words", seeds.len()));
+                }
+                let context = enumerate::brute_force::BruteForceContext {
+                    limit: Arc::new(enumerate::limit::RecordLimit::new(config.max_records)),
+                    seeds,
+                    record_tx: record_tx.cloned(),
+                    smart_order: config.smart_order,
+                    keep_wildcard_answers: config.no_wildcard_filter,
+                    patterns: config.pattern.clone().unwrap_or_default(),
+                    recurse_depth: config.recurse_depth,
+                    target_domain: config.domain.clone(),
+                    ..Default::default()
+                };
+                results.extend(
+                    enumerate::brute_force::brute_force_recursive(
                         domain,
-                        &resolved_wordlist,
+                        resolved_wordlist.as_deref(),
                         dns_helper.clone(),
-                        args.concurrency
+                        config.concurrency,
+                        context.clone(),
                     ).await?
                 );
+                sources.push("brute force".to_string());
+                findings.extend(wildcard_findings(&context, config));
             }
         },
         cli::EnumType::ZoneWalk => {
-            if let Some(ref domain) = args.domain {
+            if let Some(ref domain) = config.domain {
+                let _phase = phases.start("axfr");
                 progress.update(&format!("Performing zone walk for domain: {}", domain));
-                all_results.extend(perform_zone_walk(dns_helper.clone(), domain, &progress).await?);
+                results.extend(perform_zone_walk(dns_helper.clone(), domain, progress).await?);
+                sources.push("zone transfer".to_string());
             }
         },
         cli::EnumType::Reverse => {
-            if let Some(ref range) = args.range {
+            let _phase = phases.start("reverse");
+            if let Some(ref range) = config.range {
                 progress.update(&format!("Performing reverse lookup for range: {}", range));
-                all_results.extend(perform_reverse_lookup(range, &progress).await?);
+                results.extend(perform_reverse_lookup(dns_helper.clone(), range, config, progress).await?);
+            }
+            if let Some(ref range_file) = config.range_file {
+                progress.update(&format!("Performing reverse lookup for ranges in: {}", range_file));
+                let ips = load_ranges_from_file(range_file, config.max_range_size, |error| progress.error(&error))?;
+                results.extend(reverse_lookup_ips(dns_helper.clone(), ips, range_file, config, progress).await?);
+            }
+            if config.range.is_some() || config.range_file.is_some() {
+                sources.push("reverse".to_string());
+            }
+        },
+        cli::EnumType::Permute => {
+            if let Some(ref domain) = config.domain {
+                let _phase = phases.start("permute");
+                progress.update(&format!("Performing permutation enumeration for domain: {}", domain));
+                results.extend(perform_permutation(dns_helper.clone(), domain, config, progress, findings).await?);
+                sources.extend(["crt.sh", "permutation"].map(String::from));
             }
         },
     }
     
-    progress.update(&format!("Enumeration completed. Found {} records", all_results.len()));
-    
-    // Deduplicate results by name (case-insensitive)
-    let all_results = deduplicate_records(all_results);
-    
-    // Output results
-    if let Some(ref json_file) = args.json_file {
-        progress.update(&format!("Writing results to JSON file: {}", json_file));
-        output::format_json(&all_results, json_file)?;
-    }
-    
-    if let Some(ref xml_file) = args.xml_file {
-        progress.update(&format!("Writing results to XML file: {}", xml_file));
-        output::format_xml(&all_results, xml_file)?;
+    if config.check_axfr {
+        if let (Some(ref domain), None) = (&config.domain, config.target_ip()) {
+            let _phase = phases.start("axfr check");
+            results.extend(check_zone_transfers(dns_helper, domain, progress, findings).await?);
+        }
     }
     
-    if let Some(ref sqlite_file) = args.sqlite_file {
-        progress.update(&format!("Writing results to SQLite database: {}", sqlite_file));
-        output::export_sqlite(&all_results, sqlite_file)?;
+    if config.nsec3_crack {
+        results.extend(crack_nsec3_names(dns_helper, &results, config, progress, findings).await?);
     }
     
-    // If no output files specified, print to stdout
-    if args.json_file.is_none() && args.xml_file.is_none() && args.sqlite_file.is_none() {
-        progress.update("Writing results to stdout");
-        let json_output = output::json::to_json_string(&all_results)?;
-        println!("{}", json_output);
-    }
+    Ok(results)
+}
+
+/// Print the changes between two JSON result files in the configured stdout format
+fn compare_result_files(
+    old_file: &str,
+    new_file: &str,
+    config: &ReconConfig,
+    progress: &dyn ProgressReporter,
+) -> Result<(), DnsReconError> {
+    progress.update(&format!("Comparing {} against {}", new_file, old_file));
+    let old = output::json::read_json(old_file)?;
+    let new = output::json::read_json(new_file)?;
+    let diff = output::diff::diff_records(&old, &new);
     
-    progress.finish(&format!("DNS enumeration completed successfully in {:.2}s", progress.elapsed().as_secs_f32()));
+    let rendered = config.stdout_format.render_diff(&diff, !config.json_compact)?;
+    println!("{}", rendered.trim_end_matches('\n'));
     
+    progress.finish(&format!(
+        "Comparison completed: {} new, {} gone, {} modified",
+        diff.new.len(),
+        diff.gone.len(),
+        diff.modified.len()
+    ));
     Ok(())
 }
 
-/// Deduplicate DNS records by name (case-insensitive)
-fn deduplicate_records(records: Vec<dns::record::DnsRecord>) -> Vec<dns::record::DnsRecord> {
-    let mut seen_names = HashSet::new();
+/// Run the standard enumeration of `domain` and return the records found
+///
+/// Entry point for using the crate as a library. The sources, nameservers,
+/// proxy and scraper settings come from `config`; every other setting keeps
+/// its command line default. Nothing is printed and no output files are
+/// written; findings are dropped.
+pub async fn enumerate_domain(
+    domain: &str,
+    config: &EnumConfig,
+) -> Result<Vec<dns::record::DnsRecord>, DnsReconError> {
+    let recon_config = config.recon_config(domain);
+    recon_config.validate()?;
+    let domain = utils::validation::normalize_domain(domain).map_err(DnsReconError::Other)?;
+    
+    let progress = cli::progress::TimedProgressReporter::with_writer(std::io::sink());
+    let dns_helper = Arc::new(build_dns_helper(&recon_config, &progress)?);
+    let mut findings = Vec::new();
+    let phases = cli::progress::PhaseTimer::new();
+    let mut records =
+        perform_standard_enumeration(dns_helper, &domain, &recon_config, config, &progress, &phases, &mut findings).await?;
+    tag_domain(&mut records, &domain);
+    
+    Ok(deduplicate_records(records))
+}
+
+/// Deduplicate DNS records, keeping the first of each name, type and value
+///
+/// Names compare case-insensitively, so `WWW.example.com` and `www.example.com`
+/// with the same address are one record.
+pub fn deduplicate_records(records: Vec<dns::record::DnsRecord>) -> Vec<dns::record::DnsRecord> {
+    let mut seen = HashSet::new();
     let mut deduplicated = Vec::new();
     
     for record in records {
-        // Convert name to lowercase for case-insensitive comparison
-        let name_lower = record.name.to_lowercase();
-        
-        // Only add if we haven't seen this name before
-        if seen_names.insert(name_lower) {
+        // Only add if we haven't seen this record before
+        if seen.insert(record.dedup_key()) {
             deduplicated.push(record);
         }
     }
@@ -161,6 +501,139 @@ fn deduplicate_records(records: Vec<dns::record::DnsRecord>) -> Vec<dns::record:
     deduplicated
 }
 
+/// Record `domain` on every record that doesn't name its domain yet
+fn tag_domain(records: &mut [dns::record::DnsRecord], domain: &str) {
+    for record in records.iter_mut().filter(|record| record.domain.is_none()) {
+        record.domain = Some(domain.to_string());
+    }
+}
+
+/// Keep only the records whose type is one of `record_types`
+pub fn retain_record_types(
+    records: Vec<dns::record::DnsRecord>,
+    record_types: &[RecordType],
+) -> Vec<dns::record::DnsRecord> {
+    records.into_iter().filter(|record| record_types.contains(&record.record_type)).collect()
+}
+
+/// Check whether enough records have been collected to stop enumerating
+fn limit_reached(results: &[dns::record::DnsRecord], max_records: Option<usize>) -> bool {
+    max_records.is_some_and(|max| results.len() >= max)
+}
+
+/// Extract the text of TXT and SPF records
+fn txt_values(records: &[dns::record::DnsRecord]) -> Vec<String> {
+    let mut values: Vec<String> = Vec::new();
+    for record in records {
+        if let dns::record::RecordData::Txt(ref data) | dns::record::RecordData::Spf(ref data) = record.data {
+            // SPF records repeat their TXT record, so keep each string once
+            if !values.contains(data) {
+                values.push(data.clone());
+            }
+        }
+    }
+    values
+}
+
+/// Build the DNS helper described by `config`
+fn build_dns_helper(
+    config: &ReconConfig,
+    progress: &dyn ProgressReporter,
+) -> Result<dns::resolver::DnsHelper, DnsReconError> {
+    let nameservers = config.nameserver_addrs()?;
+    let dns_helper = if let (Some(ref dot_host), false) = (&config.dot_host, nameservers.is_empty()) {
+        progress.update(&format!("Using {} nameservers over TLS as {}", nameservers.len(), dot_host));
+        // Nameservers given without a port listen for DNS-over-TLS on 853
+        let nameservers = nameservers
+            .into_iter()
+            .map(|ns| if ns.port_given { ns.addr } else { std::net::SocketAddr::new(ns.addr.ip(), dns::resolver::DOT_PORT) })
+            .collect();
+        dns::resolver::DnsHelper::with_tls_nameservers(nameservers, dot_host.trim())?
+    } else if !nameservers.is_empty() {
+        progress.update(&format!("Using {} nameservers", nameservers.len()));
+        dns::resolver::DnsHelper::with_nameserver_entries(&nameservers, config.tcp_port, config.udp_port)?
+    } else {
+        dns::resolver::DnsHelper::default_resolver()?
+    };
+    
+    // Bind outgoing queries to the requested local address
+    let dns_helper = match config.bind {
+        Some(bind) => dns_helper.with_bind_addr(std::net::SocketAddr::new(bind, config.bind_port)),
+        None => dns_helper,
+    };
+    let dns_helper = match config.ns_strategy {
+        Some(strategy) => dns_helper.with_ns_strategy(strategy),
+        None => dns_helper,
+    };
+    let dns_helper = dns_helper
+        .with_address_families(!config.no_ipv4, !config.no_ipv6)
+        .with_first_response(config.first_response);
+    Ok(dns_helper.with_query_flags(dns::raw::QueryFlags {
+        dnssec_ok: config.do_bit,
+        checking_disabled: config.cd_bit,
+    }))
+}
+
+/// Report every level where a wildcard was found during brute force
+fn wildcard_findings(context: &enumerate::brute_force::BruteForceContext, config: &ReconConfig) -> Vec<Finding> {
+    let handling = if config.no_wildcard_filter { "kept" } else { "filtered" };
+    context
+        .wildcards
+        .detected_levels()
+        .into_iter()
+        .map(|(zone, ips)| {
+            Finding::new(
+                Severity::Info,
+                "wildcard",
+                &format!("*.{}", zone),
+                format!("Wildcard DNS resolving to {:?}; matching brute-force results were {}", ips, handling),
+            )
+        })
+        .collect()
+}
+
+/// Resolve names found on crt.sh, then brute force permutations of them
+///
+/// Permutations combine the names with the words of `--dict`, or with the
+/// common labels when no wordlist is given.
+async fn perform_permutation(
+    dns_helper: Arc<dns::resolver::DnsHelper>,
+    domain: &str,
+    config: &ReconConfig,
+    progress: &dyn ProgressReporter,
+    findings: &mut Vec<Finding>,
+) -> Result<Vec<dns::record::DnsRecord>, DnsReconError> {
+    progress.update("Collecting seed names from crt.sh");
+    let enum_config = EnumConfig::from(config);
+    let clients = utils::http::create_client_pool(&enum_config)?;
+    let subdomains = enumerate::crt_sh::scrape_crtsh_with_retry(domain, &clients, &enum_config, 3).await;
+    let known = match subdomains {
+        Ok(ref subdomains) => enumerate::mutate::relative_names(domain, subdomains),
+        Err(_) => Vec::new(),
+    };
+    let mut results = resolve_scraped(&dns_helper, "crt.sh", subdomains, config, progress).await?;
+    
+    let words = match config.dict {
+        Some(ref dict) => enumerate::brute_force::wordlist_words(&resolve_wordlist_path(dict)?)?
+            .collect::<Result<Vec<_>, _>>()?,
+        None => enumerate::brute_force::COMMON_LABELS.iter().map(|label| label.to_string()).collect(),
+    };
+    let candidates = enumerate::mutate::generate_permutations(&known, &words, config.max_permutations);
+    progress.update(&format!("Brute forcing {} permutations of {} known names", candidates.len(), known.len()));
+    
+    let context = enumerate::brute_force::BruteForceContext {
+        limit: Arc::new(enumerate::limit::RecordLimit::new(config.max_records)),
+        keep_wildcard_answers: config.no_wildcard_filter,
+        ..Default::default()
+    };
+    results.extend(
+        enumerate::brute_force::brute_force_words(domain, candidates, dns_helper, config.concurrency, context.clone()).await?
+    );
+    findings.extend(wildcard_findings(&context, config));
+    
+    Ok(results)
+}
+
 /// Resolve the wordlist path, handling both absolute paths and paths relative to the executable
 fn resolve_wordlist_path(wordlist_path: &str) -> Result<String, DnsReconError> {
     // If it's already an absolute path, return as is
@@ -190,81 +663,357 @@ fn resolve_wordlist_path(wordlist_path: &str) -> Result<String, DnsReconError> {
     Ok(wordlist_path.to_string())
 }
 
+/// Crack the NSEC3 hashes among the records and resolve the names recovered
+async fn crack_nsec3_names(
+    dns_helper: &dns::resolver::DnsHelper,
+    records: &[dns::record::DnsRecord],
+    config: &ReconConfig,
+    progress: &dyn ProgressReporter,
+    findings: &mut Vec<Finding>,
+) -> Result<Vec<dns::record::DnsRecord>, DnsReconError> {
+    let wordlist = resolve_wordlist_path(config.dict.as_deref().unwrap_or("data/subdomains-top1mil-5000.txt"))?;
+    progress.update(&format!("Cracking NSEC3 hashes with wordlist: {}", wordlist));
+    let words: Vec<String> = std::fs::read_to_string(&wordlist)?
+        .lines()
+        .map(str::trim)
+        .filter(|word| !word.is_empty() && !word.starts_with('#'))
+        .map(String::from)
+        .collect();
+    
+    let cracked = dns::nsec3::crack_records(records, &words);
+    progress.update(&format!("Cracked {} NSEC3 hashes", cracked.len()));
+    
+    let mut results = Vec::new();
+    for (hashed, name) in cracked {
+        findings.push(Finding::new(
+            Severity::Info,
+            "nsec3",
+            &hashed,
+            format!("NSEC3 hash is {}", name),
+        ));
+        match dns_helper.get_ip(&name).await {
+            Ok(records) => results.extend(records),
+            Err(e) => tracing::debug!("Failed to resolve {}: {}", name, e),
+        }
+    }
+    
+    Ok(results)
+}
+
+/// Try AXFR against every nameserver of `domain` and report each outcome
+///
+/// Returns the records of any zone that was transferred.
+async fn check_zone_transfers(
+    dns_helper: &dns::resolver::DnsHelper,
+    domain: &str,
+    progress: &dyn ProgressReporter,
+    findings: &mut Vec<Finding>,
+) -> Result<Vec<dns::record::DnsRecord>, DnsReconError> {
+    progress.update(&format!("Checking whether the nameservers of {} allow zone transfers", domain));
+    
+    // Remember which nameserver each address belongs to
+    let mut addresses: Vec<(String, String)> = Vec::new();
+    for record in dns_helper.get_ns(domain).await? {
+        if let dns::record::RecordData::Ns(ref nameserver) = record.data {
+            let ips = enumerate::wildcard::record_ips(&dns_helper.get_ip(nameserver).await.unwrap_or_default());
+            for ip in ips {
+                addresses.push((ip.to_string(), nameserver.clone()));
+            }
+        }
+    }
+    
+    let ips: Vec<String> = addresses.iter().map(|(ip, _)| ip.clone()).collect();
+    let mut results = Vec::new();
+    for ((ip, status), (_, nameserver)) in dns::zone_transfer::check_axfr_all(domain, &ips).await.into_iter().zip(&addresses) {
+        let subject = format!("{} ({})", nameserver, ip);
+        let finding = match status {
+            dns::zone_transfer::AxfrStatus::Allowed(records) => {
+                let finding = Finding::new(
+                    Severity::High,
+                    "axfr",
+                    &subject,
+                    format!("Zone transfer of {} allowed, returning {} records", domain, records.len()),
+                );
+                results.extend(records);
+                finding
+            }
+            dns::zone_transfer::AxfrStatus::Refused => {
+                Finding::new(Severity::Info, "axfr", &subject, format!("Zone transfer of {} refused", domain))
+            }
+            dns::zone_transfer::AxfrStatus::Error(e) => {
+                Finding::new(Severity::Info, "axfr", &subject, format!("Zone transfer of {} failed: {}", domain, e))
+            }
+        };
+        findings.push(finding);
+    }
+    
+    Ok(results)
+}
+
 /// Perform standard enumeration techniques
+///
+/// Runs each enabled source in the order given by `--sources`, stopping early
+/// once the record limit is reached.
 async fn perform_standard_enumeration(
     dns_helper: Arc<dns::resolver::DnsHelper>,
     domain: &str,
-    args: &cli::Args,
-    progress: &cli::progress::TimedProgressReporter,
+    config: &ReconConfig,
+    enum_config: &EnumConfig,
+    progress: &dyn ProgressReporter,
+    phases: &cli::progress::PhaseTimer,
+    findings: &mut Vec<Finding>,
 ) -> Result<Vec<dns::record::DnsRecord>, DnsReconError> {
     let mut results = Vec::new();
     
-    progress.update("Getting A/AAAA records");
-    results.extend(dns_helper.get_ip(domain)?);
+    // One set of HTTP clients, and their connection pools, serves every scraper
+    let clients = utils::http::create_client_pool(enum_config)?;
     
-    progress.update("Getting MX records");
-    results.extend(dns_helper.get_mx(domain)?);
+    for source in config.enabled_sources() {
+        if limit_reached(&results, config.max_records) {
+            break;
+        }
+        
+        let _phase = phases.start(source.name());
+        match source {
+            Source::Dns => {
+                results.extend(query_apex_records(&dns_helper, domain, config, progress, findings).await?);
+            },
+            Source::Crtsh => {
+                progress.update("Performing crt.sh enumeration");
+                let subdomains = enumerate::crt_sh::scrape_crtsh_with_retry(domain, &clients, enum_config, 3).await;
+                results.extend(resolve_scraped(&dns_helper, "crt.sh", subdomains, config, progress).await?);
+            },
+            Source::Bing => {
+                progress.update("Performing Bing enumeration");
+                let subdomains = enumerate::bing::scrape_bing_with_retry(domain, &clients, enum_config, 3).await;
+                results.extend(resolve_scraped(&dns_helper, "Bing", subdomains, config, progress).await?);
+            },
+            Source::Yandex => {
+                progress.update("Performing Yandex enumeration");
+                let subdomains = enumerate::yandex::scrape_yandex_with_retry(domain, &clients, enum_config, 3).await;
+                results.extend(resolve_scraped(&dns_helper, "Yandex", subdomains, config, progress).await?);
+            },
+        }
+    }
     
-    progress.update("Getting NS records");
-    results.extend(dns_helper.get_ns(domain)?);
+    if config.enabled_sources().contains(&Source::Dns) {
+        progress.update("Checking the apex for CNAME and ALIAS/ANAME flattening");
+        findings.extend(enumerate::apex::run_checks(domain, &results, &dns_helper).await);
+    }
     
-    progress.update("Getting SOA records");
-    results.extend(dns_helper.get_soa(domain)?);
+    if config.check_consistency {
+        progress.update("Checking MX/NS consistency and delegation");
+        findings.extend(enumerate::consistency::run_checks(domain, &results, &dns_helper).await);
+    }
     
-    progress.update("Getting TXT records");
-    results.extend(dns_helper.get_txt(domain)?);
+    if config.fingerprint_ns {
+        progress.update("Fingerprinting nameservers with CHAOS queries");
+        findings.extend(enumerate::fingerprint::fingerprint_zone_nameservers(domain, &results, &dns_helper).await);
+    }
     
-    progress.update("Getting SPF records");
-    results.extend(dns_helper.get_spf(domain)?);
+    if let Some(ref subnets) = config.ecs {
+        progress.update(&format!("Resolving {} for {} EDNS client subnets", domain, subnets.len()));
+        findings.extend(enumerate::ecs::report(domain, subnets, &dns_helper).await);
+    }
     
-    progress.update("Getting CAA records");
-    match dns_helper.get_caa(domain) {
-        Ok(caa_records) => results.extend(caa_records),
-        Err(e) => {
-            // Log error but continue - CAA records might not exist
-            progress.error(&format!("Failed to get CAA records: {}", e));
+    if config.srv {
+        progress.update(&format!("Querying SRV records of {} common services", enumerate::srv::COMMON_SERVICES.len()));
+        let services = enumerate::srv::enumerate_services(domain, &dns_helper, config.concurrency).await;
+        progress.update(&format!("Found {} SRV records", services.len()));
+        results.extend(services);
+    }
+    
+    if config.follow_ns {
+        progress.update("Resolving the zone's nameservers");
+        let nameservers = enumerate::follow_ns::follow(&results, &dns_helper, config.whois).await;
+        progress.update(&format!("Found {} nameserver addresses", nameservers.len()));
+        results.extend(nameservers);
+    }
+    
+    Ok(results)
+}
+
+/// Query the records at the domain apex and check its email policies
+async fn query_apex_records(
+    dns_helper: &dns::resolver::DnsHelper,
+    domain: &str,
+    config: &ReconConfig,
+    progress: &dyn ProgressReporter,
+    findings: &mut Vec<Finding>,
+) -> Result<Vec<dns::record::DnsRecord>, DnsReconError> {
+    let mut results: Vec<dns::record::DnsRecord> = Vec::new();
+    let record_types = config.apex_record_types();
+    
+    // Service binding records are commonly published on www as well as the apex
+    let www = format!("www.{}", domain);
+    let www_types: Vec<RecordType> = record_types
+        .iter()
+        .filter(|record_type| cli::WWW_RECORDS.contains(record_type))
+        .cloned()
+        .collect();
+    
+    if let Some(ref endpoint) = config.doh_json {
+        let client = utils::http::create_http_client(&EnumConfig::from(config))?;
+        results.extend(lookup_types_over_doh_json(domain, &record_types, endpoint, &client, progress).await);
+        results.extend(lookup_types_over_doh_json(&www, &www_types, endpoint, &client, progress).await);
+    } else {
+        results.extend(lookup_types(dns_helper, domain, &record_types, progress).await);
+        results.extend(lookup_types(dns_helper, &www, &www_types, progress).await);
+        
+        // Replace the single-shot addresses with everything seen across repeated queries
+        if let Some(rounds) = config.probe_rr.filter(|rounds| *rounds > 1) {
+            let address_types = [RecordType::A, RecordType::Aaaa];
+            progress.update(&format!("Probing {} {} times for rotating addresses", domain, rounds));
+            let rotation: Vec<_> = dns_helper
+                .get_ip_rotation(domain, rounds).await?
+                .into_iter()
+                .filter(|record| record_types.contains(&record.record_type))
+                .collect();
+            results.retain(|record| !address_types.contains(&record.record_type));
+            progress.update(&format!("Found {} addresses for {} across {} queries", rotation.len(), domain, rounds));
+            results.extend(rotation);
         }
     }
     
-    progress.update("Performing crt.sh enumeration");
-    // Perform crt.sh enumeration
-    match enumerate::crt_sh::scrape_crtsh_with_retry(domain, args, 3).await {
-        Ok(subdomains) => {
-            progress.update(&format!("Found {} subdomains from crt.sh, resolving...", subdomains.len()));
-            for subdomain in subdomains {
-                results.extend(dns_helper.get_ip(&subdomain)?);
+    // Email policies can only be judged when TXT or SPF records were queried
+    if record_types.contains(&RecordType::Txt) || record_types.contains(&RecordType::Spf) {
+        enumerate::txt_classify::categorize_txt_records(&mut results);
+        findings.extend(enumerate::txt_classify::service_findings(domain, &results));
+        
+        progress.update("Checking SPF and DMARC policies");
+        let txt_strings = txt_values(&results);
+        findings.extend(enumerate::spf::spf_findings(domain, &txt_strings));
+        let dmarc_records = match dns_helper.get_dmarc(domain).await {
+            Ok(dmarc_records) => dmarc_records,
+            Err(e) => {
+                tracing::debug!("Failed to get DMARC record for {}: {}", domain, e);
+                Vec::new()
             }
-        },
-        Err(e) => {
-            progress.error(&format!("Failed to scrape crt.sh: {}", e));
+        };
+        let dmarc = dmarc_records.iter().find_map(|record| match record.data {
+            dns::record::RecordData::Dmarc(ref dmarc) => Some(dmarc),
+            _ => None,
+        });
+        findings.extend(enumerate::dmarc::policy_findings(domain, dmarc));
+        results.extend(dmarc_records);
+    }
+    
+    if config.compare_resolvers {
+        progress.update("Comparing answers across nameservers");
+        for record_type in [RecordType::A, RecordType::Aaaa, RecordType::Mx, RecordType::Ns, RecordType::Txt] {
+            let answers = dns_helper.query_all_nameservers(domain, record_type.clone()).await;
+            let query = record_type.mnemonic();
+            findings.extend(enumerate::nameserver_diff::nameserver_findings(domain, &query, &answers));
         }
     }
     
-    progress.update("Performing Bing enumeration");
-    // Perform Bing enumeration
-    match enumerate::bing::scrape_bing_with_retry(domain, args, 3).await {
-        Ok(subdomains) => {
-            progress.update(&format!("Found {} subdomains from Bing, resolving...", subdomains.len()));
-            for subdomain in subdomains {
-                results.extend(dns_helper.get_ip(&subdomain)?);
+    Ok(results)
+}
+
+/// Query the given record types for a name through the configured nameservers
+async fn lookup_types(
+    dns_helper: &dns::resolver::DnsHelper,
+    name: &str,
+    record_types: &[RecordType],
+    progress: &dyn ProgressReporter,
+) -> Vec<dns::record::DnsRecord> {
+    let mut results = Vec::new();
+    
+    // Types that profile_host covers are fetched in one concurrent batch
+    let batched = if record_types.iter().any(|record_type| PROFILE_HOST_TYPES.contains(record_type)) {
+        progress.update("Getting A/AAAA/MX/TXT/CNAME records");
+        dns_helper.profile_host(name).await
+    } else {
+        Vec::new()
+    };
+    
+    for record_type in record_types {
+        if PROFILE_HOST_TYPES.contains(record_type) {
+            results.extend(batched.iter().filter(|record| record.record_type == *record_type).cloned());
+            continue;
+        }
+        
+        let type_name = record_type.mnemonic();
+        progress.update(&format!("Getting {} records", type_name));
+        match dns_helper.lookup_type(name, record_type.clone()).await {
+            Ok(records) => results.extend(records),
+            Err(e) => {
+                // Log error but continue - the zone might not have records of this type
+                progress.error(&format!("Failed to get {} records: {}", type_name, e));
             }
-        },
-        Err(e) => {
-            progress.error(&format!("Failed to scrape Bing: {}", e));
         }
     }
     
-    progress.update("Performing Yandex enumeration");
-    // Perform Yandex enumeration
-    match enumerate::yandex::scrape_yandex_with_retry(domain, args, 3).await {
+    results
+}
+
+/// Query the given record types for a name through a JSON DoH endpoint
+async fn lookup_types_over_doh_json(
+    name: &str,
+    record_types: &[RecordType],
+    endpoint: &str,
+    client: &reqwest::Client,
+    progress: &dyn ProgressReporter,
+) -> Vec<dns::record::DnsRecord> {
+    let mut results = Vec::new();
+    
+    for record_type in record_types {
+        let type_name = record_type.mnemonic();
+        progress.update(&format!("Getting {} records over DoH JSON", type_name));
+        match dns::doh_json::resolve(name, record_type.clone(), endpoint, client).await {
+            Ok(records) => results.extend(records),
+            Err(e) => progress.error(&format!("Failed to get {} records: {}", type_name, e)),
+        }
+    }
+    
+    results
+}
+
+/// Resolve the subdomains found by a scraping source
+///
+/// Subdomains are resolved to their addresses, or to every common record type
+/// with `all_records`. A failed scrape is reported and yields no records rather
+/// than aborting the scan.
+async fn resolve_scraped(
+    dns_helper: &dns::resolver::DnsHelper,
+    source_name: &str,
+    subdomains: Result<Vec<String>, enumerate::EnumerationError>,
+    config: &ReconConfig,
+    progress: &dyn ProgressReporter,
+) -> Result<Vec<dns::record::DnsRecord>, DnsReconError> {
+    let mut results = Vec::new();
+    
+    match subdomains {
         Ok(subdomains) => {
-            progress.update(&format!("Found {} subdomains from Yandex, resolving...", subdomains.len()));
-            for subdomain in subdomains {
-                results.extend(dns_helper.get_ip(&subdomain)?);
+            progress.update(&format!("Found {} subdomains from {}, resolving...", subdomains.len(), source_name));
+            // A failed lookup doesn't stop the others
+            let mut failed = 0;
+            utils::concurrency::resolve_bounded(
+                &subdomains,
+                config.concurrency,
+                |subdomain| async move {
+                    let answer = if config.all_records {
+                        dns_helper.get_all_records(subdomain).await
+                    } else {
+                        dns_helper.get_ip_rotation(subdomain, config.probe_rr.unwrap_or(1)).await
+                    };
+                    (subdomain, answer)
+                },
+                |(subdomain, answer)| match answer {
+                    Ok(records) => results.extend(records),
+                    Err(e) => {
+                        tracing::debug!("Failed to resolve {}: {}", subdomain, e);
+                        failed += 1;
+                    }
+                },
+            )
+            .await;
+            if failed > 0 {
+                progress.update(&format!("{} of {} subdomains from {} failed to resolve", failed, subdomains.len(), source_name));
             }
         },
         Err(e) => {
-            progress.error(&format!("Failed to scrape Yandex: {}", e));
+            progress.error(&format!("Failed to scrape {}: {}", source_name, e));
         }
     }
     
@@ -275,11 +1024,11 @@ async fn perform_standard_enumeration(
 async fn perform_zone_walk(
     dns_helper: Arc<dns::resolver::DnsHelper>,
     domain: &str,
-    progress: &cli::progress::TimedProgressReporter,
+    progress: &dyn ProgressReporter,
 ) -> Result<Vec<dns::record::DnsRecord>, DnsReconError> {
     progress.update("Getting NS records for zone walk");
     // First get NS records to know which servers to query
-    let ns_records = dns_helper.get_ns(domain)?;
+    let ns_records = dns_helper.get_ns(domain).await?;
     
     let mut results = Vec::new();
     results.extend(ns_records);
@@ -296,12 +1045,14 @@ async fn perform_zone_walk(
         .collect();
     
     // For each nameserver, attempt zone transfer
+    let mut transferred = false;
     for nameserver in nameservers {
         progress.update(&format!("Attempting zone transfer from {}", nameserver));
-        match dns::zone_transfer::zone_transfer(domain, &nameserver) {
+        match dns::zone_transfer::zone_transfer(domain, &nameserver, &dns_helper).await {
             Ok(zone_records) => {
                 progress.update(&format!("Zone transfer from {} successful, found {} records", nameserver, zone_records.len()));
                 results.extend(zone_records);
+                transferred = true;
             },
             Err(e) => {
                 progress.error(&format!("Zone transfer failed for {}: {}", nameserver, e));
@@ -309,46 +1060,293 @@ async fn perform_zone_walk(
         }
     }
     
+    // Without a transfer, list the zone by following its NSEC chain
+    if !transferred {
+        progress.update(&format!("Walking the NSEC chain of {}", domain));
+        match dns::zone_walk::nsec_walk(domain, &dns_helper).await {
+            dns::zone_walk::ZoneWalk::Nsec(chain) => {
+                progress.update(&format!("NSEC walk found {} names", chain.len()));
+                results.extend(chain);
+            },
+            dns::zone_walk::ZoneWalk::Nsec3(params) => {
+                progress.update(&format!("{} uses NSEC3, its hashed names can't be walked", domain));
+                results.extend(params);
+            },
+            dns::zone_walk::ZoneWalk::Unsigned => {
+                progress.update(&format!("{} has no NSEC records to walk", domain));
+            },
+        }
+    }
+    
     Ok(results)
 }
 
 /// Perform reverse lookup enumeration
-async fn perform_reverse_lookup(
+///
+/// PTR queries go through `dns_helper`, so they reach the configured nameservers.
+pub async fn perform_reverse_lookup(
+    dns_helper: Arc<dns::resolver::DnsHelper>,
     range: &str,
-    progress: &cli::progress::TimedProgressReporter,
+    config: &ReconConfig,
+    progress: &dyn ProgressReporter,
 ) -> Result<Vec<dns::record::DnsRecord>, DnsReconError> {
     progress.update(&format!("Processing IP range: {}", range));
     
-    // Parse the range and perform reverse lookups
-    let ips = utils::cidr::process_range(range)
+    // Large networks are swept one query name at a time instead of expanded,
+    // but are still held to --max-range-size
+    let mut large_networks = Vec::new();
+    let mut segments = Vec::new();
+    for segment in utils::cidr::split_ranges(range) {
+        match segment.parse::<ipnetwork::IpNetwork>() {
+            Ok(network) if segment.contains('/') && utils::reverse::is_large_network(&network) => {
+                utils::cidr::check_range_size(segment, utils::cidr::network_size(&network), config.max_range_size)
+                    .map_err(|e| DnsReconError::Other(format!("Failed to process range: {}", e)))?;
+                large_networks.push(network);
+            }
+            _ => segments.push(segment),
+        }
+    }
+    
+    // Parse the remaining ranges before any lookups so a bad segment fails early
+    let ips = utils::cidr::process_ranges(&segments, config.max_range_size)
         .map_err(|e| DnsReconError::Other(format!("Failed to process range: {}", e)))?;
     
-    progress.update(&format!("Performing reverse lookups for {} IP addresses", ips.len()));
+    // Addresses and networks inside a swept network are only looked up by its sweep
+    let large_networks = utils::cidr::outermost_networks(large_networks);
+    let ips: Vec<_> = ips
+        .into_iter()
+        .filter(|ip| !large_networks.iter().any(|network| network.contains(*ip)))
+        .collect();
     
     let mut results = Vec::new();
-    let mut resolved_count = 0;
+    for network in large_networks {
+        results.extend(perform_lazy_reverse_sweep(dns_helper.clone(), network, config, progress).await?);
+    }
+    if !ips.is_empty() {
+        results.extend(reverse_lookup_ips(dns_helper, ips, range, config, progress).await?);
+    }
+    Ok(results)
+}
+
+/// Load the addresses of every range listed in a file
+///
+/// Each non-empty line not starting with `#` is a CIDR, a start-end range or a
+/// single address. Malformed lines and ranges of more than `max_range_size`
+/// addresses are passed to `report` and skipped; the addresses of all other
+/// lines are returned in order without duplicates.
+pub fn load_ranges_from_file<F>(path: &str, max_range_size: usize, mut report: F) -> Result<Vec<std::net::IpAddr>, DnsReconError>
+where
+    F: FnMut(String),
+{
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| DnsReconError::Other(format!("Cannot read range file {}: {}", path, e)))?;
     
-    for (i, ip) in ips.iter().enumerate() {
-        // Show progress every 100 IPs
-        if i % 100 == 0 {
-            progress.update(&format!("Processed {}/{} IP addresses, found {} PTR records", i, ips.len(), resolved_count));
-        }
-        
-        // Create a temporary DNS helper for reverse lookups
-        let dns_helper = dns::resolver::DnsHelper::new("".to_string())?;
-        
-        match dns_helper.get_ptr(&ip.to_string()) {
-            Ok(ptr_records) => {
-                resolved_count += ptr_records.len();
-                results.extend(ptr_records);
-            },
-            Err(e) => {
-                tracing::debug!("Failed to get PTR record for {}: {}", ip, e);
+    let (numbers, lines): (Vec<usize>, Vec<&str>) = contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .unzip();
+    
+    Ok(utils::cidr::process_ranges_with(&lines, max_range_size, |index, e| {
+        report(format!("Skipping invalid range on line {} of {}: {} ({})", numbers[index], path, lines[index], e))
+    }))
+}
+
+/// Perform reverse lookups for a list of addresses taken from `source`
+async fn reverse_lookup_ips(
+    dns_helper: Arc<dns::resolver::DnsHelper>,
+    ips: Vec<std::net::IpAddr>,
+    source: &str,
+    config: &ReconConfig,
+    progress: &dyn ProgressReporter,
+) -> Result<Vec<dns::record::DnsRecord>, DnsReconError> {
+    // Addresses of a disabled family are not looked up
+    let ips: Vec<_> = ips
+        .into_iter()
+        .filter(|ip| if ip.is_ipv4() { !config.no_ipv4 } else { !config.no_ipv6 })
+        .collect();
+    
+    progress.update(&format!("Performing reverse lookups for {} IP addresses from {}", ips.len(), source));
+    
+    let total = ips.len();
+    let swept = enumerate::reverse_sweep::sweep(
+        ips,
+        config.concurrency,
+        |ip| {
+            let dns_helper = dns_helper.clone();
+            async move {
+                let ptr_records = dns_helper.get_ptr(&ip.to_string()).await.unwrap_or_else(|e| {
+                    tracing::debug!("Failed to get PTR record for {}: {}", ip, e);
+                    Vec::new()
+                });
+                (Some(ip), ptr_records)
             }
-        }
+        },
+        |completed, found| {
+            // Show progress every 100 IPs
+            if completed % 100 == 0 {
+                progress.update(&format!("Processed {}/{} IP addresses, found {} PTR records", completed, total, found));
+            }
+        },
+    )
+    .await;
+    
+    Ok(process_sweep(&dns_helper, swept, config, progress).await)
+}
+
+/// Sweep a large network by generating its reverse query names lazily
+async fn perform_lazy_reverse_sweep(
+    dns_helper: Arc<dns::resolver::DnsHelper>,
+    network: ipnetwork::IpNetwork,
+    config: &ReconConfig,
+    progress: &dyn ProgressReporter,
+) -> Result<Vec<dns::record::DnsRecord>, DnsReconError> {
+    // Addresses of a disabled family are not looked up
+    let family_disabled = match network {
+        ipnetwork::IpNetwork::V4(_) => config.no_ipv4,
+        ipnetwork::IpNetwork::V6(_) => config.no_ipv6,
+    };
+    if family_disabled {
+        progress.update(&format!("Skipping {}: address family is disabled", network));
+        return Ok(Vec::new());
     }
     
-    progress.update(&format!("Completed reverse lookup for {} IP addresses, found {} PTR records", ips.len(), resolved_count));
+    progress.update(&format!("Sweeping {} without expanding it up front", network));
     
-    Ok(results)
+    let swept = enumerate::reverse_sweep::sweep(
+        utils::reverse::ptr_names_for_cidr(network),
+        config.concurrency,
+        |name| {
+            let dns_helper = dns_helper.clone();
+            async move {
+                let ptr_records = dns_helper.get_ptr_name(&name).await.unwrap_or_else(|e| {
+                    tracing::debug!("Failed to get PTR record for {}: {}", name, e);
+                    Vec::new()
+                });
+                (utils::reverse::ip_from_reverse_name(&name), ptr_records)
+            }
+        },
+        |completed, found| {
+            if completed % 100 == 0 {
+                progress.update(&format!("Processed {} IP addresses, found {} PTR records", completed, found));
+            }
+        },
+    )
+    .await;
+    
+    Ok(process_sweep(&dns_helper, swept, config, progress).await)
+}
+
+/// Run the steps shared by every reverse sweep on its results
+///
+/// Reports the sweep as complete, then forward-confirms the PTR records,
+/// attaches WHOIS data and adds the records of their targets as configured.
+async fn process_sweep(
+    dns_helper: &dns::resolver::DnsHelper,
+    (swept_count, swept): (usize, Vec<enumerate::reverse_sweep::SweepResult>),
+    config: &ReconConfig,
+    progress: &dyn ProgressReporter,
+) -> Vec<dns::record::DnsRecord> {
+    let (mut results, mut resolved_ips) = flatten_sweep(swept);
+    progress.update(&format!("Completed reverse lookup for {} IP addresses, found {} PTR records", swept_count, results.len()));
+    
+    // Forward-confirmation and enrichment share one lookup per PTR target
+    let targets = if config.fcrdns || config.reverse_enrich {
+        resolve_ptr_targets(&results, dns_helper, config, progress).await
+    } else {
+        Default::default()
+    };
+    
+    if config.fcrdns {
+        keep_forward_confirmed(&mut results, &mut resolved_ips, &targets, progress);
+    }
+    
+    if config.whois {
+        attach_ptr_whois(&mut results, &resolved_ips, config, progress).await;
+    }
+    
+    if config.reverse_enrich {
+        results.extend(enrich_ptr_targets(&results, &targets, progress));
+    }
+    
+    results
+}
+
+/// Split sweep results into the PTR records and the address each was found for
+fn flatten_sweep(
+    swept: Vec<enumerate::reverse_sweep::SweepResult>,
+) -> (Vec<dns::record::DnsRecord>, Vec<Option<std::net::IpAddr>>) {
+    let mut results = Vec::new();
+    let mut resolved_ips = Vec::new();
+    for (ip, ptr_records) in swept {
+        resolved_ips.extend(ptr_records.iter().map(|_| ip));
+        results.extend(ptr_records);
+    }
+    (results, resolved_ips)
+}
+
+/// Look up the forward records of the targets of swept PTR records
+async fn resolve_ptr_targets(
+    records: &[dns::record::DnsRecord],
+    dns_helper: &dns::resolver::DnsHelper,
+    config: &ReconConfig,
+    progress: &dyn ProgressReporter,
+) -> enumerate::reverse_enrich::TargetRecords {
+    progress.update("Resolving PTR targets");
+    enumerate::reverse_enrich::resolve_targets(records, dns_helper, config.concurrency).await
+}
+
+/// Drop swept PTR records whose target doesn't resolve back to their address
+fn keep_forward_confirmed(
+    records: &mut Vec<dns::record::DnsRecord>,
+    resolved_ips: &mut Vec<Option<std::net::IpAddr>>,
+    targets: &enumerate::reverse_enrich::TargetRecords,
+    progress: &dyn ProgressReporter,
+) {
+    let confirmed = enumerate::fcrdns::confirm(records, targets);
+    let found = records.len();
+    let kept: Vec<_> = std::mem::take(records)
+        .into_iter()
+        .zip(std::mem::take(resolved_ips))
+        .zip(confirmed)
+        .filter_map(|(pair, confirmed)| confirmed.then_some(pair))
+        .collect();
+    (*records, *resolved_ips) = kept.into_iter().unzip();
+    progress.update(&format!("Kept {} of {} PTR records that are forward-confirmed", records.len(), found));
+}
+
+/// Records of the targets of swept PTR records
+fn enrich_ptr_targets(
+    records: &[dns::record::DnsRecord],
+    targets: &enumerate::reverse_enrich::TargetRecords,
+    progress: &dyn ProgressReporter,
+) -> Vec<dns::record::DnsRecord> {
+    let enriched = enumerate::reverse_enrich::enrich(records, targets);
+    progress.update(&format!("Found {} records for PTR targets", enriched.len()));
+    enriched
+}
+
+/// Attach WHOIS data to swept PTR records
+///
+/// `ips[i]` is the address `records[i]` was found for. The unique addresses
+/// are looked up concurrently, at most `--whois-concurrency` at a time.
+async fn attach_ptr_whois(
+    records: &mut [dns::record::DnsRecord],
+    ips: &[Option<std::net::IpAddr>],
+    config: &ReconConfig,
+    progress: &dyn ProgressReporter,
+) {
+    let mut unique: Vec<_> = ips.iter().flatten().copied().collect();
+    unique.sort();
+    unique.dedup();
+    if unique.is_empty() {
+        return;
+    }
+    
+    progress.update(&format!("Looking up WHOIS data for {} IP addresses", unique.len()));
+    let whois = enumerate::whois::lookup_all(&unique, config.whois_concurrency, enumerate::whois::cached_lookup).await;
+    for (record, ip) in records.iter_mut().zip(ips) {
+        record.whois = ip.and_then(|ip| whois.get(&ip).cloned());
+    }
 }
\ No newline at end of file
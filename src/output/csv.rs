@@ -0,0 +1,129 @@
+//! CSV output (RFC 4180)
+//!
+//! One row per record with the columns `type,name,ttl,value`. Multi-field
+//! record data is packed into the value cell as `key=value` pairs joined by
+//! `;`, so every record fits the same four columns. When any record carries
+//! GeoIP data, `asn,org,country` columns follow.
+
+use crate::dns::record::{DnsRecord, RecordData};
+use crate::output::{write_atomically, OutputError};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+/// Header row of the CSV output
+pub const CSV_HEADER: &str = "type,name,ttl,value";
+
+/// Columns appended to the header when records carry GeoIP data
+pub const GEOIP_COLUMNS: &str = "asn,org,country";
+
+/// Render records as CSV, header row first
+pub fn to_csv_string(results: &[DnsRecord]) -> String {
+    let with_geoip = results.iter().any(|record| geoip_cells(record).is_some());
+    let mut csv = CSV_HEADER.to_string();
+    if with_geoip {
+        csv.push(',');
+        csv.push_str(GEOIP_COLUMNS);
+    }
+    csv.push('\n');
+
+    for record in results {
+        let ttl = record.ttl.map(|ttl| ttl.to_string()).unwrap_or_default();
+        let mut row = vec![record.record_type.mnemonic(), record.name.clone(), ttl, value(&record.data)];
+        if with_geoip {
+            row.extend(geoip_cells(record).unwrap_or_default());
+        }
+        csv.push_str(&row.iter().map(|field| quote_field(field)).collect::<Vec<_>>().join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// ASN, organization and country cells of a record with GeoIP data
+#[cfg_attr(not(feature = "geoip"), allow(unused_variables))]
+fn geoip_cells(record: &DnsRecord) -> Option<[String; 3]> {
+    #[cfg(feature = "geoip")]
+    if let Some(ref geoip) = record.geoip {
+        return Some([
+            geoip.asn.map(|asn| asn.to_string()).unwrap_or_default(),
+            geoip.org.clone().unwrap_or_default(),
+            geoip.country.clone().unwrap_or_default(),
+        ]);
+    }
+    None
+}
+
+/// Write records as CSV
+pub fn write_csv(results: &[DnsRecord], filename: &str) -> Result<(), OutputError> {
+    write_atomically(filename, |tmp_filename| {
+        let file = File::create(tmp_filename)?;
+        let mut writer = BufWriter::new(file);
+
+        writer.write_all(to_csv_string(results).as_bytes())?;
+        writer.flush()?;
+
+        Ok(())
+    })
+}
+
+/// Quote a field if it contains a separator, quote or line break
+///
+/// Quotes inside a quoted field are doubled.
+pub fn quote_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Record data as a single cell
+pub(crate) fn value(data: &RecordData) -> String {
+    match data {
+        RecordData::A(address) => address.to_string(),
+        RecordData::Aaaa(address) => address.to_string(),
+        RecordData::Mx { preference, exchange } => format!("preference={};exchange={}", preference, exchange),
+        RecordData::Ns(target) | RecordData::Ptr(target) | RecordData::Cname(target) => target.clone(),
+        RecordData::Soa { mname, rname, serial, refresh, retry, expire, minimum } => format!(
+            "mname={};rname={};serial={};refresh={};retry={};expire={};minimum={}",
+            mname, rname, serial, refresh, retry, expire, minimum
+        ),
+        RecordData::Txt(text) | RecordData::Spf(text) => text.clone(),
+        RecordData::Dmarc(dmarc) => {
+            dmarc.tags().into_iter().map(|(key, value)| format!("{}={}", key, value)).collect::<Vec<_>>().join(";")
+        }
+        RecordData::Srv { priority, weight, port, target } => {
+            format!("priority={};weight={};port={};target={}", priority, weight, port, target)
+        }
+        RecordData::Caa { flags, tag, value } => format!("flags={};tag={};value={}", flags, tag, value),
+        RecordData::Svcb { priority, target, params } | RecordData::Https { priority, target, params } => {
+            let mut value = format!("priority={};target={}", priority, target);
+            for (key, param) in params {
+                value.push_str(&format!(";{}={}", key, param));
+            }
+            value
+        }
+        RecordData::Dnskey { flags, protocol, algorithm, public_key } => {
+            format!("flags={};protocol={};algorithm={};public_key={}", flags, protocol, algorithm, public_key)
+        }
+        RecordData::Ds { key_tag, algorithm, digest_type, digest } => {
+            format!("key_tag={};algorithm={};digest_type={};digest={}", key_tag, algorithm, digest_type, digest)
+        }
+        RecordData::Rrsig {
+            type_covered,
+            algorithm,
+            labels,
+            original_ttl,
+            expiration,
+            inception,
+            key_tag,
+            signer_name,
+            signature,
+        } => format!(
+            "type_covered={};algorithm={};labels={};original_ttl={};expiration={};inception={};key_tag={};signer_name={};signature={}",
+            type_covered, algorithm, labels, original_ttl, expiration, inception, key_tag, signer_name, signature
+        ),
+        RecordData::Nsec { next_domain, types } => format!("next_domain={};types={}", next_domain, types.join(" ")),
+        RecordData::Other { value, .. } => value.clone(),
+    }
+}
@@ -0,0 +1,169 @@
+//! Change detection between two scans
+
+use crate::dns::record::{DnsRecord, RecordData, RecordType};
+use crate::output::csv::{quote_field, value};
+use crate::output::{write_atomically, OutputError};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+/// Header row of the CSV diff output
+pub const DIFF_CSV_HEADER: &str = "change,type,name,before,after";
+
+/// A name and record type whose data changed between scans
+#[derive(Debug, Clone, Serialize)]
+pub struct ModifiedRecord {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub record_type: RecordType,
+    /// Data of the records in the previous scan
+    pub before: Vec<RecordData>,
+    /// Data of the records in the current scan
+    pub after: Vec<RecordData>,
+}
+
+/// Differences between a previous and a current scan
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RecordDiff {
+    /// Records whose name and type only appear in the current scan
+    pub new: Vec<DnsRecord>,
+    /// Records whose name and type only appear in the previous scan
+    pub gone: Vec<DnsRecord>,
+    /// Names and types present in both scans with different data
+    pub modified: Vec<ModifiedRecord>,
+}
+
+impl RecordDiff {
+    /// Check whether the scans had the same records
+    pub fn is_empty(&self) -> bool {
+        self.new.is_empty() && self.gone.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Records grouped by lowercased name and record type, in first-seen order
+fn group(records: &[DnsRecord]) -> BTreeMap<(String, String), Vec<&DnsRecord>> {
+    let mut groups: BTreeMap<(String, String), Vec<&DnsRecord>> = BTreeMap::new();
+    for record in records {
+        let key = (record.name.to_lowercase(), format!("{:?}", record.record_type));
+        groups.entry(key).or_default().push(record);
+    }
+    groups
+}
+
+/// Sorted, serialized record data, so answers in a different order compare equal
+fn data_set(records: &[&DnsRecord]) -> Vec<String> {
+    let mut data: Vec<String> = records
+        .iter()
+        .map(|record| serde_json::to_string(&record.data).unwrap_or_default())
+        .collect();
+    data.sort();
+    data.dedup();
+    data
+}
+
+/// Compare two scans
+///
+/// Records are matched on name (case-insensitive) and type. A name and type
+/// whose set of record data differs is reported as modified; TTLs and WHOIS
+/// data are ignored since they change without the zone changing.
+pub fn diff_records(old: &[DnsRecord], new: &[DnsRecord]) -> RecordDiff {
+    let old_groups = group(old);
+    let new_groups = group(new);
+    let mut diff = RecordDiff::default();
+
+    for (key, new_records) in &new_groups {
+        match old_groups.get(key) {
+            None => diff.new.extend(new_records.iter().map(|record| (*record).clone())),
+            Some(old_records) if data_set(old_records) != data_set(new_records) => {
+                diff.modified.push(ModifiedRecord {
+                    name: new_records[0].name.clone(),
+                    record_type: new_records[0].record_type.clone(),
+                    before: old_records.iter().map(|record| record.data.clone()).collect(),
+                    after: new_records.iter().map(|record| record.data.clone()).collect(),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+
+    for (key, old_records) in &old_groups {
+        if !new_groups.contains_key(key) {
+            diff.gone.extend(old_records.iter().map(|record| (*record).clone()));
+        }
+    }
+
+    diff
+}
+
+/// Read the records of a previous JSON output file
+pub fn read_previous(filename: &str) -> Result<Vec<DnsRecord>, OutputError> {
+    super::json::read_json(filename)
+}
+
+/// Serialize a diff to JSON and write to file
+pub fn write_diff(diff: &RecordDiff, filename: &str) -> Result<(), OutputError> {
+    write_atomically(filename, |tmp_filename| {
+        let mut writer = BufWriter::new(File::create(tmp_filename)?);
+        serde_json::to_writer_pretty(&mut writer, diff)?;
+        writer.flush()?;
+        Ok(())
+    })
+}
+
+/// Serialize a diff to a JSON string
+pub fn to_diff_json_string(diff: &RecordDiff, pretty: bool) -> Result<String, OutputError> {
+    let json = if pretty {
+        serde_json::to_string_pretty(diff)?
+    } else {
+        serde_json::to_string(diff)?
+    };
+    Ok(json)
+}
+
+/// Render a diff as CSV with the columns `change,type,name,before,after`
+///
+/// Modified names list each side's values joined by ` | `.
+pub fn to_diff_csv_string(diff: &RecordDiff) -> String {
+    let mut out = format!("{}\n", DIFF_CSV_HEADER);
+    let mut push_row = |change: &str, record_type: &RecordType, name: &str, before: String, after: String| {
+        let row = [change.to_string(), record_type.mnemonic(), name.to_string(), before, after];
+        out.push_str(&row.iter().map(|field| quote_field(field)).collect::<Vec<_>>().join(","));
+        out.push('\n');
+    };
+
+    for record in &diff.new {
+        push_row("new", &record.record_type, &record.name, String::new(), value(&record.data));
+    }
+    for record in &diff.gone {
+        push_row("gone", &record.record_type, &record.name, value(&record.data), String::new());
+    }
+    for modified in &diff.modified {
+        let join = |data: &[RecordData]| data.iter().map(value).collect::<Vec<_>>().join(" | ");
+        push_row("modified", &modified.record_type, &modified.name, join(&modified.before), join(&modified.after));
+    }
+
+    out
+}
+
+/// Render a diff as a human-readable summary
+pub fn render_diff_summary(diff: &RecordDiff) -> String {
+    let mut out = format!(
+        "\nChanges since last scan: {} new, {} gone, {} modified\n",
+        diff.new.len(),
+        diff.gone.len(),
+        diff.modified.len()
+    );
+
+    for record in &diff.new {
+        out.push_str(&format!("  + {} {}\n", record.record_type.mnemonic(), record.name));
+    }
+    for record in &diff.gone {
+        out.push_str(&format!("  - {} {}\n", record.record_type.mnemonic(), record.name));
+    }
+    for modified in &diff.modified {
+        out.push_str(&format!("  ~ {} {}\n", modified.record_type.mnemonic(), modified.name));
+    }
+
+    out
+}
@@ -0,0 +1,118 @@
+//! Elasticsearch `_bulk` output for SIEM ingestion
+
+use crate::dns::record::DnsRecord;
+use crate::output::{write_atomically, OutputError};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Index used when `--es-index` is not given
+pub const DEFAULT_INDEX: &str = "dnsrecon";
+
+/// Metadata attached to every indexed record
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanMetadata {
+    /// Target of the scan (domain or IP)
+    pub target: String,
+    /// Scan time in seconds since the Unix epoch
+    pub scan_time: u64,
+    /// Name of the tool that produced the document
+    pub tool: String,
+}
+
+impl ScanMetadata {
+    /// Create metadata for a scan of `target` started now
+    pub fn new(target: &str) -> Self {
+        let scan_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        Self {
+            target: target.to_string(),
+            scan_time,
+            tool: "dnsrecon-rs".to_string(),
+        }
+    }
+}
+
+/// A bulk document: the record's fields alongside the scan metadata
+#[derive(Serialize)]
+struct BulkDocument<'a> {
+    #[serde(flatten)]
+    record: &'a DnsRecord,
+    #[serde(flatten)]
+    metadata: &'a ScanMetadata,
+}
+
+/// Render records in `_bulk` format
+///
+/// Each record produces an `index` action line followed by its document line,
+/// and the body ends with the newline Elasticsearch requires.
+pub fn to_bulk_string(results: &[DnsRecord], index: &str, metadata: &ScanMetadata) -> Result<String, OutputError> {
+    let action = serde_json::to_string(&serde_json::json!({ "index": { "_index": index } }))?;
+
+    let mut body = String::new();
+    for record in results {
+        body.push_str(&action);
+        body.push('\n');
+        body.push_str(&serde_json::to_string(&BulkDocument { record, metadata })?);
+        body.push('\n');
+    }
+
+    Ok(body)
+}
+
+/// Write records in `_bulk` format to a file
+pub fn write_bulk(results: &[DnsRecord], filename: &str, index: &str, metadata: &ScanMetadata) -> Result<(), OutputError> {
+    write_atomically(filename, |tmp_filename| {
+        let file = File::create(tmp_filename)?;
+        let mut writer = BufWriter::new(file);
+
+        writer.write_all(to_bulk_string(results, index, metadata)?.as_bytes())?;
+        writer.flush()?;
+
+        Ok(())
+    })
+}
+
+/// POST records to the `_bulk` endpoint of an Elasticsearch cluster at `url`
+///
+/// Fails if the request fails or the cluster reports errors for any document.
+pub async fn post_bulk(
+    client: &reqwest::Client,
+    url: &str,
+    results: &[DnsRecord],
+    index: &str,
+    metadata: &ScanMetadata,
+) -> Result<(), OutputError> {
+    if results.is_empty() {
+        return Ok(());
+    }
+
+    let endpoint = format!("{}/_bulk", url.trim_end_matches('/'));
+    let body = to_bulk_string(results, index, metadata)?;
+
+    let response = client
+        .post(&endpoint)
+        .header(reqwest::header::CONTENT_TYPE, "application/x-ndjson")
+        .body(body)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| OutputError::Other(format!("Elasticsearch bulk request to {} failed: {}", endpoint, e)))?;
+
+    let reply: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| OutputError::Other(format!("Invalid Elasticsearch bulk response: {}", e)))?;
+
+    if reply.get("errors").and_then(|errors| errors.as_bool()).unwrap_or(false) {
+        return Err(OutputError::Other(format!(
+            "Elasticsearch rejected some documents sent to {}",
+            endpoint
+        )));
+    }
+
+    Ok(())
+}
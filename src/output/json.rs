@@ -1,23 +1,119 @@
 //! JSON output formatting
 
+use crate::cli::summary::{summary_json, ScanSummary};
 use crate::dns::record::DnsRecord;
-use crate::output::OutputError;
-use serde::Serialize;
-use std::fs::File;
-use std::io::BufWriter;
+use crate::enumerate::findings::Finding;
+use crate::output::{write_atomically, OutputError};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Write};
 
 /// Serialize DNS records to JSON and write to file
-pub fn write_json(results: &[DnsRecord], filename: &str) -> Result<(), OutputError> {
-    let file = File::create(filename)?;
-    let writer = BufWriter::new(file);
-    
-    serde_json::to_writer_pretty(writer, results)?;
-    
-    Ok(())
+///
+/// Output is indented when `pretty` is set and minified otherwise. The file is
+/// replaced atomically, so readers never see a partial array.
+pub fn write_json(results: &[DnsRecord], filename: &str, pretty: bool) -> Result<(), OutputError> {
+    write_atomically(filename, |tmp_filename| {
+        let mut writer = BufWriter::new(File::create(tmp_filename)?);
+        
+        if pretty {
+            serde_json::to_writer_pretty(&mut writer, results)?;
+        } else {
+            serde_json::to_writer(&mut writer, results)?;
+        }
+        
+        writer.flush()?;
+        Ok(())
+    })
 }
 
 /// Write DNS records to JSON string
-pub fn to_json_string(results: &[DnsRecord]) -> Result<String, OutputError> {
-    let json = serde_json::to_string_pretty(results)?;
+pub fn to_json_string(results: &[DnsRecord], pretty: bool) -> Result<String, OutputError> {
+    let json = if pretty {
+        serde_json::to_string_pretty(results)?
+    } else {
+        serde_json::to_string(results)?
+    };
     Ok(json)
-}
\ No newline at end of file
+}
+
+/// Read the records of a JSON output file written by [`write_json`]
+pub fn read_json(filename: &str) -> Result<Vec<DnsRecord>, OutputError> {
+    let file = File::open(filename)?;
+    Ok(serde_json::from_reader(BufReader::new(file))?)
+}
+
+/// Serialize findings to JSON and write to file
+pub fn write_findings(findings: &[Finding], filename: &str) -> Result<(), OutputError> {
+    write_atomically(filename, |tmp_filename| {
+        let mut writer = BufWriter::new(File::create(tmp_filename)?);
+        serde_json::to_writer_pretty(&mut writer, findings)?;
+        writer.flush()?;
+        Ok(())
+    })
+}
+
+/// Write the scan summary as JSON to file
+pub fn write_summary(summary: &ScanSummary, records: &[DnsRecord], filename: &str) -> Result<(), OutputError> {
+    write_atomically(filename, |tmp_filename| {
+        let mut writer = BufWriter::new(File::create(tmp_filename)?);
+        serde_json::to_writer_pretty(&mut writer, &summary_json(summary, records))?;
+        writer.flush()?;
+        Ok(())
+    })
+}
+
+/// Merge DNS records into an existing JSON array file
+///
+/// Records already present in the file are kept, and new records are appended
+/// unless an identical record (same name, type and data) already exists. A missing
+/// or empty file is treated as an empty array. A file that cannot be parsed is
+/// preserved as `<filename>.bak` before being replaced.
+///
+/// Like [`write_json`], the merged array replaces the file atomically so an
+/// interrupted write never leaves a truncated dataset behind.
+pub fn append_json(results: &[DnsRecord], filename: &str, pretty: bool) -> Result<(), OutputError> {
+    let mut merged = read_existing_records(filename)?;
+    
+    let mut seen: HashSet<String> = merged.iter().map(merge_key).collect();
+    for record in results {
+        if seen.insert(merge_key(record)) {
+            merged.push(record.clone());
+        }
+    }
+    
+    write_json(&merged, filename, pretty)
+}
+
+/// Read the records of an existing JSON output file, tolerating missing, empty and corrupt files
+fn read_existing_records(filename: &str) -> Result<Vec<DnsRecord>, OutputError> {
+    let metadata = match fs::metadata(filename) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    
+    if metadata.len() == 0 {
+        return Ok(Vec::new());
+    }
+    
+    let file = File::open(filename)?;
+    let reader = BufReader::new(file);
+    
+    match serde_json::from_reader(reader) {
+        Ok(records) => Ok(records),
+        Err(e) => {
+            // Keep the unreadable file around rather than silently discarding it
+            let backup = format!("{}.bak", filename);
+            tracing::warn!("Existing JSON file {} could not be parsed ({}), backing it up to {}", filename, e, backup);
+            fs::copy(filename, &backup)?;
+            Ok(Vec::new())
+        }
+    }
+}
+
+/// Build the key used to detect duplicate records when merging
+fn merge_key(record: &DnsRecord) -> String {
+    let data = serde_json::to_string(&record.data).unwrap_or_default();
+    format!("{}|{:?}|{}", record.name.to_lowercase(), record.record_type, data)
+}
@@ -5,12 +5,22 @@
 
 use thiserror::Error;
 use crate::dns::record::DnsRecord;
+use crate::enumerate::findings::Finding;
 use quick_xml::Error as XmlError;
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
 use std::string::FromUtf8Error;
+use std::sync::Arc;
 
 pub mod json;
 pub mod xml;
+pub mod csv;
+pub mod text;
 pub mod sqlite;
+pub mod elastic;
+pub mod diff;
+pub mod zonefile;
 
 /// Output-related errors
 #[derive(Error, Debug)]
@@ -32,11 +42,178 @@ pub enum OutputError {
     
     #[error("Other output error: {0}")]
     Other(String),
+    
+    #[error("{} output writers failed: {}", .0.len(), join_errors(.0))]
+    Multiple(Vec<OutputError>),
+}
+
+/// Join several errors into one message
+fn join_errors(errors: &[OutputError]) -> String {
+    errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("; ")
+}
+
+/// Write a file atomically
+///
+/// `write` is given a `.tmp` sibling of `filename` to fill, which is renamed
+/// over `filename` once it succeeds. On failure the temporary file is removed
+/// and an existing `filename` is left untouched.
+pub fn write_atomically<F>(filename: &str, write: F) -> Result<(), OutputError>
+where
+    F: FnOnce(&str) -> Result<(), OutputError>,
+{
+    let tmp_filename = format!("{}.tmp", filename);
+    match write(&tmp_filename) {
+        Ok(()) => {
+            fs::rename(&tmp_filename, filename)?;
+            Ok(())
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&tmp_filename);
+            Err(e)
+        }
+    }
+}
+
+/// How results are printed when no output file is given
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StdoutFormat {
+    #[default]
+    Json,
+    Text,
+    Csv,
+}
+
+impl StdoutFormat {
+    /// Render results in this format
+    pub fn render(&self, results: &[DnsRecord], pretty: bool) -> Result<String, OutputError> {
+        match self {
+            StdoutFormat::Json => json::to_json_string(results, pretty),
+            StdoutFormat::Text => Ok(text::to_text_string(results)),
+            StdoutFormat::Csv => Ok(csv::to_csv_string(results)),
+        }
+    }
+    
+    /// Render a comparison of two scans in this format
+    pub fn render_diff(&self, diff: &diff::RecordDiff, pretty: bool) -> Result<String, OutputError> {
+        match self {
+            StdoutFormat::Json => diff::to_diff_json_string(diff, pretty),
+            StdoutFormat::Text => Ok(diff::render_diff_summary(diff).trim_start().to_string()),
+            StdoutFormat::Csv => Ok(diff::to_diff_csv_string(diff)),
+        }
+    }
+}
+
+impl fmt::Display for StdoutFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StdoutFormat::Json => f.write_str("json"),
+            StdoutFormat::Text => f.write_str("text"),
+            StdoutFormat::Csv => f.write_str("csv"),
+        }
+    }
+}
+
+impl FromStr for StdoutFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "json" => Ok(StdoutFormat::Json),
+            "text" => Ok(StdoutFormat::Text),
+            "csv" => Ok(StdoutFormat::Csv),
+            _ => Err(format!("Unknown output format '{}'; available formats: json, text, csv", s.trim())),
+        }
+    }
+}
+
+/// A results file written at the end of a scan
+#[derive(Debug, Clone)]
+pub enum FileOutput {
+    Json { filename: String, pretty: bool, append: bool },
+    Xml { filename: String },
+    Csv { filename: String },
+    Sqlite { filename: String },
+    EsBulk { filename: String, index: String, metadata: elastic::ScanMetadata },
+    ZoneFile { filename: String, origin: String },
+}
+
+impl FileOutput {
+    /// Write the results in this output's format
+    pub fn write(&self, results: &[DnsRecord]) -> Result<(), OutputError> {
+        match self {
+            FileOutput::Json { filename, pretty, append: true } => append_json(results, filename, *pretty),
+            FileOutput::Json { filename, pretty, append: false } => format_json(results, filename, *pretty),
+            FileOutput::Xml { filename } => format_xml(results, filename),
+            FileOutput::Csv { filename } => format_csv(results, filename),
+            FileOutput::Sqlite { filename } => export_sqlite(results, filename),
+            FileOutput::EsBulk { filename, index, metadata } => format_es_bulk(results, filename, index, metadata),
+            FileOutput::ZoneFile { filename, origin } => format_zonefile(results, filename, origin),
+        }
+    }
+}
+
+impl fmt::Display for FileOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileOutput::Json { filename, .. } => write!(f, "JSON file: {}", filename),
+            FileOutput::Xml { filename } => write!(f, "XML file: {}", filename),
+            FileOutput::Csv { filename } => write!(f, "CSV file: {}", filename),
+            FileOutput::Sqlite { filename } => write!(f, "SQLite database: {}", filename),
+            FileOutput::EsBulk { filename, .. } => write!(f, "Elasticsearch bulk file: {}", filename),
+            FileOutput::ZoneFile { filename, .. } => write!(f, "Zone file: {}", filename),
+        }
+    }
+}
+
+/// Write results to every requested output file
+///
+/// A single output is written in place. Several outputs are independent, so
+/// each one is written on its own blocking task; every writer runs to
+/// completion and all failures are reported together.
+pub async fn write_outputs(results: Arc<Vec<DnsRecord>>, outputs: Vec<FileOutput>) -> Result<(), OutputError> {
+    if outputs.len() <= 1 {
+        return outputs.iter().try_for_each(|output| output.write(&results));
+    }
+    
+    let writers: Vec<_> = outputs
+        .into_iter()
+        .map(|output| {
+            let results = Arc::clone(&results);
+            tokio::task::spawn_blocking(move || {
+                output.write(&results).map_err(|e| OutputError::Other(format!("{}: {}", output, e)))
+            })
+        })
+        .collect();
+    
+    let mut errors = Vec::new();
+    for result in futures_util::future::join_all(writers).await {
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => errors.push(e),
+            Err(e) => errors.push(OutputError::Other(format!("Output writer panicked: {}", e))),
+        }
+    }
+    
+    match errors.len() {
+        0 => Ok(()),
+        1 => Err(errors.remove(0)),
+        _ => Err(OutputError::Multiple(errors)),
+    }
 }
 
 /// Format results as JSON and write to file
-pub fn format_json(results: &[DnsRecord], filename: &str) -> Result<(), OutputError> {
-    json::write_json(results, filename)
+pub fn format_json(results: &[DnsRecord], filename: &str, pretty: bool) -> Result<(), OutputError> {
+    json::write_json(results, filename, pretty)
+}
+
+/// Merge results into an existing JSON file
+pub fn append_json(results: &[DnsRecord], filename: &str, pretty: bool) -> Result<(), OutputError> {
+    json::append_json(results, filename, pretty)
+}
+
+/// Write findings as JSON to file
+pub fn format_findings(findings: &[Finding], filename: &str) -> Result<(), OutputError> {
+    json::write_findings(findings, filename)
 }
 
 /// Format results as XML and write to file
@@ -44,7 +221,22 @@ pub fn format_xml(results: &[DnsRecord], filename: &str) -> Result<(), OutputErr
     xml::write_xml(results, filename)
 }
 
+/// Write results to CSV file
+pub fn format_csv(results: &[DnsRecord], filename: &str) -> Result<(), OutputError> {
+    csv::write_csv(results, filename)
+}
+
 /// Export results to SQLite database
 pub fn export_sqlite(results: &[DnsRecord], filename: &str) -> Result<(), OutputError> {
     sqlite::write_sqlite(results, filename)
+}
+
+/// Write results in Elasticsearch `_bulk` format to file
+pub fn format_es_bulk(results: &[DnsRecord], filename: &str, index: &str, metadata: &elastic::ScanMetadata) -> Result<(), OutputError> {
+    elastic::write_bulk(results, filename, index, metadata)
+}
+
+/// Write results as a zone file for `origin`
+pub fn format_zonefile(results: &[DnsRecord], filename: &str, origin: &str) -> Result<(), OutputError> {
+    zonefile::write_zonefile(results, origin, filename)
 }
\ No newline at end of file
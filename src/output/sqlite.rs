@@ -1,64 +1,254 @@
 //! SQLite output formatting
 
-use crate::dns::record::{DnsRecord, RecordData};
-use crate::output::OutputError;
+use crate::dns::record::{DnsRecord, RecordData, RecordType};
+use crate::output::{write_atomically, OutputError};
+use crate::enumerate::exclude::ExcludeFilter;
+#[cfg(feature = "geoip")]
+use crate::enumerate::geoip::{GeoInfo, GeoIpDatabase};
 use rusqlite::Connection;
 use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+#[cfg(feature = "geoip")]
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
-/// Write DNS records to SQLite database
-pub fn write_sqlite(results: &[DnsRecord], filename: &str) -> Result<(), OutputError> {
-    let conn = Connection::open(filename)?;
-    
-    // Create tables if they don't exist
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS dns_records (
-            id INTEGER PRIMARY KEY,
-            type TEXT NOT NULL,
-            name TEXT NOT NULL,
-            ttl INTEGER,
-            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-        )",
-        [],
-    )?;
-    
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS record_data (
-            id INTEGER PRIMARY KEY,
-            record_id INTEGER,
-            key TEXT NOT NULL,
-            value TEXT,
-            FOREIGN KEY(record_id) REFERENCES dns_records(id)
-        )",
-        [],
-    )?;
+/// Records inserted between commits of a streaming sink
+pub const COMMIT_EVERY_RECORDS: usize = 500;
+
+/// Longest time inserted records stay uncommitted in a streaming sink
+pub const COMMIT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Incremental SQLite writer
+///
+/// Records are inserted inside a transaction that is committed every
+/// `COMMIT_EVERY_RECORDS` records or `COMMIT_INTERVAL`, whichever comes first,
+/// so an interrupted scan keeps everything up to the last commit. Records still
+/// pending when the sink is dropped without `finish` are rolled back.
+pub struct SqliteSink {
+    conn: Connection,
+    commit_records: usize,
+    commit_interval: Duration,
+    pending: usize,
+    written: usize,
+    last_commit: Instant,
+    #[cfg(feature = "geoip")]
+    geoip: Option<Arc<GeoIpDatabase>>,
+}
+
+impl SqliteSink {
+    /// Open (or create) the database and start the first transaction
+    pub fn open(filename: &str) -> Result<Self, OutputError> {
+        let conn = Connection::open(filename)?;
+        
+        // Create tables if they don't exist
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS dns_records (
+                id INTEGER PRIMARY KEY,
+                type TEXT NOT NULL,
+                name TEXT NOT NULL,
+                domain TEXT,
+                ttl INTEGER,
+                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )?;
+        
+        // Databases written before records carried their domain lack the column
+        let has_domain = conn
+            .prepare("SELECT 1 FROM pragma_table_info('dns_records') WHERE name = 'domain'")?
+            .exists([])?;
+        if !has_domain {
+            conn.execute("ALTER TABLE dns_records ADD COLUMN domain TEXT", [])?;
+        }
+        
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS record_data (
+                id INTEGER PRIMARY KEY,
+                record_id INTEGER,
+                key TEXT NOT NULL,
+                value TEXT,
+                FOREIGN KEY(record_id) REFERENCES dns_records(id)
+            )",
+            [],
+        )?;
+        
+        conn.execute_batch("BEGIN")?;
+        
+        Ok(Self {
+            conn,
+            commit_records: COMMIT_EVERY_RECORDS,
+            commit_interval: COMMIT_INTERVAL,
+            pending: 0,
+            written: 0,
+            last_commit: Instant::now(),
+            #[cfg(feature = "geoip")]
+            geoip: None,
+        })
+    }
     
-    // Insert records
-    let mut record_stmt = conn.prepare(
-        "INSERT INTO dns_records (type, name, ttl) VALUES (?1, ?2, ?3)",
-    )?;
+    /// Commit after `records` inserts or `interval`, whichever comes first
+    pub fn with_commit_policy(mut self, records: usize, interval: Duration) -> Self {
+        self.commit_records = records.max(1);
+        self.commit_interval = interval;
+        self
+    }
     
-    let mut data_stmt = conn.prepare(
-        "INSERT INTO record_data (record_id, key, value) VALUES (?1, ?2, ?3)",
-    )?;
+    /// Look up address records without GeoIP data in `database` as they are inserted
+    ///
+    /// Streamed records are written as soon as they are found, before the
+    /// end-of-scan annotation has run.
+    #[cfg(feature = "geoip")]
+    pub fn with_geoip(mut self, database: Arc<GeoIpDatabase>) -> Self {
+        self.geoip = Some(database);
+        self
+    }
     
-    for record in results {
-        let record_type = format!("{:?}", record.record_type);
+    /// Insert a record, committing if the commit policy says so
+    pub fn insert(&mut self, record: &DnsRecord) -> Result<(), OutputError> {
+        // Unsupported types are stored as Other, with their real type in the record data
+        let record_type = match record.record_type {
+            RecordType::Other(_) => "Other".to_string(),
+            ref record_type => format!("{:?}", record_type),
+        };
         
         // Insert the main record
-        let record_id = record_stmt.insert([
-            &record_type as &dyn rusqlite::ToSql,
-            &record.name,
-            &record.ttl.unwrap_or(0) as &dyn rusqlite::ToSql,
-        ])?;
+        let record_id = self.conn
+            .prepare_cached("INSERT INTO dns_records (type, name, domain, ttl) VALUES (?1, ?2, ?3, ?4)")?
+            .insert([
+                &record_type as &dyn rusqlite::ToSql,
+                &record.name,
+                &record.domain as &dyn rusqlite::ToSql,
+                &record.ttl.unwrap_or(0) as &dyn rusqlite::ToSql,
+            ])?;
         
         // Insert record-specific data
+        let mut data_stmt = self.conn
+            .prepare_cached("INSERT INTO record_data (record_id, key, value) VALUES (?1, ?2, ?3)")?;
         insert_record_data(&mut data_stmt, record_id, &record.data)?;
+        #[cfg(feature = "geoip")]
+        {
+            let geoip = record.geoip.clone().or_else(|| self.geoip.as_ref()?.lookup_record(record));
+            if let Some(geoip) = geoip {
+                insert_geoip(&mut data_stmt, record_id, &geoip)?;
+            }
+        }
+        drop(data_stmt);
+        
+        self.pending += 1;
+        self.written += 1;
+        self.commit_if_due()
     }
     
-    Ok(())
+    /// Commit pending records if enough have accumulated or enough time has passed
+    pub fn commit_if_due(&mut self) -> Result<(), OutputError> {
+        if self.pending >= self.commit_records
+            || (self.pending > 0 && self.last_commit.elapsed() >= self.commit_interval)
+        {
+            self.commit()?;
+        }
+        Ok(())
+    }
+    
+    /// Commit pending records and start a new transaction
+    pub fn commit(&mut self) -> Result<(), OutputError> {
+        self.conn.execute_batch("COMMIT; BEGIN")?;
+        self.pending = 0;
+        self.last_commit = Instant::now();
+        Ok(())
+    }
+    
+    /// Commit pending records and close the database, returning the number of records written
+    pub fn finish(self) -> Result<usize, OutputError> {
+        self.conn.execute_batch("COMMIT")?;
+        Ok(self.written)
+    }
+    
+    /// Number of records inserted so far
+    pub fn written(&self) -> usize {
+        self.written
+    }
+}
+
+/// Insert records into `sink` as they arrive on `receiver`, on a dedicated thread
+///
+/// Records matching `exclude`, records of types outside `record_types` and
+/// records already written for the same domain are skipped, matching the
+/// filtering and de-duplication applied to end-of-scan output. The thread finishes the sink once every sender has been dropped and
+/// returns the number of records written.
+pub fn spawn_writer(
+    mut sink: SqliteSink,
+    receiver: Receiver<DnsRecord>,
+    exclude: Option<ExcludeFilter>,
+    record_types: Option<Vec<RecordType>>,
+) -> JoinHandle<Result<usize, OutputError>> {
+    thread::spawn(move || {
+        let mut seen = HashSet::new();
+        loop {
+            match receiver.recv_timeout(sink.commit_interval) {
+                Ok(record) => {
+                    if exclude.as_ref().is_some_and(|filter| filter.excludes_record(&record)) {
+                        continue;
+                    }
+                    if record_types.as_ref().is_some_and(|types| !types.contains(&record.record_type)) {
+                        continue;
+                    }
+                    if seen.insert((record.domain.clone(), record.dedup_key())) {
+                        sink.insert(&record)?;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => sink.commit_if_due()?,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        sink.finish()
+    })
+}
+
+/// Write DNS records to SQLite database
+///
+/// Records are added to a copy of any existing database, which replaces the
+/// original once committed, so readers never see a half-written database.
+pub fn write_sqlite(results: &[DnsRecord], filename: &str) -> Result<(), OutputError> {
+    write_atomically(filename, |tmp_filename| {
+        if Path::new(filename).exists() {
+            fs::copy(filename, tmp_filename)?;
+        } else if Path::new(tmp_filename).exists() {
+            // Left over from an interrupted run
+            fs::remove_file(tmp_filename)?;
+        }
+        
+        let mut sink = SqliteSink::open(tmp_filename)?.with_commit_policy(usize::MAX, Duration::MAX);
+        
+        for record in results {
+            sink.insert(record)?;
+        }
+        
+        sink.finish()?;
+        Ok(())
+    })
 }
 
 /// Insert record-specific data into the database
+/// Store GeoIP details as `geoip_asn`, `geoip_org` and `geoip_country` data rows
+#[cfg(feature = "geoip")]
+fn insert_geoip(stmt: &mut rusqlite::Statement, record_id: i64, geoip: &GeoInfo) -> Result<(), OutputError> {
+    let fields = [
+        ("geoip_asn", geoip.asn.map(|asn| asn.to_string())),
+        ("geoip_org", geoip.org.clone()),
+        ("geoip_country", geoip.country.clone()),
+    ];
+    for (key, value) in fields {
+        if let Some(value) = value {
+            stmt.execute([&record_id as &dyn rusqlite::ToSql, &key, &value])?;
+        }
+    }
+    Ok(())
+}
+
 fn insert_record_data(stmt: &mut rusqlite::Statement, record_id: i64, data: &RecordData) -> Result<(), OutputError> {
     match data {
         RecordData::A(ip) => {
@@ -138,6 +328,15 @@ fn insert_record_data(stmt: &mut rusqlite::Statement, record_id: i64, data: &Rec
                 data as &dyn rusqlite::ToSql,
             ])?;
         },
+        RecordData::Dmarc(dmarc) => {
+            for (key, value) in dmarc.tags() {
+                stmt.execute([
+                    &record_id as &dyn rusqlite::ToSql,
+                    &key as &dyn rusqlite::ToSql,
+                    &value as &dyn rusqlite::ToSql,
+                ])?;
+            }
+        },
         RecordData::Ptr(target) => {
             stmt.execute([
                 &record_id as &dyn rusqlite::ToSql,
@@ -191,6 +390,150 @@ fn insert_record_data(stmt: &mut rusqlite::Statement, record_id: i64, data: &Rec
                 target as &dyn rusqlite::ToSql,
             ])?;
         },
+        RecordData::Svcb { priority, target, params } | RecordData::Https { priority, target, params } => {
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"priority" as &dyn rusqlite::ToSql,
+                &priority.to_string() as &dyn rusqlite::ToSql,
+            ])?;
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"target" as &dyn rusqlite::ToSql,
+                target as &dyn rusqlite::ToSql,
+            ])?;
+            for (key, value) in params {
+                stmt.execute([
+                    &record_id as &dyn rusqlite::ToSql,
+                    key as &dyn rusqlite::ToSql,
+                    value as &dyn rusqlite::ToSql,
+                ])?;
+            }
+        },
+        RecordData::Dnskey { flags, protocol, algorithm, public_key } => {
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"flags" as &dyn rusqlite::ToSql,
+                &flags.to_string() as &dyn rusqlite::ToSql,
+            ])?;
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"protocol" as &dyn rusqlite::ToSql,
+                &protocol.to_string() as &dyn rusqlite::ToSql,
+            ])?;
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"algorithm" as &dyn rusqlite::ToSql,
+                &algorithm.to_string() as &dyn rusqlite::ToSql,
+            ])?;
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"public_key" as &dyn rusqlite::ToSql,
+                public_key as &dyn rusqlite::ToSql,
+            ])?;
+        },
+        RecordData::Ds { key_tag, algorithm, digest_type, digest } => {
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"key_tag" as &dyn rusqlite::ToSql,
+                &key_tag.to_string() as &dyn rusqlite::ToSql,
+            ])?;
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"algorithm" as &dyn rusqlite::ToSql,
+                &algorithm.to_string() as &dyn rusqlite::ToSql,
+            ])?;
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"digest_type" as &dyn rusqlite::ToSql,
+                &digest_type.to_string() as &dyn rusqlite::ToSql,
+            ])?;
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"digest" as &dyn rusqlite::ToSql,
+                digest as &dyn rusqlite::ToSql,
+            ])?;
+        },
+        RecordData::Rrsig {
+            type_covered,
+            algorithm,
+            labels,
+            original_ttl,
+            expiration,
+            inception,
+            key_tag,
+            signer_name,
+            signature,
+        } => {
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"type_covered" as &dyn rusqlite::ToSql,
+                type_covered as &dyn rusqlite::ToSql,
+            ])?;
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"algorithm" as &dyn rusqlite::ToSql,
+                &algorithm.to_string() as &dyn rusqlite::ToSql,
+            ])?;
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"labels" as &dyn rusqlite::ToSql,
+                &labels.to_string() as &dyn rusqlite::ToSql,
+            ])?;
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"original_ttl" as &dyn rusqlite::ToSql,
+                &original_ttl.to_string() as &dyn rusqlite::ToSql,
+            ])?;
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"expiration" as &dyn rusqlite::ToSql,
+                &expiration.to_string() as &dyn rusqlite::ToSql,
+            ])?;
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"inception" as &dyn rusqlite::ToSql,
+                &inception.to_string() as &dyn rusqlite::ToSql,
+            ])?;
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"key_tag" as &dyn rusqlite::ToSql,
+                &key_tag.to_string() as &dyn rusqlite::ToSql,
+            ])?;
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"signer_name" as &dyn rusqlite::ToSql,
+                signer_name as &dyn rusqlite::ToSql,
+            ])?;
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"signature" as &dyn rusqlite::ToSql,
+                signature as &dyn rusqlite::ToSql,
+            ])?;
+        },
+        RecordData::Nsec { next_domain, types } => {
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"next_domain" as &dyn rusqlite::ToSql,
+                next_domain as &dyn rusqlite::ToSql,
+            ])?;
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"types" as &dyn rusqlite::ToSql,
+                &types.join(" ") as &dyn rusqlite::ToSql,
+            ])?;
+        },
+        RecordData::Other { rtype, value } => {
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"rtype" as &dyn rusqlite::ToSql,
+                rtype as &dyn rusqlite::ToSql,
+            ])?;
+            stmt.execute([
+                &record_id as &dyn rusqlite::ToSql,
+                &"value" as &dyn rusqlite::ToSql,
+                value as &dyn rusqlite::ToSql,
+            ])?;
+        },
     }
     
     Ok(())
@@ -0,0 +1,27 @@
+//! Plain-text output
+//!
+//! One record per line with aligned `type`, `name` and `value` columns, meant
+//! for `grep` and `awk` rather than for parsing back.
+
+use crate::dns::record::DnsRecord;
+
+/// Spaces between two columns
+const COLUMN_GAP: usize = 2;
+
+/// Render records one per line with aligned columns
+pub fn to_text_string(results: &[DnsRecord]) -> String {
+    let rows: Vec<(String, &str, String)> = results
+        .iter()
+        .map(|record| (record.record_type.mnemonic(), record.name.as_str(), record.value_string()))
+        .collect();
+
+    let type_width = rows.iter().map(|(rtype, _, _)| rtype.len()).max().unwrap_or(0) + COLUMN_GAP;
+    let name_width = rows.iter().map(|(_, name, _)| name.len()).max().unwrap_or(0) + COLUMN_GAP;
+
+    let mut text = String::new();
+    for (rtype, name, value) in rows {
+        text.push_str(&format!("{:<type_width$}{:<name_width$}{}\n", rtype, name, value));
+    }
+
+    text
+}
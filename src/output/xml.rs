@@ -1,34 +1,42 @@
 //! XML output formatting
 
-use crate::dns::record::{DnsRecord, RecordData};
-use crate::output::OutputError;
+use crate::dns::record::{DnsRecord, RecordData, RecordType};
+#[cfg(feature = "geoip")]
+use crate::enumerate::geoip::GeoInfo;
+use crate::enumerate::whois::WhoisInfo;
+use crate::output::{write_atomically, OutputError};
 use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
 use quick_xml::Writer;
 use std::fs::File;
-use std::io::BufWriter;
+use std::io::{BufWriter, Write};
 
 /// Write DNS records to XML file
+///
+/// The file is replaced atomically once the document is complete.
 pub fn write_xml(results: &[DnsRecord], filename: &str) -> Result<(), OutputError> {
-    let file = File::create(filename)?;
-    let writer = BufWriter::new(file);
-    let mut xml_writer = Writer::new(writer);
-    
-    // Write XML declaration
-    let decl = quick_xml::events::BytesDecl::new("1.0", Some("UTF-8"), None);
-    xml_writer.write_event(Event::Decl(decl))?;
-    
-    // Write root element
-    xml_writer.write_event(Event::Start(BytesStart::new("dnsrecon")))?;
-    
-    // Write each record
-    for record in results {
-        write_record(&mut xml_writer, record)?;
-    }
-    
-    // Close root element
-    xml_writer.write_event(Event::End(BytesEnd::new("dnsrecon")))?;
-    
-    Ok(())
+    write_atomically(filename, |tmp_filename| {
+        let file = File::create(tmp_filename)?;
+        let writer = BufWriter::new(file);
+        let mut xml_writer = Writer::new(writer);
+        
+        // Write XML declaration
+        let decl = quick_xml::events::BytesDecl::new("1.0", Some("UTF-8"), None);
+        xml_writer.write_event(Event::Decl(decl))?;
+        
+        // Write root element
+        xml_writer.write_event(Event::Start(BytesStart::new("dnsrecon")))?;
+        
+        // Write each record
+        for record in results {
+            write_record(&mut xml_writer, record)?;
+        }
+        
+        // Close root element
+        xml_writer.write_event(Event::End(BytesEnd::new("dnsrecon")))?;
+        
+        xml_writer.into_inner().flush()?;
+        Ok(())
+    })
 }
 
 /// Write DNS records to XML string
@@ -61,7 +69,11 @@ pub fn to_xml_string(results: &[DnsRecord]) -> Result<String, OutputError> {
 
 /// Write a single DNS record to XML
 fn write_record<W: std::io::Write>(writer: &mut Writer<W>, record: &DnsRecord) -> Result<(), OutputError> {
-    let element_name = format!("{:?}", record.record_type).to_lowercase();
+    // Every unsupported type shares one element; its type is written inside
+    let element_name = match record.record_type {
+        RecordType::Other(_) => "other".to_string(),
+        ref record_type => format!("{:?}", record_type).to_lowercase(),
+    };
     let element = BytesStart::new(&element_name);
     
     writer.write_event(Event::Start(element.clone()))?;
@@ -131,6 +143,13 @@ fn write_record<W: std::io::Write>(writer: &mut Writer<W>, record: &DnsRecord) -
             writer.write_event(Event::Text(BytesText::new(data)))?;
             writer.write_event(Event::End(BytesEnd::new("data")))?;
         },
+        RecordData::Dmarc(dmarc) => {
+            for (key, value) in dmarc.tags() {
+                writer.write_event(Event::Start(BytesStart::new(key)))?;
+                writer.write_event(Event::Text(BytesText::new(&value)))?;
+                writer.write_event(Event::End(BytesEnd::new(key)))?;
+            }
+        },
         RecordData::Ptr(target) => {
             writer.write_event(Event::Start(BytesStart::new("target")))?;
             writer.write_event(Event::Text(BytesText::new(target)))?;
@@ -171,6 +190,124 @@ fn write_record<W: std::io::Write>(writer: &mut Writer<W>, record: &DnsRecord) -
             writer.write_event(Event::Text(BytesText::new(target)))?;
             writer.write_event(Event::End(BytesEnd::new("target")))?;
         },
+        RecordData::Svcb { priority, target, params } | RecordData::Https { priority, target, params } => {
+            writer.write_event(Event::Start(BytesStart::new("priority")))?;
+            writer.write_event(Event::Text(BytesText::new(&priority.to_string())))?;
+            writer.write_event(Event::End(BytesEnd::new("priority")))?;
+            
+            writer.write_event(Event::Start(BytesStart::new("target")))?;
+            writer.write_event(Event::Text(BytesText::new(target)))?;
+            writer.write_event(Event::End(BytesEnd::new("target")))?;
+            
+            for (key, value) in params {
+                let mut param = BytesStart::new("param");
+                param.push_attribute(("key", key.as_str()));
+                writer.write_event(Event::Start(param))?;
+                writer.write_event(Event::Text(BytesText::new(value)))?;
+                writer.write_event(Event::End(BytesEnd::new("param")))?;
+            }
+        },
+        RecordData::Dnskey { flags, protocol, algorithm, public_key } => {
+            writer.write_event(Event::Start(BytesStart::new("flags")))?;
+            writer.write_event(Event::Text(BytesText::new(&flags.to_string())))?;
+            writer.write_event(Event::End(BytesEnd::new("flags")))?;
+            
+            writer.write_event(Event::Start(BytesStart::new("protocol")))?;
+            writer.write_event(Event::Text(BytesText::new(&protocol.to_string())))?;
+            writer.write_event(Event::End(BytesEnd::new("protocol")))?;
+            
+            writer.write_event(Event::Start(BytesStart::new("algorithm")))?;
+            writer.write_event(Event::Text(BytesText::new(&algorithm.to_string())))?;
+            writer.write_event(Event::End(BytesEnd::new("algorithm")))?;
+            
+            writer.write_event(Event::Start(BytesStart::new("public_key")))?;
+            writer.write_event(Event::Text(BytesText::new(public_key)))?;
+            writer.write_event(Event::End(BytesEnd::new("public_key")))?;
+        },
+        RecordData::Ds { key_tag, algorithm, digest_type, digest } => {
+            writer.write_event(Event::Start(BytesStart::new("key_tag")))?;
+            writer.write_event(Event::Text(BytesText::new(&key_tag.to_string())))?;
+            writer.write_event(Event::End(BytesEnd::new("key_tag")))?;
+            
+            writer.write_event(Event::Start(BytesStart::new("algorithm")))?;
+            writer.write_event(Event::Text(BytesText::new(&algorithm.to_string())))?;
+            writer.write_event(Event::End(BytesEnd::new("algorithm")))?;
+            
+            writer.write_event(Event::Start(BytesStart::new("digest_type")))?;
+            writer.write_event(Event::Text(BytesText::new(&digest_type.to_string())))?;
+            writer.write_event(Event::End(BytesEnd::new("digest_type")))?;
+            
+            writer.write_event(Event::Start(BytesStart::new("digest")))?;
+            writer.write_event(Event::Text(BytesText::new(digest)))?;
+            writer.write_event(Event::End(BytesEnd::new("digest")))?;
+        },
+        RecordData::Rrsig {
+            type_covered,
+            algorithm,
+            labels,
+            original_ttl,
+            expiration,
+            inception,
+            key_tag,
+            signer_name,
+            signature,
+        } => {
+            writer.write_event(Event::Start(BytesStart::new("type_covered")))?;
+            writer.write_event(Event::Text(BytesText::new(type_covered)))?;
+            writer.write_event(Event::End(BytesEnd::new("type_covered")))?;
+            
+            writer.write_event(Event::Start(BytesStart::new("algorithm")))?;
+            writer.write_event(Event::Text(BytesText::new(&algorithm.to_string())))?;
+            writer.write_event(Event::End(BytesEnd::new("algorithm")))?;
+            
+            writer.write_event(Event::Start(BytesStart::new("labels")))?;
+            writer.write_event(Event::Text(BytesText::new(&labels.to_string())))?;
+            writer.write_event(Event::End(BytesEnd::new("labels")))?;
+            
+            writer.write_event(Event::Start(BytesStart::new("original_ttl")))?;
+            writer.write_event(Event::Text(BytesText::new(&original_ttl.to_string())))?;
+            writer.write_event(Event::End(BytesEnd::new("original_ttl")))?;
+            
+            writer.write_event(Event::Start(BytesStart::new("expiration")))?;
+            writer.write_event(Event::Text(BytesText::new(&expiration.to_string())))?;
+            writer.write_event(Event::End(BytesEnd::new("expiration")))?;
+            
+            writer.write_event(Event::Start(BytesStart::new("inception")))?;
+            writer.write_event(Event::Text(BytesText::new(&inception.to_string())))?;
+            writer.write_event(Event::End(BytesEnd::new("inception")))?;
+            
+            writer.write_event(Event::Start(BytesStart::new("key_tag")))?;
+            writer.write_event(Event::Text(BytesText::new(&key_tag.to_string())))?;
+            writer.write_event(Event::End(BytesEnd::new("key_tag")))?;
+            
+            writer.write_event(Event::Start(BytesStart::new("signer_name")))?;
+            writer.write_event(Event::Text(BytesText::new(signer_name)))?;
+            writer.write_event(Event::End(BytesEnd::new("signer_name")))?;
+            
+            writer.write_event(Event::Start(BytesStart::new("signature")))?;
+            writer.write_event(Event::Text(BytesText::new(signature)))?;
+            writer.write_event(Event::End(BytesEnd::new("signature")))?;
+        },
+        RecordData::Nsec { next_domain, types } => {
+            writer.write_event(Event::Start(BytesStart::new("next_domain")))?;
+            writer.write_event(Event::Text(BytesText::new(next_domain)))?;
+            writer.write_event(Event::End(BytesEnd::new("next_domain")))?;
+            
+            for rtype in types {
+                writer.write_event(Event::Start(BytesStart::new("type")))?;
+                writer.write_event(Event::Text(BytesText::new(rtype)))?;
+                writer.write_event(Event::End(BytesEnd::new("type")))?;
+            }
+        },
+        RecordData::Other { rtype, value } => {
+            writer.write_event(Event::Start(BytesStart::new("rtype")))?;
+            writer.write_event(Event::Text(BytesText::new(rtype)))?;
+            writer.write_event(Event::End(BytesEnd::new("rtype")))?;
+            
+            writer.write_event(Event::Start(BytesStart::new("value")))?;
+            writer.write_event(Event::Text(BytesText::new(value)))?;
+            writer.write_event(Event::End(BytesEnd::new("value")))?;
+        },
     }
     
     // Write TTL if present
@@ -180,7 +317,91 @@ fn write_record<W: std::io::Write>(writer: &mut Writer<W>, record: &DnsRecord) -
         writer.write_event(Event::End(BytesEnd::new("ttl")))?;
     }
     
+    // Write the domain being enumerated if present
+    if let Some(ref domain) = record.domain {
+        writer.write_event(Event::Start(BytesStart::new("domain")))?;
+        writer.write_event(Event::Text(BytesText::new(domain)))?;
+        writer.write_event(Event::End(BytesEnd::new("domain")))?;
+    }
+    
+    // Write how the record was found and what it was classified as
+    let annotations = [("origin", record.origin.as_deref()), ("txt_category", record.txt_category.as_deref())];
+    for (name, value) in annotations {
+        if let Some(value) = value {
+            writer.write_event(Event::Start(BytesStart::new(name)))?;
+            writer.write_event(Event::Text(BytesText::new(value)))?;
+            writer.write_event(Event::End(BytesEnd::new(name)))?;
+        }
+    }
+    if record.dangling {
+        writer.write_event(Event::Empty(BytesStart::new("dangling")))?;
+    }
+    
+    // Write WHOIS details if present
+    if let Some(ref whois) = record.whois {
+        write_whois(writer, whois)?;
+    }
+    
+    // Write GeoIP details if present
+    #[cfg(feature = "geoip")]
+    if let Some(ref geoip) = record.geoip {
+        write_geoip(writer, geoip)?;
+    }
+    
     writer.write_event(Event::End(BytesEnd::new(&element_name)))?;
     
+    Ok(())
+}
+
+/// Write GeoIP details as a nested element
+#[cfg(feature = "geoip")]
+fn write_geoip<W: std::io::Write>(writer: &mut Writer<W>, geoip: &GeoInfo) -> Result<(), OutputError> {
+    writer.write_event(Event::Start(BytesStart::new("geoip")))?;
+    
+    let fields = [
+        ("asn", geoip.asn.map(|asn| asn.to_string())),
+        ("org", geoip.org.clone()),
+        ("country", geoip.country.clone()),
+    ];
+    for (name, value) in fields {
+        if let Some(value) = value {
+            writer.write_event(Event::Start(BytesStart::new(name)))?;
+            writer.write_event(Event::Text(BytesText::new(&value)))?;
+            writer.write_event(Event::End(BytesEnd::new(name)))?;
+        }
+    }
+    
+    writer.write_event(Event::End(BytesEnd::new("geoip")))?;
+    
+    Ok(())
+}
+
+/// Write WHOIS details as a nested element
+fn write_whois<W: std::io::Write>(writer: &mut Writer<W>, whois: &WhoisInfo) -> Result<(), OutputError> {
+    writer.write_event(Event::Start(BytesStart::new("whois")))?;
+    
+    let fields = [
+        ("org_name", &whois.org_name),
+        ("org_handle", &whois.org_handle),
+        ("country", &whois.country),
+        ("abuse_contact", &whois.abuse_contact),
+        ("asn", &whois.asn),
+    ];
+    for (name, value) in fields {
+        if let Some(value) = value {
+            writer.write_event(Event::Start(BytesStart::new(name)))?;
+            writer.write_event(Event::Text(BytesText::new(value)))?;
+            writer.write_event(Event::End(BytesEnd::new(name)))?;
+        }
+    }
+    
+    for net_range in &whois.net_ranges {
+        writer.write_event(Event::Start(BytesStart::new("net_range")))?;
+        writer.write_event(Event::Text(BytesText::new(net_range)))?;
+        writer.write_event(Event::End(BytesEnd::new("net_range")))?;
+    }
+    
+    writer.write_event(Event::End(BytesEnd::new("whois")))?;
+    
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,170 @@
+//! BIND master file output (RFC 1035 section 5)
+//!
+//! Records are written one per line as `name TTL IN TYPE rdata`. Owner names
+//! inside the origin are written relative to it, everything else is fully
+//! qualified.
+
+use crate::dns::record::{DnsRecord, RecordData, RecordType};
+use crate::output::{write_atomically, OutputError};
+use crate::utils::reverse::reverse_name;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::net::IpAddr;
+
+/// TTL written for records whose TTL wasn't captured
+pub const DEFAULT_TTL: u32 = 3600;
+
+/// Longest character-string allowed in TXT data
+const MAX_CHARACTER_STRING: usize = 255;
+
+/// Render records as a zone file for `origin`
+///
+/// SOA records come first, as a zone requires; the rest keep their order.
+pub fn to_zonefile_string(results: &[DnsRecord], origin: &str) -> String {
+    let origin = fqdn(origin);
+    let mut zone = format!("$ORIGIN {}\n$TTL {}\n", origin, DEFAULT_TTL);
+
+    let (soa, rest): (Vec<&DnsRecord>, Vec<&DnsRecord>) =
+        results.iter().partition(|record| record.record_type == RecordType::Soa);
+    for record in soa.into_iter().chain(rest) {
+        zone.push_str(&format!(
+            "{} {} IN {} {}\n",
+            owner_name(record, &origin),
+            record.ttl.unwrap_or(DEFAULT_TTL),
+            record.record_type.mnemonic(),
+            rdata(&record.data)
+        ));
+    }
+
+    zone
+}
+
+/// Write records as a zone file for `origin`
+pub fn write_zonefile(results: &[DnsRecord], origin: &str, filename: &str) -> Result<(), OutputError> {
+    write_atomically(filename, |tmp_filename| {
+        let file = File::create(tmp_filename)?;
+        let mut writer = BufWriter::new(file);
+
+        writer.write_all(to_zonefile_string(results, origin).as_bytes())?;
+        writer.flush()?;
+
+        Ok(())
+    })
+}
+
+/// Quote text as one or more RFC 1035 character-strings
+///
+/// `"` and `\` are backslash-escaped, bytes outside printable ASCII become
+/// `\DDD`, and text longer than 255 bytes is split into several strings.
+pub fn quote_text(text: &str) -> String {
+    let bytes = text.as_bytes();
+    if bytes.is_empty() {
+        return "\"\"".to_string();
+    }
+
+    bytes
+        .chunks(MAX_CHARACTER_STRING)
+        .map(|chunk| {
+            let mut quoted = String::from("\"");
+            for &byte in chunk {
+                match byte {
+                    b'"' | b'\\' => {
+                        quoted.push('\\');
+                        quoted.push(byte as char);
+                    }
+                    0x20..=0x7e => quoted.push(byte as char),
+                    _ => quoted.push_str(&format!("\\{:03}", byte)),
+                }
+            }
+            quoted.push('"');
+            quoted
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Owner name of a record, relative to `origin` where possible
+///
+/// PTR results are keyed by address, so their owner is the reverse name.
+fn owner_name(record: &DnsRecord, origin: &str) -> String {
+    let name = match (&record.data, record.name.parse::<IpAddr>()) {
+        (RecordData::Ptr(_), Ok(ip)) => fqdn(&reverse_name(ip)),
+        _ => fqdn(&record.name),
+    };
+
+    if name.eq_ignore_ascii_case(origin) {
+        "@".to_string()
+    } else if origin != "." && name.to_lowercase().ends_with(&format!(".{}", origin.to_lowercase())) {
+        name[..name.len() - origin.len() - 1].to_string()
+    } else {
+        name
+    }
+}
+
+/// Record data in presentation format with fully qualified names
+fn rdata(data: &RecordData) -> String {
+    match data {
+        RecordData::A(address) => address.to_string(),
+        RecordData::Aaaa(address) => address.to_string(),
+        RecordData::Mx { preference, exchange } => format!("{} {}", preference, fqdn(exchange)),
+        RecordData::Ns(target) | RecordData::Ptr(target) | RecordData::Cname(target) => fqdn(target),
+        RecordData::Soa { mname, rname, serial, refresh, retry, expire, minimum } => format!(
+            "{} {} {} {} {} {} {}",
+            fqdn(mname),
+            fqdn(rname),
+            serial,
+            refresh,
+            retry,
+            expire,
+            minimum
+        ),
+        RecordData::Txt(text) | RecordData::Spf(text) => quote_text(text),
+        RecordData::Dmarc(dmarc) => quote_text(&dmarc.to_txt()),
+        RecordData::Srv { priority, weight, port, target } => {
+            format!("{} {} {} {}", priority, weight, port, fqdn(target))
+        }
+        RecordData::Caa { flags, tag, value } => format!("{} {} {}", flags, tag, quote_text(value)),
+        RecordData::Svcb { priority, target, params } | RecordData::Https { priority, target, params } => {
+            let mut value = format!("{} {}", priority, fqdn(target));
+            for (key, param) in params {
+                value.push_str(&format!(" {}={}", key, param));
+            }
+            value
+        }
+        RecordData::Dnskey { flags, protocol, algorithm, public_key } => {
+            format!("{} {} {} {}", flags, protocol, algorithm, public_key)
+        }
+        RecordData::Ds { key_tag, algorithm, digest_type, digest } => {
+            format!("{} {} {} {}", key_tag, algorithm, digest_type, digest)
+        }
+        RecordData::Rrsig {
+            type_covered,
+            algorithm,
+            labels,
+            original_ttl,
+            expiration,
+            inception,
+            key_tag,
+            signer_name,
+            signature,
+        } => format!(
+            "{} {} {} {} {} {} {} {} {}",
+            type_covered,
+            algorithm,
+            labels,
+            original_ttl,
+            expiration,
+            inception,
+            key_tag,
+            fqdn(signer_name),
+            signature
+        ),
+        RecordData::Nsec { next_domain, types } => format!("{} {}", fqdn(next_domain), types.join(" ")),
+        RecordData::Other { value, .. } => value.clone(),
+    }
+}
+
+/// Name with exactly one trailing dot
+fn fqdn(name: &str) -> String {
+    format!("{}.", name.trim_end_matches('.'))
+}
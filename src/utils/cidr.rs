@@ -1,23 +1,52 @@
 //! CIDR range processing utilities
 
 use ipnetwork::IpNetwork;
-use std::net::{IpAddr, Ipv4Addr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::str::FromStr;
 
+/// Default cap on the number of addresses a range may expand to
+pub const DEFAULT_MAX_RANGE_SIZE: usize = 65_536;
+
+/// Number of addresses in a CIDR network, saturating at `u128::MAX` for `::/0`
+pub fn network_size(network: &IpNetwork) -> u128 {
+    1u128.checked_shl(host_bits(network)).unwrap_or(u128::MAX)
+}
+
+/// Number of address bits a network leaves for hosts
+pub fn host_bits(network: &IpNetwork) -> u32 {
+    match network {
+        IpNetwork::V4(network) => 32 - u32::from(network.prefix()),
+        IpNetwork::V6(network) => 128 - u32::from(network.prefix()),
+    }
+}
+
+/// Fail if a range of `size` addresses is larger than `max_size`
+pub fn check_range_size(range_str: &str, size: u128, max_size: usize) -> Result<(), Box<dyn std::error::Error>> {
+    if size > max_size as u128 {
+        return Err(format!("{} holds {} addresses, more than the limit of {}", range_str, size, max_size).into());
+    }
+    Ok(())
+}
+
 /// Expand a CIDR range to individual IP addresses
-pub fn expand_cidr(cidr: &str) -> Result<Vec<IpAddr>, Box<dyn std::error::Error>> {
+///
+/// Networks with more than `max_size` addresses are rejected.
+pub fn expand_cidr(cidr: &str, max_size: usize) -> Result<Vec<IpAddr>, Box<dyn std::error::Error>> {
     let network = IpNetwork::from_str(cidr)?;
+    check_range_size(cidr, network_size(&network), max_size)?;
     Ok(network.iter().collect())
 }
 
 /// Process an IP range string (either CIDR or start-end format)
-pub fn process_range(range_str: &str) -> Result<Vec<IpAddr>, Box<dyn std::error::Error>> {
+///
+/// Ranges with more than `max_size` addresses are rejected before expansion.
+pub fn process_range(range_str: &str, max_size: usize) -> Result<Vec<IpAddr>, Box<dyn std::error::Error>> {
     if range_str.contains('/') {
         // CIDR format
-        expand_cidr(range_str)
+        expand_cidr(range_str, max_size)
     } else if range_str.contains('-') {
         // Range format (e.g., 192.168.1.1-192.168.1.10)
-        expand_range(range_str)
+        expand_range(range_str, max_size)
     } else {
         // Single IP
         let ip = IpAddr::from_str(range_str)?;
@@ -25,30 +54,88 @@ pub fn process_range(range_str: &str) -> Result<Vec<IpAddr>, Box<dyn std::error:
     }
 }
 
+/// Split a comma-separated list of ranges into its trimmed, non-empty segments
+pub fn split_ranges(range_list: &str) -> Vec<&str> {
+    range_list.split(',').map(str::trim).filter(|segment| !segment.is_empty()).collect()
+}
+
+/// Process several ranges, returning their addresses in order without duplicates
+///
+/// Each segment is limited to `max_size` addresses on its own. An invalid
+/// segment fails the whole list with an error naming it.
+pub fn process_ranges(segments: &[&str], max_size: usize) -> Result<Vec<IpAddr>, Box<dyn std::error::Error>> {
+    let mut first_error = None;
+    let ips = process_ranges_with(segments, max_size, |index, e| {
+        first_error.get_or_insert_with(|| format!("invalid range '{}': {}", segments[index], e));
+    });
+    match first_error {
+        Some(e) => Err(e.into()),
+        None => Ok(ips),
+    }
+}
+
+/// Process several ranges, skipping the invalid ones
+///
+/// Like [`process_ranges`], but the index and error of every invalid segment
+/// are passed to `on_error` and the remaining segments are still processed.
+pub fn process_ranges_with<F>(segments: &[&str], max_size: usize, mut on_error: F) -> Vec<IpAddr>
+where
+    F: FnMut(usize, Box<dyn std::error::Error>),
+{
+    let mut seen = std::collections::HashSet::new();
+    let mut ips = Vec::new();
+    for (index, segment) in segments.iter().enumerate() {
+        match process_range(segment, max_size) {
+            Ok(segment_ips) => ips.extend(segment_ips.into_iter().filter(|ip| seen.insert(*ip))),
+            Err(e) => on_error(index, e),
+        }
+    }
+    ips
+}
+
+/// Drop the networks that lie inside another network of the list
+///
+/// The remaining networks are returned broadest first, each once.
+pub fn outermost_networks(mut networks: Vec<IpNetwork>) -> Vec<IpNetwork> {
+    networks.sort_by_key(IpNetwork::prefix);
+    let mut outermost: Vec<IpNetwork> = Vec::new();
+    for network in networks {
+        if !outermost.iter().any(|outer| outer.contains(network.network())) {
+            outermost.push(network);
+        }
+    }
+    outermost
+}
+
 /// Expand an IP range in start-end format
-fn expand_range(range_str: &str) -> Result<Vec<IpAddr>, Box<dyn std::error::Error>> {
+///
+/// Both ends are included and must be of the same address family.
+fn expand_range(range_str: &str, max_size: usize) -> Result<Vec<IpAddr>, Box<dyn std::error::Error>> {
     let parts: Vec<&str> = range_str.split('-').collect();
     if parts.len() != 2 {
         return Err("Invalid range format".into());
     }
     
-    let start = IpAddr::from_str(parts[0])?;
-    let end = IpAddr::from_str(parts[1])?;
+    let start = IpAddr::from_str(parts[0].trim())?;
+    let end = IpAddr::from_str(parts[1].trim())?;
     
-    // For IPv4 ranges
-    if let (IpAddr::V4(start_v4), IpAddr::V4(end_v4)) = (start, end) {
-        let mut ips = Vec::new();
-        let mut current = u32::from(start_v4);
-        let end_num = u32::from(end_v4);
-        
-        while current <= end_num {
-            ips.push(IpAddr::V4(Ipv4Addr::from(current)));
-            current += 1;
-        }
-        
-        return Ok(ips);
+    // Both families are expanded as integers, IPv4 widened to u128
+    let (start_num, end_num) = match (start, end) {
+        (IpAddr::V4(start), IpAddr::V4(end)) => (u128::from(u32::from(start)), u128::from(u32::from(end))),
+        (IpAddr::V6(start), IpAddr::V6(end)) => (u128::from(start), u128::from(end)),
+        _ => return Err(format!("{} mixes IPv4 and IPv6 addresses", range_str).into()),
+    };
+    if start_num > end_num {
+        return Err(format!("{} starts after it ends", range_str).into());
     }
+    check_range_size(range_str, (end_num - start_num).saturating_add(1), max_size)?;
+    
+    let ips = (start_num..=end_num)
+        .map(|num| match start {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::from(num as u32)),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::from(num)),
+        })
+        .collect();
     
-    // For IPv6 ranges (simplified - just the start and end)
-    Ok(vec![start, end])
+    Ok(ips)
 }
\ No newline at end of file
@@ -0,0 +1,35 @@
+//! Bounded concurrent lookups
+
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use std::future::Future;
+
+/// Run `lookup` on every name, keeping at most `concurrency` lookups in flight
+///
+/// Names are pulled from the iterator only when a lookup slot is free, so
+/// large inputs are never expanded up front and a lazy iterator can stop the
+/// run early. Each result is passed to `on_result` as soon as its lookup
+/// finishes, in completion order, so only the lookups in flight are held.
+pub async fn resolve_bounded<I, F, Fut, S>(names: I, concurrency: usize, mut lookup: F, mut on_result: S)
+where
+    I: IntoIterator,
+    F: FnMut(I::Item) -> Fut,
+    Fut: Future,
+    S: FnMut(Fut::Output),
+{
+    let concurrency = concurrency.max(1);
+    let mut names = names.into_iter().fuse();
+    let mut in_flight = FuturesUnordered::new();
+
+    loop {
+        // Fill the free slots before waiting on the next lookup
+        while in_flight.len() < concurrency {
+            let Some(name) = names.next() else { break };
+            in_flight.push(lookup(name));
+        }
+
+        match in_flight.next().await {
+            Some(result) => on_result(result),
+            None => break,
+        }
+    }
+}
@@ -1,24 +1,168 @@
 //! HTTP client utilities with proxy and user-agent support
 
 use reqwest;
-use tokio::time::Duration;
-use crate::cli::Args;
+use reqwest::StatusCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::time::{sleep, Duration};
+use crate::config::EnumConfig;
 use crate::enumerate::EnumerationError;
 
-/// Create an HTTP client with appropriate settings based on CLI arguments
-pub fn create_http_client(args: &Args, user_agent: &str) -> Result<reqwest::Client, EnumerationError> {
-    let mut client_builder = reqwest::Client::builder()
-        .timeout(Duration::from_secs(30))
-        .user_agent(user_agent);
-    
-    // Add proxy if specified
-    if let Some(ref proxy_url) = args.proxy {
-        if let Ok(proxy) = reqwest::Proxy::all(proxy_url) {
-            client_builder = client_builder.proxy(proxy);
-        } else {
-            return Err(EnumerationError::Network(format!("Invalid proxy URL: {}", proxy_url)));
+/// User-Agent sent by the HTTP clients unless a request overrides it
+pub const DEFAULT_USER_AGENT: &str = "Mozilla/5.0 (compatible; DNSRecon-rs/0.1; +https://github.com/example/dnsrecon-rs)";
+
+/// Timeout of a single HTTP request unless the config sets another
+pub const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Proxy schemes accepted by `--proxy`
+const PROXY_SCHEMES: [&str; 4] = ["http", "https", "socks5", "socks5h"];
+
+/// Parse a comma-separated list of proxy URLs
+///
+/// Entries without a scheme are taken as HTTP proxies, like reqwest does. Every
+/// entry must have a host and one of the supported schemes (http, https,
+/// socks5, socks5h); the first invalid entry is an error.
+pub fn parse_proxies(spec: &str) -> Result<Vec<String>, EnumerationError> {
+    let mut proxies = Vec::new();
+    
+    for entry in spec.split(',').map(|entry| entry.trim()).filter(|entry| !entry.is_empty()) {
+        let entry = if entry.contains("://") { entry.to_string() } else { format!("http://{}", entry) };
+        let url = url::Url::parse(&entry)
+            .map_err(|e| EnumerationError::Network(format!("Invalid proxy URL {}: {}", entry, e)))?;
+        if !PROXY_SCHEMES.contains(&url.scheme()) {
+            return Err(EnumerationError::Network(format!(
+                "Invalid proxy URL {}: unsupported scheme '{}' (expected one of {})",
+                entry,
+                url.scheme(),
+                PROXY_SCHEMES.join(", ")
+            )));
+        }
+        if url.host_str().is_none_or(|host| host.is_empty()) {
+            return Err(EnumerationError::Network(format!("Invalid proxy URL {}: missing host", entry)));
         }
+        proxies.push(entry);
+    }
+    
+    if proxies.is_empty() {
+        return Err(EnumerationError::Network(format!("No proxy URL in '{}'", spec)));
+    }
+    Ok(proxies)
+}
+
+/// Build a single HTTP client with the user agent and timeout of `config`, optionally routed through a proxy
+fn build_client(config: &EnumConfig, proxy_url: Option<&str>) -> Result<reqwest::Client, EnumerationError> {
+    let mut client_builder = reqwest::Client::builder()
+        .timeout(config.timeout)
+        .user_agent(config.user_agent.as_str());
+    
+    if let Some(proxy_url) = proxy_url {
+        let proxy = reqwest::Proxy::all(proxy_url)
+            .map_err(|_| EnumerationError::Network(format!("Invalid proxy URL: {}", proxy_url)))?;
+        client_builder = client_builder.proxy(proxy);
     }
     
     client_builder.build().map_err(|e| EnumerationError::Network(format!("Failed to build HTTP client: {}", e)))
-}
\ No newline at end of file
+}
+
+/// HTTP clients that requests rotate across, one per proxy
+pub struct ClientPool {
+    clients: Vec<reqwest::Client>,
+    next: AtomicUsize,
+}
+
+impl ClientPool {
+    /// Get the client for the next request, cycling through the pool
+    pub fn next_client(&self) -> &reqwest::Client {
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.clients.len();
+        &self.clients[index]
+    }
+    
+    /// Number of clients in the pool
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+    
+    /// Check whether the pool has no clients (never true for a built pool)
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+}
+
+/// Create a pool with one HTTP client per proxy in `--proxy`
+///
+/// Without `--proxy` the pool holds a single direct client. Rotation starts at a
+/// random proxy so separate scrapes don't all begin on the same one.
+pub fn create_client_pool(config: &EnumConfig) -> Result<ClientPool, EnumerationError> {
+    let clients = match config.proxy {
+        Some(ref spec) => parse_proxies(spec)?
+            .iter()
+            .map(|proxy_url| build_client(config, Some(proxy_url)))
+            .collect::<Result<Vec<_>, _>>()?,
+        None => vec![build_client(config, None)?],
+    };
+    
+    let start = rand::random::<usize>() % clients.len();
+    Ok(ClientPool { clients, next: AtomicUsize::new(start) })
+}
+
+/// Create an HTTP client with the proxy, user agent and timeout of `config`
+///
+/// When `--proxy` lists several proxies, this client uses the first one; the
+/// scrapers rotate through all of them with [`create_client_pool`].
+pub fn create_http_client(config: &EnumConfig) -> Result<reqwest::Client, EnumerationError> {
+    let proxy_url = match config.proxy {
+        Some(ref spec) => parse_proxies(spec)?.into_iter().next(),
+        None => None,
+    };
+    build_client(config, proxy_url.as_deref())
+}
+
+/// Maximum number of retries after a rate-limited (429/503) response
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+
+/// Delay used when a rate-limited response carries no usable Retry-After header
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(5);
+
+/// Upper bound on how long a single Retry-After is honored
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Fetch a URL, backing off on HTTP 429/503 according to the Retry-After header
+///
+/// Rate-limited responses are retried up to a fixed limit, sleeping for the
+/// delay requested by the server (capped at 60 seconds) between attempts.
+/// Any other non-success status is returned as an error.
+pub async fn get_with_backoff(client: &reqwest::Client, url: &str) -> Result<String, EnumerationError> {
+    let mut retries = 0;
+    
+    loop {
+        let response = client.get(url).send().await?;
+        let status = response.status();
+        
+        if status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE {
+            if retries >= MAX_RATE_LIMIT_RETRIES {
+                return Err(EnumerationError::Network(format!("Rate limited by {} after {} retries", url, retries)));
+            }
+            
+            let delay = retry_after(&response).unwrap_or(DEFAULT_RETRY_AFTER).min(MAX_RETRY_AFTER);
+            retries += 1;
+            tracing::warn!("{} returned {}, retrying in {}s (attempt {}/{})", url, status, delay.as_secs(), retries, MAX_RATE_LIMIT_RETRIES);
+            sleep(delay).await;
+            continue;
+        }
+        
+        let response = response.error_for_status()?;
+        return Ok(response.text().await?);
+    }
+}
+
+/// Parse the Retry-After header of a response as a number of seconds
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
@@ -3,7 +3,9 @@
 //! This module provides various utility functions used throughout the application.
 
 pub mod cidr;
+pub mod concurrency;
 pub mod http;
+pub mod reverse;
 pub mod validation;
 
 /// Remove duplicates from a vector while preserving order
@@ -0,0 +1,73 @@
+//! Reverse (PTR) query name generation
+
+use crate::utils::cidr::host_bits;
+use ipnetwork::IpNetwork;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Number of host bits above which a range is swept lazily instead of expanded
+///
+/// Anything larger than a /16 (or the same number of IPv6 addresses) is swept
+/// one query name at a time so memory use stays flat.
+pub const LAZY_SWEEP_HOST_BITS: u8 = 16;
+
+/// Build the reverse query name of an address
+///
+/// `192.0.2.1` becomes `1.2.0.192.in-addr.arpa`; IPv6 addresses are written as
+/// 32 reversed nibbles under `ip6.arpa`.
+pub fn reverse_name(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, d] = ip.octets();
+            format!("{}.{}.{}.{}.in-addr.arpa", d, c, b, a)
+        }
+        IpAddr::V6(ip) => {
+            let mut name = String::with_capacity(72);
+            for byte in ip.octets().iter().rev() {
+                name.push_str(&format!("{:x}.{:x}.", byte & 0x0f, byte >> 4));
+            }
+            name.push_str("ip6.arpa");
+            name
+        }
+    }
+}
+
+/// Recover the address from a reverse query name built by [`reverse_name`]
+pub fn ip_from_reverse_name(name: &str) -> Option<IpAddr> {
+    let name = name.trim_end_matches('.').to_lowercase();
+
+    if let Some(labels) = name.strip_suffix(".in-addr.arpa") {
+        let octets: Vec<u8> = labels.split('.').map(|label| label.parse().ok()).collect::<Option<_>>()?;
+        if let [d, c, b, a] = octets[..] {
+            return Some(IpAddr::V4(Ipv4Addr::new(a, b, c, d)));
+        }
+        return None;
+    }
+
+    let labels = name.strip_suffix(".ip6.arpa")?;
+    let nibbles: Vec<u8> = labels
+        .split('.')
+        .map(|label| u8::from_str_radix(label, 16).ok().filter(|_| label.len() == 1))
+        .collect::<Option<_>>()?;
+    if nibbles.len() != 32 {
+        return None;
+    }
+
+    let mut octets = [0u8; 16];
+    for (i, pair) in nibbles.rchunks(2).enumerate() {
+        octets[i] = (pair[1] << 4) | pair[0];
+    }
+    Some(IpAddr::V6(Ipv6Addr::from(octets)))
+}
+
+/// Lazily yield the reverse query name of every address in a network
+///
+/// Names are produced one at a time, so a /8 is never expanded into a list
+/// of its addresses.
+pub fn ptr_names_for_cidr(cidr: IpNetwork) -> impl Iterator<Item = String> {
+    cidr.iter().map(reverse_name)
+}
+
+/// Check whether a network is large enough to be swept lazily
+pub fn is_large_network(cidr: &IpNetwork) -> bool {
+    host_bits(cidr) > u32::from(LAZY_SWEEP_HOST_BITS)
+}
@@ -1,7 +1,7 @@
 //! Input validation utilities
 
 use regex::Regex;
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::str::FromStr;
 
 /// Validate if a string is a valid domain name
@@ -29,4 +29,56 @@ pub fn is_valid_ipv6(ip: &str) -> bool {
 /// Validate CIDR notation
 pub fn is_valid_cidr(cidr: &str) -> bool {
     cidr.parse::<ipnetwork::IpNetwork>().is_ok()
-}
\ No newline at end of file
+}
+/// Normalize a domain name to its ASCII (punycode) form for DNS queries
+///
+/// Both Unicode (`müller.de`) and punycode (`xn--mller-kva.de`) inputs produce
+/// the same lowercase ASCII name, with any trailing dot removed.
+pub fn normalize_domain(domain: &str) -> Result<String, String> {
+    let trimmed = domain.trim().trim_end_matches('.');
+    idna::domain_to_ascii(trimmed)
+        .map_err(|e| format!("Invalid internationalized domain name {}: {}", domain, e))
+}
+
+/// Convert a domain name to its Unicode form for display
+pub fn domain_to_unicode(domain: &str) -> String {
+    let (unicode, _) = idna::domain_to_unicode(domain);
+    unicode
+}
+
+/// A nameserver address and whether its port was given explicitly
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NameserverEntry {
+    pub addr: SocketAddr,
+    pub port_given: bool,
+}
+
+/// Parse a nameserver given as an IP address, optionally with a port
+///
+/// Bare addresses use port 53; IPv6 addresses with a port are written `[::1]:5353`.
+pub fn parse_nameserver(nameserver: &str) -> Result<SocketAddr, String> {
+    parse_nameserver_entry(nameserver).map(|entry| entry.addr)
+}
+
+/// Parse a nameserver like [`parse_nameserver`], remembering whether it had a port
+pub fn parse_nameserver_entry(nameserver: &str) -> Result<NameserverEntry, String> {
+    let nameserver = nameserver.trim();
+    if let Ok(ip) = nameserver.parse::<IpAddr>() {
+        return Ok(NameserverEntry { addr: SocketAddr::new(ip, 53), port_given: false });
+    }
+    nameserver
+        .parse::<SocketAddr>()
+        .map(|addr| NameserverEntry { addr, port_given: true })
+        .map_err(|_| format!("Invalid nameserver IP address: {}", nameserver))
+}
+
+/// Check whether `host` is a name strictly below `domain`
+///
+/// Matching ignores case and trailing dots and requires a label boundary, so
+/// `notexample.com` is not below `example.com`.
+pub fn is_subdomain_of(host: &str, domain: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+    host.strip_suffix(&domain)
+        .is_some_and(|prefix| prefix.len() > 1 && prefix.ends_with('.'))
+}
@@ -0,0 +1,44 @@
+//! Unit tests for the apex CNAME and flattening checks
+
+use dnsrecon_rs::dns::record::DnsRecord;
+use dnsrecon_rs::enumerate::apex::{apex_cname_findings, flattening_findings};
+use dnsrecon_rs::enumerate::findings::Severity;
+use std::net::Ipv4Addr;
+
+#[test]
+fn test_apex_cname_is_flagged() {
+    let records = vec![
+        DnsRecord::new_cname("example.com".to_string(), "lb.hosting.example.net.".to_string()),
+        DnsRecord::new_ns("example.com".to_string(), "ns1.example.com".to_string()),
+        DnsRecord::new_cname("www.example.com".to_string(), "lb.hosting.example.net".to_string()),
+    ];
+
+    let findings = apex_cname_findings("example.com", &records);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].severity, Severity::Medium);
+    assert_eq!(findings[0].category, "apex-cname");
+    assert!(findings[0].message.contains("lb.hosting.example.net"));
+
+    // A CNAME below the apex is normal
+    assert!(apex_cname_findings("example.com", &records[1..]).is_empty());
+}
+
+#[test]
+fn test_flattened_apex_is_noted() {
+    let mut records = vec![
+        DnsRecord::new_a("example.com".to_string(), Ipv4Addr::new(192, 0, 2, 10)),
+        DnsRecord::new_a("example.com".to_string(), Ipv4Addr::new(192, 0, 2, 11)),
+        DnsRecord::new_cname("www.example.com".to_string(), "edge.cdn.example.net".to_string()),
+        DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 11)),
+        DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 10)),
+    ];
+
+    let findings = flattening_findings("example.com", &records);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].category, "apex-flattening");
+    assert!(findings[0].message.contains("edge.cdn.example.net"));
+
+    // Different address sets are not evidence of flattening
+    records.pop();
+    assert!(flattening_findings("example.com", &records).is_empty());
+}
@@ -11,7 +11,7 @@ fn test_cli_parsing_without_proxy() {
     assert!(result.is_ok());
     
     let args = result.unwrap();
-    assert_eq!(args.domain, Some("example.com".to_string()));
+    assert_eq!(args.domain, vec!["example.com".to_string()]);
     assert_eq!(args.r#type, EnumType::Standard);
     assert_eq!(args.proxy, None);
 }
@@ -24,7 +24,7 @@ fn test_cli_parsing_with_http_proxy() {
     assert!(result.is_ok());
     
     let args = result.unwrap();
-    assert_eq!(args.domain, Some("example.com".to_string()));
+    assert_eq!(args.domain, vec!["example.com".to_string()]);
     assert_eq!(args.proxy, Some("http://proxy.example.com:8080".to_string()));
 }
 
@@ -36,7 +36,7 @@ fn test_cli_parsing_with_socks5_proxy() {
     assert!(result.is_ok());
     
     let args = result.unwrap();
-    assert_eq!(args.domain, Some("example.com".to_string()));
+    assert_eq!(args.domain, vec!["example.com".to_string()]);
     assert_eq!(args.proxy, Some("socks5://proxy.example.com:1080".to_string()));
 }
 
@@ -48,6 +48,6 @@ fn test_cli_parsing_with_brute_force_type() {
     assert!(result.is_ok());
     
     let args = result.unwrap();
-    assert_eq!(args.domain, Some("example.com".to_string()));
+    assert_eq!(args.domain, vec!["example.com".to_string()]);
     assert_eq!(args.r#type, EnumType::BruteForce);
 }
\ No newline at end of file
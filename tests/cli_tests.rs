@@ -1,5 +1,7 @@
 //! Unit tests for CLI functionality that mirror the original DNSRecon Python tests
 
+mod common;
+
 #[test]
 fn test_check_wildcard() {
     // This test would normally check for wildcard DNS records
@@ -213,4 +215,397 @@ fn test_write_db() {
     
     assert_eq!(records[3]["type"], "AAAA");
     assert_eq!(records[3]["address"], "2001:db8::1");
-}
\ No newline at end of file
+}
+#[test]
+fn test_target_ip_detection() {
+    use clap::Parser;
+    use dnsrecon_rs::cli::{target_ip, validate_args, Args};
+    
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "8.8.8.8"]);
+    assert_eq!(target_ip(&args), Some("8.8.8.8".parse().unwrap()));
+    assert!(validate_args(&args).is_ok());
+    
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "2001:4860:4860::8888"]);
+    assert_eq!(target_ip(&args), Some("2001:4860:4860::8888".parse().unwrap()));
+    assert!(validate_args(&args).is_ok());
+    
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com"]);
+    assert_eq!(target_ip(&args), None);
+    
+    // Brute force needs a domain to put words under
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "8.8.8.8", "-t", "brt"]);
+    assert!(validate_args(&args).is_err());
+}
+
+#[test]
+fn test_sources_selection() {
+    use clap::Parser;
+    use dnsrecon_rs::cli::{enabled_sources, Args};
+    use dnsrecon_rs::enumerate::sources::Source;
+    
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com"]);
+    assert_eq!(enabled_sources(&args), Source::ALL.to_vec());
+    
+    // Order is preserved and duplicates are dropped
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com", "--sources", "crtsh,DNS,crtsh"]);
+    assert_eq!(enabled_sources(&args), vec![Source::Crtsh, Source::Dns]);
+    
+    let err = Args::try_parse_from(["dnsrecon-rs", "-d", "example.com", "--sources", "dns,certspotter"]).unwrap_err();
+    assert!(err.to_string().contains("available sources: dns, crtsh, bing, yandex"));
+}
+
+#[test]
+fn test_apex_records_selection() {
+    use clap::Parser;
+    use dnsrecon_rs::cli::{apex_record_types, Args, DEFAULT_APEX_RECORDS};
+    use dnsrecon_rs::dns::record::RecordType;
+    
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com"]);
+    assert_eq!(apex_record_types(&args), DEFAULT_APEX_RECORDS.to_vec());
+    
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com", "--apex-records", "mx,TXT,aaaa,mx"]);
+    assert_eq!(apex_record_types(&args), vec![RecordType::Mx, RecordType::Txt, RecordType::Aaaa]);
+    
+    // Types without dedicated support are kept by their mnemonic
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com", "--apex-records", "dname,NAPTR,type65534"]);
+    assert_eq!(apex_record_types(&args), vec![
+        RecordType::Other("DNAME".to_string()),
+        RecordType::Other("NAPTR".to_string()),
+        RecordType::Other("TYPE65534".to_string()),
+    ]);
+    
+    let err = Args::try_parse_from(["dnsrecon-rs", "-d", "example.com", "--apex-records", "A,MAILX"]).unwrap_err();
+    assert!(err.to_string().contains("Unknown record type 'MAILX'"));
+}
+
+#[tokio::test]
+async fn test_apex_records_queries_types_without_dedicated_support() {
+    use clap::Parser;
+    use dnsrecon_rs::cli::progress::TimedProgressReporter;
+    use dnsrecon_rs::cli::Args;
+    use std::sync::{Arc, Mutex};
+    use trust_dns_resolver::proto::rr::RecordType;
+
+    // A nameserver that records the type of every query and answers none
+    let queried = Arc::new(Mutex::new(Vec::new()));
+    let seen = queried.clone();
+    let server = common::spawn_dns_server(move |query, _| seen.lock().unwrap().push(query.query_type()));
+
+    let args = Args::parse_from([
+        "dnsrecon-rs", "-d", "example.com", "--sources", "dns", "-n", &server.to_string(), "--apex-records", "DNAME,NAPTR", "--no-color",
+    ]);
+    let progress = TimedProgressReporter::with_writer(std::io::sink());
+    dnsrecon_rs::run_with_reporter(args, &progress).await.unwrap();
+
+    let queried = queried.lock().unwrap();
+    assert!(queried.contains(&RecordType::NAPTR));
+    assert!(queried.contains(&RecordType::Unknown(39)));
+}
+
+#[test]
+fn test_record_types_filter() {
+    use clap::Parser;
+    use dnsrecon_rs::cli::Args;
+    use dnsrecon_rs::config::ReconConfig;
+    use dnsrecon_rs::dns::record::{DnsRecord, RecordType};
+    use std::net::Ipv4Addr;
+    
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com", "--record-types", "mx,TXT"]);
+    let record_types = ReconConfig::from(&args).record_types.unwrap();
+    assert_eq!(record_types, vec![RecordType::Mx, RecordType::Txt]);
+    
+    let records = vec![
+        DnsRecord::new_a("example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+        DnsRecord::new_mx("example.com".to_string(), 10, "mail.example.com".to_string()),
+        DnsRecord::new_ns("example.com".to_string(), "ns1.example.com".to_string()),
+        DnsRecord::new_txt("example.com".to_string(), "v=spf1 -all".to_string()),
+        DnsRecord::new_cname("www.example.com".to_string(), "example.com".to_string()),
+    ];
+    let kept: Vec<RecordType> = dnsrecon_rs::retain_record_types(records, &record_types)
+        .into_iter()
+        .map(|record| record.record_type)
+        .collect();
+    assert_eq!(kept, vec![RecordType::Mx, RecordType::Txt]);
+
+    // Types without dedicated support match records kept by their mnemonic
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com", "--record-types", "naptr"]);
+    let record_types = ReconConfig::from(&args).record_types.unwrap();
+    let naptr = DnsRecord::new_other("example.com".to_string(), "NAPTR".to_string(), "100 10 \"S\" \"SIP+D2U\" \"\" _sip._udp.example.com.".to_string());
+    let kept = dnsrecon_rs::retain_record_types(vec![naptr.clone(), DnsRecord::new_a("example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1))], &record_types);
+    assert_eq!(kept, vec![naptr]);
+
+    let err = Args::try_parse_from(["dnsrecon-rs", "-d", "example.com", "--record-types", "mx,MAILX"]).unwrap_err();
+    assert!(err.to_string().contains("Unknown record type 'MAILX'"));
+}
+
+#[test]
+fn test_multiple_domains_and_domain_file() {
+    use clap::Parser;
+    use dnsrecon_rs::cli::Args;
+    use dnsrecon_rs::config::ReconConfig;
+    
+    let path = std::env::temp_dir().join(format!("dnsrecon-rs-{}-domains.txt", std::process::id()));
+    std::fs::write(&path, "# targets\nexample.org\n\nEXAMPLE.com.\n  example.net  # staging\n").unwrap();
+    
+    let args = Args::parse_from([
+        "dnsrecon-rs", "-d", "example.com", "--domain", "example.org", "--domain-file", path.to_str().unwrap(),
+    ]);
+    assert_eq!(args.domain, vec!["example.com", "example.org"]);
+    let config = ReconConfig::from(&args);
+    assert_eq!(config.target_domains().unwrap(), vec!["example.com", "example.org", "example.net"]);
+    assert!(config.validate().is_ok());
+    std::fs::remove_file(&path).unwrap();
+    
+    // A missing domain file is reported instead of scanning nothing
+    let err = config.validate().unwrap_err();
+    assert!(err.to_string().contains("Cannot read domain file"));
+}
+
+#[test]
+fn test_exclude_patterns() {
+    use clap::Parser;
+    use dnsrecon_rs::cli::Args;
+    
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com", "--exclude", "*.cdn.example.com,monitoring.*"]);
+    assert_eq!(args.exclude, Some(vec!["*.cdn.example.com".to_string(), "monitoring.*".to_string()]));
+    
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com", "--exclude-subdomains", "*.internal"]);
+    assert_eq!(args.exclude, Some(vec!["*.internal".to_string()]));
+}
+
+#[test]
+fn test_address_family_flags() {
+    use clap::Parser;
+    use dnsrecon_rs::cli::{apex_record_types, validate_args, Args};
+    use dnsrecon_rs::dns::record::RecordType;
+    
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com", "--no-ipv6"]);
+    assert!(validate_args(&args).is_ok());
+    let types = apex_record_types(&args);
+    assert!(types.contains(&RecordType::A));
+    assert!(!types.contains(&RecordType::Aaaa));
+    
+    // Disabling both families leaves nothing to resolve
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com", "--no-ipv4", "--no-ipv6"]);
+    assert!(validate_args(&args).is_err());
+}
+
+#[tokio::test]
+async fn test_internationalized_domains_are_scanned_as_punycode() {
+    use clap::Parser;
+    use dnsrecon_rs::cli::progress::TimedProgressReporter;
+    use dnsrecon_rs::cli::Args;
+    use std::sync::{Arc, Mutex};
+
+    // A nameserver that records the name of every query and answers none
+    let queried = Arc::new(Mutex::new(Vec::new()));
+    let seen = queried.clone();
+    let server = common::spawn_dns_server(move |query, _| seen.lock().unwrap().push(query.name().to_ascii()));
+
+    let zone_file = std::env::temp_dir().join(format!("dnsrecon-rs-{}-idn.zone", std::process::id()));
+    let args = Args::parse_from([
+        "dnsrecon-rs", "-d", "müller.de", "--sources", "dns", "-n", &server.to_string(), "--no-color",
+        "--zone-file", zone_file.to_str().unwrap(),
+    ]);
+    let progress = TimedProgressReporter::with_writer(std::io::sink());
+    dnsrecon_rs::run_with_reporter(args, &progress).await.unwrap();
+
+    let queried = queried.lock().unwrap();
+    assert!(queried.iter().any(|name| name == "xn--mller-kva.de."));
+    assert!(queried.iter().all(|name| name.is_ascii()));
+    // The zone file origin is the normalized domain too
+    let zone = std::fs::read_to_string(&zone_file).unwrap();
+    assert!(zone.starts_with("$ORIGIN xn--mller-kva.de"));
+
+    let _ = std::fs::remove_file(&zone_file);
+}
+
+#[tokio::test]
+async fn test_no_ipv6_sends_no_aaaa_queries() {
+    use clap::Parser;
+    use dnsrecon_rs::cli::progress::TimedProgressReporter;
+    use dnsrecon_rs::cli::Args;
+    use std::sync::{Arc, Mutex};
+    use trust_dns_resolver::proto::rr::RecordType;
+
+    // A nameserver that records the type of every query and answers none
+    let queried = Arc::new(Mutex::new(Vec::new()));
+    let seen = queried.clone();
+    let server = common::spawn_dns_server(move |query, _| seen.lock().unwrap().push(query.query_type()));
+
+    let args = Args::parse_from([
+        "dnsrecon-rs", "-d", "example.com", "--sources", "dns", "-n", &server.to_string(), "--no-ipv6", "--no-color",
+    ]);
+    let progress = TimedProgressReporter::with_writer(std::io::sink());
+    dnsrecon_rs::run_with_reporter(args, &progress).await.unwrap();
+
+    let queried = queried.lock().unwrap();
+    assert!(queried.contains(&RecordType::A));
+    assert!(!queried.contains(&RecordType::AAAA));
+}
+
+#[tokio::test]
+async fn test_incremental_sqlite_keeps_zone_transfer_records_of_a_brute_force_run() {
+    use clap::Parser;
+    use dnsrecon_rs::cli::progress::TimedProgressReporter;
+    use dnsrecon_rs::cli::Args;
+    use std::io::{Read, Write};
+    use std::net::{Ipv4Addr, TcpListener};
+    use trust_dns_resolver::proto::op::{Message, MessageType};
+    use trust_dns_resolver::proto::rr::rdata::{A, NS, SOA};
+    use trust_dns_resolver::proto::rr::{Name, RData, Record, RecordType};
+
+    // AXFR goes to port 53 of the nameserver addresses
+    let Ok(axfr_listener) = TcpListener::bind("127.0.0.77:53") else {
+        eprintln!("Skipping: cannot listen on 127.0.0.77:53");
+        return;
+    };
+    std::thread::spawn(move || {
+        let zone = Name::from_ascii("example.com.").unwrap();
+        let soa = Record::from_rdata(
+            zone.clone(),
+            300,
+            RData::SOA(SOA::new(
+                Name::from_ascii("ns1.example.com.").unwrap(),
+                Name::from_ascii("hostmaster.example.com.").unwrap(),
+                1,
+                3600,
+                600,
+                604800,
+                300,
+            )),
+        );
+        let ftp = Record::from_rdata(Name::from_ascii("ftp.example.com.").unwrap(), 300, RData::A(A(Ipv4Addr::new(192, 0, 2, 20))));
+        for mut stream in axfr_listener.incoming().flatten() {
+            let mut length = [0u8; 2];
+            if stream.read_exact(&mut length).is_err() {
+                continue;
+            }
+            let mut request = vec![0u8; u16::from_be_bytes(length) as usize];
+            stream.read_exact(&mut request).unwrap();
+            let request = Message::from_vec(&request).unwrap();
+
+            let mut response = Message::new();
+            response.set_id(request.id()).set_message_type(MessageType::Response);
+            response.add_query(request.queries()[0].clone());
+            response.add_answers([soa.clone(), ftp.clone(), soa.clone()]);
+            let response = response.to_vec().unwrap();
+            stream.write_all(&(response.len() as u16).to_be_bytes()).unwrap();
+            stream.write_all(&response).unwrap();
+        }
+    });
+
+    // www is found by brute force and ns1 points at the AXFR listener
+    let server = common::spawn_dns_server(|query, response| {
+        let name = query.name().to_ascii().to_lowercase();
+        match (name.as_str(), query.query_type()) {
+            ("www.example.com.", RecordType::A) => {
+                response.add_answer(Record::from_rdata(query.name().clone(), 300, RData::A(A(Ipv4Addr::new(192, 0, 2, 10)))));
+            }
+            ("ns1.example.com.", RecordType::A) => {
+                response.add_answer(Record::from_rdata(query.name().clone(), 300, RData::A(A(Ipv4Addr::new(127, 0, 0, 77)))));
+            }
+            ("example.com.", RecordType::NS) => {
+                let nameserver = NS(Name::from_ascii("ns1.example.com.").unwrap());
+                response.add_answer(Record::from_rdata(query.name().clone(), 300, RData::NS(nameserver)));
+            }
+            _ => {}
+        }
+    });
+
+    let dir = std::env::temp_dir().join(format!("dnsrecon-rs-{}-brute-axfr", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let wordlist = dir.join("words.txt");
+    std::fs::write(&wordlist, "www\n").unwrap();
+    let database = dir.join("results.db");
+    let _ = std::fs::remove_file(&database);
+
+    let args = Args::parse_from([
+        "dnsrecon-rs", "-d", "example.com", "-t", "brt", "-D", wordlist.to_str().unwrap(), "-n", &server.to_string(),
+        "--check-axfr", "-s", database.to_str().unwrap(), "--sqlite-incremental", "--no-ipv6", "--no-color",
+    ]);
+    let progress = TimedProgressReporter::with_writer(std::io::sink());
+    dnsrecon_rs::run_with_reporter(args, &progress).await.unwrap();
+
+    // Both the streamed brute force record and the transferred one are written, once each
+    let conn = rusqlite::Connection::open(&database).unwrap();
+    let names: Vec<String> = conn
+        .prepare("SELECT name FROM dns_records WHERE type = 'A' ORDER BY name")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(names, vec!["ftp.example.com", "www.example.com"]);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_args_convert_to_recon_config() {
+    use clap::Parser;
+    use dnsrecon_rs::cli::{Args, EnumType};
+    use dnsrecon_rs::config::ReconConfig;
+    use dnsrecon_rs::enumerate::sources::Source;
+    
+    // Defaults match the command line defaults
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com"]);
+    assert_eq!(ReconConfig::from(args), ReconConfig::builder().domain("example.com").build());
+    
+    let args = Args::parse_from([
+        "dnsrecon-rs", "-d", "example.com", "-t", "brt", "-c", "25",
+        "--sources", "dns,crtsh", "--no-ipv6", "-D", "words.txt",
+    ]);
+    let config = ReconConfig::builder()
+        .domain("example.com")
+        .enum_type(EnumType::BruteForce)
+        .concurrency(25)
+        .sources(vec![Source::Dns, Source::Crtsh])
+        .no_ipv6(true)
+        .dict("words.txt")
+        .build();
+    assert_eq!(ReconConfig::from(&args), config);
+    assert!(config.validate().is_ok());
+    
+    // Validation applies to configurations built without the CLI
+    assert!(ReconConfig::builder().enum_type(EnumType::Reverse).domain("example.com").build().validate().is_err());
+}
+
+#[test]
+fn test_args_convert_to_enum_config() {
+    use clap::Parser;
+    use dnsrecon_rs::cli::Args;
+    use dnsrecon_rs::config::{EnumConfig, ReconConfig};
+    use dnsrecon_rs::enumerate::sources::Source;
+    use std::time::Duration;
+
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com"]);
+    assert_eq!(EnumConfig::from(&args), EnumConfig::default());
+
+    let args = Args::parse_from([
+        "dnsrecon-rs", "-d", "example.com", "-c", "25", "--sources", "dns,crtsh",
+        "-n", "127.0.0.1:5353", "--proxy", "socks5://127.0.0.1:1080",
+    ]);
+    let config = EnumConfig::from(&args);
+    assert_eq!(config.concurrency, 25);
+    assert_eq!(config.sources, Some(vec![Source::Dns, Source::Crtsh]));
+    assert_eq!(config.nameservers.as_deref(), Some("127.0.0.1:5353"));
+    assert_eq!(config.proxy.as_deref(), Some("socks5://127.0.0.1:1080"));
+
+    // Converting back covers the same settings for a standard enumeration
+    let recon = config.recon_config("example.com");
+    assert_eq!(EnumConfig::from(&recon), config);
+    assert_eq!(recon.domain.as_deref(), Some("example.com"));
+    assert!(recon.validate().is_ok());
+
+    // The scraper user agent and timeout carry over in both directions
+    let recon = ReconConfig::builder()
+        .domain("example.com")
+        .user_agent("custom-agent/1.0")
+        .http_timeout(Duration::from_secs(5))
+        .build();
+    let config = EnumConfig::from(&recon);
+    assert_eq!(config.user_agent, "custom-agent/1.0");
+    assert_eq!(config.timeout, Duration::from_secs(5));
+    assert_eq!(config.recon_config("example.com"), recon);
+}
@@ -0,0 +1,34 @@
+//! Helpers shared by the integration tests
+
+use std::net::{SocketAddr, UdpSocket};
+use std::thread;
+use trust_dns_resolver::proto::op::{Message, MessageType, Query};
+
+/// Start a UDP nameserver that answers every query through `answer`
+///
+/// `answer` is given the question and a response that already carries the
+/// request id and question; it adds the answers or sets the response code.
+pub fn spawn_dns_server<F>(answer: F) -> SocketAddr
+where
+    F: Fn(&Query, &mut Message) + Send + 'static,
+{
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = socket.local_addr().unwrap();
+
+    thread::spawn(move || {
+        let mut buffer = [0u8; 512];
+        while let Ok((len, from)) = socket.recv_from(&mut buffer) {
+            let request = Message::from_vec(&buffer[..len]).unwrap();
+            let query = request.queries()[0].clone();
+
+            let mut response = Message::new();
+            response.set_id(request.id()).set_message_type(MessageType::Response).set_recursion_available(true);
+            response.add_query(query.clone());
+            answer(&query, &mut response);
+
+            socket.send_to(&response.to_vec().unwrap(), from).unwrap();
+        }
+    });
+
+    addr
+}
@@ -0,0 +1,69 @@
+//! Unit tests for MX/NS consistency checks on synthetic record sets
+
+use dnsrecon_rs::dns::record::DnsRecord;
+use dnsrecon_rs::enumerate::consistency::{
+    check_ip_literal_targets, check_lame_delegation, check_mx_cname, check_unresolvable_ns,
+};
+use dnsrecon_rs::enumerate::findings::Severity;
+use std::net::Ipv4Addr;
+
+fn zone_records() -> Vec<DnsRecord> {
+    vec![
+        DnsRecord::new_ns("example.com".to_string(), "ns1.example.com".to_string()),
+        DnsRecord::new_ns("example.com".to_string(), "ns2.example.net".to_string()),
+        DnsRecord::new_mx("example.com".to_string(), 10, "mail.example.com".to_string()),
+        DnsRecord::new_mx("example.com".to_string(), 20, "alias.example.com".to_string()),
+    ]
+}
+
+#[test]
+fn test_ip_literal_targets() {
+    let mut records = zone_records();
+    assert!(check_ip_literal_targets("example.com", &records).is_empty());
+
+    records.push(DnsRecord::new_mx("example.com".to_string(), 30, "192.0.2.25".to_string()));
+    records.push(DnsRecord::new_ns("example.com".to_string(), "192.0.2.53".to_string()));
+    let findings = check_ip_literal_targets("example.com", &records);
+    assert_eq!(findings.len(), 2);
+    assert!(findings.iter().all(|finding| finding.severity == Severity::Medium));
+}
+
+#[test]
+fn test_unresolvable_ns() {
+    let findings = check_unresolvable_ns("example.com", &zone_records(), |name| {
+        if name == "ns1.example.com" {
+            vec![DnsRecord::new_a(name.to_string(), Ipv4Addr::new(192, 0, 2, 53))]
+        } else {
+            Vec::new()
+        }
+    });
+    assert_eq!(findings.len(), 1);
+    assert!(findings[0].message.contains("ns2.example.net"));
+}
+
+#[test]
+fn test_mx_pointing_at_cname() {
+    let findings = check_mx_cname("example.com", &zone_records(), |name| {
+        if name == "alias.example.com" {
+            vec![DnsRecord::new_cname(name.to_string(), "mail.example.com".to_string())]
+        } else {
+            Vec::new()
+        }
+    });
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].severity, Severity::Low);
+    assert!(findings[0].message.contains("alias.example.com"));
+}
+
+#[test]
+fn test_lame_delegation() {
+    let zone_ns = vec!["ns1.example.com".to_string(), "ns2.example.net".to_string()];
+
+    let parent_ns = vec!["NS1.example.com".to_string(), "ns2.example.net".to_string()];
+    assert!(check_lame_delegation("example.com", &zone_ns, &parent_ns).is_empty());
+
+    let parent_ns = vec!["ns1.example.com".to_string(), "ns-old.example.org".to_string()];
+    let findings = check_lame_delegation("example.com", &zone_ns, &parent_ns);
+    assert_eq!(findings.len(), 1);
+    assert!(findings[0].message.contains("ns-old.example.org"));
+}
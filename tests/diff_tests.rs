@@ -0,0 +1,122 @@
+//! Unit tests for comparing scans
+
+use dnsrecon_rs::dns::record::{DnsRecord, RecordData};
+use dnsrecon_rs::output::diff::{diff_records, render_diff_summary};
+use dnsrecon_rs::output::StdoutFormat;
+use std::net::Ipv4Addr;
+
+fn a_record(name: &str, last_octet: u8) -> DnsRecord {
+    DnsRecord::new_a(name.to_string(), Ipv4Addr::new(192, 0, 2, last_octet))
+}
+
+#[test]
+fn test_diff_records_groups_changes() {
+    let old = vec![
+        a_record("www.example.com", 1),
+        a_record("mail.example.com", 2),
+        a_record("old.example.com", 3),
+        DnsRecord::new_mx("example.com".to_string(), 10, "mail.example.com".to_string()),
+    ];
+    let mut renewed = a_record("WWW.example.com", 1);
+    renewed.ttl = Some(300);
+    let new = vec![
+        renewed,
+        a_record("mail.example.com", 20),
+        a_record("vpn.example.com", 4),
+        DnsRecord::new_mx("example.com".to_string(), 10, "mail.example.com".to_string()),
+    ];
+
+    let diff = diff_records(&old, &new);
+
+    assert_eq!(diff.new.len(), 1);
+    assert_eq!(diff.new[0].name, "vpn.example.com");
+    assert_eq!(diff.gone.len(), 1);
+    assert_eq!(diff.gone[0].name, "old.example.com");
+
+    // Case and TTL changes are not modifications
+    assert_eq!(diff.modified.len(), 1);
+    assert_eq!(diff.modified[0].name, "mail.example.com");
+    assert!(matches!(diff.modified[0].before[..], [RecordData::A(ip)] if ip == Ipv4Addr::new(192, 0, 2, 2)));
+    assert!(matches!(diff.modified[0].after[..], [RecordData::A(ip)] if ip == Ipv4Addr::new(192, 0, 2, 20)));
+
+    let summary = render_diff_summary(&diff);
+    assert!(summary.contains("1 new, 1 gone, 1 modified"));
+    assert!(summary.contains("+ A vpn.example.com"));
+    assert!(summary.contains("- A old.example.com"));
+    assert!(summary.contains("~ A mail.example.com"));
+}
+
+#[test]
+fn test_diff_records_ignores_answer_order() {
+    let old = vec![a_record("www.example.com", 1), a_record("www.example.com", 2)];
+    let new = vec![a_record("www.example.com", 2), a_record("www.example.com", 1)];
+
+    assert!(diff_records(&old, &new).is_empty());
+    assert!(diff_records(&[], &[]).is_empty());
+
+    // A second address for the same name is a modification, not a new record
+    let diff = diff_records(&old[..1], &old);
+    assert!(diff.new.is_empty());
+    assert_eq!(diff.modified.len(), 1);
+    assert_eq!(diff.modified[0].after.len(), 2);
+}
+
+#[test]
+fn test_diff_serializes_groups() {
+    let diff = diff_records(&[a_record("old.example.com", 1)], &[a_record("new.example.com", 2)]);
+    let json: serde_json::Value = serde_json::to_value(&diff).unwrap();
+
+    assert_eq!(json["new"][0]["name"], "new.example.com");
+    assert_eq!(json["gone"][0]["name"], "old.example.com");
+    assert_eq!(json["modified"].as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn test_diff_renders_in_each_stdout_format() {
+    let old = vec![a_record("old.example.com", 1), a_record("mail.example.com", 2), a_record("mail.example.com", 3)];
+    let new = vec![a_record("new.example.com", 4), a_record("mail.example.com", 5)];
+    let diff = diff_records(&old, &new);
+
+    let csv = StdoutFormat::Csv.render_diff(&diff, true).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines, vec![
+        "change,type,name,before,after",
+        "new,A,new.example.com,,192.0.2.4",
+        "gone,A,old.example.com,192.0.2.1,",
+        "modified,A,mail.example.com,192.0.2.2 | 192.0.2.3,192.0.2.5",
+    ]);
+
+    let text = StdoutFormat::Text.render_diff(&diff, true).unwrap();
+    assert!(text.starts_with("Changes since last scan: 1 new, 1 gone, 1 modified"));
+
+    let json: serde_json::Value = serde_json::from_str(&StdoutFormat::Json.render_diff(&diff, false).unwrap()).unwrap();
+    assert_eq!(json["modified"][0]["name"], "mail.example.com");
+}
+
+#[tokio::test]
+async fn test_diff_mode_compares_two_result_files() {
+    use clap::Parser;
+    use dnsrecon_rs::cli::Args;
+    use dnsrecon_rs::config::ReconConfig;
+
+    let dir = std::env::temp_dir();
+    let old_file = dir.join(format!("dnsrecon-rs-{}-diff-old.json", std::process::id()));
+    let new_file = dir.join(format!("dnsrecon-rs-{}-diff-new.json", std::process::id()));
+    let (old_file, new_file) = (old_file.to_str().unwrap(), new_file.to_str().unwrap());
+    dnsrecon_rs::output::json::write_json(&[a_record("www.example.com", 1)], old_file, true).unwrap();
+    dnsrecon_rs::output::json::write_json(&[a_record("www.example.com", 2)], new_file, true).unwrap();
+
+    // No domain is needed to compare files
+    let args = Args::parse_from(["dnsrecon-rs", "--diff", old_file, new_file, "--format", "csv"]);
+    let config = ReconConfig::from(&args);
+    assert_eq!(config.diff, Some((old_file.to_string(), new_file.to_string())));
+    dnsrecon_rs::run_with_config(config).await.unwrap();
+
+    let missing = ReconConfig::builder().diff((old_file.to_string(), "/nonexistent/new.json".to_string())).build();
+    assert!(dnsrecon_rs::run_with_config(missing).await.is_err());
+
+    assert!(Args::try_parse_from(["dnsrecon-rs", "--diff", old_file]).is_err());
+
+    let _ = std::fs::remove_file(old_file);
+    let _ = std::fs::remove_file(new_file);
+}
@@ -4,17 +4,27 @@
 // but since they would require network access and actual DNS queries,
 // we'll focus on testing the structure and API instead.
 
+mod common;
+
 use dnsrecon_rs::dns::resolver::DnsHelper;
 use dnsrecon_rs::dns::record::{DnsRecord, RecordType, RecordData};
 use std::net::{Ipv4Addr, Ipv6Addr};
 
 #[test]
+#[allow(deprecated)]
 fn test_dns_helper_creation() {
-    // Test creating a DNS helper with default configuration
+    // Callers still passing a domain keep building a default DNS helper
     let result = DnsHelper::new("example.com".to_string());
     assert!(result.is_ok());
 }
 
+#[test]
+fn test_dns_helper_default_resolver() {
+    // The default resolver needs no domain and uses the system defaults
+    let helper = DnsHelper::default_resolver().unwrap();
+    assert!(!helper.nameserver_ips().is_empty());
+}
+
 #[test]
 fn test_dns_helper_with_nameservers() {
     // Test creating a DNS helper with custom nameservers
@@ -23,7 +33,7 @@ fn test_dns_helper_with_nameservers() {
         "8.8.4.4".parse().unwrap(),
     ];
     
-    let result = DnsHelper::with_nameservers("example.com".to_string(), nameservers);
+    let result = DnsHelper::with_nameservers(nameservers);
     assert!(result.is_ok());
 }
 
@@ -35,7 +45,6 @@ fn test_dns_helper_with_ports() {
     ];
     
     let result = DnsHelper::with_nameservers_and_ports(
-        "example.com".to_string(),
         nameservers,
         53, // TCP port
         53, // UDP port
@@ -44,6 +53,546 @@ fn test_dns_helper_with_ports() {
     assert!(result.is_ok());
 }
 
+#[test]
+fn test_caa_answers_become_caa_records() {
+    use dnsrecon_rs::dns::resolver::caa_record;
+    use std::str::FromStr;
+    use trust_dns_resolver::proto::rr::rdata::caa::{KeyValue, CAA};
+    use trust_dns_resolver::proto::rr::Name;
+    
+    let caa = CAA::new_issue(
+        true,
+        Some(Name::from_str("letsencrypt.org").unwrap()),
+        vec![KeyValue::new("validationmethods", "dns-01")],
+    );
+    let record = caa_record("example.com", &caa);
+    assert_eq!(record.record_type, RecordType::Caa);
+    match record.data {
+        RecordData::Caa { flags, ref tag, ref value } => {
+            assert_eq!(flags, 128);
+            assert_eq!(tag, "issue");
+            assert_eq!(value, "letsencrypt.org; validationmethods=dns-01");
+        },
+        ref other => panic!("Expected CAA data, got {:?}", other),
+    }
+    
+    // An empty issuer forbids wildcard issuance altogether
+    let record = caa_record("example.com", &CAA::new_issuewild(false, None, Vec::new()));
+    assert_eq!(record.value_string(), "0 issuewild \";\"");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_dns_helper_reuses_resolver_across_lookups() {
+    // localhost is answered from the hosts file, so no network is needed
+    let helper = std::sync::Arc::new(DnsHelper::default_resolver().unwrap());
+    assert!(std::sync::Arc::ptr_eq(&helper.resolver(), &helper.resolver()));
+    assert!(std::sync::Arc::ptr_eq(&helper.resolver(), &helper.as_ref().clone().resolver()));
+    let first = helper.get_a("localhost").await.map(|records| records.len()).unwrap_or(0);
+    for _ in 0..20 {
+        let again = helper.get_a("localhost").await.map(|records| records.len()).unwrap_or(0);
+        assert_eq!(again, first);
+    }
+    
+    // The shared resolver can be dropped from inside an async task
+    let clone = helper.clone();
+    drop(helper);
+    tokio::spawn(async move {
+        let _ = clone.get_a("localhost").await;
+        drop(clone);
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_resolved_records_carry_the_rrset_ttl() {
+    use trust_dns_resolver::proto::rr::rdata::A;
+    use trust_dns_resolver::proto::rr::{RData, Record};
+    
+    // Answer every query with two A records served with different TTLs
+    let server = common::spawn_dns_server(|query, response| {
+        for (ttl, last_octet) in [(300, 1), (120, 2)] {
+            let address = A(Ipv4Addr::new(192, 0, 2, last_octet));
+            response.add_answer(Record::from_rdata(query.name().clone(), ttl, RData::A(address)));
+        }
+    });
+    
+    let helper = DnsHelper::with_nameserver_addrs(vec![server]).unwrap();
+    let records = helper.get_a("www.example.com").await.unwrap();
+    assert_eq!(records.len(), 2);
+    assert!(records.iter().all(|record| record.ttl == Some(120)));
+}
+
+#[tokio::test]
+async fn test_get_all_records_merges_every_answered_type() {
+    use trust_dns_resolver::proto::op::ResponseCode;
+    use trust_dns_resolver::proto::rr::rdata::{A, MX, TXT};
+    use trust_dns_resolver::proto::rr::{Name, RData, Record, RecordType as WireType};
+    
+    // Answer A, MX and TXT queries; every other type gets an empty answer,
+    // except NS, which fails
+    let server = common::spawn_dns_server(|query, response| {
+        let data = match query.query_type() {
+            WireType::A => Some(RData::A(A(Ipv4Addr::new(192, 0, 2, 1)))),
+            WireType::MX => Some(RData::MX(MX::new(10, Name::from_ascii("mail.example.com.").unwrap()))),
+            WireType::TXT => Some(RData::TXT(TXT::new(vec!["v=spf1 -all".to_string()]))),
+            WireType::NS => {
+                response.set_response_code(ResponseCode::Refused);
+                None
+            }
+            _ => None,
+        };
+        if let Some(data) = data {
+            response.add_answer(Record::from_rdata(query.name().clone(), 300, data));
+        }
+    });
+    
+    let helper = DnsHelper::with_nameserver_addrs(vec![server]).unwrap();
+    let records = helper.get_all_records("example.com").await.unwrap();
+    let mut types: Vec<RecordType> = records.iter().map(|record| record.record_type.clone()).collect();
+    types.dedup();
+    
+    // TXT carrying SPF is also reported as SPF
+    assert_eq!(types, vec![RecordType::A, RecordType::Mx, RecordType::Txt, RecordType::Spf]);
+    assert!(records.iter().all(|record| record.name == "example.com"));
+    
+    // Skipping IPv4 leaves the A query out
+    let records = helper.with_address_families(false, true).get_all_records("example.com").await.unwrap();
+    assert!(records.iter().all(|record| record.record_type != RecordType::A));
+}
+
+#[tokio::test]
+async fn test_cname_chains_stop_at_loops_hop_limit_and_dangling_targets() {
+    use dnsrecon_rs::dns::resolver::{follow_cname_chain, MAX_CNAME_HOPS};
+    use dnsrecon_rs::dns::DnsError;
+    use std::collections::HashMap;
+    use trust_dns_resolver::error::{ResolveError, ResolveErrorKind};
+    use trust_dns_resolver::proto::op::{Query, ResponseCode};
+    use trust_dns_resolver::proto::rr::{Name, RecordType as WireType};
+    
+    // Names map to a CNAME target; "end" names have an address and
+    // anything else does not exist
+    async fn follow(host: &str, aliases: &HashMap<String, String>) -> Result<Vec<DnsRecord>, DnsError> {
+        follow_cname_chain(host, |name| {
+            let answer = match aliases.get(&name) {
+                Some(target) => Ok(vec![DnsRecord::new_cname(name.clone(), target.clone())]),
+                None if name.starts_with("end.") => Ok(vec![DnsRecord::new_a(name.clone(), Ipv4Addr::new(192, 0, 2, 1))]),
+                None => Err(DnsError::Resolution(ResolveError::from(ResolveErrorKind::NoRecordsFound {
+                    query: Box::new(Query::query(Name::from_ascii(&name).unwrap(), WireType::CNAME)),
+                    soa: None,
+                    negative_ttl: None,
+                    response_code: ResponseCode::NXDomain,
+                    trusted: true,
+                }))),
+            };
+            std::future::ready(answer)
+        })
+        .await
+    }
+    let aliases = |links: &[(&str, &str)]| -> HashMap<String, String> {
+        links.iter().map(|(name, target)| (name.to_string(), target.to_string())).collect()
+    };
+    
+    // A chain ending in an address
+    let chain = follow("www.example.com", &aliases(&[("www.example.com", "cdn.example.net"), ("cdn.example.net", "end.example.net")]))
+        .await
+        .unwrap();
+    let types: Vec<RecordType> = chain.iter().map(|record| record.record_type.clone()).collect();
+    assert_eq!(types, vec![RecordType::Cname, RecordType::Cname, RecordType::A]);
+    assert!(chain.iter().all(|record| !record.dangling));
+    
+    // A loop is followed once around
+    let chain = follow("a.example.com", &aliases(&[("a.example.com", "b.example.com"), ("b.example.com", "A.example.com.")]))
+        .await
+        .unwrap();
+    assert_eq!(chain.len(), 2);
+    
+    // A long chain is cut at the hop limit
+    let links: Vec<(String, String)> = (0..20).map(|i| (format!("h{}.example.com", i), format!("h{}.example.com", i + 1))).collect();
+    let links: Vec<(&str, &str)> = links.iter().map(|(name, target)| (name.as_str(), target.as_str())).collect();
+    let chain = follow("h0.example.com", &aliases(&links)).await.unwrap();
+    assert_eq!(chain.len(), MAX_CNAME_HOPS);
+    
+    // The CNAME pointing at a name that does not exist is dangling
+    let chain = follow("shop.example.com", &aliases(&[("shop.example.com", "gone.example.net")])).await.unwrap();
+    assert_eq!(chain.len(), 1);
+    assert!(chain[0].dangling);
+    
+    // A host that does not exist is an error
+    assert!(follow("missing.example.com", &HashMap::new()).await.unwrap_err().is_nxdomain());
+}
+
+#[test]
+fn test_dns_helper_custom_ports_reach_resolver_config() {
+    use trust_dns_resolver::config::Protocol;
+    
+    let nameservers = vec!["192.0.2.53".parse().unwrap()];
+    let helper = DnsHelper::with_nameservers_and_ports(nameservers, 5300, 5353).unwrap();
+    
+    let ports: Vec<(Protocol, u16)> = helper
+        .config()
+        .name_servers()
+        .iter()
+        .map(|ns| (ns.protocol, ns.socket_addr.port()))
+        .collect();
+    assert_eq!(ports, vec![(Protocol::Udp, 5353), (Protocol::Tcp, 5300)]);
+}
+
+#[test]
+fn test_dns_helper_with_bind_addr() {
+    // Test that the bind address is carried into every matching nameserver config
+    let nameservers = vec![
+        "8.8.8.8".parse().unwrap(),
+        "8.8.4.4".parse().unwrap(),
+    ];
+    let bind_addr: std::net::SocketAddr = "192.0.2.10:5300".parse().unwrap();
+    
+    let helper = DnsHelper::with_nameservers(nameservers)
+        .unwrap()
+        .with_bind_addr(bind_addr);
+    
+    let name_servers = helper.config().name_servers();
+    assert_eq!(name_servers.len(), 2);
+    for ns in name_servers {
+        assert_eq!(ns.bind_addr, Some(bind_addr));
+    }
+}
+
+#[tokio::test]
+async fn test_only_rotation_probes_skip_the_answer_cache() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use trust_dns_resolver::proto::rr::rdata::A;
+    use trust_dns_resolver::proto::rr::{RData, Record};
+    
+    // Count the queries that reach the nameserver
+    let queries = Arc::new(AtomicUsize::new(0));
+    let seen = queries.clone();
+    let server = common::spawn_dns_server(move |query, response| {
+        seen.fetch_add(1, Ordering::SeqCst);
+        let address = RData::A(A(Ipv4Addr::new(192, 0, 2, 1)));
+        response.add_answer(Record::from_rdata(query.name().clone(), 300, address));
+    });
+    let helper = DnsHelper::with_nameserver_addrs(vec![server]).unwrap().with_address_families(true, false);
+    
+    // Repeated lookups are answered from the cache
+    for _ in 0..3 {
+        assert_eq!(helper.get_ip("www.example.com").await.unwrap().len(), 1);
+    }
+    assert_eq!(queries.load(Ordering::SeqCst), 1);
+    
+    // Every rotation round goes to the nameserver
+    assert_eq!(helper.get_ip_rotation("www.example.com", 3).await.unwrap().len(), 1);
+    assert_eq!(queries.load(Ordering::SeqCst), 4);
+    
+    // Later lookups still use the cache
+    helper.get_ip("www.example.com").await.unwrap();
+    assert_eq!(queries.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn test_collect_rotation_unions_successive_answers() {
+    use dnsrecon_rs::dns::resolver::collect_rotation;
+
+    // A round-robin that hands out two of three addresses per query
+    let pool = [Ipv4Addr::new(192, 0, 2, 1), Ipv4Addr::new(192, 0, 2, 2), Ipv4Addr::new(192, 0, 2, 3)];
+    let mut round = 0;
+    let records = collect_rotation(3, || {
+        let answer = vec![
+            DnsRecord::new_a("lb.example.com".to_string(), pool[round % 3]),
+            DnsRecord::new_a("lb.example.com".to_string(), pool[(round + 1) % 3]),
+        ];
+        round += 1;
+        std::future::ready(Ok(answer))
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(round, 3);
+    assert_eq!(records.len(), 3);
+
+    // Zero rounds still queries once
+    let mut calls = 0;
+    collect_rotation(0, || {
+        calls += 1;
+        std::future::ready(Ok(Vec::new()))
+    })
+    .await
+    .unwrap();
+    assert_eq!(calls, 1);
+}
+
+#[test]
+fn test_address_families_limit_address_lookups() {
+    let helper = DnsHelper::default_resolver().unwrap();
+    assert_eq!(helper.address_types(), vec![RecordType::A, RecordType::Aaaa]);
+
+    // With IPv6 disabled get_ip only issues the A query
+    let helper = DnsHelper::default_resolver().unwrap().with_address_families(true, false);
+    assert_eq!(helper.address_types(), vec![RecordType::A]);
+
+    let helper = DnsHelper::default_resolver().unwrap().with_address_families(false, true);
+    assert_eq!(helper.address_types(), vec![RecordType::Aaaa]);
+}
+
+#[test]
+fn test_query_flags_are_set_on_queries() {
+    use dnsrecon_rs::dns::raw::QueryFlags;
+
+    let dns_helper = DnsHelper::default_resolver().unwrap();
+    assert!(dns_helper.query_flags().is_empty());
+    let query = dns_helper.build_query("example.com", RecordType::A).unwrap();
+    assert!(!query.checking_disabled());
+    assert!(query.extensions().is_none());
+
+    let dns_helper = dns_helper.with_query_flags(QueryFlags { dnssec_ok: true, checking_disabled: true });
+    assert!(dns_helper.options().edns0);
+    let query = dns_helper.build_query("example.com", RecordType::Soa).unwrap();
+    assert!(query.checking_disabled());
+    let edns = query.extensions().as_ref().expect("DO bit needs an EDNS OPT record");
+    assert!(edns.dnssec_ok());
+    assert!(edns.max_payload() >= 4096);
+
+    // The CD bit alone leaves EDNS off
+    let dns_helper = DnsHelper::default_resolver().unwrap().with_query_flags(QueryFlags { dnssec_ok: false, checking_disabled: true });
+    assert!(!dns_helper.options().edns0);
+    let query = dns_helper.build_query("192.0.2.1", RecordType::Ptr).unwrap();
+    assert!(query.checking_disabled());
+    assert!(query.extensions().is_none());
+    assert_eq!(query.queries()[0].name().to_string(), "1.2.0.192.in-addr.arpa");
+}
+
+#[tokio::test]
+async fn test_first_response_returns_after_faster_answer() {
+    use dnsrecon_rs::dns::resolver::first_response;
+    use std::time::{Duration, Instant};
+
+    let started = Instant::now();
+    let slow = async {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        Ok(vec![DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1))])
+    };
+    let fast = async {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        Ok(vec![DnsRecord::new_aaaa("www.example.com".to_string(), "2001:db8::1".parse::<Ipv6Addr>().unwrap())])
+    };
+
+    let records = first_response(slow, fast).await.unwrap();
+    assert!(started.elapsed() < Duration::from_secs(1));
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].record_type, RecordType::Aaaa);
+
+    // An empty answer doesn't win the race
+    let empty = async { Ok(Vec::new()) };
+    let slower = async {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        Ok(vec![DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1))])
+    };
+    let records = first_response(empty, slower).await.unwrap();
+    assert_eq!(records[0].record_type, RecordType::A);
+}
+
+#[test]
+fn test_first_response_needs_both_address_families() {
+    let dns_helper = DnsHelper::default_resolver().unwrap().with_first_response(true);
+    assert!(dns_helper.races_addresses());
+    assert!(!dns_helper.with_address_families(true, false).races_addresses());
+}
+
+#[test]
+fn test_client_subnet_is_attached_to_queries() {
+    use trust_dns_resolver::proto::rr::rdata::opt::{ClientSubnet, EdnsCode, EdnsOption};
+
+    let query = DnsHelper::default_resolver().unwrap().build_query("www.example.com", RecordType::A).unwrap();
+    assert!(query.extensions().is_none());
+
+    // Host bits are dropped from the subnet sent
+    let dns_helper = DnsHelper::default_resolver().unwrap().with_client_subnet(Some("203.0.113.77/24".parse().unwrap()));
+    assert!(dns_helper.options().edns0);
+    let query = dns_helper.build_query("www.example.com", RecordType::A).unwrap();
+    let edns = query.extensions().as_ref().unwrap();
+    assert_eq!(
+        edns.option(EdnsCode::Subnet),
+        Some(&EdnsOption::Subnet(ClientSubnet::new("203.0.113.0".parse().unwrap(), 24, 0)))
+    );
+}
+
+#[test]
+fn test_nameservers_file_merges_with_inline_nameservers() {
+    use dnsrecon_rs::config::ReconConfig;
+    use std::net::SocketAddr;
+    
+    let path = std::env::temp_dir().join(format!("dnsrecon-rs-{}-nameservers.txt", std::process::id()));
+    std::fs::write(
+        &path,
+        "# public resolvers\n8.8.8.8\n\n1.1.1.1:5353  # alternate port\n[2001:4860:4860::8888]:53\n9.9.9.9\n",
+    ).unwrap();
+    
+    let config = ReconConfig::builder()
+        .domain("example.com")
+        .nameservers("9.9.9.9,8.8.4.4")
+        .nameservers_file(path.to_string_lossy())
+        .build();
+    assert!(config.validate().is_ok());
+    let addrs = config.nameserver_addrs().unwrap();
+    
+    let helper = DnsHelper::with_nameserver_addrs(addrs.iter().map(|ns| ns.addr).collect()).unwrap();
+    let registered: Vec<SocketAddr> = helper.config().name_servers().iter().map(|ns| ns.socket_addr).collect();
+    let expected: Vec<SocketAddr> = ["9.9.9.9:53", "8.8.4.4:53", "8.8.8.8:53", "1.1.1.1:5353", "[2001:4860:4860::8888]:53"]
+        .iter()
+        .map(|addr| addr.parse().unwrap())
+        .collect();
+    assert_eq!(registered, expected);
+    let port_given: Vec<bool> = addrs.iter().map(|ns| ns.port_given).collect();
+    assert_eq!(port_given, vec![false, false, false, true, true]);
+    
+    // Every entry is validated
+    std::fs::write(&path, "8.8.8.8\nns1.example.com\n").unwrap();
+    assert!(config.validate().is_err());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_tcp_and_udp_ports_apply_to_nameservers_without_a_port() {
+    use dnsrecon_rs::utils::validation::parse_nameserver_entry;
+    use std::net::SocketAddr;
+    use trust_dns_resolver::config::Protocol;
+    
+    let entries: Vec<_> = ["192.0.2.1", "192.0.2.2:5353", "192.0.2.3:53"]
+        .iter()
+        .map(|entry| parse_nameserver_entry(entry).unwrap())
+        .collect();
+    let helper = DnsHelper::with_nameserver_entries(&entries, 5300, 5301).unwrap();
+    
+    let registered: Vec<(SocketAddr, Protocol)> =
+        helper.config().name_servers().iter().map(|ns| (ns.socket_addr, ns.protocol)).collect();
+    let expected: Vec<(SocketAddr, Protocol)> = vec![
+        ("192.0.2.1:5301".parse().unwrap(), Protocol::Udp),
+        ("192.0.2.1:5300".parse().unwrap(), Protocol::Tcp),
+        ("192.0.2.2:5353".parse().unwrap(), Protocol::Udp),
+        // An explicit :53 is kept even though it is the default port
+        ("192.0.2.3:53".parse().unwrap(), Protocol::Udp),
+    ];
+    assert_eq!(registered, expected);
+}
+
+#[tokio::test]
+async fn test_hand_built_queries_skip_tcp_and_tls_nameservers() {
+    use dnsrecon_rs::dns::raw::QueryFlags;
+    use std::net::SocketAddr;
+    
+    // Queries carrying flags are sent as UDP datagrams, so TCP ports are left out
+    let helper = DnsHelper::with_nameservers_and_ports(vec!["192.0.2.1".parse().unwrap()], 5300, 5301).unwrap();
+    let expected: Vec<SocketAddr> = vec!["192.0.2.1:5301".parse().unwrap()];
+    assert_eq!(helper.udp_nameservers(), expected);
+    
+    // A resolver without UDP nameservers fails at once instead of timing out
+    let helper = DnsHelper::with_tls_nameservers(vec!["127.0.0.1:853".parse().unwrap()], "dns.example")
+        .unwrap()
+        .with_query_flags(QueryFlags { dnssec_ok: false, checking_disabled: true });
+    assert!(helper.udp_nameservers().is_empty());
+    let error = helper.lookup_type("example.com", RecordType::A).await.unwrap_err();
+    assert!(error.to_string().contains("No UDP nameservers configured"));
+}
+
+#[test]
+fn test_tls_nameservers_carry_the_tls_name() {
+    use dnsrecon_rs::config::ReconConfig;
+    use dnsrecon_rs::dns::resolver::DOT_PORT;
+    use std::net::{IpAddr, SocketAddr};
+    use trust_dns_resolver::config::Protocol;
+    
+    let addrs = vec![
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)), DOT_PORT),
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 0, 0, 1)), DOT_PORT),
+    ];
+    let helper = DnsHelper::with_tls_nameservers(addrs.clone(), "cloudflare-dns.com").unwrap();
+    
+    let name_servers = helper.config().name_servers();
+    assert_eq!(name_servers.len(), 2);
+    for (ns, addr) in name_servers.iter().zip(&addrs) {
+        assert_eq!(ns.socket_addr, *addr);
+        assert_eq!(ns.protocol, Protocol::Tls);
+        assert_eq!(ns.tls_dns_name.as_deref(), Some("cloudflare-dns.com"));
+    }
+    
+    // DNS-over-TLS needs nameservers and can't carry the DO bit or a client subnet
+    let config = ReconConfig::builder().domain("example.com").dot_host("cloudflare-dns.com");
+    assert!(config.clone().build().validate().is_err());
+    assert!(config.clone().nameservers("1.1.1.1").build().validate().is_ok());
+    assert!(config.clone().nameservers("1.1.1.1").do_bit(true).build().validate().is_err());
+    assert!(config.nameservers("1.1.1.1").ecs(vec!["192.0.2.0/24".parse().unwrap()]).build().validate().is_err());
+}
+
+#[test]
+fn test_ns_strategy_sets_resolver_options() {
+    use dnsrecon_rs::dns::resolver::NsStrategy;
+    use std::net::IpAddr;
+    use trust_dns_resolver::config::ServerOrderingStrategy;
+    
+    let nameservers: Vec<IpAddr> = vec!["192.0.2.1".parse().unwrap(), "192.0.2.2".parse().unwrap(), "192.0.2.3".parse().unwrap()];
+    let first_server = |helper: &DnsHelper| helper.next_config().name_servers()[0].socket_addr.ip().to_string();
+    
+    // Without a strategy the resolver keeps its defaults
+    let helper = DnsHelper::with_nameservers(nameservers.clone()).unwrap();
+    assert_eq!(helper.options().server_ordering_strategy, ServerOrderingStrategy::QueryStatistics);
+    assert!(!helper.options().shuffle_dns_servers);
+    assert!(!helper.rotates_nameservers());
+    
+    let helper = DnsHelper::with_nameservers(nameservers.clone()).unwrap().with_ns_strategy(NsStrategy::Random);
+    assert!(helper.options().shuffle_dns_servers);
+    
+    let helper = DnsHelper::with_nameservers(nameservers.clone()).unwrap().with_ns_strategy(NsStrategy::Sequential);
+    assert_eq!(helper.options().server_ordering_strategy, ServerOrderingStrategy::UserProvidedOrder);
+    assert_eq!(first_server(&helper), "192.0.2.1");
+    assert_eq!(first_server(&helper), "192.0.2.1");
+    
+    // Round-robin starts each lookup at the next server, shared between clones
+    let helper = DnsHelper::with_nameservers(nameservers).unwrap().with_ns_strategy(NsStrategy::RoundRobin);
+    assert_eq!(helper.options().server_ordering_strategy, ServerOrderingStrategy::UserProvidedOrder);
+    assert!(!helper.options().shuffle_dns_servers);
+    assert_eq!(first_server(&helper), "192.0.2.1");
+    assert_eq!(first_server(&helper.clone()), "192.0.2.2");
+    assert_eq!(first_server(&helper), "192.0.2.3");
+    assert_eq!(first_server(&helper), "192.0.2.1");
+    assert_eq!(helper.next_config().name_servers().len(), 3);
+    
+    assert_eq!("round-robin".parse::<NsStrategy>().unwrap(), NsStrategy::RoundRobin);
+    assert!("fastest".parse::<NsStrategy>().is_err());
+}
+
+#[tokio::test]
+async fn test_get_dmarc_parses_the_policy_at_the_dmarc_name() {
+    use trust_dns_resolver::proto::rr::rdata::TXT;
+    use trust_dns_resolver::proto::rr::{RData, Record};
+    
+    // Publish a DMARC record and an unrelated TXT string at _dmarc.example.com only
+    let server = common::spawn_dns_server(|query, response| {
+        if query.name().to_ascii().eq_ignore_ascii_case("_dmarc.example.com.") {
+            for txt in ["v=DMARC1; p=quarantine; rua=mailto:reports@example.com; pct=25", "verification=abc"] {
+                let data = RData::TXT(TXT::new(vec![txt.to_string()]));
+                response.add_answer(Record::from_rdata(query.name().clone(), 300, data));
+            }
+        }
+    });
+    
+    let helper = DnsHelper::with_nameserver_addrs(vec![server]).unwrap();
+    let records = helper.get_dmarc("example.com").await.unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].record_type, RecordType::Txt);
+    assert_eq!(records[0].name, "_dmarc.example.com");
+    assert_eq!(records[0].ttl, Some(300));
+    
+    let RecordData::Dmarc(ref dmarc) = records[0].data else {
+        panic!("expected DMARC data, got {:?}", records[0].data);
+    };
+    assert_eq!(dmarc.policy.as_deref(), Some("quarantine"));
+    assert_eq!(dmarc.rua.as_deref(), Some("mailto:reports@example.com"));
+    assert_eq!(dmarc.pct, Some(25));
+    assert_eq!(dmarc.subdomain_policy, None);
+    assert_eq!(records[0].value_string(), "v=DMARC1; p=quarantine; rua=mailto:reports@example.com; pct=25");
+}
+
 // Note: Actual DNS resolution tests that require network access
 // should be integration tests or mocked tests, not unit tests.
 // The original Python tests that make actual DNS requests
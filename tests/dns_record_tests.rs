@@ -1,6 +1,9 @@
 #[cfg(test)]
 mod tests {
+    use dnsrecon_rs::dns::dnssec;
     use dnsrecon_rs::dns::record::{DnsRecord, RecordType, RecordData};
+    use dnsrecon_rs::output;
+    use trust_dns_resolver::proto::rr::RecordType as TrustDnsRecordType;
     use std::net::{Ipv4Addr, Ipv6Addr};
 
     #[test]
@@ -33,4 +36,194 @@ mod tests {
             _ => panic!("Expected AAAA record data"),
         }
     }
+
+    #[test]
+    fn test_record_type_mnemonics_parse_back() {
+        let types = [
+            RecordType::A, RecordType::Aaaa, RecordType::Mx, RecordType::Ns, RecordType::Soa, RecordType::Spf,
+            RecordType::Txt, RecordType::Ptr, RecordType::Srv, RecordType::Caa, RecordType::Cname, RecordType::Svcb,
+            RecordType::Https, RecordType::Dnskey, RecordType::Ds, RecordType::Rrsig, RecordType::Nsec,
+            RecordType::Other("NAPTR".to_string()),
+        ];
+        for record_type in types {
+            assert_eq!(record_type.mnemonic().parse::<RecordType>(), Ok(record_type));
+        }
+        assert_eq!(RecordType::Aaaa.mnemonic(), "AAAA");
+        assert_eq!(RecordType::Other("naptr".to_string()).mnemonic(), "NAPTR");
+    }
+
+    #[test]
+    fn test_unsupported_zone_transfer_type_is_kept_as_other() {
+        use dnsrecon_rs::dns::zone_transfer::convert_record;
+        use trust_dns_client::rr::rdata::HINFO;
+        use trust_dns_client::rr::{Name, RData, Record};
+
+        let record = Record::from_rdata(
+            Name::from_ascii("host.example.com.").unwrap(),
+            300,
+            RData::HINFO(HINFO::new("x86".to_string(), "linux".to_string())),
+        );
+        let converted = convert_record(&record, "example.com").unwrap();
+
+        assert_eq!(converted.record_type, RecordType::Other("HINFO".to_string()));
+        assert_eq!(converted.record_type.mnemonic(), "HINFO");
+        match converted.data {
+            RecordData::Other { rtype, value } => {
+                assert_eq!(rtype, "HINFO");
+                assert!(value.contains("x86"));
+            }
+            other => panic!("Expected Other record data, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_value_string_for_each_variant() {
+        use std::collections::BTreeMap;
+        let name = || "example.com".to_string();
+
+        assert_eq!(DnsRecord::new_a(name(), Ipv4Addr::new(192, 0, 2, 1)).value_string(), "192.0.2.1");
+        assert_eq!(DnsRecord::new_aaaa(name(), "2001:db8::1".parse::<Ipv6Addr>().unwrap()).value_string(), "2001:db8::1");
+        assert_eq!(DnsRecord::new_mx(name(), 10, "mail.example.com".to_string()).value_string(), "10 mail.example.com");
+        assert_eq!(DnsRecord::new_ns(name(), "ns1.example.com".to_string()).value_string(), "ns1.example.com");
+        assert_eq!(
+            DnsRecord::new_soa(
+                name(),
+                "ns1.example.com".to_string(),
+                "hostmaster.example.com".to_string(),
+                2024010101,
+                7200,
+                3600,
+                1209600,
+                300,
+            ).value_string(),
+            "ns1.example.com hostmaster.example.com 2024010101 7200 3600 1209600 300"
+        );
+        assert_eq!(DnsRecord::new_txt(name(), "hello world".to_string()).value_string(), "hello world");
+        assert_eq!(DnsRecord::new_spf(name(), "v=spf1 -all".to_string()).value_string(), "v=spf1 -all");
+        assert_eq!(DnsRecord::new_ptr("192.0.2.1".to_string(), "host.example.com".to_string()).value_string(), "host.example.com");
+        assert_eq!(
+            DnsRecord::new_srv("_sip._tcp.example.com".to_string(), 10, 60, 5060, "sip.example.com".to_string()).value_string(),
+            "10 60 5060 sip.example.com"
+        );
+        assert_eq!(
+            DnsRecord::new_caa(name(), 0, "issue".to_string(), "letsencrypt.org".to_string()).value_string(),
+            "0 issue \"letsencrypt.org\""
+        );
+        assert_eq!(DnsRecord::new_cname(name(), "target.example.net".to_string()).value_string(), "target.example.net");
+
+        let mut params = BTreeMap::new();
+        params.insert("alpn".to_string(), "h2,h3".to_string());
+        params.insert("port".to_string(), "8443".to_string());
+        assert_eq!(
+            DnsRecord::new_svcb(name(), 1, "svc.example.com".to_string(), params.clone()).value_string(),
+            "1 svc.example.com alpn=h2,h3 port=8443"
+        );
+        assert_eq!(DnsRecord::new_https(name(), 1, ".".to_string(), params).value_string(), "1 . alpn=h2,h3 port=8443");
+        assert_eq!(
+            DnsRecord::new_other(name(), "hinfo".to_string(), "\"x86\" \"linux\"".to_string()).value_string(),
+            "\"x86\" \"linux\""
+        );
+    }
+
+    #[test]
+    fn test_deduplicate_keeps_distinct_types_on_one_name() {
+        use dnsrecon_rs::deduplicate_records;
+
+        let records = vec![
+            DnsRecord::new_a("example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+            DnsRecord::new_aaaa("example.com".to_string(), Ipv6Addr::LOCALHOST),
+            DnsRecord::new_mx("example.com".to_string(), 10, "mail.example.com".to_string()),
+            DnsRecord::new_ns("example.com".to_string(), "ns1.example.com".to_string()),
+            DnsRecord::new_ns("example.com".to_string(), "ns2.example.com".to_string()),
+            DnsRecord::new_txt("example.com".to_string(), "v=spf1 -all".to_string()),
+            DnsRecord::new_soa(
+                "example.com".to_string(),
+                "ns1.example.com".to_string(),
+                "hostmaster.example.com".to_string(),
+                1, 3600, 600, 604800, 300,
+            ),
+            // Exact repeats, differing only in name case, are dropped
+            DnsRecord::new_a("EXAMPLE.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+            DnsRecord::new_ns("example.com".to_string(), "ns1.example.com".to_string()),
+        ];
+
+        let deduplicated = deduplicate_records(records);
+        let types: Vec<RecordType> = deduplicated.iter().map(|record| record.record_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                RecordType::A,
+                RecordType::Aaaa,
+                RecordType::Mx,
+                RecordType::Ns,
+                RecordType::Ns,
+                RecordType::Txt,
+                RecordType::Soa,
+            ]
+        );
+        assert_eq!(deduplicated[0].name, "example.com");
+    }
+
+    #[test]
+    fn test_dnssec_records_round_trip_through_json() {
+        let records = vec![
+            DnsRecord::new_dnskey("example.com".to_string(), 257, 3, 13, "mdsswUyr3DPW132mOi8V9xESWE8jTo0d".to_string()),
+            DnsRecord::new_ds("example.com".to_string(), 370, 13, 2, "BE74359954660069D5C63D200C39F5603827D7DD".to_string()),
+            DnsRecord::new_rrsig(
+                "www.example.com".to_string(),
+                "A".to_string(),
+                13,
+                3,
+                300,
+                1_700_600_000,
+                1_700_000_000,
+                370,
+                "example.com".to_string(),
+                "oJB1W6WNGv+ldvQ3WDG0MQkg5IEhjRip".to_string(),
+            ),
+            DnsRecord::new_nsec(
+                "example.com".to_string(),
+                "www.example.com".to_string(),
+                vec!["A".to_string(), "NS".to_string(), "SOA".to_string(), "RRSIG".to_string(), "NSEC".to_string()],
+            ),
+        ];
+        let types: Vec<RecordType> = records.iter().map(|record| record.record_type.clone()).collect();
+        assert_eq!(types, vec![RecordType::Dnskey, RecordType::Ds, RecordType::Rrsig, RecordType::Nsec]);
+
+        let json = output::json::to_json_string(&records, false).unwrap();
+        let parsed: Vec<DnsRecord> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.len(), records.len());
+        for (parsed, record) in parsed.iter().zip(&records) {
+            assert_eq!(parsed.record_type, record.record_type);
+            assert_eq!(parsed.name, record.name);
+            assert_eq!(parsed.data, record.data);
+        }
+        assert_eq!(records[1].value_string(), "370 13 2 BE74359954660069D5C63D200C39F5603827D7DD");
+        assert_eq!(records[3].value_string(), "www.example.com A NS SOA RRSIG NSEC");
+    }
+
+    #[test]
+    fn test_dnssec_wire_data_is_decoded() {
+        // DS: key tag 370, algorithm 13, digest type 2, digest
+        let ds = dnssec::record_from_wire("example.com", TrustDnsRecordType::DS, &[0x01, 0x72, 13, 2, 0xbe, 0x74])
+            .unwrap();
+        assert_eq!(ds.data, RecordData::Ds { key_tag: 370, algorithm: 13, digest_type: 2, digest: "BE74".to_string() });
+
+        // NSEC: next name www.example.com, types A (1), NS (2) and RRSIG (46)
+        let mut wire = b"\x03www\x07example\x03com\x00".to_vec();
+        wire.extend_from_slice(&[0, 6, 0x60, 0, 0, 0, 0, 0x02]);
+        let nsec = dnssec::record_from_wire("example.com", TrustDnsRecordType::NSEC, &wire).unwrap();
+        assert_eq!(
+            nsec.data,
+            RecordData::Nsec {
+                next_domain: "www.example.com".to_string(),
+                types: vec!["A".to_string(), "NS".to_string(), "RRSIG".to_string()],
+            }
+        );
+
+        // Truncated data is rejected
+        assert!(dnssec::record_from_wire("example.com", TrustDnsRecordType::DS, &[0x01]).is_none());
+        assert_eq!(dnssec::parse_signature_time("20231121000000"), Some(1_700_524_800));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,92 @@
+//! Tests for resolution over the JSON DoH API
+
+use dnsrecon_rs::dns::doh_json::{parse_response, resolve};
+use dnsrecon_rs::dns::record::{RecordData, RecordType};
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn test_resolve_a_records_from_mock_endpoint() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/resolve"))
+        .and(query_param("name", "www.example.com"))
+        .and(query_param("type", "A"))
+        .and(header("accept", "application/dns-json"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            r#"{"Status":0,"TC":false,"RD":true,"RA":true,"AD":false,"CD":false,
+                "Question":[{"name":"www.example.com.","type":1}],
+                "Answer":[
+                    {"name":"www.example.com.","type":5,"TTL":300,"data":"edge.example.net."},
+                    {"name":"edge.example.net.","type":1,"TTL":60,"data":"192.0.2.10"},
+                    {"name":"edge.example.net.","type":1,"TTL":60,"data":"192.0.2.11"}
+                ]}"#,
+        ))
+        .mount(&server)
+        .await;
+
+    let client = reqwest::Client::new();
+    let endpoint = format!("{}/resolve", server.uri());
+    let records = resolve("www.example.com", RecordType::A, &endpoint, &client).await.unwrap();
+
+    // The CNAME step of the chain is not an A record, and the addresses are
+    // named after the queried name rather than the CNAME target
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].name, "www.example.com");
+    assert_eq!(records[0].ttl, Some(60));
+    assert!(matches!(records[0].data, RecordData::A(ip) if ip.to_string() == "192.0.2.10"));
+}
+
+#[tokio::test]
+async fn test_resolve_reports_http_errors() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+
+    let client = reqwest::Client::new();
+    let result = resolve("example.com", RecordType::A, &server.uri(), &client).await;
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_parse_txt_mx_and_nxdomain() {
+    let body = r#"{"Status":0,"Answer":[
+        {"name":"example.com.","type":16,"TTL":3600,"data":"\"v=spf1 include:_spf.example.com \" \"-all\""}
+    ]}"#;
+    let records = parse_response("example.com", body, &RecordType::Txt).unwrap();
+    assert_eq!(records.len(), 1);
+    assert!(matches!(records[0].data, RecordData::Txt(ref text) if text == "v=spf1 include:_spf.example.com -all"));
+
+    let body = r#"{"Status":0,"Answer":[{"name":"example.com.","type":15,"TTL":300,"data":"10 mail.example.com."}]}"#;
+    let records = parse_response("example.com", body, &RecordType::Mx).unwrap();
+    assert!(matches!(records[0].data, RecordData::Mx { preference: 10, ref exchange } if exchange == "mail.example.com"));
+
+    // NXDOMAIN yields no records, SERVFAIL is an error
+    assert!(parse_response("example.com", r#"{"Status":3}"#, &RecordType::A).unwrap().is_empty());
+    assert!(parse_response("example.com", r#"{"Status":2}"#, &RecordType::A).is_err());
+}
+
+#[test]
+fn test_parse_https_record_params() {
+    let body = r#"{"Status":0,"Answer":[
+        {"name":"example.com.","type":65,"TTL":300,"data":"1 . alpn=h3,h2 ipv4hint=192.0.2.1,192.0.2.2 no-default-alpn"}
+    ]}"#;
+    let records = parse_response("example.com", body, &RecordType::Https).unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].record_type, RecordType::Https);
+
+    match records[0].data {
+        RecordData::Https { priority, ref target, ref params } => {
+            assert_eq!(priority, 1);
+            assert_eq!(target, ".");
+            assert_eq!(params.get("alpn").map(String::as_str), Some("h3,h2"));
+            assert_eq!(params.get("ipv4hint").map(String::as_str), Some("192.0.2.1,192.0.2.2"));
+            assert_eq!(params.get("no-default-alpn").map(String::as_str), Some(""));
+        }
+        ref other => panic!("expected HTTPS data, got {:?}", other),
+    }
+}
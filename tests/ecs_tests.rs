@@ -0,0 +1,27 @@
+//! Unit tests for EDNS Client Subnet reporting
+
+use dnsrecon_rs::dns::record::DnsRecord;
+use dnsrecon_rs::enumerate::ecs::{address_sets, ecs_findings};
+use ipnetwork::IpNetwork;
+use std::net::Ipv4Addr;
+
+#[test]
+fn test_addresses_reported_per_client_subnet() {
+    let subnets: Vec<IpNetwork> = vec!["203.0.113.0/24".parse().unwrap(), "198.51.100.0/24".parse().unwrap()];
+
+    let sets = address_sets(&subnets, |subnet| {
+        if subnet == subnets[0] {
+            vec![
+                DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 2)),
+                DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+            ]
+        } else {
+            Vec::new()
+        }
+    });
+    let findings = ecs_findings("www.example.com", &sets);
+
+    assert_eq!(findings.len(), 2);
+    assert_eq!(findings[0].message, "Client subnet 203.0.113.0/24 gets 192.0.2.1, 192.0.2.2");
+    assert!(findings[1].message.contains("No addresses"));
+}
@@ -0,0 +1,159 @@
+//! Unit tests for SPF and DMARC policy parsing and findings
+
+use dnsrecon_rs::enumerate::dmarc::{dmarc_findings, parse_dmarc};
+use dnsrecon_rs::enumerate::findings::Severity;
+use dnsrecon_rs::dns::record::{DnsRecord, RecordType};
+use dnsrecon_rs::dns::DnsError;
+use dnsrecon_rs::enumerate::spf::{expand_spf_with, parse_spf, spf_findings, SpfExpansion, MAX_SPF_LOOKUPS};
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+
+#[test]
+fn test_parse_spf() {
+    let spf = parse_spf("v=spf1 include:_spf.example.com mx -all").unwrap();
+    assert_eq!(spf.terms, vec!["include:_spf.example.com", "mx", "-all"]);
+    assert_eq!(spf.all_qualifier, Some('-'));
+
+    assert!(parse_spf("google-site-verification=abc").is_none());
+}
+
+#[test]
+fn test_spf_plus_all_is_high() {
+    let findings = spf_findings("example.com", &["v=spf1 +all".to_string()]);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].severity, Severity::High);
+
+    // A bare "all" defaults to the + qualifier
+    let findings = spf_findings("example.com", &["v=spf1 a mx all".to_string()]);
+    assert_eq!(findings[0].severity, Severity::High);
+}
+
+#[test]
+fn test_spf_missing_and_strict() {
+    let findings = spf_findings("example.com", &["some other txt".to_string()]);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].severity, Severity::Medium);
+    assert_eq!(findings[0].category, "spf");
+
+    let findings = spf_findings("example.com", &["v=spf1 mx -all".to_string()]);
+    assert!(findings.is_empty());
+}
+
+#[test]
+fn test_parse_dmarc() {
+    let dmarc = parse_dmarc("v=DMARC1; p=reject; sp=quarantine; rua=mailto:dmarc@example.com; pct=50; adkim=s; aspf=r").unwrap();
+    assert_eq!(dmarc.policy.as_deref(), Some("reject"));
+    assert_eq!(dmarc.subdomain_policy.as_deref(), Some("quarantine"));
+    assert_eq!(dmarc.rua.as_deref(), Some("mailto:dmarc@example.com"));
+    assert_eq!(dmarc.pct, Some(50));
+    assert_eq!(dmarc.adkim.as_deref(), Some("s"));
+    assert_eq!(dmarc.aspf.as_deref(), Some("r"));
+
+    assert!(parse_dmarc("v=spf1 -all").is_none());
+}
+
+#[test]
+fn test_dmarc_keeps_tags_without_a_field() {
+    let txt = "v=DMARC1; p=reject; rua=mailto:dmarc@example.com; fo=1; ri=86400; rf=afrf";
+    let dmarc = parse_dmarc(txt).unwrap();
+    assert_eq!(dmarc.other_tags, vec![
+        ("fo".to_string(), "1".to_string()),
+        ("ri".to_string(), "86400".to_string()),
+        ("rf".to_string(), "afrf".to_string()),
+    ]);
+    assert_eq!(dmarc.to_txt(), txt);
+    assert_eq!(parse_dmarc(&dmarc.to_txt()), Some(dmarc));
+}
+
+#[test]
+fn test_dmarc_policy_classification() {
+    // p=none is monitoring only
+    let findings = dmarc_findings("example.com", &["v=DMARC1; p=none".to_string()]);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].severity, Severity::Low);
+
+    // Missing DMARC
+    let findings = dmarc_findings("example.com", &[]);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].severity, Severity::Medium);
+
+    // Enforcing policy produces no findings
+    let findings = dmarc_findings("example.com", &["v=DMARC1; p=reject".to_string()]);
+    assert!(findings.is_empty());
+}
+
+/// Expand the SPF record of `domain` against a fixed set of records
+async fn expand(domain: &str, records: &HashMap<(String, RecordType), Vec<DnsRecord>>) -> SpfExpansion {
+    expand_spf_with(domain, |name, record_type| {
+        let answer = match records.get(&(name.clone(), record_type)) {
+            Some(records) => Ok(records.clone()),
+            None => Err(DnsError::Other(format!("no records for {}", name))),
+        };
+        std::future::ready(answer)
+    })
+    .await
+    .unwrap()
+}
+
+fn txt(name: &str, text: &str) -> ((String, RecordType), Vec<DnsRecord>) {
+    ((name.to_string(), RecordType::Txt), vec![DnsRecord::new_txt(name.to_string(), text.to_string())])
+}
+
+#[tokio::test]
+async fn test_expand_spf_follows_a_two_level_include_chain() {
+    let records: HashMap<_, _> = [
+        txt("example.com", "v=spf1 ip4:192.0.2.1/24 include:_spf.example.net mx -all"),
+        txt("_spf.example.net", "v=spf1 ip6:2001:db8::/32 include:_spf.example.org -ip4:203.0.113.0/24 ~all"),
+        txt("_spf.example.org", "v=spf1 a:relay.example.org/28 ip4:198.51.100.7 -all"),
+        (
+            ("example.com".to_string(), RecordType::Mx),
+            vec![DnsRecord::new_mx("example.com".to_string(), 10, "mail.example.com.".to_string())],
+        ),
+        (
+            ("mail.example.com".to_string(), RecordType::A),
+            vec![DnsRecord::new_a("mail.example.com".to_string(), Ipv4Addr::new(198, 51, 100, 25))],
+        ),
+        (
+            ("relay.example.org".to_string(), RecordType::A),
+            vec![DnsRecord::new_a("relay.example.org".to_string(), Ipv4Addr::new(203, 0, 113, 70))],
+        ),
+    ]
+    .into_iter()
+    .collect();
+
+    let expansion = expand("example.com", &records).await;
+    let networks: Vec<String> = expansion.networks.iter().map(|network| network.to_string()).collect();
+    // -ip4 excludes rather than authorizes
+    assert_eq!(
+        networks,
+        vec!["192.0.2.0/24", "198.51.100.7/32", "198.51.100.25/32", "203.0.113.64/28", "2001:db8::/32"]
+    );
+    assert_eq!(expansion.lookups, 4);
+    assert!(!expansion.limit_exceeded);
+
+    let tree = &expansion.tree;
+    assert_eq!(tree.includes.len(), 1);
+    assert_eq!(tree.includes[0].domain, "_spf.example.net");
+    assert_eq!(tree.includes[0].includes[0].domain, "_spf.example.org");
+    assert!(tree.includes[0].includes[0].includes.is_empty());
+}
+
+#[tokio::test]
+async fn test_expand_spf_stops_at_the_lookup_limit() {
+    // Each domain includes the next, one more than the limit allows
+    let records: HashMap<_, _> = (0..=MAX_SPF_LOOKUPS + 1)
+        .map(|i| txt(&format!("spf{}.example.com", i), &format!("v=spf1 ip4:192.0.2.{} include:spf{}.example.com -all", i, i + 1)))
+        .collect();
+
+    let expansion = expand("spf0.example.com", &records).await;
+    assert!(expansion.limit_exceeded);
+    assert_eq!(expansion.lookups, MAX_SPF_LOOKUPS + 1);
+    // The records reached within the limit still count
+    assert_eq!(expansion.networks.len(), MAX_SPF_LOOKUPS + 1);
+
+    // A missing include is kept in the tree rather than failing the expansion
+    let records: HashMap<_, _> = [txt("example.com", "v=spf1 include:gone.example.net -all")].into_iter().collect();
+    let expansion = expand("example.com", &records).await;
+    assert!(expansion.tree.includes[0].error.is_some());
+    assert!(expansion.networks.is_empty());
+}
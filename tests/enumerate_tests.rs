@@ -1,5 +1,7 @@
 //! Unit tests for enumeration functionality that mirror the original DNSRecon Python tests
 
+mod common;
+
 use dnsrecon_rs::enumerate::crt_sh;
 use dnsrecon_rs::enumerate::bing;
 use dnsrecon_rs::enumerate::yandex;
@@ -7,9 +9,15 @@ use dnsrecon_rs::enumerate::whois;
 use dnsrecon_rs::enumerate::brute_force;
 use dnsrecon_rs::dns::resolver::DnsHelper;
 use dnsrecon_rs::cli::Args;
+use dnsrecon_rs::config::{EnumConfig, ReconConfig};
 use std::sync::Arc;
 use clap::Parser;
 
+/// HTTP clients for the scrapers that query live services
+fn scraper_clients() -> dnsrecon_rs::utils::http::ClientPool {
+    dnsrecon_rs::utils::http::create_client_pool(&EnumConfig::default()).unwrap()
+}
+
 #[tokio::test]
 async fn test_crt_sh_scraping() {
     // Test crt.sh scraping functionality
@@ -20,7 +28,7 @@ async fn test_crt_sh_scraping() {
     let args = Args::parse_from(["dnsrecon-rs", "-d", domain]);
     
     // Test the basic scraping function
-    let result = crt_sh::scrape_crtsh(domain, &args).await;
+    let result = crt_sh::scrape_crtsh(domain, &scraper_clients(), &EnumConfig::from(&args)).await;
     
     // The function should not panic and should return a Result
     assert!(result.is_ok() || result.is_err());
@@ -46,7 +54,7 @@ async fn test_crt_sh_with_retry() {
     let args = Args::parse_from(["dnsrecon-rs", "-d", domain]);
     
     // Test the retry function
-    let result = crt_sh::scrape_crtsh_with_retry(domain, &args, max_retries).await;
+    let result = crt_sh::scrape_crtsh_with_retry(domain, &scraper_clients(), &EnumConfig::from(&args), max_retries).await;
     
     // The function should not panic and should return a Result
     assert!(result.is_ok() || result.is_err());
@@ -69,7 +77,7 @@ async fn test_bing_scraping() {
     let args = Args::parse_from(["dnsrecon-rs", "-d", domain]);
     
     // Test the basic scraping function
-    let result = bing::scrape_bing(domain, &args).await;
+    let result = bing::scrape_bing(domain, &scraper_clients(), &EnumConfig::from(&args)).await;
     
     // The function should not panic and should return a Result
     assert!(result.is_ok() || result.is_err());
@@ -92,7 +100,7 @@ async fn test_bing_with_retry() {
     let args = Args::parse_from(["dnsrecon-rs", "-d", domain]);
     
     // Test the retry function
-    let result = bing::scrape_bing_with_retry(domain, &args, max_retries).await;
+    let result = bing::scrape_bing_with_retry(domain, &scraper_clients(), &EnumConfig::from(&args), max_retries).await;
     
     // The function should not panic and should return a Result
     assert!(result.is_ok() || result.is_err());
@@ -114,7 +122,7 @@ async fn test_yandex_scraping() {
     let args = Args::parse_from(["dnsrecon-rs", "-d", domain]);
     
     // Test the basic scraping function
-    let result = yandex::scrape_yandex(domain, &args).await;
+    let result = yandex::scrape_yandex(domain, &scraper_clients(), &EnumConfig::from(&args)).await;
     
     // The function should not panic and should return a Result
     assert!(result.is_ok() || result.is_err());
@@ -137,7 +145,7 @@ async fn test_yandex_with_retry() {
     let args = Args::parse_from(["dnsrecon-rs", "-d", domain]);
     
     // Test the retry function
-    let result = yandex::scrape_yandex_with_retry(domain, &args, max_retries).await;
+    let result = yandex::scrape_yandex_with_retry(domain, &scraper_clients(), &EnumConfig::from(&args), max_retries).await;
     
     // The function should not panic and should return a Result
     assert!(result.is_ok() || result.is_err());
@@ -174,7 +182,7 @@ async fn test_brute_force_enumeration() {
     let domain = "example.com";
     
     // Create a mock DNS helper
-    let dns_helper = DnsHelper::new(domain.to_string()).unwrap();
+    let dns_helper = DnsHelper::default_resolver().unwrap();
     
     // Test the basic brute force function
     // Using an empty wordlist path for testing
@@ -191,7 +199,7 @@ async fn test_brute_force_concurrent() {
     let domain = "example.com";
     
     // Create a mock DNS helper
-    let dns_helper = Arc::new(DnsHelper::new(domain.to_string()).unwrap());
+    let dns_helper = Arc::new(DnsHelper::default_resolver().unwrap());
     let concurrency = 5;
     
     // Test the concurrent brute force function
@@ -206,4 +214,681 @@ async fn test_brute_force_concurrent() {
     // The function should not panic and should return a Result
     // It will likely return an error due to the nonexistent file
     assert!(result.is_ok() || result.is_err());
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_wildcard_tracker_multiple_levels() {
+    use dnsrecon_rs::dns::record::DnsRecord;
+    use dnsrecon_rs::enumerate::wildcard::WildcardTracker;
+    use std::collections::HashSet;
+    use std::net::Ipv4Addr;
+    
+    let tracker = WildcardTracker::new();
+    
+    // No wildcard at the apex, but one under internal.example.com
+    tracker.insert("example.com", None);
+    let wildcard_ips: HashSet<_> = ["192.0.2.50".parse().unwrap(), "192.0.2.51".parse().unwrap()].into_iter().collect();
+    tracker.insert("internal.example.com", Some(wildcard_ips));
+    
+    let wildcard_answer = vec![
+        DnsRecord::new_a("foo.internal.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 51)),
+        DnsRecord::new_a("foo.internal.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 50)),
+    ];
+    let partial_answer = vec![
+        DnsRecord::new_a("bar.internal.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 50)),
+    ];
+    let real_answer = vec![
+        DnsRecord::new_a("db.internal.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 10)),
+    ];
+    let apex_answer = vec![
+        DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 50)),
+    ];
+    
+    // Only names under the wildcard level whose addresses match are filtered
+    assert!(tracker.is_wildcard_answer("foo.internal.example.com", &wildcard_answer));
+    assert!(!tracker.is_wildcard_answer("bar.internal.example.com", &partial_answer));
+    assert!(!tracker.is_wildcard_answer("db.internal.example.com", &real_answer));
+    assert!(!tracker.is_wildcard_answer("www.example.com", &apex_answer));
+    
+    let detected = tracker.detected_levels();
+    assert_eq!(detected.len(), 1);
+    assert_eq!(detected[0].0, "internal.example.com");
+}
+
+#[tokio::test]
+async fn test_record_limit_across_tasks() {
+    use dnsrecon_rs::enumerate::limit::RecordLimit;
+    
+    let limit = Arc::new(RecordLimit::new(Some(25)));
+    
+    // Many concurrent tasks each try to keep 3 records
+    let mut tasks = Vec::new();
+    for _ in 0..50 {
+        let limit = limit.clone();
+        tasks.push(tokio::spawn(async move { limit.reserve(3) }));
+    }
+    
+    let mut kept = 0;
+    for task in tasks {
+        kept += task.await.unwrap();
+    }
+    
+    // Exactly the cap is kept and further reservations are refused
+    assert_eq!(kept, 25);
+    assert!(limit.is_reached());
+    assert_eq!(limit.reserve(1), 0);
+}
+
+#[test]
+fn test_record_limit_unlimited() {
+    use dnsrecon_rs::enumerate::limit::RecordLimit;
+    
+    let limit = RecordLimit::unlimited();
+    assert_eq!(limit.reserve(1000), 1000);
+    assert!(!limit.is_reached());
+}
+
+#[test]
+fn test_seed_labels_from_html() {
+    use dnsrecon_rs::enumerate::scrape_seeds::labels_from_html;
+    
+    let html = r#"
+        <html><head>
+            <link rel="stylesheet" href="https://static.example.com/site.css">
+            <script src="//cdn-eu.example.com/app.js"></script>
+        </head><body>
+            <a href="https://portal.example.com/login">Login</a>
+            <a href="/careers/open-roles">Jobs</a>
+            <a href="https://twitter.com/exampleinc/status">Twitter</a>
+            <img src="/img/2024/logo.png">
+        </body></html>
+    "#;
+    
+    let labels = labels_from_html("example.com", html);
+    for expected in ["static", "cdn-eu", "portal", "careers", "open-roles", "img", "logo"] {
+        assert!(labels.contains(&expected.to_string()), "missing {}", expected);
+    }
+    // Other sites' paths and purely numeric tokens are ignored
+    assert!(!labels.contains(&"exampleinc".to_string()));
+    assert!(!labels.contains(&"2024".to_string()));
+}
+
+#[test]
+fn test_whois_asn_parsing() {
+    assert_eq!(whois::get_whois_asn("NetRange: 8.8.8.0 - 8.8.8.255\nOriginAS:       AS15169\n"), "AS15169");
+    assert_eq!(whois::get_whois_asn("route: 193.0.0.0/21\norigin: as3333\n"), "AS3333");
+    assert_eq!(whois::get_whois_asn("OriginAS:\n"), "Not Found");
+}
+
+#[tokio::test]
+async fn test_crtsh_json_failure_falls_back_to_html() {
+    use wiremock::matchers::{method, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    
+    let server = MockServer::start().await;
+    
+    // The JSON API is down
+    Mock::given(method("GET"))
+        .and(query_param("output", "json"))
+        .respond_with(ResponseTemplate::new(502))
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    // The HTML page still works
+    let html = r#"<html><body><table><tr><td><table>
+        <tr><td>1</td><td>2024-01-01</td><td>2024-01-01</td><td>2025-01-01</td><td>www.example.com<br>*.api.example.com</td></tr>
+        <tr><td>2</td><td>2024-01-01</td><td>2024-01-01</td><td>2025-01-01</td><td>mail.example.com</td></tr>
+        <tr><td>3</td><td>2024-01-01</td><td>2024-01-01</td><td>2025-01-01</td><td>example.org</td></tr>
+    </table></td></tr></table></body></html>"#;
+    Mock::given(method("GET"))
+        .and(query_param("q", "%.example.com"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(html))
+        .with_priority(2)
+        .mount(&server)
+        .await;
+    
+    let client = reqwest::Client::new();
+    let base = format!("{}/", server.uri());
+    let subdomains = crt_sh::scrape_ct_chain("example.com", &client, &base, None).await.unwrap();
+    
+    assert_eq!(subdomains, vec!["api.example.com", "mail.example.com", "www.example.com"]);
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_crtsh_total_failure_uses_certspotter() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    
+    let server = MockServer::start().await;
+    
+    Mock::given(method("GET"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(503).insert_header("Retry-After", "0"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/issuances"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            r#"[{"id":"1","dns_names":["example.com","*.cdn.example.com","vpn.example.com"]}]"#,
+        ))
+        .mount(&server)
+        .await;
+    
+    let client = reqwest::Client::new();
+    let base = format!("{}/", server.uri());
+    let certspotter = format!("{}/issuances", server.uri());
+    
+    // Without certspotter the chain reports the crt.sh failure
+    assert!(crt_sh::scrape_ct_chain("example.com", &client, &base, None).await.is_err());
+    
+    let subdomains = crt_sh::scrape_ct_chain("example.com", &client, &base, Some(&certspotter)).await.unwrap();
+    assert_eq!(subdomains, vec!["cdn.example.com", "example.com", "vpn.example.com"]);
+}
+
+#[test]
+fn test_negative_cache_covers_children() {
+    use dnsrecon_rs::enumerate::negative_cache::NegativeCache;
+
+    let cache = NegativeCache::new();
+    assert!(!cache.covers("dev.example.com"));
+
+    cache.insert("Dev.Example.com.");
+    assert!(cache.covers("dev.example.com"));
+    assert!(cache.covers("api.dev.example.com"));
+    assert!(cache.covers("a.b.DEV.example.com."));
+    assert!(!cache.covers("example.com"));
+    assert!(!cache.covers("xdev.example.com"));
+    assert_eq!(cache.len(), 1);
+
+    cache.clear();
+    assert!(cache.is_empty());
+    assert!(!cache.covers("api.dev.example.com"));
+}
+
+#[test]
+fn test_negative_cache_evicts_the_least_recently_used_name() {
+    use dnsrecon_rs::enumerate::negative_cache::NegativeCache;
+
+    let cache = NegativeCache::with_capacity(2);
+    cache.insert("a.example.com");
+    cache.insert("b.example.com");
+    // Skipping a child counts as using its parent
+    assert!(cache.covers("x.a.example.com"));
+
+    cache.insert("c.example.com");
+    assert_eq!(cache.len(), 2);
+    assert!(cache.covers("a.example.com"));
+    assert!(!cache.covers("b.example.com"));
+    assert!(cache.covers("c.example.com"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_brute_force_skips_children_of_nxdomain_parent() {
+    use dnsrecon_rs::enumerate::negative_cache::NegativeCache;
+    use std::time::{Duration, Instant};
+
+    // A nameserver that never answers, so any query would wait for the timeout
+    let dns_helper = Arc::new(DnsHelper::with_nameservers(vec!["192.0.2.1".parse().unwrap()]).unwrap());
+    let negative_cache = Arc::new(NegativeCache::new());
+    negative_cache.insert("gone.example.com");
+
+    let context = brute_force::BruteForceContext {
+        negative_cache: negative_cache.clone(),
+        ..Default::default()
+    };
+
+    let started = Instant::now();
+    let results = brute_force::brute_force_with_context("gone.example.com", "nonexistent_wordlist.txt", dns_helper, 5, context)
+        .await
+        .unwrap();
+
+    assert!(results.is_empty());
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn test_exclude_filter_wildcard_and_exact() {
+    use dnsrecon_rs::dns::record::DnsRecord;
+    use dnsrecon_rs::enumerate::exclude::ExcludeFilter;
+    use std::net::Ipv4Addr;
+
+    let patterns = vec![
+        "*.cdn.example.com".to_string(),
+        "monitoring.*".to_string(),
+        "legacy.example.com".to_string(),
+    ];
+    let filter = ExcludeFilter::new(&patterns).unwrap();
+
+    // Wildcards span several labels and ignore case and a trailing dot
+    assert!(filter.is_excluded("img.cdn.example.com"));
+    assert!(filter.is_excluded("a.b.CDN.example.com."));
+    assert!(filter.is_excluded("monitoring.example.com"));
+    assert!(!filter.is_excluded("cdn.example.com"));
+    // Exact patterns only match the name itself
+    assert!(filter.is_excluded("legacy.example.com"));
+    assert!(!filter.is_excluded("api.legacy.example.com"));
+
+    let records = vec![
+        DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+        DnsRecord::new_a("img.cdn.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 2)),
+        DnsRecord::new_ptr("3.2.0.192.in-addr.arpa".to_string(), "monitoring.example.com".to_string()),
+    ];
+    let kept = filter.apply(records);
+    assert_eq!(kept.len(), 1);
+    assert_eq!(kept[0].name, "www.example.com");
+
+    assert!(ExcludeFilter::new(&["[unclosed".to_string()]).is_err());
+}
+
+#[test]
+fn test_crtsh_query_url_per_scope() {
+    use dnsrecon_rs::enumerate::crt_sh::{crtsh_query_url, CrtShScope};
+
+    let base = "https://crt.sh/";
+    assert_eq!(
+        crtsh_query_url(base, "example.com", &CrtShScope::Subdomains, true),
+        "https://crt.sh/?q=%25.example.com&output=json"
+    );
+    assert_eq!(
+        crtsh_query_url(base, "example.com", &CrtShScope::Apex, false),
+        "https://crt.sh/?q=example.com"
+    );
+    assert_eq!(
+        crtsh_query_url(base, "example.com", &CrtShScope::Deep, true),
+        "https://crt.sh/?q=%25.%25.example.com&output=json"
+    );
+    assert_eq!(
+        crtsh_query_url(base, "example.com", &"org:Example Corp".parse().unwrap(), true),
+        "https://crt.sh/?O=Example+Corp&output=json"
+    );
+
+    // The default scope matches the subdomain search crt.sh was always queried with
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com"]);
+    assert_eq!(args.crtsh_scope, CrtShScope::Subdomains);
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com", "--crtsh-scope", "APEX"]);
+    assert_eq!(args.crtsh_scope, CrtShScope::Apex);
+    assert!(Args::try_parse_from(["dnsrecon-rs", "-d", "example.com", "--crtsh-scope", "org:"]).is_err());
+    assert!(Args::try_parse_from(["dnsrecon-rs", "-d", "example.com", "--crtsh-scope", "wide"]).is_err());
+}
+
+#[test]
+fn test_smart_order_tries_common_labels_first() {
+    let mut words: Vec<String> = ["zq-legacy-01", "api", "obscure", "WWW", "mail", "another"]
+        .iter()
+        .map(|word| word.to_string())
+        .collect();
+    brute_force::smart_order(&mut words);
+
+    assert_eq!(words, vec!["WWW", "mail", "api", "zq-legacy-01", "obscure", "another"]);
+
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com", "-t", "brt", "--smart-order"]);
+    assert!(args.smart_order);
+}
+#[tokio::test]
+async fn test_scraper_pages_bound_search_requests() {
+    use dnsrecon_rs::utils::http::create_client_pool;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    
+    let server = MockServer::start().await;
+    let html = r#"<html><body><ol id="b_results"><li class="b_algo"><h2><a href="https://www.example.com/">www</a></h2></li></ol></body></html>"#;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(html))
+        .mount(&server)
+        .await;
+    
+    let clients = create_client_pool(&EnumConfig { user_agent: "test-user-agent".to_string(), ..Default::default() }).unwrap();
+    let base = format!("{}/search", server.uri());
+    
+    let subdomains = bing::scrape_bing_pages("example.com", &clients, &base, 2).await.unwrap();
+    assert_eq!(subdomains, vec!["www.example.com"]);
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    
+    yandex::scrape_yandex_pages("example.com", &clients, &base, 1).await.unwrap();
+    assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com", "--scraper-pages", "3"]);
+    assert_eq!(ReconConfig::from(&args).scraper_pages, Some(3));
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com", "--scraper-pages", "0"]);
+    assert!(ReconConfig::from(&args).validate().is_err());
+}
+
+#[tokio::test]
+async fn test_scrapers_share_an_injected_client_pool() {
+    use dnsrecon_rs::utils::http::create_client_pool;
+    use wiremock::matchers::{header, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    
+    // One pool serves every scraper; the search engines send their own user agent
+    let server = MockServer::start().await;
+    let json = r#"[{"name_value": "www.example.com"}]"#;
+    for user_agent in [bing::BING_USER_AGENT, yandex::YANDEX_USER_AGENT, "shared-agent"] {
+        Mock::given(method("GET"))
+            .and(header("user-agent", user_agent))
+            .respond_with(ResponseTemplate::new(200).set_body_string(json))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+    
+    let clients = create_client_pool(&EnumConfig { user_agent: "shared-agent".to_string(), ..Default::default() }).unwrap();
+    let base = format!("{}/search", server.uri());
+    bing::scrape_bing_pages("example.com", &clients, &base, 1).await.unwrap();
+    yandex::scrape_yandex_pages("example.com", &clients, &base, 1).await.unwrap();
+    let names = crt_sh::scrape_ct_chain("example.com", clients.next_client(), &server.uri(), None).await.unwrap();
+    assert_eq!(names, vec!["www.example.com"]);
+    server.verify().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_streaming_brute_force_bounds_concurrency() {
+    use dnsrecon_rs::dns::record::DnsRecord;
+    use dnsrecon_rs::enumerate::limit::RecordLimit;
+    use std::io::Write;
+    use std::net::Ipv4Addr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Synthetic wordlist with a comment and blank lines mixed in
+    let path = std::env::temp_dir().join(format!("dnsrecon-rs-{}-streaming-words.txt", std::process::id()));
+    {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(&path).unwrap());
+        writeln!(file, "# generated\n").unwrap();
+        for n in 0..50_000 {
+            writeln!(file, "host{}", n).unwrap();
+        }
+    }
+
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let words = brute_force::wordlist_words(path.to_str().unwrap()).unwrap();
+    let results = brute_force::resolve_words_bounded(words, 8, &RecordLimit::unlimited(), |word| {
+        let in_flight = in_flight.clone();
+        let peak = peak.clone();
+        async move {
+            let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            tokio::task::yield_now().await;
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            // Every thousandth host exists
+            let n: usize = word.trim_start_matches("host").parse().unwrap();
+            n.is_multiple_of(1000)
+                .then(|| vec![DnsRecord::new_a(format!("{}.example.com", word), Ipv4Addr::new(192, 0, 2, 1))])
+        }
+    })
+    .await
+    .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(peak.load(Ordering::SeqCst) <= 8);
+    assert_eq!(results.len(), 50);
+    let mut names: Vec<&str> = results.iter().map(|record| record.name.as_str()).collect();
+    names.sort();
+    assert_eq!(names[0], "host0.example.com");
+    assert!(names.contains(&"host49000.example.com"));
+}
+
+#[tokio::test]
+async fn test_streaming_brute_force_stops_at_record_limit() {
+    use dnsrecon_rs::dns::record::DnsRecord;
+    use dnsrecon_rs::enumerate::limit::RecordLimit;
+    use std::net::Ipv4Addr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let limit = Arc::new(RecordLimit::new(Some(3)));
+    let started = Arc::new(AtomicUsize::new(0));
+    let words = (0..10_000).map(|n| Ok(format!("host{}", n)));
+    let results = brute_force::resolve_words_bounded(words, 1, &limit, |word| {
+        let limit = limit.clone();
+        let started = started.clone();
+        async move {
+            started.fetch_add(1, Ordering::SeqCst);
+            let mut records = vec![DnsRecord::new_a(format!("{}.example.com", word), Ipv4Addr::new(192, 0, 2, 1))];
+            records.truncate(limit.reserve(records.len()));
+            Some(records)
+        }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(results.len(), 3);
+    assert!(started.load(Ordering::SeqCst) <= 4);
+}
+
+#[tokio::test]
+async fn test_search_scrapers_go_through_the_proxy() {
+    use dnsrecon_rs::utils::http::create_client_pool;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+    
+    // A proxy that records the request line of each connection, then hangs up
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let proxy = format!("http://{}", listener.local_addr().unwrap());
+    let requests = std::thread::spawn(move || {
+        let mut lines = Vec::new();
+        for stream in listener.incoming().take(2) {
+            let mut line = String::new();
+            BufReader::new(stream.unwrap()).read_line(&mut line).unwrap();
+            lines.push(line.trim_end().to_string());
+        }
+        lines
+    });
+    
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com", "--proxy", &proxy, "--scraper-pages", "1"]);
+    let config = EnumConfig::from(&args);
+    let clients = create_client_pool(&config).unwrap();
+    assert!(bing::scrape_bing("example.com", &clients, &config).await.is_err());
+    assert!(yandex::scrape_yandex("example.com", &clients, &config).await.is_err());
+    
+    let lines = requests.join().unwrap();
+    assert!(lines[0].starts_with("CONNECT www.bing.com:443"), "{}", lines[0]);
+    assert!(lines[1].starts_with("CONNECT yandex.com:443"), "{}", lines[1]);
+}
+
+#[test]
+fn test_crtsh_json_sample_yields_every_san() {
+    use dnsrecon_rs::enumerate::crt_sh::{parse_crtsh_json, CrtShScope};
+    
+    // Trimmed from a real https://crt.sh/?q=%25.example.com&output=json response
+    let body = r#"[
+        {"issuer_ca_id":185752,"issuer_name":"C=US, O=DigiCert Inc, CN=DigiCert Global G2 TLS RSA SHA256 2020 CA1","common_name":"www.example.org","name_value":"example.com\nexample.net\nexample.org\nwww.example.com\nWWW.Example.com","id":11771233452,"entry_timestamp":"2024-01-30T19:22:50.288","not_before":"2024-01-30T00:00:00","not_after":"2025-03-01T23:59:59","serial_number":"075bcef30689c8addf13e51af4afe187","result_count":5},
+        {"issuer_ca_id":295815,"issuer_name":"C=US, O=Let's Encrypt, CN=R3","common_name":"*.dev.example.com","name_value":"*.dev.example.com\nmail.example.com","id":9876543210,"entry_timestamp":"2023-06-01T10:00:00.000","not_before":"2023-06-01T09:00:00","not_after":"2023-08-30T09:00:00","serial_number":"03a1b2c3d4","result_count":2},
+        {"issuer_ca_id":295815,"issuer_name":"C=US, O=Let's Encrypt, CN=R3","common_name":null,"name_value":"mail.example.com","id":9876543211,"entry_timestamp":"2023-06-02T10:00:00.000","not_before":"2023-06-02T09:00:00","not_after":"2023-08-31T09:00:00","serial_number":"03a1b2c3d5","result_count":1}
+    ]"#;
+    
+    let names = parse_crtsh_json("example.com", &CrtShScope::Subdomains, body).unwrap();
+    assert_eq!(names, vec!["dev.example.com", "example.com", "mail.example.com", "www.example.com"]);
+    
+    assert!(parse_crtsh_json("example.com", &CrtShScope::Subdomains, "<html>").is_err());
+}
+
+#[tokio::test]
+async fn test_detect_wildcard_without_a_wildcard() {
+    use trust_dns_resolver::proto::op::ResponseCode;
+
+    // Random names under the zone don't exist
+    let server = common::spawn_dns_server(|_, response| {
+        response.set_response_code(ResponseCode::NXDomain);
+    });
+
+    let dns_helper = DnsHelper::with_nameserver_addrs(vec![server]).unwrap();
+    assert_eq!(dns_helper.detect_wildcard("example.com").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_brute_force_drops_answers_matching_the_wildcard() {
+    use std::net::Ipv4Addr;
+    use trust_dns_resolver::proto::rr::rdata::A;
+    use trust_dns_resolver::proto::rr::{RData, Record, RecordType as WireType};
+
+    // Every name under example.com resolves to the wildcard address, except www
+    let server = common::spawn_dns_server(|query, response| {
+        if query.query_type() == WireType::A {
+            let address = if query.name().to_ascii().starts_with("www.") {
+                Ipv4Addr::new(198, 51, 100, 7)
+            } else {
+                Ipv4Addr::new(192, 0, 2, 50)
+            };
+            response.add_answer(Record::from_rdata(query.name().clone(), 300, RData::A(A(address))));
+        }
+    });
+
+    let dns_helper = Arc::new(DnsHelper::with_nameserver_addrs(vec![server]).unwrap());
+    let wildcard_ips = dns_helper.detect_wildcard("example.com").await.unwrap();
+    assert_eq!(wildcard_ips, Some(vec!["192.0.2.50".parse().unwrap()]));
+
+    let words = || vec!["www".to_string(), "foo".to_string(), "bar".to_string()];
+    let results = brute_force::brute_force_words("example.com", words(), dns_helper.clone(), 2, Default::default())
+        .await
+        .unwrap();
+    let names: Vec<&str> = results.iter().map(|record| record.name.as_str()).collect();
+    assert_eq!(names, vec!["www.example.com"]);
+
+    // With the filter disabled the wildcard answers are kept
+    let context = brute_force::BruteForceContext {
+        keep_wildcard_answers: true,
+        ..Default::default()
+    };
+    let results = brute_force::brute_force_words("example.com", words(), dns_helper, 2, context).await.unwrap();
+    assert_eq!(results.len(), 3);
+
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com", "--no-wildcard-filter"]);
+    assert!(ReconConfig::from(&args).no_wildcard_filter);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_brute_force_concurrent_streams_a_large_wordlist() {
+    use std::io::Write;
+    use std::net::Ipv4Addr;
+    use trust_dns_resolver::proto::op::ResponseCode;
+    use trust_dns_resolver::proto::rr::rdata::A;
+    use trust_dns_resolver::proto::rr::{RData, Record, RecordType as WireType};
+
+    // Only every thousandth host exists; everything else is NXDOMAIN
+    let server = common::spawn_dns_server(|query, response| {
+        let name = query.name().to_ascii();
+        let host: Option<usize> = name.strip_prefix("host").and_then(|rest| rest.split('.').next()?.parse().ok());
+        match host {
+            Some(n) if n.is_multiple_of(1000) => {
+                if query.query_type() == WireType::A {
+                    let address = A(Ipv4Addr::new(192, 0, 2, 1));
+                    response.add_answer(Record::from_rdata(query.name().clone(), 300, RData::A(address)));
+                }
+            }
+            _ => {
+                response.set_response_code(ResponseCode::NXDomain);
+            }
+        }
+    });
+
+    // Synthetic wordlist with a comment and blank lines mixed in
+    let path = std::env::temp_dir().join(format!("dnsrecon-rs-{}-concurrent-words.txt", std::process::id()));
+    {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(&path).unwrap());
+        writeln!(file, "# generated\n").unwrap();
+        for n in 0..20_000 {
+            writeln!(file, "host{}", n).unwrap();
+        }
+    }
+
+    let dns_helper = Arc::new(DnsHelper::with_nameserver_addrs(vec![server]).unwrap());
+    let results = brute_force::brute_force_concurrent("example.com", path.to_str().unwrap(), dns_helper, 32)
+        .await
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let mut names: Vec<&str> = results.iter().map(|record| record.name.as_str()).collect();
+    names.sort();
+    names.dedup();
+    assert_eq!(names.len(), 20);
+    assert!(names.contains(&"host19000.example.com"));
+}
+
+#[tokio::test]
+async fn test_recursive_brute_force_queues_found_names() {
+    use dnsrecon_rs::dns::record::DnsRecord;
+    use std::net::Ipv4Addr;
+
+    // Names the mocked brute force "resolves" below each base
+    let found = |base: &str| -> Vec<&'static str> {
+        match base {
+            "example.com" => vec!["internal.example.com", "www.example.com", "other.example.net"],
+            "internal.example.com" => vec!["db.internal.example.com", "www.example.com"],
+            "db.internal.example.com" => vec!["x.db.internal.example.com"],
+            _ => Vec::new(),
+        }
+    };
+    let run = |recurse_depth: usize, max_candidates: usize| async move {
+        let mut bases = Vec::new();
+        let records = brute_force::recurse_found_names("Example.com.", recurse_depth, 10, max_candidates, |base| {
+            bases.push(base.clone());
+            let records = found(&base)
+                .into_iter()
+                .map(|name| DnsRecord::new_a(name.to_string(), Ipv4Addr::new(192, 0, 2, 1)))
+                .collect();
+            std::future::ready(Ok(records))
+        })
+        .await
+        .unwrap();
+        (bases, records.len())
+    };
+
+    // Without recursion only the domain itself is brute forced
+    assert_eq!(run(0, 100).await.0, vec!["example.com"]);
+
+    // Depth-2 candidates are generated under internal.example.com, and names
+    // outside the base or already brute forced are not queued again
+    let (bases, records) = run(1, 100).await;
+    assert_eq!(bases, vec!["example.com", "internal.example.com", "www.example.com"]);
+    assert_eq!(records, 5);
+
+    let (bases, _) = run(2, 100).await;
+    assert!(bases.contains(&"db.internal.example.com".to_string()));
+    assert!(!bases.contains(&"x.db.internal.example.com".to_string()));
+
+    // The candidate cap stops recursion after the first found name
+    assert_eq!(run(2, 15).await.0, vec!["example.com", "internal.example.com"]);
+
+    let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com", "-t", "brt", "--recurse-depth", "2"]);
+    assert_eq!(ReconConfig::from(&args).recurse_depth, 2);
+}
+
+#[tokio::test]
+async fn test_resolve_bounded_keeps_going_past_a_failed_lookup() {
+    use dnsrecon_rs::dns::record::DnsRecord;
+    use dnsrecon_rs::dns::DnsError;
+    use dnsrecon_rs::utils::concurrency::resolve_bounded;
+    use std::net::Ipv4Addr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    
+    let names: Vec<String> = ["a", "b", "broken", "c"].iter().map(|label| format!("{}.example.com", label)).collect();
+    let in_flight = AtomicUsize::new(0);
+    let most_in_flight = AtomicUsize::new(0);
+    
+    let mut answers = Vec::new();
+    resolve_bounded(&names, 2, |name| {
+        let (in_flight, most_in_flight, names) = (&in_flight, &most_in_flight, &names);
+        async move {
+            let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            most_in_flight.fetch_max(now, Ordering::SeqCst);
+            // Earlier names take longer, so lookups finish out of order
+            for _ in 0..names.len() - names.iter().position(|n| n == name).unwrap() {
+                tokio::task::yield_now().await;
+            }
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            
+            if name.starts_with("broken.") {
+                return Err(DnsError::Timeout);
+            }
+            Ok(DnsRecord::new_a(name.clone(), Ipv4Addr::new(192, 0, 2, 1)))
+        }
+    }, |answer| answers.push(answer))
+    .await;
+    
+    // Answers arrive as lookups finish, so later names come first
+    let resolved: Vec<&str> = answers.iter().flatten().map(|record| record.name.as_str()).collect();
+    assert_eq!(resolved, vec!["b.example.com", "a.example.com", "c.example.com"]);
+    assert_eq!(answers.iter().filter(|answer| matches!(answer, Err(DnsError::Timeout))).count(), 1);
+    assert_eq!(most_in_flight.load(Ordering::SeqCst), 2);
+}
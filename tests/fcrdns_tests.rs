@@ -0,0 +1,59 @@
+//! Unit tests for forward-confirmed reverse DNS
+
+use dnsrecon_rs::dns::record::DnsRecord;
+use dnsrecon_rs::enumerate::fcrdns::{confirm_with, is_forward_confirmed};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+#[test]
+fn test_ptr_is_confirmed_only_when_forward_records_contain_the_address() {
+    let ip: IpAddr = "192.0.2.1".parse().unwrap();
+
+    let matching = vec![
+        DnsRecord::new_a("web.example.com".to_string(), Ipv4Addr::new(198, 51, 100, 7)),
+        DnsRecord::new_a("web.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+    ];
+    assert!(is_forward_confirmed(ip, &matching));
+
+    let other = vec![
+        DnsRecord::new_a("web.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 2)),
+        DnsRecord::new_aaaa("web.example.com".to_string(), Ipv6Addr::LOCALHOST),
+    ];
+    assert!(!is_forward_confirmed(ip, &other));
+    assert!(!is_forward_confirmed(ip, &[]));
+}
+
+#[test]
+fn test_unconfirmed_ptrs_are_flagged() {
+    let records = vec![
+        DnsRecord::new_ptr("192.0.2.1".to_string(), "Web.Example.com.".to_string()),
+        DnsRecord::new_ptr("192.0.2.2".to_string(), "web.example.com".to_string()),
+        DnsRecord::new_ptr("192.0.2.3".to_string(), "gone.example.com".to_string()),
+        DnsRecord::new_a("unrelated.example.com".to_string(), Ipv4Addr::new(203, 0, 113, 1)),
+    ];
+
+    let confirmed = confirm_with(&records, |target| match target {
+        "web.example.com" => vec![DnsRecord::new_a("web.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1))],
+        _ => Vec::new(),
+    });
+
+    assert_eq!(confirmed, vec![true, false, false, true]);
+}
+
+#[test]
+fn test_confirmation_and_enrichment_share_the_resolved_targets() {
+    use dnsrecon_rs::enumerate::fcrdns::confirm;
+    use dnsrecon_rs::enumerate::reverse_enrich::{enrich, TargetRecords};
+
+    let records = vec![
+        DnsRecord::new_ptr("192.0.2.1".to_string(), "www.example.com.".to_string()),
+        DnsRecord::new_ptr("192.0.2.2".to_string(), "www.example.com.".to_string()),
+    ];
+    let mut resolved = TargetRecords::new();
+    resolved.insert("www.example.com".to_string(), vec![
+        DnsRecord::new_cname("www.example.com".to_string(), "web.example.com".to_string()),
+        DnsRecord::new_a("web.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+    ]);
+
+    assert_eq!(confirm(&records, &resolved), vec![true, false]);
+    assert_eq!(enrich(&records, &resolved).len(), 2);
+}
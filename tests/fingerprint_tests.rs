@@ -0,0 +1,66 @@
+//! Unit tests for CHAOS-class nameserver fingerprinting against a mock server
+
+use dnsrecon_rs::dns::resolver::DnsHelper;
+use dnsrecon_rs::enumerate::findings::Severity;
+use dnsrecon_rs::enumerate::fingerprint::fingerprint_nameserver;
+use std::net::{SocketAddr, UdpSocket};
+use std::thread;
+use trust_dns_resolver::proto::op::{Message, MessageType, ResponseCode};
+use trust_dns_resolver::proto::rr::rdata::TXT;
+use trust_dns_resolver::proto::rr::{DNSClass, RData, Record};
+
+/// Start a UDP server that answers CH TXT version.bind and refuses everything else
+fn spawn_chaos_server(requests: usize) -> SocketAddr {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = socket.local_addr().unwrap();
+
+    thread::spawn(move || {
+        let mut buffer = [0u8; 512];
+        for _ in 0..requests {
+            let (len, from) = socket.recv_from(&mut buffer).unwrap();
+            let request = Message::from_vec(&buffer[..len]).unwrap();
+            let query = request.queries()[0].clone();
+
+            let mut response = Message::new();
+            response.set_id(request.id()).set_message_type(MessageType::Response);
+            response.add_query(query.clone());
+
+            if query.query_class() == DNSClass::CH && query.name().to_ascii() == "version.bind." {
+                let mut record = Record::from_rdata(
+                    query.name().clone(),
+                    0,
+                    RData::TXT(TXT::new(vec!["9.18.24-MockBIND".to_string()])),
+                );
+                record.set_dns_class(DNSClass::CH);
+                response.add_answer(record);
+            } else {
+                response.set_response_code(ResponseCode::Refused);
+            }
+
+            socket.send_to(&response.to_vec().unwrap(), from).unwrap();
+        }
+    });
+
+    addr
+}
+
+#[tokio::test]
+async fn test_query_chaos_version_bind() {
+    let server = spawn_chaos_server(2);
+    let helper = DnsHelper::default_resolver().unwrap();
+
+    assert_eq!(helper.query_chaos("version.bind", server).await.as_deref(), Some("9.18.24-MockBIND"));
+    assert_eq!(helper.query_chaos("hostname.bind", server).await, None);
+}
+
+#[tokio::test]
+async fn test_fingerprint_nameserver_emits_banner_finding() {
+    let server = spawn_chaos_server(3);
+    let helper = DnsHelper::default_resolver().unwrap();
+
+    let findings = fingerprint_nameserver(&helper, "ns1.example.com", server).await;
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].severity, Severity::Low);
+    assert_eq!(findings[0].category, "ns-fingerprint");
+    assert!(findings[0].message.contains("9.18.24-MockBIND"));
+}
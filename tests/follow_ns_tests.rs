@@ -0,0 +1,46 @@
+//! Unit tests for following NS delegations
+
+use dnsrecon_rs::dns::record::DnsRecord;
+use dnsrecon_rs::dns::DnsError;
+use dnsrecon_rs::enumerate::follow_ns::{follow_nameservers, nameserver_hosts, NS_INFRASTRUCTURE_ORIGIN};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+fn ns_records() -> Vec<DnsRecord> {
+    vec![
+        DnsRecord::new_ns("example.com".to_string(), "ns1.hosting.example.net.".to_string()),
+        DnsRecord::new_ns("example.com".to_string(), "NS1.hosting.example.net".to_string()),
+        DnsRecord::new_ns("example.com".to_string(), "ns2.example.org".to_string()),
+        DnsRecord::new_ns("example.com".to_string(), "ns3.lame.example".to_string()),
+        DnsRecord::new_a("example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+    ]
+}
+
+#[test]
+fn test_nameserver_hosts_are_deduplicated() {
+    assert_eq!(
+        nameserver_hosts(&ns_records()),
+        vec!["ns1.hosting.example.net", "ns2.example.org", "ns3.lame.example"]
+    );
+}
+
+#[test]
+fn test_follow_nameservers_tags_addresses() {
+    let mut queried = Vec::new();
+    let results = follow_nameservers(&ns_records(), |host| {
+        queried.push(host.to_string());
+        match host {
+            "ns1.hosting.example.net" => Ok(vec![
+                DnsRecord::new_a(host.to_string(), Ipv4Addr::new(198, 51, 100, 1)),
+                DnsRecord::new_aaaa(host.to_string(), "2001:db8::53".parse::<Ipv6Addr>().unwrap()),
+            ]),
+            "ns2.example.org" => Ok(vec![DnsRecord::new_a(host.to_string(), Ipv4Addr::new(203, 0, 113, 2))]),
+            _ => Err(DnsError::Other("NXDOMAIN".to_string())),
+        }
+    });
+
+    // Every nameserver is looked up once; the failed one is skipped
+    assert_eq!(queried, vec!["ns1.hosting.example.net", "ns2.example.org", "ns3.lame.example"]);
+    assert_eq!(results.len(), 3);
+    assert!(results.iter().all(|record| record.origin.as_deref() == Some(NS_INFRASTRUCTURE_ORIGIN)));
+    assert_eq!(results[2].name, "ns2.example.org");
+}
@@ -0,0 +1,101 @@
+//! Unit tests for GeoIP/ASN annotation
+//!
+//! `tests/data/geoip-test.mmdb` is a small MaxMind database holding
+//! 203.0.113.0/24 (AS64500, "Example Transit", AU), 198.51.100.0/24 (country
+//! JP only) and 2001:db8::/32 (AS64501, "Example IPv6 Networks", DE).
+#![cfg(feature = "geoip")]
+
+use dnsrecon_rs::dns::record::DnsRecord;
+use dnsrecon_rs::enumerate::geoip::{annotate, GeoInfo, GeoIpDatabase};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+const TEST_DATABASE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/geoip-test.mmdb");
+
+#[test]
+fn test_lookup_returns_asn_org_and_country() {
+    let database = GeoIpDatabase::open(TEST_DATABASE).unwrap();
+
+    assert_eq!(
+        database.lookup("203.0.113.10".parse().unwrap()),
+        Some(GeoInfo { asn: Some(64500), org: Some("Example Transit".to_string()), country: Some("AU".to_string()) })
+    );
+    assert_eq!(
+        database.lookup("198.51.100.1".parse().unwrap()),
+        Some(GeoInfo { asn: None, org: None, country: Some("JP".to_string()) })
+    );
+    assert_eq!(database.lookup("192.0.2.1".parse().unwrap()), None);
+    assert!(GeoIpDatabase::open("tests/data/missing.mmdb").is_err());
+}
+
+#[test]
+fn test_annotate_only_touches_address_records() {
+    let database = GeoIpDatabase::open(TEST_DATABASE).unwrap();
+    let mut records = vec![
+        DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(203, 0, 113, 10)),
+        DnsRecord::new_aaaa("www.example.com".to_string(), "2001:db8::1".parse::<Ipv6Addr>().unwrap()),
+        DnsRecord::new_a("mail.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+        DnsRecord::new_ptr("203.0.113.10".to_string(), "www.example.com".to_string()),
+    ];
+
+    assert_eq!(annotate(&mut records, &database), 2);
+    assert_eq!(records[0].geoip.as_ref().and_then(|info| info.asn), Some(64500));
+    assert_eq!(records[1].geoip.as_ref().and_then(|info| info.asn), Some(64501));
+    assert_eq!(records[1].geoip.as_ref().and_then(|info| info.org.as_deref()), Some("Example IPv6 Networks"));
+    assert!(records[2].geoip.is_none());
+    assert!(records[3].geoip.is_none());
+}
+
+#[test]
+fn test_csv_has_geoip_columns_only_when_annotated() {
+    use dnsrecon_rs::output::csv::to_csv_string;
+
+    let database = GeoIpDatabase::open(TEST_DATABASE).unwrap();
+    let mut records = vec![
+        DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(203, 0, 113, 10)),
+        DnsRecord::new_a("mail.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+    ];
+    assert!(to_csv_string(&records).starts_with("type,name,ttl,value\n"));
+
+    annotate(&mut records, &database);
+    assert_eq!(
+        to_csv_string(&records),
+        "type,name,ttl,value,asn,org,country\n\
+         A,www.example.com,,203.0.113.10,64500,Example Transit,AU\n\
+         A,mail.example.com,,192.0.2.1,,,\n"
+    );
+}
+
+#[test]
+fn test_streamed_sqlite_records_are_annotated() {
+    use dnsrecon_rs::output::sqlite::{spawn_writer, SqliteSink};
+    use std::sync::mpsc;
+    use std::sync::Arc;
+
+    let path = std::env::temp_dir().join(format!("dnsrecon-rs-{}-geoip.db", std::process::id()));
+    let filename = path.to_str().unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let database = Arc::new(GeoIpDatabase::open(TEST_DATABASE).unwrap());
+    let (record_tx, record_rx) = mpsc::channel();
+    let writer = spawn_writer(SqliteSink::open(filename).unwrap().with_geoip(database), record_rx, None, None);
+    record_tx.send(DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(203, 0, 113, 10))).unwrap();
+    drop(record_tx);
+    assert_eq!(writer.join().unwrap().unwrap(), 1);
+
+    let conn = rusqlite::Connection::open(filename).unwrap();
+    let mut stmt = conn.prepare("SELECT key, value FROM record_data WHERE key LIKE 'geoip_%' ORDER BY id").unwrap();
+    let rows: Vec<(String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(rows, vec![
+        ("geoip_asn".to_string(), "64500".to_string()),
+        ("geoip_org".to_string(), "Example Transit".to_string()),
+        ("geoip_country".to_string(), "AU".to_string()),
+    ]);
+
+    drop(stmt);
+    drop(conn);
+    let _ = std::fs::remove_file(&path);
+}
@@ -2,6 +2,7 @@
 
 use dnsrecon_rs::utils::http::create_http_client;
 use dnsrecon_rs::cli::Args;
+use dnsrecon_rs::config::EnumConfig;
 use clap::Parser;
 
 #[test]
@@ -9,7 +10,7 @@ fn test_create_http_client_integration() {
     // Test creating an HTTP client in an integration context
     let args = Args::parse_from(["dnsrecon-rs", "-d", "example.com"]);
     
-    let result = create_http_client(&args, "dnsrecon-rs-test-agent/0.1");
+    let result = create_http_client(&EnumConfig { user_agent: "dnsrecon-rs-test-agent/0.1".to_string(), ..EnumConfig::from(&args) });
     assert!(result.is_ok());
     
     // We can verify the client was created successfully
@@ -26,7 +27,7 @@ fn test_create_http_client_with_proxy_integration() {
         "--proxy", "http://127.0.0.1:8080"
     ]);
     
-    let result = create_http_client(&args, "dnsrecon-rs-test-agent/0.1");
+    let result = create_http_client(&EnumConfig { user_agent: "dnsrecon-rs-test-agent/0.1".to_string(), ..EnumConfig::from(&args) });
     // This might succeed or fail depending on whether a proxy is actually running
     // The important thing is that it doesn't panic
     assert!(result.is_ok() || result.is_err());
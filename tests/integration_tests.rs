@@ -1,21 +1,23 @@
 //! Integration tests for the DNSRecon-rs application
 
+mod common;
+
 use dnsrecon_rs::dns::resolver::DnsHelper;
 
 #[tokio::test]
 async fn test_dns_helper_creation() {
     // Test creating a DNS helper with default configuration
-    let result = DnsHelper::new("example.com".to_string());
+    let result = DnsHelper::default_resolver();
     assert!(result.is_ok());
 }
 
 #[tokio::test]
 async fn test_basic_dns_resolution() {
     // Test basic DNS resolution capabilities
-    let dns_helper = DnsHelper::new("example.com".to_string()).unwrap();
+    let dns_helper = DnsHelper::default_resolver().unwrap();
     
     // Try to resolve a known domain
-    let result = dns_helper.get_ip("example.com");
+    let result = dns_helper.get_ip("example.com").await;
     assert!(result.is_ok());
     
     // Note: We don't assert specific results because DNS records can change
@@ -33,7 +35,7 @@ async fn test_cli_parsing() {
     assert!(result.is_ok());
     
     let args = result.unwrap();
-    assert_eq!(args.domain, Some("example.com".to_string()));
+    assert_eq!(args.domain, vec!["example.com".to_string()]);
     assert_eq!(args.r#type, EnumType::Standard);
 }
 
@@ -52,10 +54,65 @@ async fn test_output_formatting() {
     let records = vec![record];
     
     // Test JSON output
-    let json_result = output::json::to_json_string(&records);
+    let json_result = output::json::to_json_string(&records, true);
     assert!(json_result.is_ok());
     
     // Test XML output
     let xml_result = output::xml::to_xml_string(&records);
     assert!(xml_result.is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_profile_host_matches_individual_lookups() {
+    use std::collections::HashSet;
+    
+    let dns_helper = DnsHelper::default_resolver().unwrap();
+    let name = "example.com";
+    
+    let mut expected = Vec::new();
+    expected.extend(dns_helper.get_ip(name).await.unwrap_or_default());
+    expected.extend(dns_helper.get_mx(name).await.unwrap_or_default());
+    expected.extend(dns_helper.get_txt(name).await.unwrap_or_default());
+    expected.extend(dns_helper.get_cname(name).await.unwrap_or_default());
+    
+    let profiled = dns_helper.profile_host(name).await;
+    
+    // Compare as sets of (type, data) since answer order and TTLs may vary
+    let key = |record: &dnsrecon_rs::dns::record::DnsRecord| format!("{:?} {:?}", record.record_type, record.data);
+    let expected: HashSet<String> = expected.iter().map(key).collect();
+    let profiled: HashSet<String> = profiled.iter().map(key).collect();
+    assert_eq!(profiled, expected);
+}
+
+#[tokio::test]
+async fn test_enumerate_domain_with_a_hand_built_config() {
+    use dnsrecon_rs::config::EnumConfig;
+    use dnsrecon_rs::dns::record::RecordData;
+    use dnsrecon_rs::enumerate::sources::Source;
+    use std::net::Ipv4Addr;
+    use trust_dns_resolver::proto::rr::rdata::A;
+    use trust_dns_resolver::proto::rr::{RData, Record, RecordType};
+
+    // Answer A queries for the apex; every other query gets an empty answer
+    let server = common::spawn_dns_server(|query, response| {
+        if query.query_type() == RecordType::A && query.name().to_ascii().eq_ignore_ascii_case("example.com.") {
+            let address = A(Ipv4Addr::new(192, 0, 2, 1));
+            response.add_answer(Record::from_rdata(query.name().clone(), 300, RData::A(address)));
+        }
+    });
+
+    let config = EnumConfig {
+        sources: Some(vec![Source::Dns]),
+        nameservers: Some(server.to_string()),
+        concurrency: 4,
+        ..Default::default()
+    };
+    let records = dnsrecon_rs::enumerate_domain("example.com", &config).await.unwrap();
+
+    let addresses: Vec<&RecordData> = records.iter().filter(|record| record.name == "example.com").map(|record| &record.data).collect();
+    assert_eq!(addresses, vec![&RecordData::A(Ipv4Addr::new(192, 0, 2, 1))]);
+
+    // The config is validated before any query is sent
+    let config = EnumConfig { nameservers: Some("not-a-nameserver".to_string()), ..config };
+    assert!(dnsrecon_rs::enumerate_domain("example.com", &config).await.is_err());
 }
\ No newline at end of file
@@ -0,0 +1,47 @@
+//! Unit tests for permutations of known subdomains
+
+use dnsrecon_rs::enumerate::mutate::{generate_permutations, relative_names};
+use std::collections::HashSet;
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+#[test]
+fn test_small_seed_and_word_set_yields_expected_permutations() {
+    let known = strings(&["api", "web01.staging"]);
+    let words = strings(&["dev"]);
+
+    let permutations = generate_permutations(&known, &words, 100);
+    let expected: HashSet<String> = strings(&[
+        "dev-api", "api-dev", "dev.api", "api.dev", "api1", "api2",
+        "dev-web01.staging", "web01-dev.staging", "dev.web01.staging", "web01.dev.staging",
+        "web00.staging", "web02.staging",
+    ])
+    .into_iter()
+    .collect();
+
+    assert_eq!(permutations.len(), expected.len());
+    assert_eq!(permutations.into_iter().collect::<HashSet<_>>(), expected);
+}
+
+#[test]
+fn test_permutations_are_deduplicated_and_capped() {
+    // api1 is both known and a number variant of api, and api2 is generated twice
+    let known = strings(&["API", "api1", "api"]);
+    let words = strings(&["dev", "Dev", "2"]);
+
+    let permutations = generate_permutations(&known, &words, 1000);
+    assert!(!permutations.contains(&"api1".to_string()));
+    assert_eq!(permutations.iter().filter(|name| *name == "api2").count(), 1);
+    assert_eq!(permutations.iter().collect::<HashSet<_>>().len(), permutations.len());
+
+    let capped = generate_permutations(&known, &words, 3);
+    assert_eq!(capped, vec!["dev-api", "api-dev", "dev.api"]);
+}
+
+#[test]
+fn test_relative_names_strip_the_domain() {
+    let names = strings(&["api.example.com", "*.dev.Example.com.", "example.com", "www.example.net"]);
+    assert_eq!(relative_names("example.com", &names), vec!["api", "dev"]);
+}
@@ -0,0 +1,54 @@
+//! Unit tests for comparing answers across nameservers
+
+use dnsrecon_rs::dns::record::DnsRecord;
+use dnsrecon_rs::dns::resolver::DnsHelper;
+use dnsrecon_rs::enumerate::findings::Severity;
+use dnsrecon_rs::enumerate::nameserver_diff::{answers_differ, nameserver_findings};
+use std::net::{IpAddr, Ipv4Addr};
+
+fn a_record(name: &str, address: [u8; 4]) -> DnsRecord {
+    DnsRecord::new_a(name.to_string(), Ipv4Addr::from(address))
+}
+
+#[test]
+fn test_nameservers_disagree() {
+    let first: IpAddr = "192.0.2.1".parse().unwrap();
+    let second: IpAddr = "192.0.2.2".parse().unwrap();
+
+    let answers = vec![
+        (first, vec![a_record("www.example.com", [203, 0, 113, 10])]),
+        (second, vec![a_record("www.example.com", [198, 51, 100, 66])]),
+    ];
+
+    assert!(answers_differ(&answers));
+    let findings = nameserver_findings("www.example.com", "A", &answers);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].severity, Severity::Medium);
+    assert_eq!(findings[0].category, "resolver-mismatch");
+    assert!(findings[0].message.contains("192.0.2.2"));
+}
+
+#[test]
+fn test_nameservers_agree_regardless_of_order_and_ttl() {
+    let first: IpAddr = "192.0.2.1".parse().unwrap();
+    let second: IpAddr = "192.0.2.2".parse().unwrap();
+
+    let mut later = a_record("www.example.com", [203, 0, 113, 10]);
+    later.ttl = Some(42);
+    let answers = vec![
+        (first, vec![a_record("www.example.com", [203, 0, 113, 10]), a_record("www.example.com", [203, 0, 113, 11])]),
+        (second, vec![a_record("www.example.com", [203, 0, 113, 11]), later]),
+    ];
+
+    assert!(!answers_differ(&answers));
+    assert!(nameserver_findings("www.example.com", "A", &answers).is_empty());
+}
+
+#[test]
+fn test_for_nameserver_keeps_single_server() {
+    let helper = DnsHelper::with_nameservers(vec!["192.0.2.1".parse().unwrap(), "192.0.2.2".parse().unwrap()]).unwrap();
+    assert_eq!(helper.nameserver_ips().len(), 2);
+
+    let single = helper.for_nameserver("192.0.2.2".parse().unwrap());
+    assert_eq!(single.nameserver_ips(), vec!["192.0.2.2".parse::<IpAddr>().unwrap()]);
+}
@@ -0,0 +1,51 @@
+//! Unit tests for NSEC3 hashing, using the RFC 5155 appendix A vectors
+
+use dnsrecon_rs::dns::nsec3::{crack, crack_records, nsec3_hash, parse_salt, Nsec3Params};
+use dnsrecon_rs::dns::record::DnsRecord;
+
+const SALT: &str = "aabbccdd";
+const ITERATIONS: u16 = 12;
+
+#[test]
+fn test_nsec3_hash_matches_rfc_vectors() {
+    let salt = parse_salt(SALT).unwrap();
+    assert_eq!(nsec3_hash("example", &salt, ITERATIONS).unwrap(), "0p9mhaveqvm6t7vbl5lop2u3t2rp3tom");
+    assert_eq!(nsec3_hash("a.example", &salt, ITERATIONS).unwrap(), "35mthgpgcu1qg68fab165klnsnk3dpvl");
+    assert_eq!(nsec3_hash("NS1.example.", &salt, ITERATIONS).unwrap(), "2t7b4g4vsa5smi47k61mv5bv1a22bojr");
+    assert_eq!(nsec3_hash("x.w.example", &salt, ITERATIONS).unwrap(), "b4um86eghhds6nea196smvmlo4ors995");
+}
+
+#[test]
+fn test_crack_maps_hashes_to_wordlist_names() {
+    let salt = parse_salt(SALT).unwrap();
+    let hashes = vec![
+        "0p9mhaveqvm6t7vbl5lop2u3t2rp3tom.example".to_string(),
+        "2T7B4G4VSA5SMI47K61MV5BV1A22BOJR.example.".to_string(),
+        "35mthgpgcu1qg68fab165klnsnk3dpvl.example".to_string(),
+    ];
+    let wordlist = vec!["www".to_string(), "ns1".to_string(), "mail".to_string()];
+
+    let cracked = crack(&hashes, &salt, ITERATIONS, &wordlist);
+    assert_eq!(cracked.len(), 2);
+    assert_eq!(cracked["0p9mhaveqvm6t7vbl5lop2u3t2rp3tom.example"], "example");
+    assert_eq!(cracked["2t7b4g4vsa5smi47k61mv5bv1a22bojr.example"], "ns1.example");
+}
+
+#[test]
+fn test_nsec3_params_from_presentation_and_wire_data() {
+    let params = Nsec3Params::parse("1 1 12 AABBCCDD 2vptu5timamqttgl4luu9kg21e0aor3s A RRSIG").unwrap();
+    assert_eq!(params, Nsec3Params { salt: vec![0xaa, 0xbb, 0xcc, 0xdd], iterations: 12 });
+    assert_eq!(Nsec3Params::parse("1 0 0 -").unwrap().salt, Vec::<u8>::new());
+    assert!(Nsec3Params::parse("2 0 12 AABBCCDD").is_none());
+
+    // algorithm 1, flags 1, 12 iterations, 4-byte salt
+    assert_eq!(Nsec3Params::parse("AQEADASqu8zd").unwrap(), params);
+
+    let records = vec![DnsRecord::new_other(
+        "35mthgpgcu1qg68fab165klnsnk3dpvl.example".to_string(),
+        "NSEC3".to_string(),
+        "1 1 12 aabbccdd b4um86eghhds6nea196smvmlo4ors995 NS DS RRSIG".to_string(),
+    )];
+    let cracked = crack_records(&records, &["a".to_string()]);
+    assert_eq!(cracked["35mthgpgcu1qg68fab165klnsnk3dpvl.example"], "a.example");
+}
@@ -1 +1,439 @@
-//! Unit tests for output functionality\n\nuse dnsrecon_rs::dns::record::DnsRecord;\nuse dnsrecon_rs::output;\nuse std::net::Ipv4Addr;\n\n#[test]\nfn test_json_output() {\n    let record = DnsRecord::new_a(\n        \"example.com\".to_string(),\n        Ipv4Addr::new(192, 168, 1, 1)\n    );\n    \n    let records = vec![record];\n    let json_string = output::json::to_json_string(&records);\n    \n    assert!(json_string.is_ok());\n    let json = json_string.unwrap();\n    println!(\"JSON output: {}\", json); // For debugging\n    assert!(json.contains(\"\\\"type\\\": \\\"A\\\"\") || json.contains(\"\\\"type\\\":\\\"A\\\"\"));\n    assert!(json.contains(\"\\\"name\\\": \\\"example.com\\\"\") || json.contains(\"\\\"name\\\":\\\"example.com\\\"\"));\n    // For A records, the IP address is nested in the \"data\" field\n    assert!(json.contains(\"\\\"data\\\"\") && json.contains(\"\\\"A\\\": \\\"192.168.1.1\\\"\") || json.contains(\"\\\"A\\\":\\\"192.168.1.1\\\"\"));\n}\n\n#[test]\nfn test_xml_output() {\n    let record = DnsRecord::new_a(\n        \"example.com\".to_string(),\n        Ipv4Addr::new(192, 168, 1, 1)\n    );\n    \n    let records = vec![record];\n    let xml_string = output::xml::to_xml_string(&records);\n    \n    assert!(xml_string.is_ok());\n    let xml = xml_string.unwrap();\n    println!(\"XML output: {}\", xml); // For debugging\n    assert!(xml.contains(\"<a>\"));\n    assert!(xml.contains(\"<name>example.com</name>\"));\n    assert!(xml.contains(\"<address>192.168.1.1</address>\"));\n}
\ No newline at end of file
+//! Unit tests for output formatting and export
+
+use dnsrecon_rs::dns::record::DnsRecord;
+use dnsrecon_rs::output;
+use std::fs;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::path::PathBuf;
+
+/// Build a unique path in the system temp directory for a test file
+fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("dnsrecon-rs-{}-{}", std::process::id(), name));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn test_append_json_creates_new_file() {
+    let path = temp_path("append-new.json");
+    let filename = path.to_str().unwrap();
+
+    let records = vec![
+        DnsRecord::new_a("example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+    ];
+
+    output::append_json(&records, filename, true).unwrap();
+
+    let written: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(written.as_array().unwrap().len(), 1);
+    assert_eq!(written[0]["name"], "example.com");
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_append_json_merges_into_existing_file() {
+    let path = temp_path("append-merge.json");
+    let filename = path.to_str().unwrap();
+
+    let first = vec![
+        DnsRecord::new_a("example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+        DnsRecord::new_mx("example.com".to_string(), 10, "mail.example.com".to_string()),
+    ];
+    output::format_json(&first, filename, true).unwrap();
+
+    // One duplicate and one new record
+    let second = vec![
+        DnsRecord::new_a("example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+        DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 2)),
+    ];
+    output::append_json(&second, filename, true).unwrap();
+
+    let written: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    let written = written.as_array().unwrap();
+    assert_eq!(written.len(), 3);
+    assert_eq!(written[2]["name"], "www.example.com");
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_append_json_handles_empty_and_corrupt_files() {
+    let path = temp_path("append-corrupt.json");
+    let filename = path.to_str().unwrap();
+    let records = vec![
+        DnsRecord::new_a("example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+    ];
+
+    // An empty file is treated as an empty array
+    fs::write(&path, "").unwrap();
+    output::append_json(&records, filename, true).unwrap();
+
+    // A corrupt file is backed up and replaced
+    fs::write(&path, "{ not json").unwrap();
+    output::append_json(&records, filename, true).unwrap();
+
+    let backup = format!("{}.bak", filename);
+    assert_eq!(fs::read_to_string(&backup).unwrap(), "{ not json");
+
+    let written: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(written.as_array().unwrap().len(), 1);
+
+    fs::remove_file(&path).unwrap();
+    fs::remove_file(&backup).unwrap();
+}
+
+#[test]
+fn test_compact_json_is_smaller_than_pretty() {
+    let records: Vec<DnsRecord> = (0..20)
+        .map(|i| DnsRecord::new_a(format!("host{}.example.com", i), Ipv4Addr::new(192, 0, 2, i)))
+        .collect();
+
+    let pretty = output::json::to_json_string(&records, true).unwrap();
+    let compact = output::json::to_json_string(&records, false).unwrap();
+    assert!(compact.len() < pretty.len());
+    assert!(!compact.contains('\n'));
+
+    // Both forms carry the same data
+    let pretty_value: serde_json::Value = serde_json::from_str(&pretty).unwrap();
+    let compact_value: serde_json::Value = serde_json::from_str(&compact).unwrap();
+    assert_eq!(pretty_value, compact_value);
+
+    let path = temp_path("compact.json");
+    let filename = path.to_str().unwrap();
+    output::format_json(&records, filename, false).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), compact);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_es_bulk_format() {
+    use dnsrecon_rs::output::elastic::{to_bulk_string, ScanMetadata};
+
+    let records = vec![
+        DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+        DnsRecord::new_a("mail.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 2)),
+    ];
+    let metadata = ScanMetadata::new("example.com");
+
+    let body = to_bulk_string(&records, "recon", &metadata).unwrap();
+    assert!(body.ends_with('\n'));
+
+    let lines: Vec<&str> = body.lines().collect();
+    assert_eq!(lines.len(), records.len() * 2);
+
+    for (pair, record) in lines.chunks(2).zip(&records) {
+        let action: serde_json::Value = serde_json::from_str(pair[0]).unwrap();
+        assert_eq!(action["index"]["_index"], "recon");
+
+        let document: serde_json::Value = serde_json::from_str(pair[1]).unwrap();
+        assert_eq!(document["name"], record.name.as_str());
+        assert_eq!(document["type"], "A");
+        assert_eq!(document["target"], "example.com");
+        assert_eq!(document["tool"], "dnsrecon-rs");
+        assert!(document["scan_time"].as_u64().is_some());
+    }
+}
+
+#[test]
+fn test_sqlite_sink_keeps_committed_records_after_interruption() {
+    use dnsrecon_rs::output::sqlite::SqliteSink;
+    use std::time::Duration;
+
+    let path = temp_path("incremental.db");
+    let filename = path.to_str().unwrap();
+
+    let mut sink = SqliteSink::open(filename).unwrap().with_commit_policy(2, Duration::from_secs(3600));
+    for i in 1..=3 {
+        sink.insert(&DnsRecord::new_a(format!("host{}.example.com", i), Ipv4Addr::new(192, 0, 2, i))).unwrap();
+    }
+    // The scan dies before the third record is committed
+    drop(sink);
+
+    let conn = rusqlite::Connection::open(filename).unwrap();
+    let names: Vec<String> = conn
+        .prepare("SELECT name FROM dns_records ORDER BY id")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(names, vec!["host1.example.com", "host2.example.com"]);
+
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn test_sqlite_writer_streams_from_channel() {
+    use dnsrecon_rs::output::sqlite::{spawn_writer, SqliteSink};
+    use std::sync::mpsc;
+
+    let path = temp_path("streamed.db");
+    let filename = path.to_str().unwrap();
+
+    let (record_tx, record_rx) = mpsc::channel();
+    let writer = spawn_writer(SqliteSink::open(filename).unwrap(), record_rx, None, None);
+    record_tx.send(DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1))).unwrap();
+    // Repeated records are written once
+    record_tx.send(DnsRecord::new_a("WWW.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1))).unwrap();
+    record_tx.send(DnsRecord::new_a("mail.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 2))).unwrap();
+    // Other records of a name already written are kept
+    record_tx.send(DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 3))).unwrap();
+    record_tx.send(DnsRecord::new_txt("www.example.com".to_string(), "v=spf1 -all".to_string())).unwrap();
+    // So is the same record found for another target domain
+    record_tx.send(DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)).with_domain("example.org")).unwrap();
+    drop(record_tx);
+
+    assert_eq!(writer.join().unwrap().unwrap(), 5);
+
+    let conn = rusqlite::Connection::open(filename).unwrap();
+    let count: i64 = conn.query_row("SELECT COUNT(*) FROM dns_records", [], |row| row.get(0)).unwrap();
+    assert_eq!(count, 5);
+
+    let _ = fs::remove_file(&path);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_write_outputs_concurrently() {
+    let json_path = temp_path("concurrent.json");
+    let xml_path = temp_path("concurrent.xml");
+    let sqlite_path = temp_path("concurrent.db");
+    let records = std::sync::Arc::new(vec![
+        DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+        DnsRecord::new_a("mail.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 2)),
+    ]);
+
+    let outputs = vec![
+        output::FileOutput::Json { filename: json_path.to_str().unwrap().to_string(), pretty: true, append: false },
+        output::FileOutput::Xml { filename: xml_path.to_str().unwrap().to_string() },
+        output::FileOutput::Sqlite { filename: sqlite_path.to_str().unwrap().to_string() },
+    ];
+    output::write_outputs(records, outputs).await.unwrap();
+
+    assert!(fs::read_to_string(&json_path).unwrap().contains("mail.example.com"));
+    assert!(fs::read_to_string(&xml_path).unwrap().contains("mail.example.com"));
+    assert!(fs::metadata(&sqlite_path).unwrap().len() > 0);
+
+    let _ = fs::remove_file(&json_path);
+    let _ = fs::remove_file(&xml_path);
+    let _ = fs::remove_file(&sqlite_path);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_write_outputs_reports_every_failure() {
+    let json_path = temp_path("partial-failure.json");
+    let missing_dir = std::env::temp_dir().join(format!("dnsrecon-rs-{}-missing-dir", std::process::id()));
+    let records = std::sync::Arc::new(vec![DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1))]);
+
+    let outputs = vec![
+        output::FileOutput::Xml { filename: missing_dir.join("out.xml").to_str().unwrap().to_string() },
+        output::FileOutput::Json { filename: json_path.to_str().unwrap().to_string(), pretty: false, append: false },
+        output::FileOutput::Sqlite { filename: missing_dir.join("out.db").to_str().unwrap().to_string() },
+    ];
+    let err = output::write_outputs(records, outputs).await.unwrap_err();
+
+    // Both failures are reported, and the writer that could succeed still did
+    match err {
+        output::OutputError::Multiple(ref errors) => assert_eq!(errors.len(), 2),
+        other => panic!("expected both failures, got {}", other),
+    }
+    assert!(err.to_string().contains("XML file"));
+    assert!(err.to_string().contains("SQLite database"));
+    assert!(fs::read_to_string(&json_path).unwrap().contains("www.example.com"));
+
+    let _ = fs::remove_file(&json_path);
+}
+
+#[test]
+fn test_failed_write_leaves_original_file_untouched() {
+    let path = temp_path("atomic.json");
+    let filename = path.to_str().unwrap();
+    let tmp_path = PathBuf::from(format!("{}.tmp", filename));
+
+    let records = vec![DnsRecord::new_a("example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1))];
+    output::format_json(&records, filename, false).unwrap();
+    let original = fs::read_to_string(&path).unwrap();
+    assert!(!tmp_path.exists());
+
+    // Serialization fails halfway through the new file
+    let result = output::write_atomically(filename, |tmp_filename| {
+        fs::write(tmp_filename, "[{\"type\":")?;
+        Err(output::OutputError::Other("serialization failed".to_string()))
+    });
+
+    assert!(result.is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), original);
+    assert!(!tmp_path.exists());
+
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn test_sqlite_output_keeps_existing_records() {
+    let path = temp_path("atomic.db");
+    let filename = path.to_str().unwrap();
+
+    output::export_sqlite(&[DnsRecord::new_a("a.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1))], filename).unwrap();
+    output::export_sqlite(&[DnsRecord::new_a("b.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 2))], filename).unwrap();
+    assert!(!PathBuf::from(format!("{}.tmp", filename)).exists());
+
+    let conn = rusqlite::Connection::open(filename).unwrap();
+    let count: i64 = conn.query_row("SELECT COUNT(*) FROM dns_records", [], |row| row.get(0)).unwrap();
+    assert_eq!(count, 2);
+
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn test_zone_file_output_is_a_valid_master_file() {
+    let mut www = DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1));
+    www.ttl = Some(300);
+    let records = vec![
+        www,
+        DnsRecord::new_mx("example.com".to_string(), 10, "mail.example.com".to_string()),
+        DnsRecord::new_txt("example.com".to_string(), "say \"hi\" \\ bye\u{7}".to_string()),
+        DnsRecord::new_txt("long.example.com".to_string(), "x".repeat(300)),
+        DnsRecord::new_cname("docs.example.com".to_string(), "hosting.example.net".to_string()),
+        DnsRecord::new_srv("_sip._tcp.example.com".to_string(), 10, 60, 5060, "sip.example.com".to_string()),
+        DnsRecord::new_soa(
+            "example.com".to_string(),
+            "ns1.example.com".to_string(),
+            "hostmaster.example.com".to_string(),
+            2024010101,
+            3600,
+            600,
+            604800,
+            300,
+        ),
+    ];
+
+    let path = temp_path("zone.db");
+    let filename = path.to_str().unwrap();
+    output::zonefile::write_zonefile(&records, "example.com", filename).unwrap();
+    let zone = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let lines: Vec<&str> = zone.lines().collect();
+    assert_eq!(lines[0], "$ORIGIN example.com.");
+    assert_eq!(lines[1], "$TTL 3600");
+    assert_eq!(lines.len(), records.len() + 2);
+
+    // Every record line has an owner, TTL, class, type and data
+    for line in &lines[2..] {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        assert!(fields.len() >= 5, "short line: {}", line);
+        assert!(fields[1].parse::<u32>().is_ok(), "bad TTL: {}", line);
+        assert_eq!(fields[2], "IN");
+    }
+
+    assert_eq!(lines[2], "@ 3600 IN SOA ns1.example.com. hostmaster.example.com. 2024010101 3600 600 604800 300");
+    assert!(lines.contains(&"www 300 IN A 192.0.2.1"));
+    assert!(lines.contains(&"@ 3600 IN MX 10 mail.example.com."));
+    assert!(lines.contains(&"docs 3600 IN CNAME hosting.example.net."));
+    assert!(lines.contains(&"_sip._tcp 3600 IN SRV 10 60 5060 sip.example.com."));
+    assert!(lines.contains(&r#"@ 3600 IN TXT "say \"hi\" \\ bye\007""#));
+
+    // Long TXT data is split into 255-byte character-strings
+    let long = lines.iter().find(|line| line.starts_with("long ")).unwrap();
+    assert!(long.ends_with(&format!("\"{}\" \"{}\"", "x".repeat(255), "x".repeat(45))));
+}
+
+#[test]
+fn test_write_csv_has_header_and_one_row_per_record() {
+    let records = vec![
+        DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)).with_ttl(300),
+        DnsRecord::new_mx("example.com".to_string(), 10, "mail.example.com".to_string()),
+        DnsRecord::new_txt("example.com".to_string(), "v=spf1 a, mx \"soft\"".to_string()),
+    ];
+
+    let path = temp_path("results.csv");
+    let filename = path.to_str().unwrap();
+    output::csv::write_csv(&records, filename).unwrap();
+    let csv = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "type,name,ttl,value");
+    assert_eq!(lines[1], "A,www.example.com,300,192.0.2.1");
+    assert_eq!(lines[2], "MX,example.com,,preference=10;exchange=mail.example.com");
+    assert_eq!(lines[3], r#"TXT,example.com,,"v=spf1 a, mx ""soft""""#);
+    assert_eq!(lines.len(), records.len() + 1);
+}
+
+#[test]
+fn test_text_output_aligns_one_record_per_line() {
+    let records = vec![
+        DnsRecord::new_a("example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+        DnsRecord::new_mx("example.com".to_string(), 10, "mail.example.com".to_string()),
+        DnsRecord::new_cname("www.example.com".to_string(), "example.com".to_string()),
+    ];
+
+    let text = output::text::to_text_string(&records);
+    let lines: Vec<&str> = text.lines().collect();
+
+    assert_eq!(lines.len(), records.len());
+    assert_eq!(lines[0], "A      example.com      192.0.2.1");
+    assert_eq!(lines[1], "MX     example.com      10 mail.example.com");
+    assert_eq!(lines[2], "CNAME  www.example.com  example.com");
+
+    // The same formats are selectable by name for stdout
+    let format: output::StdoutFormat = "text".parse().unwrap();
+    assert_eq!(format.render(&records, true).unwrap(), text);
+    assert!("yaml".parse::<output::StdoutFormat>().is_err());
+}
+
+#[test]
+fn test_domain_is_written_to_json_xml_and_sqlite() {
+    let records = vec![
+        DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)).with_domain("example.com"),
+        DnsRecord::new_a("reverse.example.net".to_string(), Ipv4Addr::new(192, 0, 2, 2)),
+    ];
+
+    let json: serde_json::Value = serde_json::from_str(&output::json::to_json_string(&records, true).unwrap()).unwrap();
+    assert_eq!(json[0]["domain"], "example.com");
+    // Records found without a target domain leave the key out
+    assert!(json[1].get("domain").is_none());
+
+    let xml = output::xml::to_xml_string(&records).unwrap();
+    assert_eq!(xml.matches("<domain>example.com</domain>").count(), 1);
+
+    let path = temp_path("domain.db");
+    let filename = path.to_str().unwrap();
+    output::export_sqlite(&records, filename).unwrap();
+    let conn = rusqlite::Connection::open(filename).unwrap();
+    let domains: Vec<Option<String>> = conn
+        .prepare("SELECT domain FROM dns_records ORDER BY id")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(domains, vec![Some("example.com".to_string()), None]);
+
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn test_read_json_returns_the_records_written() {
+    let mut with_whois = DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1))
+        .with_domain("example.com")
+        .with_ttl(300);
+    with_whois.whois = Some(dnsrecon_rs::enumerate::whois::WhoisInfo {
+        org_name: Some("Example Org".to_string()),
+        ..Default::default()
+    });
+    let records = vec![
+        with_whois,
+        DnsRecord::new_aaaa("www.example.com".to_string(), "2001:db8::1".parse::<Ipv6Addr>().unwrap()),
+        DnsRecord::new_mx("example.com".to_string(), 10, "mail.example.com".to_string()).with_origin("ns-infrastructure"),
+        DnsRecord::new_srv("_sip._tcp.example.com".to_string(), 10, 60, 5060, "sip.example.com".to_string()),
+        DnsRecord::new_other("example.com".to_string(), "TYPE65534".to_string(), "\\# 4 0102".to_string()),
+    ];
+
+    let path = temp_path("read-back.json");
+    let filename = path.to_str().unwrap();
+    output::json::write_json(&records, filename, true).unwrap();
+    assert_eq!(output::json::read_json(filename).unwrap(), records);
+
+    let _ = fs::remove_file(&path);
+}
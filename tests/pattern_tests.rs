@@ -0,0 +1,47 @@
+//! Unit tests for brute force label patterns
+
+use dnsrecon_rs::enumerate::brute_force::{candidate_words, BruteForceContext};
+use dnsrecon_rs::enumerate::pattern::{expand_pattern, expand_patterns};
+
+#[test]
+fn test_numeric_range_keeps_zero_padding() {
+    assert_eq!(expand_pattern("web[01-03]").unwrap(), vec!["web01", "web02", "web03"]);
+    assert_eq!(expand_pattern("app[8-11]").unwrap(), vec!["app8", "app9", "app10", "app11"]);
+    assert_eq!(expand_pattern("[098-100]-db").unwrap(), vec!["098-db", "099-db", "100-db"]);
+}
+
+#[test]
+fn test_character_classes_and_combinations() {
+    assert_eq!(expand_pattern("db-[a-c]").unwrap(), vec!["db-a", "db-b", "db-c"]);
+    assert_eq!(expand_pattern("[xz][1-2]").unwrap(), vec!["x1", "x2", "z1", "z2"]);
+    assert_eq!(expand_pattern("plain").unwrap(), vec!["plain"]);
+
+    // Labels shared between patterns are kept once
+    let patterns = vec!["web[1-2]".to_string(), "web[2-3]".to_string()];
+    assert_eq!(expand_patterns(&patterns).unwrap(), vec!["web1", "web2", "web3"]);
+}
+
+#[test]
+fn test_invalid_patterns_are_rejected() {
+    assert!(expand_pattern("web[01-03").is_err());
+    assert!(expand_pattern("web01]").is_err());
+    assert!(expand_pattern("web[]").is_err());
+    assert!(expand_pattern("web[50-01]").is_err());
+    assert!(expand_pattern("[0-999][0-999]").is_err());
+}
+
+#[test]
+fn test_patterns_feed_brute_force_candidates() {
+    let context = BruteForceContext {
+        patterns: vec!["web[01-03]".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(candidate_words(None, &context).unwrap(), vec!["web01", "web02", "web03"]);
+
+    // Pattern labels already in the wordlist aren't tried twice
+    let path = std::env::temp_dir().join(format!("dnsrecon-rs-{}-pattern-words.txt", std::process::id()));
+    std::fs::write(&path, "www\nweb02\n").unwrap();
+    let words = candidate_words(Some(path.to_str().unwrap()), &context).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(words, vec!["www", "web02", "web01", "web03"]);
+}
@@ -0,0 +1,128 @@
+//! Tests for progress reporting
+
+mod common;
+
+use dnsrecon_rs::cli::progress::{ProgressReporter, TimedProgressReporter};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Writer that appends into a shared buffer, written in small pieces to invite interleaving
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = buf.len().min(3);
+        self.0.lock().unwrap().extend_from_slice(&buf[..n]);
+        thread::yield_now();
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_concurrent_progress_lines_do_not_interleave() {
+    let buffer = SharedBuffer::default();
+    let progress = Arc::new(TimedProgressReporter::with_writer(buffer.clone()));
+
+    let threads: Vec<_> = (0..16)
+        .map(|task| {
+            let progress = progress.clone();
+            thread::spawn(move || {
+                for message in 0..100 {
+                    if message % 2 == 0 {
+                        progress.update(&format!("task {} message {}", task, message));
+                    } else {
+                        progress.error(&format!("task {} message {}", task, message));
+                    }
+                }
+            })
+        })
+        .collect();
+    for handle in threads {
+        handle.join().unwrap();
+    }
+
+    let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    let line_format = regex::Regex::new(r"^\[[*!]\] \[\d+\.\d{2}s\] task \d+ message \d+$").unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+
+    assert_eq!(lines.len(), 16 * 100);
+    for line in lines {
+        assert!(line_format.is_match(line), "garbled line: {:?}", line);
+    }
+}
+
+/// Reporter that records every message it receives
+#[derive(Default)]
+struct RecordingReporter(Mutex<Vec<String>>);
+
+impl ProgressReporter for RecordingReporter {
+    fn update(&self, message: &str) {
+        self.0.lock().unwrap().push(format!("update: {}", message));
+    }
+
+    fn finish(&self, message: &str) {
+        self.0.lock().unwrap().push(format!("finish: {}", message));
+    }
+
+    fn error(&self, message: &str) {
+        self.0.lock().unwrap().push(format!("error: {}", message));
+    }
+
+    fn summary(&self, text: &str) {
+        self.0.lock().unwrap().push(format!("summary: {}", text.trim()));
+    }
+}
+
+#[test]
+fn test_print_summary_goes_through_the_reporter() {
+    use dnsrecon_rs::cli::summary::{print_summary, ScanSummary};
+    use dnsrecon_rs::dns::record::DnsRecord;
+    use std::net::Ipv4Addr;
+
+    let records = vec![DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1))];
+    let reporter = RecordingReporter::default();
+    print_summary(&ScanSummary::default(), &records, false, &reporter);
+
+    let messages = reporter.0.into_inner().unwrap();
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].starts_with("summary: Summary"));
+    assert!(messages[0].contains("Records:      1"));
+}
+
+#[tokio::test]
+async fn test_run_with_reporter_sends_progress_to_the_caller() {
+    use clap::Parser;
+    use dnsrecon_rs::cli::Args;
+
+    // A nameserver that answers every query with no records
+    let server = common::spawn_dns_server(|_, _| {});
+
+    let args = Args::parse_from([
+        "dnsrecon-rs", "-d", "example.com", "--sources", "dns", "-n", &server.to_string(), "--no-color",
+    ]);
+    let reporter = RecordingReporter::default();
+    dnsrecon_rs::run_with_reporter(args, &reporter).await.unwrap();
+
+    let messages = reporter.0.into_inner().unwrap();
+    assert_eq!(
+        messages[..4],
+        [
+            "update: Starting DNS enumeration",
+            "update: Setting up DNS resolver",
+            "update: Using 1 nameservers",
+            "update: Performing standard enumeration for domain: example.com",
+        ]
+    );
+    let completed = messages.iter().position(|message| message == "update: Enumeration completed. Found 0 records");
+    let stdout = messages.iter().position(|message| message == "update: Writing results to stdout");
+    assert!(completed.unwrap() < stdout.unwrap());
+    let summary = messages.iter().position(|message| message.starts_with("summary: Summary"));
+    assert_eq!(summary, Some(messages.len() - 2));
+    assert!(messages.last().unwrap().starts_with("finish: DNS enumeration completed successfully in "));
+}
@@ -0,0 +1,81 @@
+//! Unit tests for the forward/reverse DNS consistency audit
+
+use dnsrecon_rs::dns::record::DnsRecord;
+use dnsrecon_rs::enumerate::rdns_audit::audit_with;
+use std::net::{IpAddr, Ipv4Addr};
+
+fn ptr(ip: IpAddr, target: &str) -> Option<Vec<DnsRecord>> {
+    Some(vec![DnsRecord::new_ptr(ip.to_string(), target.to_string())])
+}
+
+fn address(name: &str, ip: Ipv4Addr) -> Option<Vec<DnsRecord>> {
+    Some(vec![DnsRecord::new_a(name.to_string(), ip)])
+}
+
+#[test]
+fn test_matched_pairs_have_no_findings() {
+    let records = vec![
+        DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+        DnsRecord::new_ptr("192.0.2.1".to_string(), "WWW.example.com.".to_string()),
+    ];
+
+    let findings = audit_with(
+        &records,
+        |ip| ptr(ip, "www.example.com."),
+        |name| address(name, Ipv4Addr::new(192, 0, 2, 1)),
+    );
+    assert!(findings.is_empty());
+}
+
+#[test]
+fn test_mismatched_and_missing_reverse_records() {
+    let records = vec![
+        DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+        DnsRecord::new_a("mail.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 2)),
+    ];
+
+    let findings = audit_with(
+        &records,
+        |ip| match ip.to_string().as_str() {
+            "192.0.2.1" => ptr(ip, "host-1.provider.example"),
+            _ => Some(Vec::new()),
+        },
+        |_| Some(Vec::new()),
+    );
+
+    assert_eq!(findings.len(), 2);
+    assert_eq!(findings[0].subject, "mail.example.com");
+    assert!(findings[0].message.contains("no PTR"));
+    assert_eq!(findings[1].subject, "www.example.com");
+    assert!(findings[1].message.contains("host-1.provider.example"));
+}
+
+#[test]
+fn test_ptr_targets_must_resolve_back() {
+    let records = vec![
+        DnsRecord::new_ptr("192.0.2.1".to_string(), "moved.example.com".to_string()),
+        DnsRecord::new_ptr("192.0.2.2".to_string(), "gone.example.com".to_string()),
+    ];
+
+    let findings = audit_with(&records, |_| Some(Vec::new()), |name| match name {
+        "moved.example.com" => address(name, Ipv4Addr::new(198, 51, 100, 7)),
+        _ => Some(Vec::new()),
+    });
+
+    assert_eq!(findings.len(), 2);
+    assert_eq!(findings[0].subject, "192.0.2.1");
+    assert!(findings[0].message.contains("198.51.100.7"));
+    assert_eq!(findings[1].subject, "192.0.2.2");
+    assert!(findings[1].message.contains("does not resolve"));
+}
+
+#[test]
+fn test_failed_lookups_are_inconclusive() {
+    let records = vec![
+        DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+        DnsRecord::new_ptr("192.0.2.2".to_string(), "mail.example.com".to_string()),
+    ];
+
+    let findings = audit_with(&records, |_| None, |_| None);
+    assert!(findings.is_empty());
+}
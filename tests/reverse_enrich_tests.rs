@@ -0,0 +1,50 @@
+//! Unit tests for forward-resolving PTR targets found in reverse mode
+
+use dnsrecon_rs::dns::record::{DnsRecord, RecordData};
+use dnsrecon_rs::enumerate::reverse_enrich::{enrich_with, ptr_targets, REVERSE_ENRICH_ORIGIN};
+use std::cell::RefCell;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+#[test]
+fn test_ptr_targets_are_normalized_and_deduplicated() {
+    let records = vec![
+        DnsRecord::new_ptr("192.0.2.1".to_string(), "Web.Example.com.".to_string()),
+        DnsRecord::new_ptr("192.0.2.2".to_string(), "web.example.com".to_string()),
+        DnsRecord::new_a("web.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+    ];
+
+    assert_eq!(ptr_targets(&records).into_iter().collect::<Vec<_>>(), vec!["web.example.com"]);
+}
+
+#[test]
+fn test_ptr_targets_are_forward_resolved() {
+    let records = vec![
+        DnsRecord::new_ptr("192.0.2.1".to_string(), "web.example.com.".to_string()),
+        DnsRecord::new_ptr("192.0.2.2".to_string(), "WEB.example.com".to_string()),
+        DnsRecord::new_ptr("192.0.2.9".to_string(), "gone.example.com".to_string()),
+    ];
+    let queried = RefCell::new(Vec::new());
+
+    let enriched = enrich_with(&records, |target| {
+        queried.borrow_mut().push(target.to_string());
+        match target {
+            "web.example.com" => vec![
+                DnsRecord::new_cname("web.example.com".to_string(), "lb.example.net".to_string()),
+                DnsRecord::new_a("web.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+                DnsRecord::new_a("web.example.com".to_string(), Ipv4Addr::new(198, 51, 100, 7)),
+                DnsRecord::new_aaaa("web.example.com".to_string(), Ipv6Addr::LOCALHOST),
+            ],
+            _ => Vec::new(),
+        }
+    });
+
+    // Each target is resolved once, even when several PTRs point at it
+    assert_eq!(*queried.borrow(), vec!["gone.example.com", "web.example.com"]);
+
+    assert_eq!(enriched.len(), 4);
+    assert!(enriched.iter().all(|record| record.origin.as_deref() == Some(REVERSE_ENRICH_ORIGIN)));
+    assert!(matches!(&enriched[0].data, RecordData::Cname(target) if target == "lb.example.net"));
+    assert!(enriched
+        .iter()
+        .any(|record| matches!(record.data, RecordData::A(ip) if ip == Ipv4Addr::new(198, 51, 100, 7))));
+}
@@ -0,0 +1,256 @@
+//! Unit tests for reverse query name generation
+
+mod common;
+
+use dnsrecon_rs::utils::reverse::{ip_from_reverse_name, is_large_network, ptr_names_for_cidr, reverse_name};
+use ipnetwork::IpNetwork;
+use std::net::IpAddr;
+
+#[test]
+fn test_ptr_names_for_slash_30() {
+    let network: IpNetwork = "192.0.2.0/30".parse().unwrap();
+    let names: Vec<String> = ptr_names_for_cidr(network).collect();
+
+    assert_eq!(
+        names,
+        vec![
+            "0.2.0.192.in-addr.arpa",
+            "1.2.0.192.in-addr.arpa",
+            "2.2.0.192.in-addr.arpa",
+            "3.2.0.192.in-addr.arpa",
+        ]
+    );
+}
+
+#[test]
+fn test_ptr_names_are_lazy() {
+    // A /8 is never expanded up front
+    let network: IpNetwork = "10.0.0.0/8".parse().unwrap();
+    let first: Vec<String> = ptr_names_for_cidr(network).take(2).collect();
+    assert_eq!(first, vec!["0.0.0.10.in-addr.arpa", "1.0.0.10.in-addr.arpa"]);
+}
+
+#[test]
+fn test_reverse_name_round_trip() {
+    let ip: IpAddr = "2001:db8::1".parse().unwrap();
+    let name = reverse_name(ip);
+    assert_eq!(
+        name,
+        "1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa"
+    );
+    assert_eq!(ip_from_reverse_name(&name), Some(ip));
+
+    let ip: IpAddr = "192.0.2.1".parse().unwrap();
+    assert_eq!(ip_from_reverse_name("1.2.0.192.in-addr.arpa."), Some(ip));
+    assert_eq!(ip_from_reverse_name("example.com"), None);
+}
+
+#[test]
+fn test_is_large_network() {
+    assert!(is_large_network(&"10.0.0.0/8".parse().unwrap()));
+    assert!(!is_large_network(&"192.0.2.0/24".parse().unwrap()));
+    assert!(!is_large_network(&"10.0.0.0/16".parse().unwrap()));
+    assert!(is_large_network(&"2001:db8::/64".parse().unwrap()));
+    assert_eq!(dnsrecon_rs::utils::cidr::host_bits(&"2001:db8::/64".parse().unwrap()), 64);
+}
+
+#[test]
+fn test_load_ranges_from_file_skips_bad_lines() {
+    use dnsrecon_rs::load_ranges_from_file;
+    use dnsrecon_rs::utils::cidr::DEFAULT_MAX_RANGE_SIZE;
+
+    let path = std::env::temp_dir().join(format!("dnsrecon-rs-{}-ranges.txt", std::process::id()));
+    std::fs::write(
+        &path,
+        "# office networks\n192.0.2.0/30\n\n198.51.100.10-198.51.100.12\n203.0.113.7\nnot-a-range\n192.0.2.1\n",
+    )
+    .unwrap();
+
+    let mut errors = Vec::new();
+    let ips = load_ranges_from_file(path.to_str().unwrap(), DEFAULT_MAX_RANGE_SIZE, |error| errors.push(error)).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let expected: Vec<IpAddr> = [
+        "192.0.2.0",
+        "192.0.2.1",
+        "192.0.2.2",
+        "192.0.2.3",
+        "198.51.100.10",
+        "198.51.100.11",
+        "198.51.100.12",
+        "203.0.113.7",
+    ]
+    .iter()
+    .map(|ip| ip.parse().unwrap())
+    .collect();
+    assert_eq!(ips, expected);
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("line 6"), "{}", errors[0]);
+
+    assert!(load_ranges_from_file("nonexistent_ranges.txt", DEFAULT_MAX_RANGE_SIZE, |_| {}).is_err());
+}
+
+#[tokio::test]
+async fn test_sweep_keeps_lookups_under_the_concurrency_cap_and_drops_misses() {
+    use dnsrecon_rs::dns::record::DnsRecord;
+    use dnsrecon_rs::enumerate::reverse_sweep::sweep;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let ips: Vec<IpAddr> = ["127.0.0.1", "192.0.2.1", "192.0.2.2", "198.51.100.1", "203.0.113.1", "::1", "2001:db8::1"]
+        .iter()
+        .map(|ip| ip.parse().unwrap())
+        .collect();
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+
+    let mut progress = Vec::new();
+    let results = sweep(
+        ips.clone(),
+        3,
+        |ip| {
+            let in_flight = in_flight.clone();
+            let peak = peak.clone();
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                // IPv6 lookups finish first
+                for _ in 0..if ip.is_ipv4() { 3 } else { 1 } {
+                    tokio::task::yield_now().await;
+                }
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                // Loopback addresses have no PTR records
+                if ip.is_loopback() {
+                    return (Some(ip), Vec::new());
+                }
+                (Some(ip), vec![DnsRecord::new_ptr(ip.to_string(), "host.example.com".to_string())])
+            }
+        },
+        |completed, found| progress.push((completed, found)),
+    )
+    .await;
+
+    assert_eq!(peak.load(Ordering::SeqCst), 3);
+    let (completed, results) = results;
+    assert_eq!(completed, 7);
+    let mut swept: Vec<IpAddr> = results.iter().filter_map(|(ip, _)| *ip).collect();
+    swept.sort();
+    let mut expected: Vec<IpAddr> = ips.iter().copied().filter(|ip| !ip.is_loopback()).collect();
+    expected.sort();
+    assert_eq!(swept, expected);
+    assert_eq!(progress.last(), Some(&(7, 5)));
+}
+
+#[tokio::test]
+async fn test_reverse_lookup_uses_the_configured_nameserver() {
+    use dnsrecon_rs::cli::progress::TimedProgressReporter;
+    use dnsrecon_rs::config::ReconConfig;
+    use dnsrecon_rs::dns::record::RecordData;
+    use dnsrecon_rs::dns::resolver::DnsHelper;
+    use dnsrecon_rs::perform_reverse_lookup;
+    use std::sync::Arc;
+    use trust_dns_resolver::proto::rr::rdata::PTR;
+    use trust_dns_resolver::proto::rr::{Name, RData, Record};
+
+    // An internal nameserver that knows the PTR of every address it is asked about
+    let server = common::spawn_dns_server(|query, response| {
+        let target = Name::from_ascii("host.internal.example.").unwrap();
+        response.add_answer(Record::from_rdata(query.name().clone(), 300, RData::PTR(PTR(target))));
+    });
+
+    let helper = Arc::new(DnsHelper::with_nameserver_addrs(vec![server]).unwrap());
+    assert_eq!(helper.config().name_servers()[0].socket_addr, server);
+
+    let config = ReconConfig::default();
+    let progress = TimedProgressReporter::with_writer(std::io::sink());
+    let records = perform_reverse_lookup(helper, "192.0.2.1-192.0.2.2", &config, &progress).await.unwrap();
+
+    let mut names: Vec<&str> = records.iter().map(|record| record.name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, vec!["192.0.2.1", "192.0.2.2"]);
+    assert!(records.iter().all(|record| record.data == RecordData::Ptr("host.internal.example".to_string())));
+}
+
+#[tokio::test]
+async fn test_reverse_sweep_of_a_slash_64_fails_on_the_range_size() {
+    use clap::Parser;
+    use dnsrecon_rs::cli::progress::TimedProgressReporter;
+    use dnsrecon_rs::cli::Args;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    // A nameserver that counts queries; the sweep must fail before sending any
+    let queries = Arc::new(AtomicUsize::new(0));
+    let seen = queries.clone();
+    let server = common::spawn_dns_server(move |_, _| {
+        seen.fetch_add(1, Ordering::SeqCst);
+    });
+
+    let args = Args::parse_from([
+        "dnsrecon-rs", "-t", "reverse", "-r", "2001:db8::/64", "-n", &server.to_string(), "--no-color",
+    ]);
+    let progress = TimedProgressReporter::with_writer(std::io::sink());
+    let err = dnsrecon_rs::run_with_reporter(args, &progress).await.unwrap_err();
+
+    assert!(err.to_string().contains("more than the limit of 65536"), "{}", err);
+    assert_eq!(queries.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn test_ipv6_ranges_expand_to_every_address() {
+    use dnsrecon_rs::utils::cidr::{process_range, DEFAULT_MAX_RANGE_SIZE};
+
+    let ips = process_range("2001:db8::fe-2001:db8::1:1", DEFAULT_MAX_RANGE_SIZE).unwrap();
+    assert_eq!(ips.len(), 0x1_0001 - 0xfe + 1);
+    assert_eq!(ips[0], "2001:db8::fe".parse::<IpAddr>().unwrap());
+    assert_eq!(ips[2], "2001:db8::100".parse::<IpAddr>().unwrap());
+    assert_eq!(*ips.last().unwrap(), "2001:db8::1:1".parse::<IpAddr>().unwrap());
+
+    let ips = process_range("2001:db8::/120", DEFAULT_MAX_RANGE_SIZE).unwrap();
+    assert_eq!(ips.len(), 256);
+
+    assert!(process_range("2001:db8::10-2001:db8::1", DEFAULT_MAX_RANGE_SIZE).is_err());
+    assert!(process_range("192.0.2.1-2001:db8::1", DEFAULT_MAX_RANGE_SIZE).is_err());
+}
+
+#[test]
+fn test_ranges_over_the_size_limit_are_rejected() {
+    use dnsrecon_rs::utils::cidr::{process_range, DEFAULT_MAX_RANGE_SIZE};
+
+    let err = process_range("2001:db8::/64", DEFAULT_MAX_RANGE_SIZE).unwrap_err();
+    assert!(err.to_string().contains("18446744073709551616 addresses"), "{}", err);
+    assert!(process_range("::/0", DEFAULT_MAX_RANGE_SIZE).is_err());
+    assert!(process_range("2001:db8::-2001:db8::1:0", DEFAULT_MAX_RANGE_SIZE).is_err());
+
+    // The limit itself is allowed
+    assert_eq!(process_range("10.0.0.0/16", DEFAULT_MAX_RANGE_SIZE).unwrap().len(), DEFAULT_MAX_RANGE_SIZE);
+    assert_eq!(process_range("10.0.0.0-10.0.0.9", 10).unwrap().len(), 10);
+    assert!(process_range("10.0.0.0-10.0.0.10", 10).is_err());
+}
+
+#[test]
+fn test_comma_separated_ranges_are_merged_without_duplicates() {
+    use dnsrecon_rs::utils::cidr::{process_ranges, split_ranges, DEFAULT_MAX_RANGE_SIZE};
+
+    let segments = split_ranges("10.0.0.0/30,10.0.0.2/31, 10.0.0.3-10.0.0.5,");
+    assert_eq!(segments, vec!["10.0.0.0/30", "10.0.0.2/31", "10.0.0.3-10.0.0.5"]);
+
+    let ips = process_ranges(&segments, DEFAULT_MAX_RANGE_SIZE).unwrap();
+    let expected: Vec<IpAddr> = (0..=5).map(|i| format!("10.0.0.{}", i).parse().unwrap()).collect();
+    assert_eq!(ips, expected);
+
+    let err = process_ranges(&split_ranges("10.0.0.0/30,10.0.0.300/31"), DEFAULT_MAX_RANGE_SIZE).unwrap_err();
+    assert!(err.to_string().contains("'10.0.0.300/31'"), "{}", err);
+}
+
+#[test]
+fn test_networks_inside_another_are_dropped() {
+    use dnsrecon_rs::utils::cidr::outermost_networks;
+
+    let networks: Vec<IpNetwork> = ["10.1.0.0/15", "10.0.0.0/8", "172.16.0.0/12", "10.0.0.0/8", "2001:db8::/32"]
+        .iter()
+        .map(|network| network.parse().unwrap())
+        .collect();
+    let outermost: Vec<String> = outermost_networks(networks).iter().map(IpNetwork::to_string).collect();
+    assert_eq!(outermost, vec!["10.0.0.0/8", "172.16.0.0/12", "2001:db8::/32"]);
+}
@@ -0,0 +1,89 @@
+//! Unit tests for SRV service enumeration
+
+mod common;
+
+use dnsrecon_rs::dns::record::{RecordData, RecordType};
+use dnsrecon_rs::dns::resolver::DnsHelper;
+use dnsrecon_rs::enumerate::srv::{enumerate_services, COMMON_SERVICES};
+use std::collections::HashSet;
+
+#[test]
+fn test_common_services_are_service_and_protocol_labels() {
+    let mut seen = HashSet::new();
+    for service in COMMON_SERVICES {
+        let labels: Vec<&str> = service.split('.').collect();
+        assert_eq!(labels.len(), 2, "{}", service);
+        assert!(labels[0].len() > 1 && labels[0].starts_with('_'), "{}", service);
+        assert!(["_tcp", "_udp", "_tls"].contains(&labels[1]), "{}", service);
+        assert_eq!(service.to_lowercase(), *service);
+        assert!(seen.insert(service), "{} listed twice", service);
+    }
+    for service in ["_sip._tcp", "_sips._tcp", "_ldap._tcp", "_kerberos._udp", "_xmpp-client._tcp", "_autodiscover._tcp", "_caldav._tcp"] {
+        assert!(COMMON_SERVICES.contains(&service), "{} missing", service);
+    }
+}
+
+#[tokio::test]
+async fn test_enumerate_services_skips_services_that_do_not_exist() {
+    use trust_dns_resolver::proto::op::ResponseCode;
+    use trust_dns_resolver::proto::rr::rdata::SRV;
+    use trust_dns_resolver::proto::rr::{Name, RData, Record};
+
+    // Only _sip._tcp and _ldap._tcp exist; every other name is NXDOMAIN
+    let server = common::spawn_dns_server(|query, response| {
+        let target = match query.name().to_ascii().to_lowercase().as_str() {
+            "_sip._tcp.example.com." => Some((5060, "sip.example.com.")),
+            "_ldap._tcp.example.com." => Some((389, "dc1.example.com.")),
+            _ => None,
+        };
+        match target {
+            Some((port, target)) => {
+                let data = RData::SRV(SRV::new(10, 60, port, Name::from_ascii(target).unwrap()));
+                response.add_answer(Record::from_rdata(query.name().clone(), 300, data));
+            }
+            None => {
+                response.set_response_code(ResponseCode::NXDomain);
+            }
+        }
+    });
+
+    let helper = DnsHelper::with_nameserver_addrs(vec![server]).unwrap();
+    let records = enumerate_services("example.com", &helper, 4).await;
+
+    assert_eq!(records.len(), 2);
+    assert!(records.iter().all(|record| record.record_type == RecordType::Srv));
+    let found: Vec<(&str, u16, &str)> = records
+        .iter()
+        .filter_map(|record| match record.data {
+            RecordData::Srv { port, ref target, .. } => Some((record.name.as_str(), port, target.as_str())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(found, vec![
+        ("_sip._tcp.example.com", 5060, "sip.example.com"),
+        ("_ldap._tcp.example.com", 389, "dc1.example.com"),
+    ]);
+}
+
+#[tokio::test]
+async fn test_enumerate_services_keeps_lookups_under_the_concurrency_cap() {
+    use dnsrecon_rs::enumerate::srv::enumerate_services_with;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let in_flight = AtomicUsize::new(0);
+    let peak = AtomicUsize::new(0);
+    let records = enumerate_services_with("example.com", COMMON_SERVICES, 3, |_| {
+        let (in_flight, peak) = (&in_flight, &peak);
+        async move {
+            let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            tokio::task::yield_now().await;
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(Vec::new())
+        }
+    })
+    .await;
+
+    assert!(records.is_empty());
+    assert_eq!(peak.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,78 @@
+//! Unit tests for the end-of-run summary
+
+use dnsrecon_rs::cli::progress::PhaseTimer;
+use dnsrecon_rs::cli::summary::{render_summary, summary_json, ScanSummary};
+use dnsrecon_rs::dns::record::DnsRecord;
+use dnsrecon_rs::enumerate::findings::{Finding, Severity};
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+#[test]
+fn test_render_summary_counts() {
+    let records = vec![
+        DnsRecord::new_a("example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+        DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 2)),
+        DnsRecord::new_mx("example.com".to_string(), 10, "mail.example.com".to_string()),
+    ];
+    let summary = ScanSummary {
+        sources: vec!["dns".to_string(), "crt.sh".to_string()],
+        elapsed: Duration::from_millis(1500),
+        findings: vec![Finding::new(Severity::High, "spf", "example.com", "SPF policy ends in +all")],
+        ..Default::default()
+    };
+
+    let text = render_summary(&summary, &records, false);
+
+    assert!(text.contains("Records:      3"));
+    assert!(text.contains("A          2"));
+    assert!(text.contains("MX         1"));
+    assert!(text.contains("Unique hosts: 2"));
+    assert!(text.contains("Sources:      dns, crt.sh"));
+    assert!(text.contains("Elapsed:      1.50s"));
+    assert!(text.contains("[HIGH] spf example.com: SPF policy ends in +all"));
+}
+
+#[test]
+fn test_render_summary_without_color_has_no_escapes() {
+    let summary = ScanSummary::default();
+    let text = render_summary(&summary, &[], false);
+    assert!(!text.contains('\u{1b}'));
+
+    let colored = render_summary(&summary, &[], true);
+    assert!(colored.contains('\u{1b}'));
+}
+
+#[test]
+fn test_timings_in_human_and_json_summary() {
+    let summary = ScanSummary {
+        sources: vec!["crtsh".to_string()],
+        elapsed: Duration::from_millis(16_700),
+        timings: vec![
+            ("crtsh".to_string(), Duration::from_millis(4_200)),
+            ("brute".to_string(), Duration::from_millis(12_500)),
+        ],
+        ..Default::default()
+    };
+
+    let text = render_summary(&summary, &[], false);
+    assert!(text.contains("Timings:      crtsh 4.20s, brute 12.50s"));
+
+    let json = summary_json(&summary, &[]);
+    assert_eq!(json["timings"]["crtsh"], 4.2);
+    assert_eq!(json["timings"]["brute"], 12.5);
+    assert_eq!(json["elapsed"], 16.7);
+}
+
+#[test]
+fn test_phase_timer_accumulates_repeated_phases() {
+    let timer = PhaseTimer::new();
+    timer.record("bing", Duration::from_secs(1));
+    timer.record("crtsh", Duration::from_secs(2));
+    timer.record("bing", Duration::from_secs(3));
+    drop(timer.start("brute"));
+
+    let timings = timer.timings();
+    let phases: Vec<&str> = timings.iter().map(|(phase, _)| phase.as_str()).collect();
+    assert_eq!(phases, vec!["bing", "crtsh", "brute"]);
+    assert_eq!(timings[0].1, Duration::from_secs(4));
+}
@@ -0,0 +1,43 @@
+//! Unit tests for TXT record classification
+
+use dnsrecon_rs::dns::record::DnsRecord;
+use dnsrecon_rs::enumerate::findings::Severity;
+use dnsrecon_rs::enumerate::txt_classify::{classify_txt, service_findings, categorize_txt_records};
+
+#[test]
+fn test_classify_txt() {
+    assert_eq!(classify_txt("v=spf1 include:_spf.google.com ~all"), "spf");
+    assert_eq!(classify_txt("V=DMARC1; p=reject"), "dmarc");
+    assert_eq!(classify_txt("v=DKIM1; k=rsa; p=MIGf"), "dkim");
+    assert_eq!(classify_txt("google-site-verification=abc123"), "google-site-verification");
+    assert_eq!(classify_txt("MS=ms12345678"), "ms");
+    assert_eq!(classify_txt("\"atlassian-domain-verification=xyz\""), "atlassian");
+    assert_eq!(classify_txt("some free-form text"), "generic");
+    assert_eq!(classify_txt("ms"), "generic");
+}
+
+#[test]
+fn test_categorize_txt_records_and_services() {
+    let mut records = vec![
+        DnsRecord::new_txt("example.com".to_string(), "google-site-verification=abc".to_string()),
+        DnsRecord::new_txt("example.com".to_string(), "google-site-verification=def".to_string()),
+        DnsRecord::new_txt("example.com".to_string(), "MS=ms12345678".to_string()),
+        DnsRecord::new_txt("example.com".to_string(), "hello".to_string()).with_origin("ns-infrastructure"),
+        DnsRecord::new_ns("example.com".to_string(), "ns1.example.com".to_string()),
+    ];
+
+    categorize_txt_records(&mut records);
+    let categories: Vec<Option<&str>> = records.iter().map(|record| record.txt_category.as_deref()).collect();
+    assert_eq!(
+        categories,
+        vec![Some("google-site-verification"), Some("google-site-verification"), Some("ms"), Some("generic"), None]
+    );
+    // Categorizing doesn't overwrite how a record was found
+    assert_eq!(records[3].origin.as_deref(), Some("ns-infrastructure"));
+
+    // Each service is reported once
+    let findings = service_findings("example.com", &records);
+    assert_eq!(findings.len(), 2);
+    assert!(findings.iter().all(|finding| finding.severity == Severity::Info && finding.category == "txt-service"));
+    assert!(findings.iter().any(|finding| finding.message.contains("Microsoft 365")));
+}
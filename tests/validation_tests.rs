@@ -0,0 +1,36 @@
+//! Unit tests for input validation utilities
+
+use dnsrecon_rs::utils::validation::{normalize_domain, domain_to_unicode, is_subdomain_of};
+
+#[test]
+fn test_normalize_unicode_and_punycode_domains() {
+    // A Unicode domain and its punycode equivalent must produce the same query name
+    let unicode = normalize_domain("müller.de").unwrap();
+    let punycode = normalize_domain("xn--mller-kva.de").unwrap();
+
+    assert_eq!(unicode, "xn--mller-kva.de");
+    assert_eq!(unicode, punycode);
+}
+
+#[test]
+fn test_normalize_ascii_domain() {
+    // Plain ASCII domains are lowercased and lose any trailing dot
+    assert_eq!(normalize_domain("Example.COM.").unwrap(), "example.com");
+}
+
+#[test]
+fn test_domain_to_unicode() {
+    assert_eq!(domain_to_unicode("xn--mller-kva.de"), "müller.de");
+}
+
+#[test]
+fn test_is_subdomain_of_requires_label_boundary() {
+    assert!(is_subdomain_of("a.example.com", "example.com"));
+    assert!(is_subdomain_of("WWW.Example.com.", "example.com"));
+    assert!(is_subdomain_of("x.y.example.com", "example.com."));
+
+    assert!(!is_subdomain_of("notexample.com", "example.com"));
+    assert!(!is_subdomain_of("example.com", "example.com"));
+    assert!(!is_subdomain_of(".example.com", "example.com"));
+    assert!(!is_subdomain_of("example.com.evil.net", "example.com"));
+}
@@ -0,0 +1,147 @@
+//! Unit tests for parsing WHOIS responses into structured fields
+
+use dnsrecon_rs::dns::record::DnsRecord;
+use dnsrecon_rs::enumerate::whois::{lookup_all, parse_whois, ServerRateLimiter, WhoisCache, WhoisInfo};
+use dnsrecon_rs::output;
+
+const ARIN_RESPONSE: &str = "\
+NetRange:       8.8.8.0 - 8.8.8.255
+CIDR:           8.8.8.0/24
+NetName:        GOGL
+OriginAS:       AS15169
+Organization:   Google LLC (GOGL)
+
+OrgName:        Google LLC
+OrgId:          GOGL
+Country:        US
+
+OrgAbuseHandle: ABUSE5250-ARIN
+OrgAbuseEmail:  network-abuse@google.com
+";
+
+const RIPE_RESPONSE: &str = "\
+% Abuse contact for '193.0.0.0 - 193.0.7.255' is 'abuse@ripe.net'
+
+inetnum:        193.0.0.0 - 193.0.7.255
+netname:        RIPE-NCC
+country:        NL
+org:            ORG-RIEN1-RIPE
+
+organisation:   ORG-RIEN1-RIPE
+org-name:       Reseaux IP Europeens Network Coordination Centre (RIPE NCC)
+
+route:          193.0.0.0/21
+origin:         AS3333
+";
+
+#[test]
+fn test_parse_arin_response() {
+    let info = parse_whois(ARIN_RESPONSE);
+    assert_eq!(info.org_name.as_deref(), Some("Google LLC"));
+    assert_eq!(info.org_handle.as_deref(), Some("GOGL"));
+    assert_eq!(info.net_ranges, vec!["8.8.8.0 - 8.8.8.255", "8.8.8.0/24"]);
+    assert_eq!(info.country.as_deref(), Some("US"));
+    assert_eq!(info.abuse_contact.as_deref(), Some("network-abuse@google.com"));
+    assert_eq!(info.asn.as_deref(), Some("AS15169"));
+}
+
+#[test]
+fn test_parse_ripe_response() {
+    let info = parse_whois(RIPE_RESPONSE);
+    assert_eq!(info.net_ranges, vec!["193.0.0.0 - 193.0.7.255"]);
+    assert_eq!(info.country.as_deref(), Some("NL"));
+    assert_eq!(info.abuse_contact.as_deref(), Some("abuse@ripe.net"));
+    assert_eq!(info.asn.as_deref(), Some("AS3333"));
+    assert!(info.org_name.is_some());
+}
+
+#[test]
+fn test_whois_info_in_output() {
+    let mut record = DnsRecord::new_ptr("8.8.8.8".to_string(), "dns.google".to_string());
+    record.whois = Some(WhoisInfo {
+        org_name: Some("Google LLC".to_string()),
+        country: Some("US".to_string()),
+        net_ranges: vec!["8.8.8.0/24".to_string()],
+        ..Default::default()
+    });
+    let plain = DnsRecord::new_ptr("192.0.2.1".to_string(), "host.example.com".to_string());
+
+    let json = output::json::to_json_string(&[record.clone(), plain], true).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value[0]["whois"]["org_name"], "Google LLC");
+    assert_eq!(value[0]["whois"]["net_ranges"][0], "8.8.8.0/24");
+    // Records without WHOIS data keep their previous shape
+    assert!(value[1].get("whois").is_none());
+
+    let xml = output::xml::to_xml_string(&[record]).unwrap();
+    assert!(xml.contains("<whois><org_name>Google LLC</org_name><country>US</country><net_range>8.8.8.0/24</net_range></whois>"));
+}
+
+#[test]
+fn test_cache_shares_one_query_per_netrange() {
+    use std::cell::Cell;
+    use std::net::IpAddr;
+    use std::time::Duration;
+
+    let cache = WhoisCache::new(Duration::ZERO);
+    let queries = Cell::new(0);
+    let query = |_: IpAddr| {
+        queries.set(queries.get() + 1);
+        Ok(ARIN_RESPONSE.to_string())
+    };
+
+    let first = cache.lookup_with("8.8.8.8".parse().unwrap(), query).unwrap();
+    let second = cache.lookup_with("8.8.8.200".parse().unwrap(), query).unwrap();
+    assert_eq!(queries.get(), 1);
+    assert_eq!(first, second);
+
+    // Addresses outside the netblock need their own query
+    cache.lookup_with("8.8.4.4".parse().unwrap(), query).unwrap();
+    assert_eq!(queries.get(), 2);
+}
+
+#[test]
+fn test_rate_limiter_spaces_queries_per_server() {
+    use std::time::{Duration, Instant};
+
+    let limiter = ServerRateLimiter::new(Duration::from_millis(100));
+    let started = Instant::now();
+    limiter.wait("whois.arin.net");
+    limiter.wait("whois.ripe.net");
+    assert!(started.elapsed() < Duration::from_millis(100));
+
+    limiter.wait("WHOIS.ARIN.NET");
+    assert!(started.elapsed() >= Duration::from_millis(100));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_lookup_all_respects_concurrency_cap() {
+    use std::net::IpAddr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let queries = Arc::new(AtomicUsize::new(0));
+    let (in_flight_clone, peak_clone, queries_clone) = (in_flight.clone(), peak.clone(), queries.clone());
+    let query = move |_: IpAddr| {
+        let now = in_flight_clone.fetch_add(1, Ordering::SeqCst) + 1;
+        peak_clone.fetch_max(now, Ordering::SeqCst);
+        queries_clone.fetch_add(1, Ordering::SeqCst);
+        std::thread::sleep(Duration::from_millis(50));
+        in_flight_clone.fetch_sub(1, Ordering::SeqCst);
+        Ok(ARIN_RESPONSE.to_string())
+    };
+
+    // Eight netblocks, one with two addresses and a duplicate
+    let mut ips: Vec<IpAddr> = (1..=8).map(|n| format!("198.51.{}.1", n).parse().unwrap()).collect();
+    ips.push("198.51.1.2".parse().unwrap());
+    ips.push("198.51.1.1".parse().unwrap());
+
+    let results = lookup_all(&ips, 2, query).await;
+    assert_eq!(results.len(), 9);
+    assert_eq!(queries.load(Ordering::SeqCst), 9);
+    assert_eq!(peak.load(Ordering::SeqCst), 2);
+    assert_eq!(results[&ips[0]].org_name.as_deref(), Some("Google LLC"));
+}
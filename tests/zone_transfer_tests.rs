@@ -0,0 +1,125 @@
+//! Unit tests for classifying AXFR attempts
+
+mod common;
+
+use dnsrecon_rs::dns::record::DnsRecord;
+use dnsrecon_rs::dns::zone_transfer::{check_axfr, check_axfr_all, classify_response, AxfrStatus};
+use std::net::{Ipv4Addr, TcpListener};
+use trust_dns_client::op::ResponseCode;
+
+#[test]
+fn test_refused_connection_is_refused() {
+    // Grab a free local port, then close it so connecting is rejected
+    let addr = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+
+    assert!(matches!(check_axfr("example.com", addr), AxfrStatus::Refused));
+}
+
+#[test]
+fn test_each_attempt_opens_one_connection() {
+    use std::io::{Read, Write};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use trust_dns_client::op::{Message, MessageType};
+
+    // A nameserver that counts its connections and refuses every transfer
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let connections = Arc::new(AtomicUsize::new(0));
+    let accepted = connections.clone();
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            accepted.fetch_add(1, Ordering::SeqCst);
+            let mut length = [0u8; 2];
+            if stream.read_exact(&mut length).is_err() {
+                continue;
+            }
+            let mut request = vec![0u8; u16::from_be_bytes(length) as usize];
+            stream.read_exact(&mut request).unwrap();
+            let request = Message::from_vec(&request).unwrap();
+
+            let mut response = Message::new();
+            response.set_id(request.id()).set_message_type(MessageType::Response).set_response_code(ResponseCode::Refused);
+            response.add_query(request.queries()[0].clone());
+            let response = response.to_vec().unwrap();
+            stream.write_all(&(response.len() as u16).to_be_bytes()).unwrap();
+            stream.write_all(&response).unwrap();
+        }
+    });
+
+    assert!(matches!(check_axfr("example.com", addr), AxfrStatus::Refused));
+    assert_eq!(connections.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_transferred_zone_is_allowed() {
+    let records = vec![
+        DnsRecord::new_soa(
+            "example.com".to_string(),
+            "ns1.example.com".to_string(),
+            "hostmaster.example.com".to_string(),
+            2024010101,
+            3600,
+            600,
+            604800,
+            300,
+        ),
+        DnsRecord::new_a("www.example.com".to_string(), Ipv4Addr::new(192, 0, 2, 1)),
+    ];
+
+    match classify_response(ResponseCode::NoError, records) {
+        AxfrStatus::Allowed(records) => assert_eq!(records.len(), 2),
+        status => panic!("Expected an allowed transfer, got {:?}", status),
+    }
+}
+
+#[test]
+fn test_denied_transfers_are_refused() {
+    assert!(matches!(classify_response(ResponseCode::Refused, Vec::new()), AxfrStatus::Refused));
+    assert!(matches!(classify_response(ResponseCode::NotAuth, Vec::new()), AxfrStatus::Refused));
+    assert!(matches!(classify_response(ResponseCode::NoError, Vec::new()), AxfrStatus::Refused));
+    assert!(matches!(classify_response(ResponseCode::ServFail, Vec::new()), AxfrStatus::Error(_)));
+}
+
+#[tokio::test]
+async fn test_check_axfr_all_reports_every_nameserver() {
+    let closed = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    let nameservers = vec![closed.to_string(), "ns1.example.com".to_string()];
+
+    let statuses = check_axfr_all("example.com", &nameservers).await;
+    assert_eq!(statuses.len(), 2);
+    assert_eq!(statuses[0].0, closed.to_string());
+    assert!(matches!(statuses[0].1, AxfrStatus::Refused));
+    assert!(matches!(statuses[1].1, AxfrStatus::Error(_)));
+}
+
+#[tokio::test]
+async fn test_zone_transfer_accepts_addresses_and_hostnames() {
+    use dnsrecon_rs::dns::resolver::DnsHelper;
+    use dnsrecon_rs::dns::zone_transfer::{resolve_nameserver, zone_transfer};
+    use std::net::SocketAddr;
+    use dnsrecon_rs::dns::DnsError;
+    use trust_dns_resolver::proto::rr::rdata::A;
+    use trust_dns_resolver::proto::rr::{RData, Record, RecordType};
+
+    // Resolve every A query to the loopback address
+    let server = common::spawn_dns_server(|query, response| {
+        if query.query_type() == RecordType::A {
+            let address = RData::A(A(Ipv4Addr::LOCALHOST));
+            response.add_answer(Record::from_rdata(query.name().clone(), 300, address));
+        }
+    });
+    let helper = DnsHelper::with_nameserver_addrs(vec![server]).unwrap();
+
+    // An address is connected to directly
+    let closed = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    match zone_transfer("example.com", &closed.to_string(), &helper).await {
+        Err(DnsError::ZoneTransferFailed(_)) => {}
+        result => panic!("Expected a failed connection, got {:?}", result.map(|records| records.len())),
+    }
+
+    // A hostname is resolved first, and its addresses are tried on port 53
+    let addrs = resolve_nameserver("ns1.example.com", &helper).await.unwrap();
+    assert_eq!(addrs, vec![SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 53)]);
+    assert_eq!(resolve_nameserver(&closed.to_string(), &helper).await.unwrap(), vec![closed]);
+}
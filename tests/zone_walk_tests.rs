@@ -0,0 +1,66 @@
+//! Unit tests for NSEC zone walking against mocked NSEC chains
+
+use dnsrecon_rs::dns::record::DnsRecord;
+use dnsrecon_rs::dns::zone_walk::walk_chain;
+use dnsrecon_rs::dns::DnsError;
+use std::collections::HashMap;
+
+/// Walk a chain given as `(owner, next)` pairs and return the owners visited
+async fn walk(domain: &str, links: &[(&str, &str)]) -> (Vec<String>, Vec<String>) {
+    let chain: HashMap<String, String> =
+        links.iter().map(|(owner, next)| (owner.to_string(), next.to_string())).collect();
+    let mut queried = Vec::new();
+
+    let records = walk_chain(domain, |name| {
+        queried.push(name.clone());
+        let answer = match chain.get(&name) {
+            Some(next) => Ok(vec![DnsRecord::new_nsec(name, next.clone(), vec!["A".to_string(), "NSEC".to_string()])]),
+            None => Err(DnsError::Other(format!("no NSEC for {}", name))),
+        };
+        std::future::ready(answer)
+    })
+    .await;
+
+    (records.into_iter().map(|record| record.name).collect(), queried)
+}
+
+#[tokio::test]
+async fn test_walk_stops_when_the_chain_wraps_to_the_apex() {
+    let links = [
+        ("example.com", "api.example.com"),
+        ("api.example.com", "mail.example.com"),
+        ("mail.example.com", "WWW.example.com."),
+        ("www.example.com", "Example.COM."),
+    ];
+
+    let (names, queried) = walk("example.com.", &links).await;
+    assert_eq!(names, vec!["example.com", "api.example.com", "mail.example.com", "www.example.com"]);
+    // The apex is not queried a second time
+    assert_eq!(queried.len(), 4);
+}
+
+#[tokio::test]
+async fn test_walk_stops_on_loops_gaps_and_names_outside_the_zone() {
+    // A chain looping back to a name other than the apex
+    let links = [
+        ("example.com", "a.example.com"),
+        ("a.example.com", "b.example.com"),
+        ("b.example.com", "a.example.com"),
+    ];
+    let (names, _) = walk("example.com", &links).await;
+    assert_eq!(names, vec!["example.com", "a.example.com", "b.example.com"]);
+
+    // A name without an NSEC record ends the walk with what was found
+    let (names, _) = walk("example.com", &[("example.com", "a.example.com")]).await;
+    assert_eq!(names, vec!["example.com"]);
+
+    // An NSEC pointing outside the zone
+    let links = [("example.com", "a.example.com"), ("a.example.com", "example.net")];
+    let (names, queried) = walk("example.com", &links).await;
+    assert_eq!(names, vec!["example.com", "a.example.com"]);
+    assert_eq!(queried, vec!["example.com", "a.example.com"]);
+
+    // An unsigned zone gives nothing
+    let (names, _) = walk("example.com", &[]).await;
+    assert!(names.is_empty());
+}